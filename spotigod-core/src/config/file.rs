@@ -0,0 +1,314 @@
+//! Formato de `config.toml`: las opciones de `Config` agrupadas en secciones. Los tokens no
+//! están aquí sino en `tokens.json`, así que SpotiGod nunca reescribe este archivo después de
+//! crearlo y se conservan los comentarios y cambios del usuario.
+
+use super::*;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub(super) struct ConfigFile {
+    auth: AuthSection,
+    #[serde(default)]
+    ui: UiSection,
+    #[serde(default)]
+    keybindings: KeybindingsSection,
+    #[serde(default)]
+    theme: ThemeSection,
+    #[serde(default)]
+    behavior: BehaviorSection,
+    #[serde(default)]
+    listenbrainz: ListenBrainzSection,
+    #[serde(default)]
+    log: LogSection,
+    #[serde(default)]
+    hooks: Hooks,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct AuthSection {
+    client_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    client_secret: Option<String>,
+    #[serde(default = "default_redirect_uri")]
+    redirect_uri: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    callback_host: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    callback_port: Option<u16>,
+    #[serde(default = "default_auth_timeout_secs")]
+    timeout_secs: u64,
+    #[serde(default)]
+    manual: bool,
+    #[serde(default = "default_encrypt_tokens")]
+    encrypt_tokens: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+struct UiSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    language: Option<String>,
+    ascii_symbols: bool,
+    accessible: bool,
+    sidebar: bool,
+    scroll_step: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    statusline_format: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    statusline_max_width: Option<usize>,
+}
+
+impl Default for UiSection {
+    fn default() -> Self {
+        Self {
+            language: None,
+            ascii_symbols: false,
+            accessible: false,
+            sidebar: default_sidebar(),
+            scroll_step: default_scroll_step(),
+            statusline_format: None,
+            statusline_max_width: None,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct KeybindingsSection {
+    #[serde(default)]
+    preset: KeymapPreset,
+    /// Teclas de cada acción
+    #[serde(flatten)]
+    actions: HashMap<String, KeyList>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ThemeSection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    preset: Option<String>,
+    /// Color de cada papel
+    #[serde(flatten)]
+    colors: HashMap<String, String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+struct BehaviorSection {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    market: Option<String>,
+    requests_per_second: f64,
+    library_cache_ttl_secs: u64,
+    catalog_cache_ttl_secs: u64,
+    search_debounce_ms: u64,
+    volume_step: u8,
+    open_in_app: bool,
+    lyrics_provider: LyricsSource,
+}
+
+impl Default for BehaviorSection {
+    fn default() -> Self {
+        Self {
+            market: None,
+            requests_per_second: default_requests_per_second(),
+            library_cache_ttl_secs: default_library_cache_ttl_secs(),
+            catalog_cache_ttl_secs: default_catalog_cache_ttl_secs(),
+            search_debounce_ms: default_search_debounce_ms(),
+            volume_step: default_volume_step(),
+            open_in_app: false,
+            lyrics_provider: LyricsSource::default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct ListenBrainzSection {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    url: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(default)]
+struct LogSection {
+    level: String,
+}
+
+impl Default for LogSection {
+    fn default() -> Self {
+        Self { level: default_log_level() }
+    }
+}
+
+/// Comentario de cada opción y, para las que no tienen valor, un ejemplo que se deja comentado
+const DOCS: &[(&str, &str, &str, Option<&str>)] = &[
+    ("auth", "client_id", "Client ID de tu aplicación en https://developer.spotify.com/dashboard", None),
+    ("auth", "client_secret", "Solo para el flujo clásico; sin él se usa Authorization Code + PKCE", Some("\"tu_client_secret\"")),
+    ("auth", "redirect_uri", "Debe coincidir con la Redirect URI de la aplicación", None),
+    ("auth", "callback_host", "Dirección en la que escuchar la redirección (por defecto, el host de redirect_uri)", Some("\"0.0.0.0\"")),
+    ("auth", "callback_port", "Puerto en el que escuchar la redirección (por defecto, el de redirect_uri; 0 elige uno libre)", Some("0")),
+    ("auth", "timeout_secs", "Segundos que se espera la redirección de Spotify", None),
+    ("auth", "manual", "Pegar a mano la URL de redirección, sin navegador ni puerto local", None),
+    ("auth", "encrypt_tokens", "Cifrar los tokens guardados en tokens.json", None),
+    ("ui", "language", "\"es\" o \"en\"; si falta se usa el de LANG", Some("\"es\"")),
+    ("ui", "ascii_symbols", "Iconos en ASCII para fuentes o multiplexores que descuadran los emoji", None),
+    ("ui", "accessible", "Marca los estados con negrita, subrayado y bordes gruesos además del color", None),
+    ("ui", "sidebar", "Barra lateral con las vistas y las playlists", None),
+    ("ui", "scroll_step", "Filas que avanza cada paso de la rueda del ratón", None),
+    ("ui", "statusline_format", "Plantilla de `spotigod statusline` sin --format", Some("\"{state} {artist} - {title}\"")),
+    ("ui", "statusline_max_width", "Columnas como mucho de `spotigod statusline`", Some("40")),
+    ("keybindings", "preset", "\"default\" o \"vim\" (añade j/k, gg/G, Ctrl+d/Ctrl+u y h/l)", None),
+    ("keybindings", "play_pause", "Teclas de cada acción, una o una lista; las que falten usan las de siempre", Some("[\"space\", \"p\"]")),
+    ("theme", "preset", "gruvbox, dracula, nord, solarized-light...", Some("\"gruvbox\"")),
+    ("theme", "accent", "Color de cada papel (nombre, \"#rrggbb\" o índice de 256 colores)", Some("\"magenta\"")),
+    ("behavior", "market", "País (ISO 3166-1) de búsquedas y disponibilidad; si falta se usa el del perfil", Some("\"ES\"")),
+    ("behavior", "requests_per_second", "Peticiones por segundo como mucho a la API", None),
+    ("behavior", "library_cache_ttl_secs", "Segundos que se reutilizan playlists, favoritos y episodios (0 desactiva la caché)", None),
+    ("behavior", "catalog_cache_ttl_secs", "Segundos que se reutilizan artistas y álbumes (0 desactiva la caché)", None),
+    ("behavior", "search_debounce_ms", "Milisegundos sin escribir tras los que se busca solo; 0 busca solo con Enter", None),
+    ("behavior", "volume_step", "Puntos que suben o bajan volume_up y volume_down", None),
+    ("behavior", "open_in_app", "Abrir los enlaces en la aplicación de Spotify en lugar del navegador", None),
+    ("behavior", "lyrics_provider", "\"lrclib\" o \"none\" para no descargar letras", None),
+    ("listenbrainz", "token", "Token de usuario de https://listenbrainz.org/settings/ para enviar lo que escuchas", Some("\"tu_token\"")),
+    ("listenbrainz", "url", "Servidor compatible con la API de ListenBrainz", Some("\"https://api.listenbrainz.org\"")),
+    ("log", "level", "Detalle del registro en logs/ del directorio de estado: \"off\", \"error\", \"warn\", \"info\", \"debug\" o \"trace\"", None),
+    ("hooks", "track_change", "Orden (sh -c) al cambiar de canción; recibe SPOTIGOD_TITLE, SPOTIGOD_ARTIST, SPOTIGOD_ALBUM...", Some("'notify-send \"$SPOTIGOD_TITLE\" \"$SPOTIGOD_ARTIST\"'")),
+    ("hooks", "play", "Orden al reanudar la reproducción", Some("'echo playing > /tmp/spotigod-state'")),
+    ("hooks", "pause", "Orden al pausar o parar la reproducción", Some("'echo paused > /tmp/spotigod-state'")),
+    ("hooks", "track_liked", "Orden al guardar una canción en \"Tus me gusta\"", Some("'echo \"$SPOTIGOD_URL\" >> ~/liked.txt'")),
+];
+
+const HEADER: &str = "\
+# Configuración de SpotiGod. SpotiGod no modifica este archivo: los tokens de la sesión se
+# guardan aparte, en tokens.json. Las opciones comentadas muestran un ejemplo de su valor.
+";
+
+impl ConfigFile {
+    pub(super) fn from_config(config: &Config) -> Self {
+        Self {
+            auth: AuthSection {
+                client_id: config.client_id.clone(),
+                client_secret: config.client_secret.clone(),
+                redirect_uri: config.redirect_uri.clone(),
+                callback_host: config.callback_host.clone(),
+                callback_port: config.callback_port,
+                timeout_secs: config.auth_timeout_secs,
+                manual: config.manual_auth,
+                encrypt_tokens: config.encrypt_tokens,
+            },
+            ui: UiSection {
+                language: config.language.clone(),
+                ascii_symbols: config.ascii_symbols,
+                accessible: config.accessible,
+                sidebar: config.sidebar,
+                scroll_step: config.scroll_step,
+                statusline_format: config.statusline_format.clone(),
+                statusline_max_width: config.statusline_max_width,
+            },
+            keybindings: KeybindingsSection {
+                preset: config.keymap_preset,
+                actions: config.keymap.clone(),
+            },
+            theme: ThemeSection {
+                preset: config.theme_preset.clone(),
+                colors: config.theme.clone(),
+            },
+            behavior: BehaviorSection {
+                market: config.market.clone(),
+                requests_per_second: config.requests_per_second,
+                library_cache_ttl_secs: config.library_cache_ttl_secs,
+                catalog_cache_ttl_secs: config.catalog_cache_ttl_secs,
+                search_debounce_ms: config.search_debounce_ms,
+                volume_step: config.volume_step,
+                open_in_app: config.open_in_app,
+                lyrics_provider: config.lyrics_provider,
+            },
+            listenbrainz: ListenBrainzSection {
+                token: config.listenbrainz_token.clone(),
+                url: config.listenbrainz_url.clone(),
+            },
+            log: LogSection {
+                level: config.log_level.clone(),
+            },
+            hooks: config.hooks.clone(),
+        }
+    }
+
+    /// Config sin sesión; los tokens se leen después de tokens.json
+    pub(super) fn into_config(self) -> Config {
+        Config {
+            client_id: self.auth.client_id,
+            client_secret: self.auth.client_secret,
+            redirect_uri: self.auth.redirect_uri,
+            callback_host: self.auth.callback_host,
+            callback_port: self.auth.callback_port,
+            auth_timeout_secs: self.auth.timeout_secs,
+            access_token: None,
+            refresh_token: None,
+            token_expires_at: None,
+            market: self.behavior.market,
+            requests_per_second: self.behavior.requests_per_second,
+            library_cache_ttl_secs: self.behavior.library_cache_ttl_secs,
+            catalog_cache_ttl_secs: self.behavior.catalog_cache_ttl_secs,
+            manual_auth: self.auth.manual,
+            encrypt_tokens: self.auth.encrypt_tokens,
+            keymap: self.keybindings.actions,
+            keymap_preset: self.keybindings.preset,
+            language: self.ui.language,
+            theme_preset: self.theme.preset,
+            theme: self.theme.colors,
+            scroll_step: self.ui.scroll_step,
+            ascii_symbols: self.ui.ascii_symbols,
+            accessible: self.ui.accessible,
+            lyrics_provider: self.behavior.lyrics_provider,
+            search_debounce_ms: self.behavior.search_debounce_ms,
+            volume_step: self.behavior.volume_step,
+            sidebar: self.ui.sidebar,
+            open_in_app: self.behavior.open_in_app,
+            statusline_format: self.ui.statusline_format,
+            statusline_max_width: self.ui.statusline_max_width,
+            listenbrainz_token: self.listenbrainz.token,
+            listenbrainz_url: self.listenbrainz.url,
+            token_scope: None,
+            log_level: self.log.level,
+            hooks: self.hooks,
+            encrypted_tokens: None,
+        }
+    }
+
+    /// El TOML con un comentario encima de cada opción y las que no tienen valor comentadas
+    pub(super) fn to_documented_toml(&self) -> Result<String> {
+        let mut output = HEADER.to_string();
+        let sections = [
+            ("auth", toml::to_string(&self.auth)?),
+            ("ui", toml::to_string(&self.ui)?),
+            ("keybindings", toml::to_string(&self.keybindings)?),
+            ("theme", toml::to_string(&self.theme)?),
+            ("behavior", toml::to_string(&self.behavior)?),
+            ("listenbrainz", toml::to_string(&self.listenbrainz)?),
+            ("log", toml::to_string(&self.log)?),
+            ("hooks", toml::to_string(&self.hooks)?),
+        ];
+        for (section, body) in sections {
+            output.push_str(&format!("\n[{}]\n", section));
+            let mut documented = Vec::new();
+            for line in body.lines() {
+                let key = line.split('=').next().unwrap_or_default().trim();
+                if let Some((_, _, doc, _)) = DOCS.iter().find(|(s, k, _, _)| *s == section && *k == key) {
+                    output.push_str(&format!("# {}\n", doc));
+                    documented.push(key);
+                }
+                output.push_str(line);
+                output.push('\n');
+            }
+            for (_, key, doc, example) in DOCS.iter().filter(|(s, k, _, _)| *s == section && !documented.contains(k)) {
+                if let Some(example) = example {
+                    output.push_str(&format!("# {}\n# {} = {}\n", doc, key, example));
+                }
+            }
+        }
+        Ok(output)
+    }
+}
+
+fn default_redirect_uri() -> String {
+    "http://127.0.0.1:8888/callback".to_string()
+}
@@ -0,0 +1,516 @@
+use anyhow::{anyhow, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use crate::hooks::Hooks;
+use crate::tr;
+
+mod file;
+mod token_crypto;
+
+use file::ConfigFile;
+
+/// Directorio elegido con `--config`: en él van todos los archivos (configuración, sesión,
+/// estado y cachés), para instalaciones portátiles
+static DIR_OVERRIDE: OnceLock<PathBuf> = OnceLock::new();
+
+/// Ruta absoluta de una variable de entorno; la especificación XDG pide ignorar las relativas
+fn env_dir(var: &str) -> Option<PathBuf> {
+    std::env::var_os(var).map(PathBuf::from).filter(|path| path.is_absolute())
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Config {
+    pub client_id: String,
+    /// Solo necesario para el flujo clásico; sin él se usa Authorization Code + PKCE
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    /// Dirección en la que escuchar la redirección (por defecto, el host de redirect_uri)
+    #[serde(default)]
+    pub callback_host: Option<String>,
+    /// Puerto en el que escuchar la redirección (por defecto, el de redirect_uri; 0 elige uno libre)
+    #[serde(default)]
+    pub callback_port: Option<u16>,
+    /// Segundos que se espera la redirección de Spotify antes de abandonar la autorización
+    #[serde(default = "default_auth_timeout_secs")]
+    pub auth_timeout_secs: u64,
+    pub access_token: Option<String>,
+    pub refresh_token: Option<String>,
+    pub token_expires_at: Option<i64>,
+    /// Código de país (ISO 3166-1) para disponibilidad y relinking; si falta se usa el del perfil
+    #[serde(default)]
+    pub market: Option<String>,
+    /// Peticiones por segundo que el cliente se permite hacer a la API
+    #[serde(default = "default_requests_per_second")]
+    pub requests_per_second: f64,
+    /// Segundos que se reutilizan playlists, favoritos y episodios descargados (0 desactiva la caché)
+    #[serde(default = "default_library_cache_ttl_secs")]
+    pub library_cache_ttl_secs: u64,
+    /// Segundos que se reutilizan artistas y álbumes descargados (0 desactiva la caché)
+    #[serde(default = "default_catalog_cache_ttl_secs")]
+    pub catalog_cache_ttl_secs: u64,
+    /// Autenticación sin navegador ni servidor local: se pega a mano la URL de redirección
+    #[serde(default)]
+    pub manual_auth: bool,
+    /// Cifrar los tokens al guardar tokens.json (activado por defecto)
+    #[serde(default = "default_encrypt_tokens")]
+    pub encrypt_tokens: bool,
+    /// Teclas de cada acción de la interfaz (p. ej. `"next": ["n", "ctrl+right"]`); las que falten usan las de siempre
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub keymap: HashMap<String, KeyList>,
+    /// Teclas de navegación adicionales: `"vim"` añade j/k, gg/G, Ctrl+d/Ctrl+u y h/l
+    #[serde(default)]
+    pub keymap_preset: KeymapPreset,
+    /// Idioma de la interfaz (`"es"` o `"en"`); si falta se usa el de `LANG`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// Tema incluido en el que se basan los colores (`gruvbox`, `dracula`, `nord`, `solarized-light`...)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub theme_preset: Option<String>,
+    /// Colores de la interfaz por papel (p. ej. `"accent": "magenta"`); los que falten usan los de siempre
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub theme: HashMap<String, String>,
+    /// Filas que avanza la selección con cada paso de la rueda del ratón
+    #[serde(default = "default_scroll_step")]
+    pub scroll_step: usize,
+    /// Sustituye los emoji y símbolos de la interfaz por ASCII (para fuentes y multiplexores
+    /// que los dibujan con el ancho equivocado)
+    #[serde(default)]
+    pub ascii_symbols: bool,
+    /// Marca los estados (pestaña activa, aleatorio...) con negrita, subrayado y bordes gruesos
+    /// además del color, sea cual sea el tema
+    #[serde(default)]
+    pub accessible: bool,
+    /// Servicio del que se descargan las letras (`"lrclib"`, por defecto, o `"none"` para desactivarlas)
+    #[serde(default)]
+    pub lyrics_provider: LyricsSource,
+    /// Milisegundos sin escribir tras los que la búsqueda se lanza sola; 0 busca solo con Enter
+    #[serde(default = "default_search_debounce_ms")]
+    pub search_debounce_ms: u64,
+    /// Puntos de volumen que suben o bajan `volume_up` y `volume_down`
+    #[serde(default = "default_volume_step")]
+    pub volume_step: u8,
+    /// Barra lateral con las vistas y las playlists (activada por defecto)
+    #[serde(default = "default_sidebar")]
+    pub sidebar: bool,
+    /// Abrir los elementos en la aplicación oficial (URI `spotify:`) en lugar de en el navegador
+    #[serde(default)]
+    pub open_in_app: bool,
+    /// Plantilla de `spotigod statusline` cuando no se pasa `--format`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statusline_format: Option<String>,
+    /// Columnas como mucho de `spotigod statusline`; lo que sobra se corta con "…"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statusline_max_width: Option<usize>,
+    /// Token de usuario de ListenBrainz; con él se envían allí las canciones escuchadas
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listenbrainz_token: Option<String>,
+    /// Servidor compatible con la API de ListenBrainz en lugar de api.listenbrainz.org
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listenbrainz_url: Option<String>,
+    /// Permisos concedidos con el token (separados por espacios); `None` en sesiones anteriores a guardarlos
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token_scope: Option<String>,
+    /// Nivel del registro en el archivo de log (`"off"`, `"error"`, `"warn"`, `"info"`, `"debug"` o `"trace"`)
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// Órdenes que se ejecutan con los eventos de la reproducción
+    #[serde(default, skip_serializing_if = "Hooks::is_empty")]
+    pub hooks: Hooks,
+    /// Tokens cifrados tal como se guardaban en config.json; en memoria siempre se usan los campos en claro
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encrypted_tokens: Option<String>,
+}
+
+/// Una tecla o una lista de teclas asignadas a una acción
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KeyList {
+    One(String),
+    Many(Vec<String>),
+}
+
+impl KeyList {
+    pub fn keys(&self) -> Vec<&str> {
+        match self {
+            KeyList::One(key) => vec![key.as_str()],
+            KeyList::Many(keys) => keys.iter().map(String::as_str).collect(),
+        }
+    }
+}
+
+/// Conjunto de teclas de navegación que se suma a las flechas, Enter y Esc
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeymapPreset {
+    #[default]
+    Default,
+    Vim,
+}
+
+/// Servicio de letras de la vista Letra
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LyricsSource {
+    #[default]
+    Lrclib,
+    None,
+}
+
+/// Parte de la sesión que se guarda cifrada
+#[derive(Serialize, Deserialize)]
+struct StoredTokens {
+    access_token: Option<String>,
+    refresh_token: Option<String>,
+}
+
+/// Sesión guardada en tokens.json, aparte de la configuración
+#[derive(Default, Serialize, Deserialize)]
+struct TokenFile {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    access_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    refresh_token: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    token_expires_at: Option<i64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    scope: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    encrypted_tokens: Option<String>,
+}
+
+fn default_encrypt_tokens() -> bool {
+    true
+}
+
+fn default_requests_per_second() -> f64 {
+    10.0
+}
+
+fn default_auth_timeout_secs() -> u64 {
+    300
+}
+
+fn default_scroll_step() -> usize {
+    3
+}
+
+fn default_search_debounce_ms() -> u64 {
+    300
+}
+
+fn default_volume_step() -> u8 {
+    5
+}
+
+fn default_sidebar() -> bool {
+    true
+}
+
+fn default_library_cache_ttl_secs() -> u64 {
+    60
+}
+
+fn default_catalog_cache_ttl_secs() -> u64 {
+    600
+}
+
+/// Escribe un archivo que en unix solo puede leer el usuario, como el socket del demonio
+fn write_private(path: &Path, content: &str) -> Result<()> {
+    #[cfg(unix)]
+    {
+        use std::io::Write;
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        let mut file = fs::OpenOptions::new().write(true).create(true).truncate(true).mode(0o600).open(path)?;
+        // `mode` solo cuenta al crearlo: un archivo de versiones anteriores puede seguir legible por otros
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(content.as_bytes())?;
+    }
+    #[cfg(not(unix))]
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
+}
+
+impl Config {
+    pub async fn load() -> Result<Self> {
+        Self::migrate_legacy_files()?;
+        let config_path = Self::get_config_path()?;
+        let legacy_path = Self::config_dir()?.join("config.json");
+        
+        if config_path.exists() {
+            let content = fs::read_to_string(&config_path)?;
+            let file: ConfigFile = toml::from_str(&content)
+                .map_err(|e| anyhow!(tr!("Error en {}: {}", config_path.display(), e)))?;
+            let mut config = file.into_config();
+            config.load_tokens()?;
+            Ok(config)
+        } else if legacy_path.exists() {
+            Self::migrate(&legacy_path).await
+        } else {
+            // Primera vez, crear configuración con valores por defecto
+            let config = Config {
+                client_id: std::env::var("SPOTIFY_CLIENT_ID").unwrap_or_else(|_| {
+                    println!("{}", tr!("⚠️  No se encontró SPOTIFY_CLIENT_ID en las variables de entorno"));
+                    println!("{}", tr!("📝 Por favor, ve a https://developer.spotify.com/dashboard"));
+                    println!("{}", tr!("   1. Crea una nueva app"));
+                    println!("{}", tr!("   2. Copia el Client ID"));
+                    println!("{}", tr!("   3. Agrega http://127.0.0.1:8888/callback como Redirect URI"));
+                    println!("{}", tr!("   4. Ejecuta: export SPOTIFY_CLIENT_ID=tu_client_id"));
+                    println!("{}", tr!("   (Opcional) export SPOTIFY_CLIENT_SECRET=tu_client_secret para el flujo sin PKCE"));
+                    std::process::exit(1);
+                }),
+                client_secret: std::env::var("SPOTIFY_CLIENT_SECRET").ok().filter(|s| !s.is_empty()),
+                redirect_uri: "http://127.0.0.1:8888/callback".to_string(),
+                callback_host: None,
+                callback_port: None,
+                auth_timeout_secs: default_auth_timeout_secs(),
+                access_token: None,
+                refresh_token: None,
+                token_expires_at: None,
+                market: None,
+                requests_per_second: default_requests_per_second(),
+                library_cache_ttl_secs: default_library_cache_ttl_secs(),
+                catalog_cache_ttl_secs: default_catalog_cache_ttl_secs(),
+                manual_auth: false,
+                encrypt_tokens: default_encrypt_tokens(),
+                keymap: HashMap::new(),
+                keymap_preset: KeymapPreset::default(),
+                language: None,
+                theme_preset: None,
+                theme: HashMap::new(),
+                scroll_step: default_scroll_step(),
+                ascii_symbols: false,
+                accessible: false,
+                lyrics_provider: LyricsSource::default(),
+                search_debounce_ms: default_search_debounce_ms(),
+                volume_step: default_volume_step(),
+                sidebar: default_sidebar(),
+                open_in_app: false,
+                statusline_format: None,
+                statusline_max_width: None,
+                listenbrainz_token: None,
+                listenbrainz_url: None,
+                token_scope: None,
+                log_level: default_log_level(),
+                hooks: Hooks::default(),
+                encrypted_tokens: None,
+            };
+            
+            config.write_config_file()?;
+            Ok(config)
+        }
+    }
+
+    /// Convierte el config.json de versiones anteriores: las opciones pasan a config.toml y los
+    /// tokens a tokens.json, y el original se guarda como config.json.bak
+    async fn migrate(legacy_path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(legacy_path)?;
+        let mut config: Config = serde_json::from_str(&content)?;
+        config.decrypt_tokens();
+        config.write_config_file()?;
+        config.save().await?;
+        // El original lleva los tokens: la copia se escribe solo legible por el usuario, sean cuales
+        // sean los permisos que tenía config.json
+        let backup = legacy_path.with_extension("json.bak");
+        write_private(&backup, &content)?;
+        fs::remove_file(legacy_path)?;
+        println!("{}", tr!("📝 config.json se ha convertido en config.toml; el original queda en {}", backup.display()));
+        Ok(config)
+    }
+
+    /// Escribe config.toml con las opciones actuales; solo al crearlo o al migrar
+    fn write_config_file(&self) -> Result<()> {
+        let config_path = Self::get_config_path()?;
+        if let Some(parent) = config_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(config_path, ConfigFile::from_config(self).to_documented_toml()?)?;
+        Ok(())
+    }
+    
+    /// Guarda la sesión (tokens) en tokens.json; config.toml no se toca
+    pub async fn save(&self) -> Result<()> {
+        let tokens_path = Self::tokens_path()?;
+        
+        if let Some(parent) = tokens_path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        
+        let content = serde_json::to_string_pretty(&self.tokens_for_disk()?)?;
+        write_private(&tokens_path, &content)?;
+        Ok(())
+    }
+
+    /// Sesión a escribir en disco: con el cifrado activado los tokens solo van en `encrypted_tokens`
+    fn tokens_for_disk(&self) -> Result<TokenFile> {
+        let mut stored = TokenFile {
+            access_token: self.access_token.clone(),
+            refresh_token: self.refresh_token.clone(),
+            token_expires_at: self.token_expires_at,
+            scope: self.token_scope.clone(),
+            encrypted_tokens: None,
+        };
+        if self.encrypt_tokens && (stored.access_token.is_some() || stored.refresh_token.is_some()) {
+            let tokens = StoredTokens {
+                access_token: stored.access_token.take(),
+                refresh_token: stored.refresh_token.take(),
+            };
+            stored.encrypted_tokens = Some(token_crypto::encrypt(&serde_json::to_string(&tokens)?)?);
+        }
+        Ok(stored)
+    }
+
+    /// Lee la sesión de tokens.json; sin archivo no hay sesión
+    fn load_tokens(&mut self) -> Result<()> {
+        let tokens_path = Self::tokens_path()?;
+        if !tokens_path.exists() {
+            return Ok(());
+        }
+        let tokens: TokenFile = serde_json::from_str(&fs::read_to_string(tokens_path)?)?;
+        self.access_token = tokens.access_token;
+        self.refresh_token = tokens.refresh_token;
+        self.token_expires_at = tokens.token_expires_at;
+        self.token_scope = tokens.scope;
+        self.encrypted_tokens = tokens.encrypted_tokens;
+        self.decrypt_tokens();
+        Ok(())
+    }
+
+    /// Recupera los tokens cifrados; si no se pueden descifrar se descartan y habrá que autenticarse
+    fn decrypt_tokens(&mut self) {
+        let Some(encrypted) = self.encrypted_tokens.take() else {
+            return;
+        };
+        match token_crypto::decrypt(&encrypted).and_then(|json| Ok(serde_json::from_str::<StoredTokens>(&json)?)) {
+            Ok(tokens) => {
+                self.access_token = tokens.access_token;
+                self.refresh_token = tokens.refresh_token;
+            }
+            Err(e) => {
+                // Sin tokens se vuelve a pedir la autorización; aquí no se puede escribir en la terminal
+                tracing::warn!(error = %e, "no se pudieron descifrar los tokens");
+                self.access_token = None;
+                self.refresh_token = None;
+                self.token_expires_at = None;
+            }
+        }
+    }
+    
+    /// Usa `dir` para todos los archivos de SpotiGod (`--config`); hay que llamarlo antes de `load`
+    pub fn set_dir_override(dir: PathBuf) {
+        let _ = DIR_OVERRIDE.set(dir);
+    }
+
+    /// Directorio de `--config` o de `SPOTIGOD_CONFIG_DIR`, si se indicó alguno
+    fn dir_override() -> Option<PathBuf> {
+        DIR_OVERRIDE.get().cloned().or_else(|| env_dir("SPOTIGOD_CONFIG_DIR"))
+    }
+
+    /// Configuración: `$XDG_CONFIG_HOME/spotigod` (`~/.config/spotigod`)
+    pub fn config_dir() -> Result<PathBuf> {
+        Self::xdg_dir("XDG_CONFIG_HOME", ".config")
+    }
+
+    /// Sesión y estado de la interfaz: `$XDG_STATE_HOME/spotigod` (`~/.local/state/spotigod`)
+    pub fn state_dir() -> Result<PathBuf> {
+        Self::xdg_dir("XDG_STATE_HOME", ".local/state")
+    }
+
+    /// Copia local de la biblioteca: `$XDG_CACHE_HOME/spotigod` (`~/.cache/spotigod`)
+    pub fn cache_dir() -> Result<PathBuf> {
+        Self::xdg_dir("XDG_CACHE_HOME", ".cache")
+    }
+
+    /// Archivos de log: `logs/` dentro del directorio de estado
+    pub fn log_dir() -> Result<PathBuf> {
+        Ok(Self::state_dir()?.join("logs"))
+    }
+
+    /// Socket del demonio: `$XDG_RUNTIME_DIR/spotigod`, o el directorio de estado si no existe
+    pub fn runtime_dir() -> Result<PathBuf> {
+        match (Self::dir_override(), env_dir("XDG_RUNTIME_DIR")) {
+            (None, Some(runtime)) => Ok(runtime.join("spotigod")),
+            _ => Self::state_dir(),
+        }
+    }
+
+    fn xdg_dir(var: &str, fallback: &str) -> Result<PathBuf> {
+        if let Some(dir) = Self::dir_override() {
+            return Ok(dir);
+        }
+        let base = match env_dir(var) {
+            Some(base) => base,
+            None => Self::home_dir()?.join(fallback),
+        };
+        Ok(base.join("spotigod"))
+    }
+
+    fn home_dir() -> Result<PathBuf> {
+        dirs::home_dir().ok_or_else(|| anyhow!(tr!("No se pudo determinar el directorio home")))
+    }
+
+    /// Antes todo estaba en `~/.config/spotigod`: mueve cada archivo a su directorio XDG si
+    /// allí todavía no hay uno. Con `--config` no se toca nada
+    fn migrate_legacy_files() -> Result<()> {
+        if Self::dir_override().is_some() {
+            return Ok(());
+        }
+        let legacy_dir = Self::home_dir()?.join(".config").join("spotigod");
+        let (config_dir, state_dir, cache_dir) = (Self::config_dir()?, Self::state_dir()?, Self::cache_dir()?);
+        let moves = [
+            ("config.toml", &config_dir),
+            ("config.json", &config_dir),
+            ("config.json.bak", &config_dir),
+            ("tokens.json", &state_dir),
+            ("state.json", &state_dir),
+            ("library.db", &cache_dir),
+        ];
+        for (name, dir) in moves {
+            let (from, to) = (legacy_dir.join(name), dir.join(name));
+            if from == to || !from.exists() || to.exists() {
+                continue;
+            }
+            fs::create_dir_all(dir)?;
+            // rename no funciona entre sistemas de archivos distintos
+            if fs::rename(&from, &to).is_err() {
+                fs::copy(&from, &to)?;
+                fs::remove_file(&from)?;
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get_config_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join("config.toml"))
+    }
+
+    pub fn tokens_path() -> Result<PathBuf> {
+        Ok(Self::state_dir()?.join("tokens.json"))
+    }
+    
+    /// Olvida los tokens de la sesión actual; hay que llamar a `save` para borrarlos del disco
+    pub fn clear_tokens(&mut self) {
+        self.access_token = None;
+        self.refresh_token = None;
+        self.token_expires_at = None;
+        self.token_scope = None;
+        self.encrypted_tokens = None;
+    }
+
+    pub fn is_token_valid(&self) -> bool {
+        if let (Some(_), Some(expires_at)) = (&self.access_token, self.token_expires_at) {
+            let now = chrono::Utc::now().timestamp();
+            expires_at > now + 60 // Token válido si expira en más de 1 minuto
+        } else {
+            false
+        }
+    }
+} 
\ No newline at end of file
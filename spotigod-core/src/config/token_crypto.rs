@@ -0,0 +1,106 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD as Base64, Engine as _};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use sha2::{Digest, Sha256};
+use std::fs;
+
+use crate::tr;
+
+const NONCE_LEN: usize = 12;
+
+/// Clave derivada de `SPOTIGOD_PASSPHRASE` o, si no está definida, de la identidad de la máquina
+fn derive_key() -> Key {
+    let secret = std::env::var("SPOTIGOD_PASSPHRASE").ok().filter(|p| !p.is_empty()).unwrap_or_else(machine_secret);
+    key_from_secret(&secret)
+}
+
+fn key_from_secret(secret: &str) -> Key {
+    let digest = Sha256::new()
+        .chain_update(b"spotigod-tokens")
+        .chain_update(secret.as_bytes())
+        .finalize();
+    *Key::from_slice(&digest)
+}
+
+/// machine-id del sistema más el usuario; si no hay machine-id se usa el directorio home
+fn machine_secret() -> String {
+    let machine_id = ["/etc/machine-id", "/var/lib/dbus/machine-id"]
+        .iter()
+        .find_map(|path| fs::read_to_string(path).ok())
+        .map(|id| id.trim().to_string())
+        .or_else(|| dirs::home_dir().map(|home| home.display().to_string()))
+        .unwrap_or_default();
+    let user = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default();
+    format!("{}:{}", machine_id, user)
+}
+
+/// Cifra el texto y devuelve nonce + texto cifrado en base64
+pub fn encrypt(plaintext: &str) -> Result<String> {
+    encrypt_with(&derive_key(), plaintext)
+}
+
+pub fn decrypt(encoded: &str) -> Result<String> {
+    decrypt_with(&derive_key(), encoded)
+}
+
+fn encrypt_with(key: &Key, plaintext: &str) -> Result<String> {
+    let cipher = ChaCha20Poly1305::new(key);
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext.as_bytes())
+        .map_err(|_| anyhow!(tr!("No se pudieron cifrar los tokens")))?;
+    let mut data = nonce.to_vec();
+    data.extend(ciphertext);
+    Ok(Base64.encode(data))
+}
+
+fn decrypt_with(key: &Key, encoded: &str) -> Result<String> {
+    let data = Base64.decode(encoded)?;
+    if data.len() < NONCE_LEN {
+        return Err(anyhow!(tr!("Tokens cifrados corruptos")));
+    }
+    let (nonce, ciphertext) = data.split_at(NONCE_LEN);
+    let cipher = ChaCha20Poly1305::new(key);
+    let plaintext = cipher
+        .decrypt(Nonce::from_slice(nonce), ciphertext)
+        .map_err(|_| anyhow!(tr!("No se pudieron descifrar los tokens (¿otra máquina o frase de paso?)")))?;
+    Ok(String::from_utf8(plaintext)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let key = key_from_secret("frase de prueba");
+        let encrypted = encrypt_with(&key, "{\"access_token\":\"abc\"}").unwrap();
+        assert_ne!(encrypted, "{\"access_token\":\"abc\"}");
+        assert_eq!(decrypt_with(&key, &encrypted).unwrap(), "{\"access_token\":\"abc\"}");
+    }
+
+    #[test]
+    fn nonce_changes_every_time() {
+        let key = key_from_secret("frase de prueba");
+        assert_ne!(encrypt_with(&key, "token").unwrap(), encrypt_with(&key, "token").unwrap());
+    }
+
+    #[test]
+    fn wrong_key_fails() {
+        let encrypted = encrypt_with(&key_from_secret("una"), "token").unwrap();
+        assert!(decrypt_with(&key_from_secret("otra"), &encrypted).is_err());
+    }
+
+    #[test]
+    fn corrupt_data_fails() {
+        let key = key_from_secret("frase de prueba");
+        assert!(decrypt_with(&key, "no es base64!").is_err());
+        assert!(decrypt_with(&key, &Base64.encode([0u8; 4])).is_err());
+
+        let mut data = Base64.decode(encrypt_with(&key, "token").unwrap()).unwrap();
+        let last = data.len() - 1;
+        data[last] ^= 1;
+        assert!(decrypt_with(&key, &Base64.encode(data)).is_err());
+    }
+}
@@ -0,0 +1,672 @@
+//! Demonio (`spotigod daemon`): un único proceso mantiene la sesión con Spotify y el estado de
+//! la reproducción, y la interfaz y los subcomandos le hacen las peticiones por un socket Unix.
+//!
+//! El protocolo es una línea de JSON por petición (`{"method": "next_track"}`,
+//! `{"method": "set_volume", "volume": 50}`...) y otra por respuesta (`{"ok": ...}` o
+//! `{"error": {"kind": ..., "detail": ...}}`), así que también se puede usar desde scripts.
+
+use crate::config::Config;
+use crate::hooks::{HookTracker, Hooks};
+use crate::scrobble::{self, ScrobbleTracker, Scrobbler};
+use crate::spotify::error::Result;
+use crate::spotify::{
+    Album, AlbumSearchResult, ApiCall, AlbumTracksResponse, AudioAnalysis, Category, Device, Episode, FullArtist,
+    PlaybackContext, PlaybackOffset, PlaybackState, Playlist, PlaylistTracksResponse, PlaylistsResponse, QueueResponse,
+    Reauthorization, SavedAlbumsResponse, SavedTracksResponse, SearchResults, SearchType, SpotifyApi, SpotifyClient,
+    SpotifyError, TimeRange, Track, UserProfile,
+};
+use crate::status::PlaybackStatus;
+use crate::tr;
+use anyhow::anyhow;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::os::unix::fs::{DirBuilderExt, PermissionsExt};
+use std::path::PathBuf;
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::net::unix::{OwnedReadHalf, OwnedWriteHalf};
+use tokio::net::{UnixListener, UnixStream};
+use tokio::sync::Mutex;
+
+/// Cada cuánto consulta el demonio la reproducción mientras haya alguien conectado
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Ruta del socket: `$XDG_RUNTIME_DIR/spotigod/daemon.sock`
+pub fn socket_path() -> anyhow::Result<PathBuf> {
+    Ok(Config::runtime_dir()?.join("daemon.sock"))
+}
+
+/// Una operación de `SpotifyApi`, con sus argumentos
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum Request {
+    SaveSession,
+    Logout,
+    /// Vuelve a leer los tokens de tokens.json (tras autorizar desde un cliente)
+    ReloadSession,
+    GetCurrentPlayback,
+    Play,
+    Pause,
+    NextTrack,
+    PreviousTrack,
+    SetVolume { volume: u8 },
+    Seek { position_ms: u64 },
+    GetDevices,
+    TransferPlayback { device_id: String, play: bool },
+    Search { query: String, types: Vec<SearchType>, limit: u8, offset: u32 },
+    AddToQueue { uri: String },
+    GetQueue,
+    GetUserPlaylists { limit: u8, offset: u32 },
+    GetSavedTracks { limit: u8, offset: u32 },
+    GetSavedAlbums { limit: u8, offset: u32 },
+    GetTopTracks { time_range: TimeRange, limit: u8 },
+    GetTopArtists { time_range: TimeRange, limit: u8 },
+    GetFollowedArtists,
+    GetArtistTopTracks { artist_id: String },
+    GetArtistAlbums { artist_id: String, limit: u8 },
+    GetRelatedArtists { artist_id: String },
+    GetRecommendations { seed_tracks: Vec<String>, seed_artists: Vec<String>, limit: u8 },
+    GetAudioAnalysis { track_id: String },
+    GetNewReleases { limit: u8, offset: u32 },
+    GetFeaturedPlaylists { limit: u8 },
+    GetCategories { limit: u8 },
+    GetCategoryPlaylists { category_id: String, limit: u8 },
+    GetUserProfile,
+    CreatePlaylist { user_id: String, name: String, public: bool },
+    AddTracksToPlaylist { playlist_id: String, uris: Vec<String> },
+    GetPlaylistTracks { playlist_id: String, limit: u8, offset: u32 },
+    GetAlbumTracks { album_id: String, limit: u8, offset: u32 },
+    ReorderPlaylistTracks { playlist_id: String, range_start: usize, insert_before: usize },
+    RemovePlaylistTracks { playlist_id: String, uris: Vec<String> },
+    UpdatePlaylistDetails { playlist_id: String, name: String, description: String },
+    FollowPlaylist { playlist_id: String },
+    SaveTracks { ids: Vec<String> },
+    RemoveSavedTracks { ids: Vec<String> },
+    CheckSavedTracks { ids: Vec<String> },
+    GetSavedEpisodes,
+    SaveEpisodes { ids: Vec<String> },
+    RemoveSavedEpisodes { ids: Vec<String> },
+    UnfollowPlaylist { playlist_id: String },
+    StartPlayback { context: PlaybackContext, offset: Option<PlaybackOffset>, position_ms: Option<i64> },
+    ToggleShuffle,
+    ToggleRepeat,
+}
+
+impl Request {
+    /// Órdenes tras las que la reproducción guardada deja de valer
+    fn changes_playback(&self) -> bool {
+        matches!(
+            self,
+            Request::Logout
+                | Request::ReloadSession
+                | Request::Play
+                | Request::Pause
+                | Request::NextTrack
+                | Request::PreviousTrack
+                | Request::SetVolume { .. }
+                | Request::Seek { .. }
+                | Request::TransferPlayback { .. }
+                | Request::StartPlayback { .. }
+                | Request::ToggleShuffle
+                | Request::ToggleRepeat
+        )
+    }
+}
+
+/// Respuesta a una petición, con los avisos del cliente que la interfaz muestra
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Reply {
+    #[serde(default)]
+    pub ok: serde_json::Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<SpotifyError>,
+    /// Dispositivo elegido al no haber ninguno activo (`take_auto_selected_device`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub auto_selected_device: Option<String>,
+    /// Esperas por límite de peticiones (`take_rate_limit_waits`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rate_limit_waits: Vec<u64>,
+    /// Peticiones a Spotify que hizo el demonio para atender esta (`take_api_calls`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub api_calls: Vec<ApiCall>,
+}
+
+impl Reply {
+    fn new(result: Result<serde_json::Value>) -> Self {
+        match result {
+            Ok(ok) => Self { ok, ..Self::default() },
+            Err(error) => Self { error: Some(error), ..Self::default() },
+        }
+    }
+}
+
+/// Abre el socket del demonio. Si quedó uno de un demonio que ya no está se borra; si hay otro
+/// escuchando es un error
+pub async fn bind() -> anyhow::Result<UnixListener> {
+    let path = socket_path()?;
+    if path.exists() {
+        if UnixStream::connect(&path).await.is_ok() {
+            return Err(anyhow!(tr!("Ya hay un demonio en marcha en {}", path.display())));
+        }
+        std::fs::remove_file(&path)?;
+    }
+    // Solo el usuario puede controlar su reproducción: el socket nace dentro de un directorio
+    // privado, sin un momento en que otros puedan conectarse
+    if let Some(parent) = path.parent() {
+        std::fs::DirBuilder::new().recursive(true).mode(0o700).create(parent)?;
+        std::fs::set_permissions(parent, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(UnixListener::bind(&path)?)
+}
+
+/// Estado que comparten todas las conexiones
+struct Shared {
+    client: Mutex<SpotifyClient>,
+    /// Última reproducción consultada y cuándo
+    playback: RefCell<Option<(Option<PlaybackState>, Instant)>>,
+    connections: Cell<usize>,
+    scrobblers: Vec<Arc<dyn Scrobbler>>,
+    scrobble_tracker: RefCell<ScrobbleTracker>,
+    /// `track_liked` no se usa aquí: lo ejecuta el cliente que guarda la canción
+    hooks: Hooks,
+    hook_tracker: RefCell<HookTracker>,
+}
+
+impl Shared {
+    fn cached_playback(&self, max_age: Duration) -> Option<Option<PlaybackState>> {
+        match &*self.playback.borrow() {
+            Some((playback, at)) if at.elapsed() < max_age => Some(playback.clone()),
+            _ => None,
+        }
+    }
+
+    /// Guarda la última consulta, envía a los servicios de scrobbling lo que toque y ejecuta
+    /// los hooks de la reproducción
+    fn store_playback(&self, playback: Option<PlaybackState>) {
+        if !self.scrobblers.is_empty() {
+            for event in self.scrobble_tracker.borrow_mut().update(playback.as_ref()) {
+                for scrobbler in &self.scrobblers {
+                    let scrobbler = scrobbler.clone();
+                    let event = event.clone();
+                    tokio::task::spawn_local(async move {
+                        if let Err(e) = scrobble::send(scrobbler.as_ref(), &event).await {
+                            tracing::warn!(service = scrobbler.name(), error = %e, "no se pudo enviar la escucha");
+                            eprintln!("{}", tr!("No se pudo enviar la escucha a {}: {}", scrobbler.name(), e));
+                        }
+                    });
+                }
+            }
+        }
+        if self.hooks.watches_playback() {
+            let status = PlaybackStatus::new(playback.as_ref());
+            for event in self.hook_tracker.borrow_mut().update(&status) {
+                self.hooks.run(event, &status);
+            }
+        }
+        *self.playback.borrow_mut() = Some((playback, Instant::now()));
+    }
+
+    async fn handle(&self, request: Request) -> Reply {
+        if let Request::GetCurrentPlayback = request {
+            if let Some(playback) = self.cached_playback(POLL_INTERVAL) {
+                return Reply::new(value(Ok(playback)));
+            }
+        }
+        let changes_playback = request.changes_playback();
+        let mut client = self.client.lock().await;
+        let result = match request {
+            Request::SaveSession => value(client.save_session().await),
+            Request::Logout => value(client.logout().await),
+            Request::ReloadSession => match Config::load().await {
+                Ok(config) => {
+                    tracing::info!("sesión recargada desde tokens.json");
+                    *client = SpotifyClient::new(config);
+                    Ok(serde_json::Value::Null)
+                }
+                Err(e) => Err(SpotifyError::Storage(e.to_string())),
+            },
+            Request::GetCurrentPlayback => {
+                let result = client.get_current_playback().await;
+                if let Ok(playback) = &result {
+                    self.store_playback(playback.clone());
+                }
+                value(result)
+            }
+            Request::Play => value(client.play().await),
+            Request::Pause => value(client.pause().await),
+            Request::NextTrack => value(client.next_track().await),
+            Request::PreviousTrack => value(client.previous_track().await),
+            Request::SetVolume { volume } => value(client.set_volume(volume).await),
+            Request::Seek { position_ms } => value(client.seek(position_ms).await),
+            Request::GetDevices => value(client.get_devices().await),
+            Request::TransferPlayback { device_id, play } => value(client.transfer_playback(&device_id, play).await),
+            Request::Search { query, types, limit, offset } => value(client.search(&query, &types, limit, offset).await),
+            Request::AddToQueue { uri } => value(client.add_to_queue(&uri).await),
+            Request::GetQueue => value(client.get_queue().await),
+            Request::GetUserPlaylists { limit, offset } => value(client.get_user_playlists(limit, offset).await),
+            Request::GetSavedTracks { limit, offset } => value(client.get_saved_tracks(limit, offset).await),
+            Request::GetSavedAlbums { limit, offset } => value(client.get_saved_albums(limit, offset).await),
+            Request::GetTopTracks { time_range, limit } => value(client.get_top_tracks(time_range, limit).await),
+            Request::GetTopArtists { time_range, limit } => value(client.get_top_artists(time_range, limit).await),
+            Request::GetFollowedArtists => value(client.get_followed_artists().await),
+            Request::GetArtistTopTracks { artist_id } => value(client.get_artist_top_tracks(&artist_id).await),
+            Request::GetArtistAlbums { artist_id, limit } => value(client.get_artist_albums(&artist_id, limit).await),
+            Request::GetRelatedArtists { artist_id } => value(client.get_related_artists(&artist_id).await),
+            Request::GetRecommendations { seed_tracks, seed_artists, limit } => {
+                value(client.get_recommendations(&seed_tracks, &seed_artists, limit).await)
+            }
+            Request::GetAudioAnalysis { track_id } => value(client.get_audio_analysis(&track_id).await),
+            Request::GetNewReleases { limit, offset } => value(client.get_new_releases(limit, offset).await),
+            Request::GetFeaturedPlaylists { limit } => value(client.get_featured_playlists(limit).await),
+            Request::GetCategories { limit } => value(client.get_categories(limit).await),
+            Request::GetCategoryPlaylists { category_id, limit } => {
+                value(client.get_category_playlists(&category_id, limit).await)
+            }
+            Request::GetUserProfile => value(client.get_user_profile().await),
+            Request::CreatePlaylist { user_id, name, public } => value(client.create_playlist(&user_id, &name, public).await),
+            Request::AddTracksToPlaylist { playlist_id, uris } => {
+                value(client.add_tracks_to_playlist(&playlist_id, &uris).await)
+            }
+            Request::GetPlaylistTracks { playlist_id, limit, offset } => {
+                value(client.get_playlist_tracks(&playlist_id, limit, offset).await)
+            }
+            Request::GetAlbumTracks { album_id, limit, offset } => {
+                value(client.get_album_tracks(&album_id, limit, offset).await)
+            }
+            Request::ReorderPlaylistTracks { playlist_id, range_start, insert_before } => {
+                value(client.reorder_playlist_tracks(&playlist_id, range_start, insert_before).await)
+            }
+            Request::RemovePlaylistTracks { playlist_id, uris } => {
+                value(client.remove_playlist_tracks(&playlist_id, &uris).await)
+            }
+            Request::UpdatePlaylistDetails { playlist_id, name, description } => {
+                value(client.update_playlist_details(&playlist_id, &name, &description).await)
+            }
+            Request::FollowPlaylist { playlist_id } => value(client.follow_playlist(&playlist_id).await),
+            Request::SaveTracks { ids } => value(client.save_tracks(&ids).await),
+            Request::RemoveSavedTracks { ids } => value(client.remove_saved_tracks(&ids).await),
+            Request::CheckSavedTracks { ids } => value(client.check_saved_tracks(&ids).await),
+            Request::GetSavedEpisodes => value(client.get_saved_episodes().await),
+            Request::SaveEpisodes { ids } => value(client.save_episodes(&ids).await),
+            Request::RemoveSavedEpisodes { ids } => value(client.remove_saved_episodes(&ids).await),
+            Request::UnfollowPlaylist { playlist_id } => value(client.unfollow_playlist(&playlist_id).await),
+            Request::StartPlayback { context, offset, position_ms } => {
+                value(client.start_playback(context, offset, position_ms).await)
+            }
+            Request::ToggleShuffle => value(client.toggle_shuffle().await),
+            Request::ToggleRepeat => value(client.toggle_repeat().await),
+        };
+        if changes_playback {
+            self.playback.borrow_mut().take();
+        }
+        Reply {
+            auto_selected_device: client.take_auto_selected_device(),
+            rate_limit_waits: client.take_rate_limit_waits(),
+            api_calls: client.take_api_calls(),
+            ..Reply::new(result)
+        }
+    }
+
+    /// Atiende una conexión hasta que el cliente la cierra
+    async fn serve_connection(&self, stream: UnixStream) {
+        self.connections.set(self.connections.get() + 1);
+        tracing::debug!(connections = self.connections.get(), "cliente conectado al demonio");
+        let (read, mut write) = stream.into_split();
+        let mut lines = BufReader::new(read).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            let reply = match serde_json::from_str::<Request>(&line) {
+                Ok(request) => self.handle(request).await,
+                Err(e) => {
+                    tracing::warn!(error = %e, "petición al demonio no válida");
+                    Reply::new(Err(e.into()))
+                }
+            };
+            let mut json = serde_json::to_string(&reply).unwrap_or_default();
+            json.push('\n');
+            if write.write_all(json.as_bytes()).await.is_err() {
+                break;
+            }
+        }
+        self.connections.set(self.connections.get() - 1);
+    }
+
+    /// Mantiene fresca la reproducción mientras haya clientes conectados, para que todos la
+    /// lean del demonio en lugar de preguntar cada uno a Spotify. Con scrobbling o hooks se
+    /// consulta siempre, para no perder lo que se escucha con la interfaz cerrada
+    async fn poll(&self) {
+        let mut ticker = tokio::time::interval(POLL_INTERVAL);
+        loop {
+            ticker.tick().await;
+            let idle = self.connections.get() == 0 && self.scrobblers.is_empty() && !self.hooks.watches_playback();
+            if idle || self.cached_playback(POLL_INTERVAL / 2).is_some() {
+                continue;
+            }
+            let result = {
+                let mut client = self.client.lock().await;
+                let result = client.get_current_playback().await;
+                // Las consultas propias del demonio no son de ningún cliente
+                client.take_api_calls();
+                result
+            };
+            if let Ok(playback) = result {
+                self.store_playback(playback);
+            }
+        }
+    }
+}
+
+fn value<T: Serialize>(result: Result<T>) -> Result<serde_json::Value> {
+    Ok(serde_json::to_value(result?)?)
+}
+
+/// Atiende las conexiones del socket con el cliente ya autorizado, envía las escuchas a
+/// `scrobblers` y ejecuta los hooks de la reproducción; no termina salvo que falle el socket
+pub async fn serve(
+    client: SpotifyClient,
+    scrobblers: Vec<Arc<dyn Scrobbler>>,
+    hooks: Hooks,
+    listener: UnixListener,
+) -> anyhow::Result<()> {
+    let shared = Rc::new(Shared {
+        client: Mutex::new(client),
+        playback: RefCell::new(None),
+        connections: Cell::new(0),
+        scrobblers,
+        scrobble_tracker: RefCell::new(ScrobbleTracker::new()),
+        hooks,
+        hook_tracker: RefCell::new(HookTracker::new()),
+    });
+    // El cliente no tiene por qué poder cambiar de hilo: todo corre en un `LocalSet`
+    tokio::task::LocalSet::new()
+        .run_until(async move {
+            let poller = shared.clone();
+            tokio::task::spawn_local(async move { poller.poll().await });
+            loop {
+                let (stream, _) = listener.accept().await?;
+                let shared = shared.clone();
+                tokio::task::spawn_local(async move { shared.serve_connection(stream).await });
+            }
+        })
+        .await
+}
+
+/// `SpotifyApi` sobre el socket del demonio
+pub struct DaemonClient {
+    lines: Lines<BufReader<OwnedReadHalf>>,
+    writer: OwnedWriteHalf,
+    /// Cliente local solo para volver a autorizar: el navegador y el puerto de la redirección
+    /// están en este equipo. Los tokens nuevos se guardan en tokens.json y el demonio los relee
+    local: SpotifyClient,
+    auto_selected_device: Option<String>,
+    rate_limit_waits: Vec<u64>,
+    api_calls: Vec<ApiCall>,
+}
+
+impl DaemonClient {
+    /// Se conecta al demonio; `None` si no hay ninguno en marcha
+    pub async fn connect(config: Config) -> Option<Self> {
+        let stream = UnixStream::connect(socket_path().ok()?).await.ok()?;
+        tracing::debug!("conectado al demonio");
+        let (read, writer) = stream.into_split();
+        Some(Self {
+            lines: BufReader::new(read).lines(),
+            writer,
+            local: SpotifyClient::new(config),
+            auto_selected_device: None,
+            rate_limit_waits: Vec::new(),
+            api_calls: Vec::new(),
+        })
+    }
+
+    async fn call<T: DeserializeOwned>(&mut self, request: Request) -> Result<T> {
+        let mut json = serde_json::to_string(&request)?;
+        json.push('\n');
+        let lost = |e: std::io::Error| SpotifyError::Daemon(e.to_string());
+        self.writer.write_all(json.as_bytes()).await.map_err(lost)?;
+        let line = self
+            .lines
+            .next_line()
+            .await
+            .map_err(lost)?
+            .ok_or_else(|| SpotifyError::Daemon(tr!("el demonio cerró la conexión").to_string()))?;
+        let reply: Reply = serde_json::from_str(&line)?;
+        if reply.auto_selected_device.is_some() {
+            self.auto_selected_device = reply.auto_selected_device;
+        }
+        self.rate_limit_waits.extend(reply.rate_limit_waits);
+        self.api_calls.extend(reply.api_calls);
+        match reply.error {
+            Some(error) => Err(error),
+            None => Ok(serde_json::from_value(reply.ok)?),
+        }
+    }
+}
+
+impl SpotifyApi for DaemonClient {
+    fn begin_reauthorization(&mut self) -> Reauthorization {
+        self.local.begin_reauthorization()
+    }
+
+    async fn complete_reauthorization(&mut self, input: &str) -> Result<()> {
+        self.local.complete_reauthorization(input).await?;
+        self.call(Request::ReloadSession).await
+    }
+
+    async fn save_session(&mut self) -> Result<()> {
+        self.call(Request::SaveSession).await
+    }
+
+    async fn logout(&mut self) -> Result<()> {
+        self.call(Request::Logout).await
+    }
+
+    fn take_auto_selected_device(&mut self) -> Option<String> {
+        self.auto_selected_device.take()
+    }
+
+    fn take_rate_limit_waits(&mut self) -> Vec<u64> {
+        std::mem::take(&mut self.rate_limit_waits)
+    }
+
+    fn take_api_calls(&mut self) -> Vec<ApiCall> {
+        std::mem::take(&mut self.api_calls)
+    }
+
+    async fn get_current_playback(&mut self) -> Result<Option<PlaybackState>> {
+        self.call(Request::GetCurrentPlayback).await
+    }
+
+    async fn play(&mut self) -> Result<()> {
+        self.call(Request::Play).await
+    }
+
+    async fn pause(&mut self) -> Result<()> {
+        self.call(Request::Pause).await
+    }
+
+    async fn next_track(&mut self) -> Result<()> {
+        self.call(Request::NextTrack).await
+    }
+
+    async fn previous_track(&mut self) -> Result<()> {
+        self.call(Request::PreviousTrack).await
+    }
+
+    async fn set_volume(&mut self, volume: u8) -> Result<()> {
+        self.call(Request::SetVolume { volume }).await
+    }
+
+    async fn seek(&mut self, position_ms: u64) -> Result<()> {
+        self.call(Request::Seek { position_ms }).await
+    }
+
+    async fn get_devices(&mut self) -> Result<Vec<Device>> {
+        self.call(Request::GetDevices).await
+    }
+
+    async fn transfer_playback(&mut self, device_id: &str, play: bool) -> Result<()> {
+        self.call(Request::TransferPlayback { device_id: device_id.to_string(), play }).await
+    }
+
+    async fn search(&mut self, query: &str, types: &[SearchType], limit: u8, offset: u32) -> Result<SearchResults> {
+        self.call(Request::Search { query: query.to_string(), types: types.to_vec(), limit, offset }).await
+    }
+
+    async fn add_to_queue(&mut self, uri: &str) -> Result<()> {
+        self.call(Request::AddToQueue { uri: uri.to_string() }).await
+    }
+
+    async fn get_queue(&mut self) -> Result<QueueResponse> {
+        self.call(Request::GetQueue).await
+    }
+
+    async fn get_user_playlists(&mut self, limit: u8, offset: u32) -> Result<PlaylistsResponse> {
+        self.call(Request::GetUserPlaylists { limit, offset }).await
+    }
+
+    async fn get_saved_tracks(&mut self, limit: u8, offset: u32) -> Result<SavedTracksResponse> {
+        self.call(Request::GetSavedTracks { limit, offset }).await
+    }
+
+    async fn get_saved_albums(&mut self, limit: u8, offset: u32) -> Result<SavedAlbumsResponse> {
+        self.call(Request::GetSavedAlbums { limit, offset }).await
+    }
+
+    async fn get_top_tracks(&mut self, time_range: TimeRange, limit: u8) -> Result<Vec<Track>> {
+        self.call(Request::GetTopTracks { time_range, limit }).await
+    }
+
+    async fn get_top_artists(&mut self, time_range: TimeRange, limit: u8) -> Result<Vec<FullArtist>> {
+        self.call(Request::GetTopArtists { time_range, limit }).await
+    }
+
+    async fn get_followed_artists(&mut self) -> Result<Vec<FullArtist>> {
+        self.call(Request::GetFollowedArtists).await
+    }
+
+    async fn get_artist_top_tracks(&mut self, artist_id: &str) -> Result<Vec<Track>> {
+        self.call(Request::GetArtistTopTracks { artist_id: artist_id.to_string() }).await
+    }
+
+    async fn get_artist_albums(&mut self, artist_id: &str, limit: u8) -> Result<Vec<Album>> {
+        self.call(Request::GetArtistAlbums { artist_id: artist_id.to_string(), limit }).await
+    }
+
+    async fn get_related_artists(&mut self, artist_id: &str) -> Result<Vec<FullArtist>> {
+        self.call(Request::GetRelatedArtists { artist_id: artist_id.to_string() }).await
+    }
+
+    async fn get_recommendations(&mut self, seed_tracks: &[String], seed_artists: &[String], limit: u8) -> Result<Vec<Track>> {
+        self.call(Request::GetRecommendations {
+            seed_tracks: seed_tracks.to_vec(),
+            seed_artists: seed_artists.to_vec(),
+            limit,
+        })
+        .await
+    }
+
+    async fn get_audio_analysis(&mut self, track_id: &str) -> Result<AudioAnalysis> {
+        self.call(Request::GetAudioAnalysis { track_id: track_id.to_string() }).await
+    }
+
+    async fn get_new_releases(&mut self, limit: u8, offset: u32) -> Result<AlbumSearchResult> {
+        self.call(Request::GetNewReleases { limit, offset }).await
+    }
+
+    async fn get_featured_playlists(&mut self, limit: u8) -> Result<(Option<String>, Vec<Playlist>)> {
+        self.call(Request::GetFeaturedPlaylists { limit }).await
+    }
+
+    async fn get_categories(&mut self, limit: u8) -> Result<Vec<Category>> {
+        self.call(Request::GetCategories { limit }).await
+    }
+
+    async fn get_category_playlists(&mut self, category_id: &str, limit: u8) -> Result<Vec<Playlist>> {
+        self.call(Request::GetCategoryPlaylists { category_id: category_id.to_string(), limit }).await
+    }
+
+    async fn get_user_profile(&mut self) -> Result<UserProfile> {
+        self.call(Request::GetUserProfile).await
+    }
+
+    async fn create_playlist(&mut self, user_id: &str, name: &str, public: bool) -> Result<Playlist> {
+        self.call(Request::CreatePlaylist { user_id: user_id.to_string(), name: name.to_string(), public }).await
+    }
+
+    async fn add_tracks_to_playlist(&mut self, playlist_id: &str, uris: &[String]) -> Result<()> {
+        self.call(Request::AddTracksToPlaylist { playlist_id: playlist_id.to_string(), uris: uris.to_vec() }).await
+    }
+
+    async fn get_playlist_tracks(&mut self, playlist_id: &str, limit: u8, offset: u32) -> Result<PlaylistTracksResponse> {
+        self.call(Request::GetPlaylistTracks { playlist_id: playlist_id.to_string(), limit, offset }).await
+    }
+
+    async fn get_album_tracks(&mut self, album_id: &str, limit: u8, offset: u32) -> Result<AlbumTracksResponse> {
+        self.call(Request::GetAlbumTracks { album_id: album_id.to_string(), limit, offset }).await
+    }
+
+    async fn reorder_playlist_tracks(&mut self, playlist_id: &str, range_start: usize, insert_before: usize) -> Result<String> {
+        self.call(Request::ReorderPlaylistTracks { playlist_id: playlist_id.to_string(), range_start, insert_before })
+            .await
+    }
+
+    async fn remove_playlist_tracks(&mut self, playlist_id: &str, uris: &[String]) -> Result<()> {
+        self.call(Request::RemovePlaylistTracks { playlist_id: playlist_id.to_string(), uris: uris.to_vec() }).await
+    }
+
+    async fn update_playlist_details(&mut self, playlist_id: &str, name: &str, description: &str) -> Result<()> {
+        self.call(Request::UpdatePlaylistDetails {
+            playlist_id: playlist_id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+        })
+        .await
+    }
+
+    async fn follow_playlist(&mut self, playlist_id: &str) -> Result<()> {
+        self.call(Request::FollowPlaylist { playlist_id: playlist_id.to_string() }).await
+    }
+
+    async fn save_tracks(&mut self, ids: &[String]) -> Result<()> {
+        self.call(Request::SaveTracks { ids: ids.to_vec() }).await
+    }
+
+    async fn remove_saved_tracks(&mut self, ids: &[String]) -> Result<()> {
+        self.call(Request::RemoveSavedTracks { ids: ids.to_vec() }).await
+    }
+
+    async fn check_saved_tracks(&mut self, ids: &[String]) -> Result<Vec<bool>> {
+        self.call(Request::CheckSavedTracks { ids: ids.to_vec() }).await
+    }
+
+    async fn get_saved_episodes(&mut self) -> Result<Vec<Episode>> {
+        self.call(Request::GetSavedEpisodes).await
+    }
+
+    async fn save_episodes(&mut self, ids: &[String]) -> Result<()> {
+        self.call(Request::SaveEpisodes { ids: ids.to_vec() }).await
+    }
+
+    async fn remove_saved_episodes(&mut self, ids: &[String]) -> Result<()> {
+        self.call(Request::RemoveSavedEpisodes { ids: ids.to_vec() }).await
+    }
+
+    async fn unfollow_playlist(&mut self, playlist_id: &str) -> Result<()> {
+        self.call(Request::UnfollowPlaylist { playlist_id: playlist_id.to_string() }).await
+    }
+
+    async fn start_playback(&mut self, context: PlaybackContext, offset: Option<PlaybackOffset>, position_ms: Option<i64>) -> Result<()> {
+        self.call(Request::StartPlayback { context, offset, position_ms }).await
+    }
+
+    async fn toggle_shuffle(&mut self) -> Result<()> {
+        self.call(Request::ToggleShuffle).await
+    }
+
+    async fn toggle_repeat(&mut self) -> Result<()> {
+        self.call(Request::ToggleRepeat).await
+    }
+}
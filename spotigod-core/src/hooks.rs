@@ -0,0 +1,154 @@
+//! Órdenes del usuario que se ejecutan con los eventos de la reproducción (sección `[hooks]`).
+//!
+//! `HookTracker` decide, a partir de las consultas de la reproducción, cuándo cambia la canción
+//! o se pausa; cada orden se lanza con `sh -c` y recibe los datos de la canción en variables
+//! de entorno `SPOTIGOD_*` (las mismas que los campos de `spotigod status`).
+
+use crate::status::PlaybackStatus;
+use serde::{Deserialize, Serialize};
+use std::process::Stdio;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HookEvent {
+    /// Empieza a sonar otra canción o episodio (también el que suena al arrancar)
+    TrackChange,
+    Play,
+    Pause,
+    /// Se guarda una canción en "Tus me gusta"
+    TrackLiked,
+}
+
+impl HookEvent {
+    /// Nombre de la opción en `[hooks]` y valor de `SPOTIGOD_EVENT`
+    pub fn name(self) -> &'static str {
+        match self {
+            HookEvent::TrackChange => "track_change",
+            HookEvent::Play => "play",
+            HookEvent::Pause => "pause",
+            HookEvent::TrackLiked => "track_liked",
+        }
+    }
+}
+
+/// Orden de cada evento; los que no tienen no ejecutan nada
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_change: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub play: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pause: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub track_liked: Option<String>,
+}
+
+impl Hooks {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Hay alguna orden para los eventos que salen de consultar la reproducción
+    pub fn watches_playback(&self) -> bool {
+        self.track_change.is_some() || self.play.is_some() || self.pause.is_some()
+    }
+
+    /// Deja solo `track_liked`: con un demonio en marcha es él quien sigue la reproducción
+    pub fn without_playback(&self) -> Self {
+        Self { track_liked: self.track_liked.clone(), ..Self::default() }
+    }
+
+    fn command(&self, event: HookEvent) -> Option<&str> {
+        let command = match event {
+            HookEvent::TrackChange => &self.track_change,
+            HookEvent::Play => &self.play,
+            HookEvent::Pause => &self.pause,
+            HookEvent::TrackLiked => &self.track_liked,
+        };
+        command.as_deref().filter(|command| !command.trim().is_empty())
+    }
+
+    /// Lanza la orden del evento, si tiene, sin esperar a que termine. Los fallos solo quedan en el log
+    pub fn run(&self, event: HookEvent, status: &PlaybackStatus) {
+        let Some(command) = self.command(event) else {
+            return;
+        };
+        let mut process = shell(command);
+        process
+            .env("SPOTIGOD_EVENT", event.name())
+            .envs(status.env())
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped());
+        let child = match process.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                tracing::warn!(event = event.name(), "no se pudo ejecutar el hook: {}", e);
+                return;
+            }
+        };
+        tracing::debug!(event = event.name(), "hook ejecutado");
+        tokio::spawn(async move {
+            match child.wait_with_output().await {
+                Ok(output) if !output.status.success() => {
+                    let stderr = String::from_utf8_lossy(&output.stderr);
+                    tracing::warn!(event = event.name(), "el hook terminó con {}: {}", output.status, stderr.trim());
+                }
+                Ok(_) => {}
+                Err(e) => tracing::warn!(event = event.name(), "error esperando al hook: {}", e),
+            }
+        });
+    }
+}
+
+#[cfg(unix)]
+fn shell(command: &str) -> tokio::process::Command {
+    let mut process = tokio::process::Command::new("sh");
+    process.arg("-c").arg(command);
+    process
+}
+
+#[cfg(not(unix))]
+fn shell(command: &str) -> tokio::process::Command {
+    let mut process = tokio::process::Command::new("cmd");
+    process.arg("/C").arg(command);
+    process
+}
+
+/// Sigue la reproducción entre consultas y devuelve los eventos que han ocurrido
+#[derive(Debug, Default)]
+pub struct HookTracker {
+    /// Canción (id, o título para los archivos locales) y si sonaba en la consulta anterior
+    previous: Option<(Option<String>, bool)>,
+}
+
+impl HookTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn update(&mut self, status: &PlaybackStatus) -> Vec<HookEvent> {
+        let key = status.id.clone().or_else(|| status.title.clone());
+        let mut events = Vec::new();
+        match &self.previous {
+            None => {
+                if key.is_some() {
+                    events.push(HookEvent::TrackChange);
+                }
+            }
+            Some((previous_key, was_playing)) => {
+                if key.is_some() && key != *previous_key {
+                    events.push(HookEvent::TrackChange);
+                }
+                if status.playing && !was_playing {
+                    events.push(HookEvent::Play);
+                } else if !status.playing && *was_playing {
+                    events.push(HookEvent::Pause);
+                }
+            }
+        }
+        self.previous = Some((key, status.playing));
+        events
+    }
+}
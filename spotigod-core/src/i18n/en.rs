@@ -0,0 +1,453 @@
+/// Traducciones al inglés, indexadas por el texto original en español
+pub(super) const STRINGS: &[(&str, &str)] = &[
+    ("🎵 Bienvenido a SpotiGod - Tu cliente de Spotify en terminal 🎵", "🎵 Welcome to SpotiGod - Your Spotify client in the terminal 🎵"),
+    ("🔐 Necesitas autenticarte con Spotify...", "🔐 You need to sign in to Spotify..."),
+    ("✅ Autenticación exitosa!", "✅ Signed in!"),
+    ("👋 Sesión cerrada: se han borrado los tokens y la biblioteca guardada", "👋 Logged out: stored tokens and library have been deleted"),
+    ("tecla vacía", "empty key"),
+    ("modificador desconocido \"{}\" en \"{}\"", "unknown modifier \"{}\" in \"{}\""),
+    ("tecla desconocida \"{}\"", "unknown key \"{}\""),
+    ("keybindings.{}: la tecla {} está reservada para navegar", "keybindings.{}: key {} is reserved for navigation"),
+    ("keybindings: acción desconocida \"{}\" (válidas: {})", "keybindings: unknown action \"{}\" (valid: {})"),
+    ("keybindings: la tecla {} está asignada a {} y a {}", "keybindings: key {} is bound to both {} and {}"),
+    ("keybindings: la tecla {} de {} la usa el preset vim", "keybindings: key {} of {} is used by the vim preset"),
+    ("Pulsa {} de nuevo para cerrar sesión y borrar los datos guardados", "Press {} again to log out and delete stored data"),
+    ("Error al cerrar sesión", "Error logging out"),
+    ("No se pudo borrar la biblioteca guardada: {}", "Could not delete the stored library: {}"),
+    ("Sesión cerrada: autoriza una cuenta para continuar", "Logged out: authorize an account to continue"),
+    ("Biblioteca actualizada", "Library updated"),
+    ("⏳ Spotify limitó las peticiones: reintentado tras esperar {}s", "⏳ Spotify rate-limited requests: retried after waiting {}s"),
+    ("🔈 No había ningún dispositivo activo: se ha usado el último, «{}»", "🔈 There was no active device: used the last one, “{}”"),
+    ("No hay ningún dispositivo activo: elige uno en la lista (si no aparece, abre Spotify en él)", "No active device: pick one from the list (if it is missing, open Spotify on it)"),
+    ("Esta acción requiere una cuenta de Spotify Premium", "This action requires a Spotify Premium account"),
+    ("La sesión ha caducado: pulsa {} para volver a autorizar SpotiGod", "Your session has expired: press {} to authorize SpotiGod again"),
+    ("⏳ Spotify está limitando las peticiones, vuelve a intentarlo en {}s", "⏳ Spotify is rate-limiting requests, try again in {}s"),
+    ("Sin conexión con Spotify: mostrando la biblioteca guardada (solo lectura)", "No connection to Spotify: showing the stored library (read-only)"),
+    ("Error al actualizar reproducción", "Error updating playback"),
+    ("Error al comprobar favoritos", "Error checking liked songs"),
+    ("No hay canción reproduciéndose", "Nothing is playing"),
+    ("Eliminada de favoritos: {}", "Removed from liked songs: {}"),
+    ("Añadida a favoritos: {}", "Added to liked songs: {}"),
+    ("Semillas eliminadas", "Seeds cleared"),
+    ("Temas disponibles: {}", "Available themes: {}"),
+    ("Tema: {}", "Theme: {}"),
+    ("Orden desconocida: {}", "Unknown command: {}"),
+    ("El nombre de la playlist no puede estar vacío", "The playlist name cannot be empty"),
+    ("{}. Pega la URL de redirección a mano", "{}. Paste the redirect URL manually"),
+    ("No se pudo recibir la redirección de Spotify: {}. Pega la URL a mano", "Could not receive Spotify's redirect: {}. Paste the URL manually"),
+    ("Sesión renovada", "Session renewed"),
+    ("{}. Vuelve a autorizar", "{}. Authorize again"),
+    ("El volumen debe estar entre 0 y 100", "Volume must be between 0 and 100"),
+    ("Volumen inválido", "Invalid volume"),
+    ("No se encontraron dispositivos. Abre Spotify en algún dispositivo", "No devices found. Open Spotify on a device"),
+    ("Error al cargar dispositivos", "Error loading devices"),
+    ("El dispositivo {} no admite transferencias", "Device {} does not support transfers"),
+    ("Reproducción transferida a {}", "Playback transferred to {}"),
+    ("Pausado", "Paused"),
+    ("Reproduciendo", "Playing"),
+    ("No hay reproducción activa", "No active playback"),
+    ("Siguiente canción", "Next track"),
+    ("Canción anterior", "Previous track"),
+    ("Shuffle cambiado", "Shuffle toggled"),
+    ("Modo repetición cambiado", "Repeat mode changed"),
+    ("Volumen: {}%", "Volume: {}%"),
+    ("Encontrados: {} canciones, {} álbumes, {} artistas, {} playlists, {} podcasts", "Found: {} tracks, {} albums, {} artists, {} playlists, {} podcasts"),
+    ("Error en búsqueda", "Search error"),
+    ("{}: {} resultados", "{}: {} results"),
+    ("Reproduciendo podcast: {}", "Playing podcast: {}"),
+    ("Vista previa: {} ({} canciones)", "Preview: {} ({} tracks)"),
+    ("Error al cargar vista previa", "Error loading preview"),
+    ("Siguiendo playlist: {}", "Following playlist: {}"),
+    ("Reproduciendo playlist: {}", "Playing playlist: {}"),
+    ("Reproduciendo artista: {}", "Playing artist: {}"),
+    ("Reproduciendo álbum: {}", "Playing album: {}"),
+    ("Reproduciendo: {}", "Playing: {}"),
+    ("«{}» es un archivo local: Spotify no permite usarlo desde la API", "“{}” is a local file: Spotify does not allow using it through the API"),
+    ("Añadida a la cola: {}", "Added to queue: {}"),
+    ("Error al cargar playlists", "Error loading playlists"),
+    ("Playlists: {}/{} cargadas", "Playlists: {}/{} loaded"),
+    ("Error al guardar la biblioteca local: {}", "Error saving the local library: {}"),
+    ("Error al cargar favoritos", "Error loading liked songs"),
+    ("Favoritos: {}/{} cargadas", "Liked songs: {}/{} loaded"),
+    ("Error al cargar álbumes guardados", "Error loading saved albums"),
+    ("Álbumes: {}/{} cargados", "Albums: {}/{} loaded"),
+    ("{} canciones en cola", "{} tracks in queue"),
+    ("Error al cargar la cola", "Error loading the queue"),
+    ("Tu top: {}", "Your top: {}"),
+    ("Error al cargar tu top", "Error loading your top"),
+    ("Máximo {} semillas para Descubrir", "At most {} seeds for Discover"),
+    ("Semilla añadida: {}", "Seed added: {}"),
+    ("Añade al menos una semilla (c: canción actual, C: artistas, +: canción seleccionada)", "Add at least one seed (c: current track, C: artists, +: selected track)"),
+    ("{} recomendaciones", "{} recommendations"),
+    ("Error al cargar recomendaciones", "Error loading recommendations"),
+    ("No hay ninguna canción seleccionada", "No track selected"),
+    ("Error al iniciar la radio", "Error starting the radio"),
+    ("Error al encolar la radio", "Error queueing the radio"),
+    ("Radio de {}: {} canciones en cola", "{} radio: {} tracks queued"),
+    ("No tienes playlists propias. Crea una con 'c' en la vista de Playlists", "You have no playlists of your own. Create one with 'c' in the Playlists view"),
+    ("Añadida a {}", "Added to {}"),
+    ("{} canciones añadidas a {}", "{} tracks added to {}"),
+    ("{}: {}/{} canciones cargadas", "{}: {}/{} tracks loaded"),
+    ("Error al cargar la playlist", "Error loading the playlist"),
+    ("Reproduciendo: {} ({})", "Playing: {} ({})"),
+    ("Solo puedes reordenar tus propias playlists", "You can only reorder your own playlists"),
+    ("Pulsa x de nuevo para quitar \"{}\" de tu biblioteca", "Press x again to remove \"{}\" from your library"),
+    ("Has dejado de seguir: {}", "Unfollowed: {}"),
+    ("Solo puedes editar tus propias playlists", "You can only edit your own playlists"),
+    ("Playlist actualizada: {}", "Playlist updated: {}"),
+    ("Playlist creada: {}", "Playlist created: {}"),
+    ("Error al crear playlist", "Error creating playlist"),
+    ("No hay recomendaciones para guardar", "There are no recommendations to save"),
+    ("SpotiGod Descubrir {}", "SpotiGod Discover {}"),
+    ("Error al guardar playlist", "Error saving playlist"),
+    ("Cargados {} episodios guardados", "Loaded {} saved episodes"),
+    ("Error al cargar episodios", "Error loading episodes"),
+    ("Reproduciendo episodio: {}", "Playing episode: {}"),
+    ("Episodio guardado: {}", "Episode saved: {}"),
+    ("Episodio eliminado: {} (x para deshacer)", "Episode removed: {} (x to undo)"),
+    ("Novedades: {}/{} cargadas", "New releases: {}/{} loaded"),
+    ("Error al cargar novedades", "Error loading new releases"),
+    ("Cargadas {} categorías", "Loaded {} categories"),
+    ("Error al cargar categorías", "Error loading categories"),
+    ("Error al cargar la categoría", "Error loading the category"),
+    ("Cargadas {} playlists destacadas", "Loaded {} featured playlists"),
+    ("Error al cargar playlists destacadas", "Error loading featured playlists"),
+    ("Error al cargar el artista", "Error loading the artist"),
+    ("Error al cargar álbumes", "Error loading albums"),
+    ("Error al cargar artistas relacionados", "Error loading related artists"),
+    ("Artista: {}", "Artist: {}"),
+    ("Error al cargar el álbum", "Error loading the album"),
+    ("🎵 SpotiGod - Reproductor", "🎵 SpotiGod - Player"),
+    ("🔍 SpotiGod - Búsqueda", "🔍 SpotiGod - Search"),
+    ("🎶 SpotiGod - Favoritos", "🎶 SpotiGod - Liked Songs"),
+    ("⏭️  SpotiGod - Cola", "⏭️  SpotiGod - Queue"),
+    ("📊 SpotiGod - Tu Top", "📊 SpotiGod - Your Top"),
+    ("✨ SpotiGod - Descubrir", "✨ SpotiGod - Discover"),
+    ("🎙️  SpotiGod - Episodios guardados", "🎙️  SpotiGod - Saved Episodes"),
+    ("🧭 SpotiGod - Explorar", "🧭 SpotiGod - Browse"),
+    ("🎤 SpotiGod - Artista", "🎤 SpotiGod - Artist"),
+    ("💿 SpotiGod - Álbum", "💿 SpotiGod - Album"),
+    ("💿 SpotiGod - Álbumes guardados", "💿 SpotiGod - Saved Albums"),
+    ("🎤 SpotiGod - Letra", "🎤 SpotiGod - Lyrics"),
+    ("{} · 📴 Sin conexión (solo lectura)", "{} · 📴 Offline (read-only)"),
+    ("No se detectó reproducción activa\n\nAsegúrate de que Spotify esté abierto\ny reproduciendo música en algún dispositivo", "No active playback detected\n\nMake sure Spotify is open\nand playing music on some device"),
+    ("Controles:", "Controls:"),
+    ("{}: Play/Pause | {}: Anterior | {}: Siguiente | {}: Me gusta | {}: Ver artista | {}: Relacionados", "{}: Play/Pause | {}: Previous | {}: Next | {}: Like | {}: Show artist | {}: Related"),
+    ("{}: Shuffle | {}: Repeat | {}: Volumen | {}: Dispositivos | {}: Buscar | {}: Añadir a la cola | {}: Radio", "{}: Shuffle | {}: Repeat | {}: Volume | {}: Devices | {}: Search | {}: Add to queue | {}: Radio"),
+    ("{}: Reproductor | {}: Búsqueda | {}: Playlists | {}: Favoritos | {}: Cola | {}: Tu Top | {}: Descubrir | {}: Episodios | {}: Explorar | {}: Letra | {}: Salir", "{}: Player | {}: Search | {}: Playlists | {}: Liked | {}: Queue | {}: Your Top | {}: Discover | {}: Episodes | {}: Browse | {}: Lyrics | {}: Quit"),
+    ("Controles", "Controls"),
+    ("Visualizador", "Visualizer"),
+    ("↑/↓: Navegar | Enter: Reproducir | a: Cola | P: Añadir a playlist | m: Más | Tab: Tipo | /: Nueva búsqueda", "↑/↓: Navigate | Enter: Play | a: Queue | P: Add to playlist | m: More | Tab: Type | /: New search"),
+    ("↑/↓: Navegar | Enter: Reproducir álbum | i: Ver canciones | m: Más | Tab: Tipo | /: Nueva búsqueda", "↑/↓: Navigate | Enter: Play album | i: Show tracks | m: More | Tab: Type | /: New search"),
+    ("↑/↓: Navegar | Enter: Reproducir artista | i: Ver artista | m: Más | Tab: Tipo | /: Nueva búsqueda", "↑/↓: Navigate | Enter: Play artist | i: Show artist | m: More | Tab: Type | /: New search"),
+    ("↑/↓: Navegar | Enter: Reproducir | i: Vista previa | F: Seguir | m: Más | Tab: Tipo | /: Nueva búsqueda", "↑/↓: Navigate | Enter: Play | i: Preview | F: Follow | m: More | Tab: Type | /: New search"),
+    ("↑/↓: Navegar | Enter: Reproducir podcast | m: Más | Tab: Tipo | /: Nueva búsqueda", "↑/↓: Navigate | Enter: Play podcast | m: More | Tab: Type | /: New search"),
+    ("Presiona '/' para buscar | Tab / Alt+1..5: Cambiar pestaña", "Press '/' to search | Tab / Alt+1..5: Switch tab"),
+    ("Vista previa", "Preview"),
+    ("Resultados", "Results"),
+    ("Canciones", "Tracks"),
+    ("Álbumes", "Albums"),
+    ("Artistas", "Artists"),
+    ("{} canciones", "{} tracks"),
+    (" - {} seguidores", " - {} followers"),
+    (" ({}, {} canciones)", " ({}, {} tracks)"),
+    ("Tus Playlists ({}/{}) | Enter: Abrir | c: Crear | e: Editar | x: Dejar de seguir", "Your Playlists ({}/{}) | Enter: Open | c: Create | e: Edit | x: Unfollow"),
+    ("No se encontraron playlists", "No playlists found"),
+    ("Tus Canciones Favoritas ({}/{} cargadas)", "Your Liked Songs ({}/{} loaded)"),
+    ("No se encontraron canciones favoritas", "No liked songs found"),
+    ("Tus Álbumes ({}/{} cargados) | Enter: Ver canciones", "Your Albums ({}/{} loaded) | Enter: Show tracks"),
+    ("No se encontraron álbumes guardados", "No saved albums found"),
+    ("Sonando ahora", "Now playing"),
+    ("A continuación", "Up next"),
+    ("La cola está vacía", "The queue is empty"),
+    ("Periodo: {} | t: Cambiar periodo | Enter: Reproducir | a: Añadir a la cola", "Period: {} | t: Change period | Enter: Play | a: Add to queue"),
+    ("Sin semillas", "No seeds"),
+    ("c: Canción actual | C: Artistas actuales | +: Canción seleccionada | x: Limpiar | g: Generar | S: Guardar playlist", "c: Current track | C: Current artists | +: Selected track | x: Clear | g: Generate | S: Save playlist"),
+    ("Semillas", "Seeds"),
+    ("Recomendaciones (Enter: Reproducir | a: Añadir a la cola)", "Recommendations (Enter: Play | a: Add to queue)"),
+    ("Añade semillas y pulsa 'g' para generar recomendaciones", "Add seeds and press 'g' to generate recommendations"),
+    ("Recomendaciones", "Recommendations"),
+    ("Tus Episodios Guardados | Enter: Reanudar | x: Quitar/Guardar", "Your Saved Episodes | Enter: Resume | x: Remove/Save"),
+    ("escuchado", "played"),
+    ("No se encontraron episodios guardados", "No saved episodes found"),
+    ("{} ({}/{}) | Enter: Reproducir desde aquí | Shift+↑/↓: Mover | Esc: Volver", "{} ({}/{}) | Enter: Play from here | Shift+↑/↓: Move | Esc: Back"),
+    ("{} - {} | Enter: Reproducir desde aquí | R: Álbum completo | Esc: Volver", "{} - {} | Enter: Play from here | R: Whole album | Esc: Back"),
+    ("Canciones ({}/{}) - {}", "Tracks ({}/{}) - {}"),
+    ("Enter: Reproducir | a: Cola | P: Añadir a playlist", "Enter: Play | a: Queue | P: Add to playlist"),
+    ("Enter: Reproducir álbum | i: Ver canciones", "Enter: Play album | i: Show tracks"),
+    ("Enter: Saltar al artista", "Enter: Jump to artist"),
+    ("{} | Tab: Sección | {} | Esc: Volver", "{} | Tab: Section | {} | Esc: Back"),
+    ("Populares", "Popular"),
+    ("Relacionados", "Related"),
+    ("Enter: Reproducir | F: Seguir", "Enter: Play | F: Follow"),
+    ("Enter: Reproducir | F: Seguir | Esc: Volver", "Enter: Play | F: Follow | Esc: Back"),
+    ("Enter: Abrir categoría", "Enter: Open category"),
+    (" Novedades ", " New Releases "),
+    (" Destacadas ", " Featured "),
+    (" Categorías ", " Categories "),
+    ("  Tab: Cambiar | {}", "  Tab: Switch | {}"),
+    ("Novedades ({}/{})", "New Releases ({}/{})"),
+    ("Playlists destacadas", "Featured playlists"),
+    ("Categorías › {}", "Categories › {}"),
+    ("Categorías", "Categories"),
+    ("Últimas 4 semanas", "Last 4 weeks"),
+    ("Últimos 6 meses", "Last 6 months"),
+    ("Desde siempre", "All time"),
+    ("Estado: ", "Status: "),
+    ("Listo", "Ready"),
+    ("Actualizado: {}s", "Updated: {}s"),
+    ("Escribe para buscar...", "Type to search..."),
+    ("Buscar (Tab: abrir en {})", "Search (Tab: open in {})"),
+    ("Orden (theme <nombre>)", "Command (theme <name>)"),
+    ("Volumen (%)", "Volume (%)"),
+    ("Nombre de la playlist...", "Playlist name..."),
+    ("Pública", "Public"),
+    ("Privada", "Private"),
+    ("Visibilidad: {} (Tab: cambiar)", "Visibility: {} (Tab: change)"),
+    ("Nueva playlist (Enter: crear | Esc: cancelar)", "New playlist (Enter: create | Esc: cancel)"),
+    ("Se ha abierto el navegador; la sesión se renovará sola al autorizar.", "The browser has been opened; the session will renew itself once you authorize."),
+    ("Abre la URL en cualquier navegador y autoriza el acceso.", "Open the URL in any browser and authorize access."),
+    ("URL de redirección o código...", "Redirect URL or code..."),
+    ("O pega aquí la URL a la que te redirigió Spotify:", "Or paste here the URL Spotify redirected you to:"),
+    ("🔐 Sesión caducada (Enter: confirmar | Esc: cancelar)", "🔐 Session expired (Enter: confirm | Esc: cancel)"),
+    ("Nombre: ", "Name: "),
+    ("Descripción: ", "Description: "),
+    ("Editar playlist (Tab: cambiar campo | Enter: guardar | Esc: cancelar)", "Edit playlist (Tab: switch field | Enter: save | Esc: cancel)"),
+    ("Añadir a playlist (Enter: añadir | Esc: cancelar)", "Add to playlist (Enter: add | Esc: cancel)"),
+    (" • activo", " • active"),
+    (" • restringido", " • restricted"),
+    ("Dispositivos (Enter: transferir | Esc: cerrar)", "Devices (Enter: transfer | Esc: close)"),
+    ("theme.{}: \"{}\" no es un color", "theme.{}: \"{}\" is not a color"),
+    ("theme: color desconocido \"{}\" (válidos: accent, text, secondary, dim, title, info, detail, error, success, highlight_bg, highlight_fg, gauge)", "theme: unknown color \"{}\" (valid: accent, text, secondary, dim, title, info, detail, error, success, highlight_bg, highlight_fg, gauge)"),
+    ("tema desconocido \"{}\" (disponibles: {})", "unknown theme \"{}\" (available: {})"),
+    ("No se pudieron cifrar los tokens", "Could not encrypt the tokens"),
+    ("Tokens cifrados corruptos", "Encrypted tokens are corrupted"),
+    ("No se pudieron descifrar los tokens (¿otra máquina o frase de paso?)", "Could not decrypt the tokens (different machine or passphrase?)"),
+    ("⚠️  No se encontró SPOTIFY_CLIENT_ID en las variables de entorno", "⚠️  SPOTIFY_CLIENT_ID was not found in the environment"),
+    ("📝 Por favor, ve a https://developer.spotify.com/dashboard", "📝 Please go to https://developer.spotify.com/dashboard"),
+    ("   1. Crea una nueva app", "   1. Create a new app"),
+    ("   2. Copia el Client ID", "   2. Copy the Client ID"),
+    ("   3. Agrega http://127.0.0.1:8888/callback como Redirect URI", "   3. Add http://127.0.0.1:8888/callback as a Redirect URI"),
+    ("   4. Ejecuta: export SPOTIFY_CLIENT_ID=tu_client_id", "   4. Run: export SPOTIFY_CLIENT_ID=your_client_id"),
+    ("   (Opcional) export SPOTIFY_CLIENT_SECRET=tu_client_secret para el flujo sin PKCE", "   (Optional) export SPOTIFY_CLIENT_SECRET=your_client_secret for the non-PKCE flow"),
+    ("No se pudo determinar el directorio home", "Could not determine the home directory"),
+    ("⚠️  Continuando con la autorización manual", "⚠️  Continuing with manual authorization"),
+    ("🌐 Abriendo navegador para autenticación...", "🌐 Opening the browser to sign in..."),
+    ("📋 Si no se abre automáticamente, copia esta URL:", "📋 If it does not open automatically, copy this URL:"),
+    ("⚠️  No se pudo abrir el navegador automáticamente", "⚠️  Could not open the browser automatically"),
+    ("🔄 Esperando callback de Spotify en {}...", "🔄 Waiting for Spotify's callback on {}..."),
+    ("No hay ninguna autorización en curso", "There is no authorization in progress"),
+    ("🔗 Abre esta URL en cualquier navegador y autoriza el acceso:", "🔗 Open this URL in any browser and authorize access:"),
+    ("📋 Después pega aquí la URL a la que te redirigió Spotify (aunque la página no cargue) o solo el código:", "📋 Then paste here the URL Spotify redirected you to (even if the page does not load) or just the code:"),
+    ("No se introdujo ningún código", "No code was entered"),
+    ("El parámetro state no coincide; vuelve a iniciar la autenticación", "The state parameter does not match; start the sign-in again"),
+    ("No se encontró el código en la URL", "No code found in the URL"),
+    ("Error al obtener token: {}", "Error obtaining token: {}"),
+    ("redirect_uri no válida ({}): {}", "invalid redirect_uri ({}): {}"),
+    ("No se pudo abrir {}:{} para recibir la redirección ({}). Cambia callback_port o pon 0 para usar uno libre", "Could not open {}:{} to receive the redirect ({}). Change callback_port or set it to 0 to use a free one"),
+    ("redirect_uri no admite puerto: {}", "redirect_uri does not accept a port: {}"),
+    ("No se recibió la autorización de Spotify en {} segundos", "Spotify's authorization was not received within {} seconds"),
+    ("El servidor de redirección se cerró antes de recibir la autorización", "The redirect server closed before receiving the authorization"),
+    ("⚠️ Autorización no reconocida", "⚠️ Unrecognized authorization"),
+    ("Esta redirección no corresponde a la autorización en curso. Vuelve a la terminal e inténtalo de nuevo.", "This redirect does not match the authorization in progress. Go back to the terminal and try again."),
+    ("Has denegado el acceso a SpotiGod en Spotify", "You denied SpotiGod access on Spotify"),
+    ("Spotify rechazó la autorización: {}", "Spotify rejected the authorization: {}"),
+    ("❌ Autorización cancelada", "❌ Authorization cancelled"),
+    ("{}. Puedes cerrar esta ventana y volver a intentarlo desde la terminal.", "{}. You can close this window and try again from the terminal."),
+    ("✅ ¡Autenticación exitosa!", "✅ Signed in!"),
+    ("Puedes cerrar esta ventana y volver a la terminal.", "You can close this window and go back to the terminal."),
+    ("⚠️ Falta el código", "⚠️ Missing code"),
+    ("La redirección no incluye el código de autorización.", "The redirect does not include the authorization code."),
+    ("No autorizado: la sesión ha caducado", "Unauthorized: the session has expired"),
+    ("No hay ningún dispositivo activo", "No active device"),
+    ("Límite de peticiones alcanzado (reintentar en {}s)", "Rate limit reached (retry in {}s)"),
+    ("Se requiere Spotify Premium", "Spotify Premium is required"),
+    ("Acceso denegado: {}", "Access denied: {}"),
+    ("Error de red: {}", "Network error: {}"),
+    ("Respuesta inesperada de Spotify: {}", "Unexpected response from Spotify: {}"),
+    ("Error al guardar la configuración: {}", "Error saving the configuration: {}"),
+    ("Error de autenticación: {}", "Authentication error: {}"),
+    ("Error de la API ({}): {}", "API error ({}): {}"),
+    ("Reproductor", "Player"),
+    ("Búsqueda", "Search"),
+    ("Favoritos", "Liked"),
+    ("Cola", "Queue"),
+    ("Descubrir", "Discover"),
+    ("Episodios", "Episodes"),
+    ("Explorar", "Browse"),
+    ("Letra", "Lyrics"),
+    ("Letra: {}", "Lyrics: {}"),
+    ("Letras desactivadas (lyrics_provider: \"none\")", "Lyrics disabled (lyrics_provider: \"none\")"),
+    ("Cargando letra...", "Loading lyrics..."),
+    ("No se ha encontrado la letra de esta canción", "No lyrics found for this track"),
+    ("No se pudo descargar la letra: {}", "Could not download the lyrics: {}"),
+    ("lrclib respondió {}", "lrclib returned {}"),
+    ("Reproducir", "Play"),
+    ("Añadir a la cola", "Add to queue"),
+    ("Añadir a playlist", "Add to playlist"),
+    ("Quitar de favoritos", "Remove from Liked Songs"),
+    ("Me gusta", "Like"),
+    ("Ir al artista", "Go to artist"),
+    ("Ir al álbum", "Go to album"),
+    ("Copiar enlace", "Copy link"),
+    ("{} (Enter: elegir | Esc: cerrar)", "{} (Enter: choose | Esc: close)"),
+    ("«{}» no tiene enlace de Spotify", "“{}” has no Spotify link"),
+    ("Enlace copiado: {}", "Link copied: {}"),
+    ("No se pudo copiar al portapapeles: {}", "Could not copy to the clipboard: {}"),
+    ("{} canciones marcadas", "{} marked tracks"),
+    ("{} canciones marcadas ({}: acciones)", "{} marked tracks ({}: actions)"),
+    ("Añadir todas a la cola", "Add all to queue"),
+    ("Añadir todas a una playlist", "Add all to a playlist"),
+    ("Me gusta a todas", "Like all"),
+    ("Quitar todas de la playlist", "Remove all from the playlist"),
+    ("Desmarcar todas", "Unmark all"),
+    ("Error tras añadir {} canciones a la cola", "Error after adding {} tracks to the queue"),
+    ("{} canciones añadidas a la cola", "{} tracks added to the queue"),
+    ("{} canciones añadidas a favoritos", "{} tracks added to Liked Songs"),
+    ("{} canciones quitadas de {}", "{} tracks removed from {}"),
+    ("relevancia", "relevance"),
+    ("añadidas recientemente", "recently added"),
+    ("orden de la playlist", "playlist order"),
+    ("título", "title"),
+    ("artista", "artist"),
+    ("álbum", "album"),
+    ("duración", "duration"),
+    ("popularidad", "popularity"),
+    ("Orden: {} ({}: cambiar)", "Sort: {} ({}: change)"),
+    ("Quita el filtro y vuelve al orden de la playlist para mover canciones", "Clear the filter and switch back to the playlist order to move tracks"),
+    ("Filtro: {}", "Filter: {}"),
+    ("Filtrar (Enter: aplicar | Esc: quitar)", "Filter (Enter: apply | Esc: clear)"),
+    ("El dispositivo no informa de su volumen", "The device does not report its volume"),
+    ("El volumen ya está a 0", "The volume is already at 0"),
+    ("🔇 Silenciado ({}%)", "🔇 Muted ({}%)"),
+    ("Sigues a {} artistas", "Following {} artists"),
+    ("Error al cargar los artistas que sigues", "Error loading followed artists"),
+    ("👤 SpotiGod - Artistas que sigues", "👤 SpotiGod - Followed artists"),
+    ("No sigues a ningún artista", "You don't follow any artists"),
+    ("Artistas que sigues ({}) | Enter: Ver artista", "Followed artists ({}) | Enter: View artist"),
+    ("Free (el control de la reproducción requiere Premium)", "Free (playback control requires Premium)"),
+    ("No hay nada seleccionado ni sonando", "Nothing is selected or playing"),
+    ("Abrir en Spotify", "Open in Spotify"),
+    ("Abriendo: {}", "Opening: {}"),
+    ("No se pudo abrir {}: {}", "Could not open {}: {}"),
+    ("Cargando...", "Loading..."),
+    ("Título", "Title"),
+    ("Artista", "Artist"),
+    ("Álbum", "Album"),
+    ("Tiempo", "Time"),
+    ("(no disponible)", "(unavailable)"),
+    ("(archivo local)", "(local file)"),
+    ("No hay nada sonando", "Nothing is playing"),
+    ("La canción que suena no está en esta lista", "The playing track is not in this list"),
+    ("Ir a (Enter: abrir | Tab: reproducir | Esc: cerrar)", "Go to (Enter: open | Tab: play | Esc: close)"),
+    ("Primeros {} resultados", "First {} results"),
+    ("{} resultados", "{} results"),
+    ("Registro", "Log"),
+    ("📜 SpotiGod - Registro", "📜 SpotiGod - Log"),
+    ("Todavía no hay nada en el registro", "Nothing in the log yet"),
+    ("aviso ", "info  "),
+    ("acción", "action"),
+    ("Registro ({}/{}) | c: Vaciar", "Log ({}/{}) | c: Clear"),
+    ("{} canciones en tu cola propia", "{} tracks in your own queue"),
+    ("Quita el filtro y vuelve al orden de la cola para cambiarla", "Clear the filter and go back to the queue order to change it"),
+    ("Quitada de la cola: {}", "Removed from queue: {}"),
+    ("Se está reproduciendo otra cosa: vuelve la cola de Spotify", "Something else is playing: back to Spotify's queue"),
+    ("Error al reanudar la cola propia", "Error resuming your own queue"),
+    ("A continuación (cola propia) | Shift+↑/↓: Mover | Supr: Quitar", "Up next (own queue) | Shift+↑/↓: Move | Del: Remove"),
+    ("A continuación | Shift+↑/↓: Mover | Supr: Quitar", "Up next | Shift+↑/↓: Move | Del: Remove"),
+    ("Saltando a {}", "Seeking to {}"),
+    ("No hay sesión: abre spotigod sin argumentos para autorizarlo", "Not logged in: run spotigod without arguments to authorize it"),
+    ("Error de conexión con el demonio: {}", "Connection error with the daemon: {}"),
+    ("Ya hay un demonio en marcha en {}", "A daemon is already running at {}"),
+    ("el demonio cerró la conexión", "the daemon closed the connection"),
+    ("🎧 Demonio escuchando en {}", "🎧 Daemon listening on {}"),
+    ("ListenBrainz respondió {} {}", "ListenBrainz replied {} {}"),
+    ("Escucha enviada: {}", "Listen submitted: {}"),
+    ("No se pudo enviar la escucha a {}: {}", "Could not submit the listen to {}: {}"),
+    ("Error en {}: {}", "Error in {}: {}"),
+    ("📝 config.json se ha convertido en config.toml; el original queda en {}", "📝 config.json has been converted to config.toml; the original is kept at {}"),
+    ("SPOTIGOD_LOG no válido \"{}\": {}", "Invalid SPOTIGOD_LOG \"{}\": {}"),
+    ("Nivel de log no válido: \"{}\"", "Invalid log level: \"{}\""),
+    ("No se pudo abrir el log en {}: {}", "Could not open the log in {}: {}"),
+    ("red", "net"),
+    ("Peticiones a la API ({}/{}) | {}: cerrar", "API requests ({}/{}) | {}: close"),
+    ("🩺 Comprobando SpotiGod...", "🩺 Checking SpotiGod..."),
+    ("✅ Todo en orden", "✅ Everything is fine"),
+    ("Sin problemas; {} avisos", "No problems; {} warnings"),
+    ("{} comprobaciones fallaron", "{} checks failed"),
+    ("Configuración", "Configuration"),
+    ("no existe {}", "{} does not exist"),
+    ("Exporta SPOTIFY_CLIENT_ID con el Client ID de tu aplicación y abre spotigod para crearlo", "Export SPOTIFY_CLIENT_ID with your app's Client ID and open spotigod to create it"),
+    ("Corrige config.toml; el mensaje indica la línea del error", "Fix config.toml; the message points to the line with the error"),
+    ("client_id está vacío", "client_id is empty"),
+    ("Copia el Client ID de https://developer.spotify.com/dashboard en [auth]", "Copy the Client ID from https://developer.spotify.com/dashboard into [auth]"),
+    ("Revisa la sección [keybindings]", "Check the [keybindings] section"),
+    ("Revisa la sección [theme]", "Check the [theme] section"),
+    ("Pon language = \"es\" o \"en\" en [ui], o quítalo", "Set language = \"es\" or \"en\" in [ui], or remove it"),
+    ("nivel de log no válido: \"{}\"", "invalid log level: \"{}\""),
+    ("Pon level = \"off\", \"error\", \"warn\", \"info\", \"debug\" o \"trace\" en [log]", "Set level = \"off\", \"error\", \"warn\", \"info\", \"debug\" or \"trace\" in [log]"),
+    ("Red", "Network"),
+    ("{} responde en {} ms", "{} answers in {} ms"),
+    ("no se pudo conectar con {}: {}", "could not connect to {}: {}"),
+    ("Comprueba la conexión, el cortafuegos y el proxy (HTTPS_PROXY)", "Check your connection, firewall and proxy (HTTPS_PROXY)"),
+    ("Sesión", "Session"),
+    ("Ejecuta spotigod logout --login para volver a autorizar", "Run spotigod logout --login to authorize again"),
+    ("no hay sesión guardada", "there is no saved session"),
+    ("Abre spotigod sin argumentos para autorizarlo", "Open spotigod without arguments to authorize it"),
+    ("token válido hasta las {}", "token valid until {}"),
+    ("el token caducó; se renovará con el refresh token", "the token expired; it will be renewed with the refresh token"),
+    ("el token caducó y no hay refresh token", "the token expired and there is no refresh token"),
+    ("hay un demonio en marcha; las peticiones pasan por él", "a daemon is running; requests go through it"),
+    ("Permisos", "Scopes"),
+    ("la sesión es anterior a esta versión y no se sabe qué permisos concedió", "the session predates this version and its granted scopes are unknown"),
+    ("concedidos los {} que usa SpotiGod", "all {} used by SpotiGod are granted"),
+    ("faltan {}", "missing {}"),
+    ("Cuenta", "Account"),
+    ("Spotify solo permite controlar la reproducción con Premium", "Spotify only allows controlling playback with Premium"),
+    ("Con esta cuenta puedes navegar, pero play, pausa, volumen y cola fallarán con 403", "With this account you can browse, but play, pause, volume and queue will fail with 403"),
+    ("Spotify rechazó la sesión", "Spotify rejected the session"),
+    ("Vuelve a probar; si sigue fallando, mira el log con [log] level = \"debug\"", "Try again; if it keeps failing, check the log with [log] level = \"debug\""),
+    ("Dispositivos", "Devices"),
+    ("no hay ningún dispositivo disponible", "no device is available"),
+    ("Abre Spotify en el ordenador, el móvil o un altavoz (o usa spotifyd/librespot)", "Open Spotify on your computer, phone or a speaker (or use spotifyd/librespot)"),
+    ("activo «{}» (disponibles: {})", "active “{}” (available: {})"),
+    ("ninguno activo (disponibles: {})", "none active (available: {})"),
+    ("Pon algo a sonar en uno o elígelo con d en la interfaz", "Play something on one or pick it with d in the interface"),
+    ("Redirección", "Redirect"),
+    ("Spotify ya no acepta localhost en las Redirect URI", "Spotify no longer accepts localhost in Redirect URIs"),
+    ("Usa http://127.0.0.1:8888/callback en redirect_uri y en el dashboard de tu aplicación", "Use http://127.0.0.1:8888/callback in redirect_uri and in your app's dashboard"),
+    ("modo manual: se pega la URL de redirección, no se abre ningún puerto", "manual mode: the redirect URL is pasted, no port is opened"),
+    ("{} se puede abrir; debe coincidir con la Redirect URI del dashboard", "{} can be opened; it must match the Redirect URI in the dashboard"),
+    ("Si no puedes abrir ningún puerto (contenedor, WSL), pon manual = true en [auth]", "If you cannot open any port (container, WSL), set manual = true in [auth]"),
+    ("la salida no es una terminal; lo siguiente puede no corresponder a la que uses", "the output is not a terminal; the following may not match the one you use"),
+    ("TERM=\"{}\" no admite la interfaz", "TERM=\"{}\" does not support the interface"),
+    ("Exporta TERM, por ejemplo TERM=xterm-256color", "Export TERM, for example TERM=xterm-256color"),
+    ("{}x{} es pequeña: se ocultan columnas y paneles", "{}x{} is small: columns and panels are hidden"),
+    ("Agranda la ventana al menos a {}x{}", "Enlarge the window to at least {}x{}"),
+    ("color de 24 bits", "24-bit color"),
+    ("En tmux añade set -as terminal-features ',*:RGB' y exporta COLORTERM=truecolor, o usa el tema default", "In tmux add set -as terminal-features ',*:RGB' and export COLORTERM=truecolor, or use the default theme"),
+    ("Usa una terminal con color de 24 bits y exporta COLORTERM=truecolor, o usa el tema default", "Use a terminal with 24-bit color and export COLORTERM=truecolor, or use the default theme"),
+    ("el tema usa colores #rrggbb y la terminal no anuncia color de 24 bits (COLORTERM)", "the theme uses #rrggbb colors and the terminal does not announce 24-bit color (COLORTERM)"),
+    ("sin color de 24 bits (COLORTERM); los temas con #rrggbb se verán aproximados", "no 24-bit color (COLORTERM); themes with #rrggbb will be approximated"),
+    ("el idioma del sistema no es UTF-8: los emoji pueden verse mal", "the system locale is not UTF-8: emoji may look wrong"),
+    ("Exporta LANG con UTF-8 (p. ej. es_ES.UTF-8) o pon ascii_symbols = true en [ui]", "Export LANG with UTF-8 (e.g. en_US.UTF-8) or set ascii_symbols = true in [ui]"),
+    ("imágenes con el protocolo {}", "images with the {} protocol"),
+    ("no se detecta ningún protocolo de imágenes (kitty, iTerm2 o sixel)", "no image protocol detected (kitty, iTerm2 or sixel)"),
+    ("la tecla {} está reservada para navegar", "the {} key is reserved for navigation"),
+    ("la tecla {} ya está asignada a {}", "the {} key is already bound to {}"),
+    ("la tecla {} la usa el preset vim", "the {} key is used by the vim preset"),
+    ("la tecla {} ya está asignada a otro script", "the {} key is already bound by another script"),
+    ("No se pudo leer el script {}: {}", "Could not read the script {}: {}"),
+    ("Error en el script {}: {}", "Error in the script {}: {}"),
+    ("Error en el script: {}", "Script error: {}"),
+    ("Añadida a la cola", "Added to the queue"),
+    ("No hay ninguna playlist «{}» en tu biblioteca", "There is no playlist “{}” in your library"),
+    ("Corrige el script o quítalo de scripts/ en el directorio del config", "Fix the script or remove it from scripts/ in the config directory"),
+    ("Pulsa una cifra del 0 al 9 para saltar a esa parte de la canción", "Press a digit from 0 to 9 to jump to that part of the track"),
+    ("El script ha apuntado más de {} órdenes", "The script queued more than {} commands"),
+    ("Todavía se están ejecutando las órdenes de otro script", "Another script's commands are still running"),
+    ("El script ha tardado más de {} s y se ha detenido", "The script ran for more than {} s and was stopped"),
+    ("Comprobando la autorización...", "Checking the authorization..."),
+    ("Todavía no se ha cargado tu perfil de Spotify: vuelve a intentarlo en un momento", "Your Spotify profile hasn't loaded yet: try again in a moment"),
+    ("Error al mover la canción", "Error moving the track"),
+];
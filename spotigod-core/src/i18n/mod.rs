@@ -0,0 +1,152 @@
+//! Traducción de los textos de la interfaz y de la línea de órdenes.
+//!
+//! Los textos se escriben en español en el código y ese mismo texto sirve de clave en la
+//! tabla de cada idioma (como en gettext): si falta una traducción se muestra el original.
+
+use anyhow::{anyhow, Result};
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::OnceLock;
+
+mod en;
+
+/// Idiomas disponibles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Es,
+    En,
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::Es as u8);
+
+impl Locale {
+    /// Idioma del config (`"es"`, `"en"`) o, si no se indica, el de `LC_ALL`, `LC_MESSAGES` o `LANG`.
+    /// Sin variables (o con `C`/`POSIX`) se usa el español; con otro idioma no disponible, el inglés
+    pub fn detect(configured: Option<&str>) -> Result<Self> {
+        if let Some(tag) = configured {
+            return Self::parse(tag).ok_or_else(|| anyhow!("language: idioma desconocido \"{}\" (disponibles: es, en)", tag));
+        }
+        let env = ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty());
+        Ok(match env.as_deref() {
+            None | Some("C") | Some("POSIX") => Locale::Es,
+            Some(tag) if tag.starts_with("C.") => Locale::Es,
+            Some(tag) => Self::parse(tag).unwrap_or(Locale::En),
+        })
+    }
+
+    /// Interpreta "es", "en_US.UTF-8", "es-MX"...
+    fn parse(tag: &str) -> Option<Self> {
+        let language = tag.split(['_', '-', '.', '@']).next().unwrap_or_default().to_lowercase();
+        match language.as_str() {
+            "es" => Some(Locale::Es),
+            "en" => Some(Locale::En),
+            _ => None,
+        }
+    }
+}
+
+/// Fija el idioma de todos los textos a partir de ahora
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    if LOCALE.load(Ordering::Relaxed) == Locale::En as u8 {
+        Locale::En
+    } else {
+        Locale::Es
+    }
+}
+
+/// Texto en el idioma actual; `text` es el original en español
+pub fn tr(text: &'static str) -> &'static str {
+    match locale() {
+        Locale::Es => text,
+        Locale::En => {
+            static TABLE: OnceLock<HashMap<&'static str, &'static str>> = OnceLock::new();
+            TABLE.get_or_init(|| en::STRINGS.iter().copied().collect()).get(text).copied().unwrap_or(text)
+        }
+    }
+}
+
+/// Sustituye en `template` cada `{}` por el siguiente argumento y cada `{n}` por el argumento n,
+/// para que las traducciones puedan cambiar el orden; `{{` y `}}` escriben las llaves
+pub fn fill(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let mut index = String::new();
+                for c in chars.by_ref() {
+                    if c == '}' {
+                        break;
+                    }
+                    index.push(c);
+                }
+                let arg = if index.is_empty() {
+                    next += 1;
+                    args.get(next - 1)
+                } else {
+                    index.parse::<usize>().ok().and_then(|i| args.get(i))
+                };
+                if let Some(arg) = arg {
+                    out.push_str(&arg.to_string());
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Traduce un texto y, si lleva argumentos, los coloca en sus `{}`:
+/// `tr!("Reproduciendo: {}", track.name)`
+#[macro_export]
+macro_rules! tr {
+    ($text:literal) => {
+        $crate::i18n::tr($text)
+    };
+    ($text:literal, $($arg:expr),+ $(,)?) => {
+        $crate::i18n::fill($crate::i18n::tr($text), &[$(&$arg as &dyn ::std::fmt::Display),+])
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fills_in_order() {
+        assert_eq!(fill("{} de {}", &[&3, &"diez"]), "3 de diez");
+    }
+
+    #[test]
+    fn fills_by_position() {
+        assert_eq!(fill("{1}, {0} y {1}", &[&"a", &"b"]), "b, a y b");
+    }
+
+    #[test]
+    fn escaped_braces() {
+        assert_eq!(fill("{{}} {} }}{{", &[&1]), "{} 1 }{");
+    }
+
+    #[test]
+    fn missing_args_are_left_empty() {
+        assert_eq!(fill("[{}] [{}] [{5}] [{x}]", &[&"a"]), "[a] [] [] []");
+        assert_eq!(fill("sin argumentos", &[]), "sin argumentos");
+    }
+}
@@ -0,0 +1,16 @@
+//! Núcleo de SpotiGod: cliente de la API de Spotify, modelos y configuración.
+//!
+//! La interfaz de terminal (`spotigod`) se construye sobre esta biblioteca; otros
+//! scripts o frontends pueden usarla directamente.
+
+pub mod config;
+pub mod hooks;
+#[cfg(unix)]
+pub mod daemon;
+pub mod i18n;
+pub mod library;
+pub mod lyrics;
+pub mod scrobble;
+pub mod spotify;
+pub mod status;
+pub mod ui_state;
@@ -0,0 +1,117 @@
+use crate::config::Config;
+use crate::spotify::{Album, Playlist, Track};
+use anyhow::Result;
+use rusqlite::{params, Connection};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+
+/// Copia local de la biblioteca (playlists, favoritos y álbumes guardados) en SQLite,
+/// para arrancar con datos al instante y poder consultarla sin conexión
+pub struct LibraryStore {
+    conn: Connection,
+}
+
+/// Elementos guardados de una colección junto con el total que indicó Spotify
+pub struct StoredCollection<T> {
+    pub items: Vec<T>,
+    pub total: usize,
+}
+
+const PLAYLISTS: &str = "playlists";
+const SAVED_TRACKS: &str = "saved_tracks";
+const SAVED_ALBUMS: &str = "saved_albums";
+
+impl LibraryStore {
+    /// Abre (o crea) la base de datos en `~/.cache/spotigod/library.db`
+    pub fn open_default() -> Result<Self> {
+        Self::open(&Config::cache_dir()?.join("library.db"))
+    }
+
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let conn = Connection::open(path)?;
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS collections (
+                name TEXT PRIMARY KEY,
+                total INTEGER NOT NULL,
+                updated_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS items (
+                collection TEXT NOT NULL,
+                position INTEGER NOT NULL,
+                data TEXT NOT NULL,
+                PRIMARY KEY (collection, position)
+            );",
+        )?;
+        Ok(Self { conn })
+    }
+
+    pub fn playlists(&self) -> Result<StoredCollection<Playlist>> {
+        self.load(PLAYLISTS)
+    }
+
+    pub fn save_playlists(&mut self, playlists: &[Playlist], total: usize) -> Result<()> {
+        self.save(PLAYLISTS, playlists, total)
+    }
+
+    pub fn saved_tracks(&self) -> Result<StoredCollection<Track>> {
+        self.load(SAVED_TRACKS)
+    }
+
+    pub fn save_saved_tracks(&mut self, tracks: &[Track], total: usize) -> Result<()> {
+        self.save(SAVED_TRACKS, tracks, total)
+    }
+
+    pub fn saved_albums(&self) -> Result<StoredCollection<Album>> {
+        self.load(SAVED_ALBUMS)
+    }
+
+    pub fn save_saved_albums(&mut self, albums: &[Album], total: usize) -> Result<()> {
+        self.save(SAVED_ALBUMS, albums, total)
+    }
+
+    /// Borra toda la biblioteca guardada (al cerrar sesión o cambiar de cuenta)
+    pub fn clear(&mut self) -> Result<()> {
+        self.conn.execute_batch("DELETE FROM items; DELETE FROM collections;")?;
+        Ok(())
+    }
+
+    fn load<T: DeserializeOwned>(&self, collection: &str) -> Result<StoredCollection<T>> {
+        let total: usize = self
+            .conn
+            .query_row("SELECT total FROM collections WHERE name = ?1", params![collection], |row| row.get(0))
+            .unwrap_or(0);
+        let mut statement = self
+            .conn
+            .prepare("SELECT data FROM items WHERE collection = ?1 ORDER BY position")?;
+        let rows = statement.query_map(params![collection], |row| row.get::<_, String>(0))?;
+        let mut items = Vec::new();
+        for data in rows {
+            items.push(serde_json::from_str(&data?)?);
+        }
+        Ok(StoredCollection { items, total })
+    }
+
+    /// Sustituye el contenido guardado de la colección por `items`
+    fn save<T: Serialize>(&mut self, collection: &str, items: &[T], total: usize) -> Result<()> {
+        let transaction = self.conn.transaction()?;
+        transaction.execute("DELETE FROM items WHERE collection = ?1", params![collection])?;
+        {
+            let mut insert = transaction
+                .prepare("INSERT INTO items (collection, position, data) VALUES (?1, ?2, ?3)")?;
+            for (position, item) in items.iter().enumerate() {
+                insert.execute(params![collection, position as i64, serde_json::to_string(item)?])?;
+            }
+        }
+        transaction.execute(
+            "INSERT OR REPLACE INTO collections (name, total, updated_at) VALUES (?1, ?2, ?3)",
+            params![collection, total as i64, chrono::Utc::now().timestamp()],
+        )?;
+        transaction.commit()?;
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use super::{parse_lrc, Lyrics, LyricsProvider, LyricsQuery};
+use anyhow::{anyhow, Result};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::tr;
+
+const API_URL: &str = "https://lrclib.net/api/get";
+
+/// Letras de lrclib.net, una base de datos abierta que no necesita cuenta
+pub struct Lrclib {
+    http: reqwest::Client,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LrclibTrack {
+    #[serde(default)]
+    instrumental: bool,
+    plain_lyrics: Option<String>,
+    synced_lyrics: Option<String>,
+}
+
+impl Lrclib {
+    pub fn new() -> Self {
+        // lrclib pide identificar al cliente en el User-Agent
+        let http = reqwest::Client::builder()
+            .user_agent(concat!("spotigod/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { http }
+    }
+
+    async fn get(&self, query: &LyricsQuery) -> Result<Option<Lyrics>> {
+        let duration = (query.duration_ms / 1000).to_string();
+        let response = self
+            .http
+            .get(API_URL)
+            .query(&[
+                ("track_name", query.track.as_str()),
+                ("artist_name", query.artist.as_str()),
+                ("album_name", query.album.as_str()),
+                ("duration", duration.as_str()),
+            ])
+            .send()
+            .await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !response.status().is_success() {
+            return Err(anyhow!(tr!("lrclib respondió {}", response.status())));
+        }
+
+        let track: LrclibTrack = response.json().await?;
+        if track.instrumental {
+            return Ok(Some(Lyrics::Instrumental));
+        }
+        if let Some(synced) = track.synced_lyrics.as_deref().map(parse_lrc).filter(|lines| !lines.is_empty()) {
+            return Ok(Some(Lyrics::Synced(synced)));
+        }
+        Ok(track
+            .plain_lyrics
+            .filter(|text| !text.trim().is_empty())
+            .map(|text| Lyrics::Plain(text.lines().map(str::to_string).collect())))
+    }
+}
+
+impl Default for Lrclib {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LyricsProvider for Lrclib {
+    fn fetch<'a>(&'a self, query: &'a LyricsQuery) -> Pin<Box<dyn Future<Output = Result<Option<Lyrics>>> + Send + 'a>> {
+        Box::pin(self.get(query))
+    }
+}
@@ -0,0 +1,98 @@
+//! Letras de canciones obtenidas de servicios externos.
+//!
+//! Cada servicio implementa `LyricsProvider`; el config elige cuál se usa con `lyrics_provider`.
+
+mod lrclib;
+
+pub use lrclib::Lrclib;
+
+use crate::config::LyricsSource;
+use anyhow::Result;
+use std::future::Future;
+use std::pin::Pin;
+
+/// Canción de la que se busca la letra
+#[derive(Debug, Clone)]
+pub struct LyricsQuery {
+    pub track: String,
+    pub artist: String,
+    pub album: String,
+    pub duration_ms: u64,
+}
+
+/// Línea de una letra sincronizada y el instante en que empieza
+#[derive(Debug, Clone)]
+pub struct SyncedLine {
+    pub time_ms: u64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub enum Lyrics {
+    /// Con el instante de cada línea, para seguir la canción
+    Synced(Vec<SyncedLine>),
+    /// Texto sin tiempos
+    Plain(Vec<String>),
+    Instrumental,
+}
+
+impl Lyrics {
+    /// Línea que suena en `progress_ms`; solo en letras sincronizadas y una vez empezada la primera
+    pub fn current_line(&self, progress_ms: u64) -> Option<usize> {
+        match self {
+            Lyrics::Synced(lines) => lines.iter().rposition(|line| line.time_ms <= progress_ms),
+            _ => None,
+        }
+    }
+}
+
+/// Servicio de letras. `fetch` devuelve `Ok(None)` si no tiene la letra de la canción
+pub trait LyricsProvider: Send + Sync {
+    fn fetch<'a>(&'a self, query: &'a LyricsQuery) -> Pin<Box<dyn Future<Output = Result<Option<Lyrics>>> + Send + 'a>>;
+}
+
+impl LyricsSource {
+    /// Proveedor configurado, o ninguno si las letras están desactivadas
+    pub fn provider(self) -> Option<Box<dyn LyricsProvider>> {
+        match self {
+            LyricsSource::Lrclib => Some(Box::new(Lrclib::new())),
+            LyricsSource::None => None,
+        }
+    }
+}
+
+/// Interpreta una letra en formato LRC ("[01:23.45] texto"); una línea puede llevar varias marcas
+/// de tiempo y las etiquetas de metadatos ("[ar: ...]") se descartan
+pub fn parse_lrc(text: &str) -> Vec<SyncedLine> {
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let mut rest = raw.trim();
+        let mut times = Vec::new();
+        while let Some(tag) = rest.strip_prefix('[') {
+            let Some(end) = tag.find(']') else {
+                break;
+            };
+            match parse_timestamp(&tag[..end]) {
+                Some(time_ms) => times.push(time_ms),
+                None => break,
+            }
+            rest = &tag[end + 1..];
+        }
+        for time_ms in times {
+            lines.push(SyncedLine { time_ms, text: rest.trim().to_string() });
+        }
+    }
+    lines.sort_by_key(|line| line.time_ms);
+    lines
+}
+
+/// "mm:ss.xx" (o "mm:ss") en milisegundos
+fn parse_timestamp(tag: &str) -> Option<u64> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u64 = minutes.trim().parse().ok()?;
+    let seconds: f64 = seconds.trim().parse().ok()?;
+    if !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    Some(minutes * 60_000 + (seconds * 1000.0).round() as u64)
+}
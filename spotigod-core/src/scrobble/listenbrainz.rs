@@ -0,0 +1,89 @@
+use super::{Listen, Scrobbler};
+use anyhow::{anyhow, Result};
+use serde_json::json;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::tr;
+
+const API_URL: &str = "https://api.listenbrainz.org";
+
+/// Escuchas en ListenBrainz (o en un servidor compatible) con el token de usuario de
+/// https://listenbrainz.org/settings/
+pub struct ListenBrainz {
+    http: reqwest::Client,
+    token: String,
+    url: String,
+}
+
+impl ListenBrainz {
+    /// `url` permite usar otro servidor con la misma API; sin ella se usa listenbrainz.org
+    pub fn new(token: &str, url: Option<&str>) -> Self {
+        let http = reqwest::Client::builder()
+            .user_agent(concat!("spotigod/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self {
+            http,
+            token: token.to_string(),
+            url: url.unwrap_or(API_URL).trim_end_matches('/').to_string(),
+        }
+    }
+
+    async fn submit(&self, listen_type: &str, listen: &Listen) -> Result<()> {
+        let mut additional_info = json!({
+            "artist_names": listen.artists,
+            "duration_ms": listen.duration_ms,
+            "media_player": "SpotiGod",
+            "submission_client": "spotigod",
+            "submission_client_version": env!("CARGO_PKG_VERSION"),
+            "music_service": "spotify.com",
+        });
+        if let Some(url) = &listen.url {
+            additional_info["origin_url"] = json!(url);
+            additional_info["spotify_id"] = json!(url);
+        }
+        let mut entry = json!({
+            "track_metadata": {
+                "artist_name": listen.artists.join(", "),
+                "track_name": listen.track,
+                "release_name": listen.album,
+                "additional_info": additional_info,
+            }
+        });
+        // "playing_now" no lleva fecha: es lo que suena en este momento
+        if listen_type == "single" {
+            entry["listened_at"] = json!(listen.started_at);
+        }
+        let body = json!({ "listen_type": listen_type, "payload": [entry] });
+
+        let response = self
+            .http
+            .post(format!("{}/1/submit-listens", self.url))
+            .header("Authorization", format!("Token {}", self.token))
+            .json(&body)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body: serde_json::Value = response.json().await.unwrap_or_default();
+            let message = body["error"].as_str().unwrap_or_default().to_string();
+            return Err(anyhow!(tr!("ListenBrainz respondió {} {}", status, message)));
+        }
+        Ok(())
+    }
+}
+
+impl Scrobbler for ListenBrainz {
+    fn name(&self) -> &'static str {
+        "ListenBrainz"
+    }
+
+    fn now_playing<'a>(&'a self, listen: &'a Listen) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(self.submit("playing_now", listen))
+    }
+
+    fn scrobble<'a>(&'a self, listen: &'a Listen) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>> {
+        Box::pin(self.submit("single", listen))
+    }
+}
@@ -0,0 +1,275 @@
+//! Envío de lo que se escucha a servicios de scrobbling.
+//!
+//! `ScrobbleTracker` decide, a partir de las consultas de la reproducción, cuándo empieza a
+//! sonar una canción y cuándo cuenta como escuchada; cada servicio implementa `Scrobbler` y el
+//! config activa los que tengan credenciales.
+
+mod listenbrainz;
+
+pub use listenbrainz::ListenBrainz;
+
+use crate::config::Config;
+use crate::spotify::PlaybackState;
+use anyhow::Result;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// Las canciones más cortas no se envían
+const MIN_DURATION_MS: u64 = 30_000;
+/// Una canción cuenta como escuchada al llegar a la mitad o a este tiempo, lo que ocurra antes
+const MAX_LISTEN_MS: u64 = 240_000;
+/// Margen sobre el tiempo real entre consultas, por el retraso con el que llega el progreso
+const PROGRESS_SLACK_MS: u64 = 1_500;
+
+/// Canción escuchada (o que empieza a sonar)
+#[derive(Debug, Clone)]
+pub struct Listen {
+    pub track: String,
+    pub artists: Vec<String>,
+    pub album: String,
+    pub duration_ms: u64,
+    /// Momento (Unix, en segundos) en que empezó a sonar
+    pub started_at: i64,
+    /// Enlace de open.spotify.com; los archivos locales no tienen
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub enum ScrobbleEvent {
+    /// Empieza a sonar: los servicios lo muestran como "escuchando ahora"
+    NowPlaying(Listen),
+    /// Se ha escuchado lo suficiente para guardarla en el historial
+    Listened(Listen),
+}
+
+impl ScrobbleEvent {
+    pub fn listen(&self) -> &Listen {
+        match self {
+            ScrobbleEvent::NowPlaying(listen) | ScrobbleEvent::Listened(listen) => listen,
+        }
+    }
+}
+
+/// Servicio de scrobbling
+pub trait Scrobbler: Send + Sync {
+    /// Nombre para los mensajes
+    fn name(&self) -> &'static str;
+    fn now_playing<'a>(&'a self, listen: &'a Listen) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+    fn scrobble<'a>(&'a self, listen: &'a Listen) -> Pin<Box<dyn Future<Output = Result<()>> + Send + 'a>>;
+}
+
+/// Manda el evento al servicio
+pub async fn send(scrobbler: &dyn Scrobbler, event: &ScrobbleEvent) -> Result<()> {
+    match event {
+        ScrobbleEvent::NowPlaying(listen) => scrobbler.now_playing(listen).await,
+        ScrobbleEvent::Listened(listen) => scrobbler.scrobble(listen).await,
+    }
+}
+
+/// Servicios con credenciales en el config
+pub fn scrobblers(config: &Config) -> Vec<Arc<dyn Scrobbler>> {
+    let mut scrobblers: Vec<Arc<dyn Scrobbler>> = Vec::new();
+    if let Some(token) = config.listenbrainz_token.as_deref().filter(|token| !token.is_empty()) {
+        scrobblers.push(Arc::new(ListenBrainz::new(token, config.listenbrainz_url.as_deref())));
+    }
+    scrobblers
+}
+
+/// Canción que está sonando y cuánto se ha escuchado de ella
+struct Play {
+    key: String,
+    listen: Listen,
+    /// Tiempo escuchado de verdad: los saltos hacia delante no cuentan
+    played_ms: u64,
+    progress_ms: u64,
+    playing: bool,
+    seen_at: Instant,
+    announced: bool,
+    scrobbled: bool,
+}
+
+/// Sigue la reproducción consulta a consulta para saber qué enviar a los servicios
+#[derive(Default)]
+pub struct ScrobbleTracker {
+    play: Option<Play>,
+}
+
+impl ScrobbleTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Actualiza con la última consulta de la reproducción y devuelve lo que hay que enviar.
+    /// Los episodios de podcast no se envían
+    pub fn update(&mut self, playback: Option<&PlaybackState>) -> Vec<ScrobbleEvent> {
+        let Some((playback, track)) = playback.and_then(|p| p.track().map(|track| (p, track))) else {
+            self.play = None;
+            return Vec::new();
+        };
+        let artists: Vec<String> = track.artists.iter().map(|a| a.name.clone()).collect();
+        let key = format!("{} - {}", track.name, artists.join(", "));
+        let progress_ms = playback.progress_ms.unwrap_or(0).max(0) as u64;
+        let now = Instant::now();
+
+        // La misma canción que vuelve al principio (modo repetir) es otra escucha
+        let same = self.play.as_ref().is_some_and(|play| {
+            play.key == key && !(progress_ms < 10_000 && progress_ms + 10_000 < play.progress_ms)
+        });
+        if !same {
+            self.play = Some(Play {
+                key,
+                listen: Listen {
+                    track: track.name.clone(),
+                    artists,
+                    album: track.album.name.clone(),
+                    duration_ms: track.duration_ms.max(0) as u64,
+                    started_at: chrono::Utc::now().timestamp() - (progress_ms / 1000) as i64,
+                    url: Some(track.external_urls.spotify.clone()).filter(|url| !url.is_empty()),
+                },
+                played_ms: 0,
+                progress_ms,
+                playing: playback.is_playing,
+                seen_at: now,
+                announced: false,
+                scrobbled: false,
+            });
+        }
+        let Some(play) = self.play.as_mut() else {
+            return Vec::new();
+        };
+        if same && play.playing {
+            let advanced = progress_ms.saturating_sub(play.progress_ms);
+            let elapsed = now.duration_since(play.seen_at).as_millis() as u64 + PROGRESS_SLACK_MS;
+            play.played_ms += advanced.min(elapsed);
+        }
+        play.progress_ms = progress_ms;
+        play.playing = playback.is_playing;
+        play.seen_at = now;
+
+        let mut events = Vec::new();
+        if play.playing && !play.announced {
+            play.announced = true;
+            events.push(ScrobbleEvent::NowPlaying(play.listen.clone()));
+        }
+        let duration = play.listen.duration_ms;
+        if !play.scrobbled && duration > MIN_DURATION_MS && play.played_ms >= (duration / 2).min(MAX_LISTEN_MS) {
+            play.scrobbled = true;
+            events.push(ScrobbleEvent::Listened(play.listen.clone()));
+        }
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn playback(name: &str, duration_ms: u64, progress_ms: u64, is_playing: bool) -> PlaybackState {
+        serde_json::from_value(json!({
+            "device": {
+                "id": "d1", "is_active": true, "is_private_session": false, "is_restricted": false,
+                "name": "Portátil", "type": "Computer", "volume_percent": 50
+            },
+            "repeat_state": "off",
+            "shuffle_state": false,
+            "timestamp": 0,
+            "progress_ms": progress_ms,
+            "is_playing": is_playing,
+            "item": {
+                "type": "track",
+                "id": "t1",
+                "name": name,
+                "artists": [{ "id": "a1", "name": "Artista", "external_urls": {} }],
+                "album": { "id": "al1", "name": "Álbum", "artists": [], "external_urls": {} },
+                "duration_ms": duration_ms,
+                "external_urls": { "spotify": "https://open.spotify.com/track/t1" }
+            },
+            "currently_playing_type": "track",
+            "actions": {}
+        }))
+        .unwrap()
+    }
+
+    /// Consultas seguidas avanzando un segundo cada vez; devuelve en qué segundo se marcó como escuchada
+    fn listened_at(tracker: &mut ScrobbleTracker, duration_ms: u64, seconds: u64) -> Option<u64> {
+        let mut listened = None;
+        for second in 1..=seconds {
+            let events = tracker.update(Some(&playback("Canción", duration_ms, second * 1000, true)));
+            if events.iter().any(|event| matches!(event, ScrobbleEvent::Listened(_))) {
+                assert!(listened.is_none(), "se marcó como escuchada dos veces");
+                listened = Some(second);
+            }
+        }
+        listened
+    }
+
+    #[test]
+    fn announces_once_when_playing() {
+        let mut tracker = ScrobbleTracker::new();
+        assert!(tracker.update(Some(&playback("Canción", 180_000, 0, false))).is_empty());
+
+        let events = tracker.update(Some(&playback("Canción", 180_000, 0, true)));
+        assert!(matches!(events.as_slice(), [ScrobbleEvent::NowPlaying(listen)] if listen.track == "Canción"));
+        assert!(tracker.update(Some(&playback("Canción", 180_000, 1000, true))).is_empty());
+    }
+
+    #[test]
+    fn listened_at_half_the_track() {
+        let mut tracker = ScrobbleTracker::new();
+        tracker.update(Some(&playback("Canción", 60_000, 0, true)));
+        assert_eq!(listened_at(&mut tracker, 60_000, 60), Some(30));
+    }
+
+    #[test]
+    fn long_tracks_are_listened_at_four_minutes() {
+        let mut tracker = ScrobbleTracker::new();
+        tracker.update(Some(&playback("Canción", 600_000, 0, true)));
+        assert_eq!(listened_at(&mut tracker, 600_000, 300), Some(240));
+    }
+
+    #[test]
+    fn short_tracks_are_never_listened() {
+        let mut tracker = ScrobbleTracker::new();
+        tracker.update(Some(&playback("Canción", MIN_DURATION_MS, 0, true)));
+        assert_eq!(listened_at(&mut tracker, MIN_DURATION_MS, 30), None);
+    }
+
+    #[test]
+    fn seeking_forward_does_not_count() {
+        let mut tracker = ScrobbleTracker::new();
+        tracker.update(Some(&playback("Canción", 60_000, 0, true)));
+        let events = tracker.update(Some(&playback("Canción", 60_000, 50_000, true)));
+        assert!(events.is_empty());
+    }
+
+    #[test]
+    fn paused_time_does_not_count() {
+        let mut tracker = ScrobbleTracker::new();
+        tracker.update(Some(&playback("Canción", 60_000, 0, false)));
+        // En pausa el progreso no debería moverse, pero aunque llegue adelantado no cuenta
+        tracker.update(Some(&playback("Canción", 60_000, 1_000, false)));
+        assert!(tracker.play.as_ref().is_some_and(|play| play.played_ms == 0));
+    }
+
+    #[test]
+    fn repeating_the_track_is_a_new_listen() {
+        let mut tracker = ScrobbleTracker::new();
+        tracker.update(Some(&playback("Canción", 60_000, 0, true)));
+        assert_eq!(listened_at(&mut tracker, 60_000, 59), Some(30));
+
+        let events = tracker.update(Some(&playback("Canción", 60_000, 0, true)));
+        assert!(matches!(events.as_slice(), [ScrobbleEvent::NowPlaying(_)]));
+        assert_eq!(listened_at(&mut tracker, 60_000, 30), Some(30));
+    }
+
+    #[test]
+    fn nothing_playing_resets() {
+        let mut tracker = ScrobbleTracker::new();
+        tracker.update(Some(&playback("Canción", 60_000, 0, true)));
+        assert!(tracker.update(None).is_empty());
+        assert!(tracker.play.is_none());
+    }
+}
@@ -0,0 +1,99 @@
+use super::callback::CallbackListener;
+use serde::{Deserialize, Serialize};
+use super::error::Result;
+use super::models::{PlaybackState, Device, SearchType, SearchResults, QueueResponse, PlaylistsResponse, SavedTracksResponse, SavedAlbumsResponse, TimeRange, Track, FullArtist, Album, AudioAnalysis, AlbumSearchResult, Playlist, Category, UserProfile, PlaylistTracksResponse, AlbumTracksResponse, Episode, PlaybackContext, PlaybackOffset};
+
+/// Operaciones de la API de Spotify que usa la interfaz.
+///
+/// `SpotifyClient` la implementa sobre HTTP; otras implementaciones (simuladas o con
+/// otro backend) pueden sustituirlo sin tocar la interfaz.
+#[allow(async_fn_in_trait)]
+pub trait SpotifyApi {
+    /// Inicia una nueva autorización OAuth (por ejemplo, si el refresh token dejó de valer)
+    fn begin_reauthorization(&mut self) -> Reauthorization;
+    /// Completa la autorización con la URL de redirección o el código que pegó el usuario
+    async fn complete_reauthorization(&mut self, input: &str) -> Result<()>;
+
+    /// Guarda en disco el estado de la sesión (tokens) antes de salir
+    async fn save_session(&mut self) -> Result<()>;
+    /// Cierra la sesión: borra los tokens guardados y todo lo descargado de la cuenta
+    async fn logout(&mut self) -> Result<()>;
+    /// Dispositivo en el que se reintentó una orden porque no había ninguno activo
+    fn take_auto_selected_device(&mut self) -> Option<String>;
+    /// Esperas por límite de peticiones (en segundos) ocurridas desde la última consulta
+    fn take_rate_limit_waits(&mut self) -> Vec<u64>;
+    /// Peticiones HTTP hechas desde la última consulta, para el panel de depuración
+    fn take_api_calls(&mut self) -> Vec<ApiCall>;
+
+    async fn get_current_playback(&mut self) -> Result<Option<PlaybackState>>;
+    async fn play(&mut self) -> Result<()>;
+    async fn pause(&mut self) -> Result<()>;
+    async fn next_track(&mut self) -> Result<()>;
+    async fn previous_track(&mut self) -> Result<()>;
+    async fn set_volume(&mut self, volume: u8) -> Result<()>;
+    /// Salta a esa posición de la canción que suena
+    async fn seek(&mut self, position_ms: u64) -> Result<()>;
+    async fn get_devices(&mut self) -> Result<Vec<Device>>;
+    async fn transfer_playback(&mut self, device_id: &str, play: bool) -> Result<()>;
+    async fn search(&mut self, query: &str, types: &[SearchType], limit: u8, offset: u32) -> Result<SearchResults>;
+    async fn add_to_queue(&mut self, uri: &str) -> Result<()>;
+    async fn get_queue(&mut self) -> Result<QueueResponse>;
+    async fn get_user_playlists(&mut self, limit: u8, offset: u32) -> Result<PlaylistsResponse>;
+    async fn get_saved_tracks(&mut self, limit: u8, offset: u32) -> Result<SavedTracksResponse>;
+    async fn get_saved_albums(&mut self, limit: u8, offset: u32) -> Result<SavedAlbumsResponse>;
+    async fn get_top_tracks(&mut self, time_range: TimeRange, limit: u8) -> Result<Vec<Track>>;
+    async fn get_top_artists(&mut self, time_range: TimeRange, limit: u8) -> Result<Vec<FullArtist>>;
+    /// Todos los artistas que sigue el usuario, recorriendo todas las páginas
+    async fn get_followed_artists(&mut self) -> Result<Vec<FullArtist>>;
+    async fn get_artist_top_tracks(&mut self, artist_id: &str) -> Result<Vec<Track>>;
+    async fn get_artist_albums(&mut self, artist_id: &str, limit: u8) -> Result<Vec<Album>>;
+    async fn get_related_artists(&mut self, artist_id: &str) -> Result<Vec<FullArtist>>;
+    async fn get_recommendations(&mut self, seed_tracks: &[String], seed_artists: &[String], limit: u8) -> Result<Vec<Track>>;
+    async fn get_audio_analysis(&mut self, track_id: &str) -> Result<AudioAnalysis>;
+    async fn get_new_releases(&mut self, limit: u8, offset: u32) -> Result<AlbumSearchResult>;
+    async fn get_featured_playlists(&mut self, limit: u8) -> Result<(Option<String>, Vec<Playlist>)>;
+    async fn get_categories(&mut self, limit: u8) -> Result<Vec<Category>>;
+    async fn get_category_playlists(&mut self, category_id: &str, limit: u8) -> Result<Vec<Playlist>>;
+    async fn get_user_profile(&mut self) -> Result<UserProfile>;
+    async fn create_playlist(&mut self, user_id: &str, name: &str, public: bool) -> Result<Playlist>;
+    async fn add_tracks_to_playlist(&mut self, playlist_id: &str, uris: &[String]) -> Result<()>;
+    async fn get_playlist_tracks(&mut self, playlist_id: &str, limit: u8, offset: u32) -> Result<PlaylistTracksResponse>;
+    async fn get_album_tracks(&mut self, album_id: &str, limit: u8, offset: u32) -> Result<AlbumTracksResponse>;
+    async fn reorder_playlist_tracks(&mut self, playlist_id: &str, range_start: usize, insert_before: usize) -> Result<String>;
+    /// Quita de la playlist todas las apariciones de las canciones
+    async fn remove_playlist_tracks(&mut self, playlist_id: &str, uris: &[String]) -> Result<()>;
+    async fn update_playlist_details(&mut self, playlist_id: &str, name: &str, description: &str) -> Result<()>;
+    async fn follow_playlist(&mut self, playlist_id: &str) -> Result<()>;
+    async fn save_tracks(&mut self, ids: &[String]) -> Result<()>;
+    async fn remove_saved_tracks(&mut self, ids: &[String]) -> Result<()>;
+    async fn check_saved_tracks(&mut self, ids: &[String]) -> Result<Vec<bool>>;
+    async fn get_saved_episodes(&mut self) -> Result<Vec<Episode>>;
+    async fn save_episodes(&mut self, ids: &[String]) -> Result<()>;
+    async fn remove_saved_episodes(&mut self, ids: &[String]) -> Result<()>;
+    async fn unfollow_playlist(&mut self, playlist_id: &str) -> Result<()>;
+    async fn start_playback(&mut self, context: PlaybackContext, offset: Option<PlaybackOffset>, position_ms: Option<i64>) -> Result<()>;
+    async fn toggle_shuffle(&mut self) -> Result<()>;
+    async fn toggle_repeat(&mut self) -> Result<()>;
+}
+
+/// Petición HTTP a Spotify, tal como la muestra el panel de depuración
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiCall {
+    pub method: String,
+    /// Ruta bajo https://api.spotify.com/v1, con la consulta
+    pub path: String,
+    /// `None` si no llegó respuesta (error de red)
+    pub status: Option<u16>,
+    pub duration_ms: u64,
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Autorización iniciada desde la interfaz
+pub struct Reauthorization {
+    /// URL que el usuario debe abrir para autorizar SpotiGod
+    pub url: String,
+    /// Puerto local en el que esperar la redirección; `None` si el usuario debe pegarla a mano
+    pub callback: Option<CallbackListener>,
+    /// Motivo por el que no se pudo abrir el puerto local, si se intentó
+    pub callback_error: Option<String>,
+}
@@ -0,0 +1,117 @@
+use std::collections::HashMap;
+use tokio::time::{Duration, Instant};
+
+/// Caché en memoria de respuestas GET, indexada por ruta (incluida la query)
+pub struct ResponseCache {
+    entries: HashMap<String, CacheEntry>,
+}
+
+struct CacheEntry {
+    body: String,
+    /// ETag de la respuesta, para revalidarla con If-None-Match cuando caduque
+    etag: Option<String>,
+    expires_at: Instant,
+}
+
+impl ResponseCache {
+    pub fn new() -> Self {
+        Self { entries: HashMap::new() }
+    }
+
+    /// Cuerpo guardado para la ruta si todavía no ha caducado
+    pub fn get(&mut self, path: &str) -> Option<String> {
+        match self.entries.get(path) {
+            Some(entry) if entry.expires_at > Instant::now() => Some(entry.body.clone()),
+            // Caducada pero revalidable: se conserva para la petición condicional
+            Some(entry) if entry.etag.is_some() => None,
+            Some(_) => {
+                self.entries.remove(path);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// ETag de una respuesta guardada (aunque haya caducado)
+    pub fn etag(&self, path: &str) -> Option<String> {
+        self.entries.get(path).and_then(|entry| entry.etag.clone())
+    }
+
+    /// Spotify respondió 304: la copia guardada sigue valiendo otro `ttl`
+    pub fn revalidate(&mut self, path: &str, ttl: Duration) -> Option<String> {
+        let entry = self.entries.get_mut(path)?;
+        entry.expires_at = Instant::now() + ttl;
+        Some(entry.body.clone())
+    }
+
+    /// Guarda una respuesta. Con TTL cero solo se guarda si trae ETag, y siempre se revalida
+    pub fn insert(&mut self, path: &str, body: String, etag: Option<String>, ttl: Duration) {
+        if ttl.is_zero() && etag.is_none() {
+            return;
+        }
+        self.entries.insert(path.to_string(), CacheEntry { body, etag, expires_at: Instant::now() + ttl });
+    }
+
+    /// Descarta todas las respuestas cuya ruta empiece por `prefix`
+    pub fn invalidate(&mut self, prefix: &str) {
+        self.entries.retain(|path, _| !path.starts_with(prefix));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TTL: Duration = Duration::from_secs(60);
+
+    #[tokio::test(start_paused = true)]
+    async fn expires_after_ttl() {
+        let mut cache = ResponseCache::new();
+        cache.insert("/me", "perfil".to_string(), None, TTL);
+        assert_eq!(cache.get("/me").as_deref(), Some("perfil"));
+
+        tokio::time::advance(TTL).await;
+        assert_eq!(cache.get("/me"), None);
+        // Sin ETag no hay nada que revalidar: la entrada se descarta
+        assert_eq!(cache.revalidate("/me", TTL), None);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn expired_entry_with_etag_is_revalidated() {
+        let mut cache = ResponseCache::new();
+        cache.insert("/me/playlists", "listas".to_string(), Some("\"v1\"".to_string()), TTL);
+
+        tokio::time::advance(TTL).await;
+        assert_eq!(cache.get("/me/playlists"), None);
+        assert_eq!(cache.etag("/me/playlists").as_deref(), Some("\"v1\""));
+
+        assert_eq!(cache.revalidate("/me/playlists", TTL).as_deref(), Some("listas"));
+        assert_eq!(cache.get("/me/playlists").as_deref(), Some("listas"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn zero_ttl_is_only_kept_with_etag() {
+        let mut cache = ResponseCache::new();
+        cache.insert("/sin-etag", "a".to_string(), None, Duration::ZERO);
+        cache.insert("/con-etag", "b".to_string(), Some("\"v1\"".to_string()), Duration::ZERO);
+
+        assert_eq!(cache.get("/sin-etag"), None);
+        assert_eq!(cache.etag("/sin-etag"), None);
+        // Se guarda pero siempre hay que revalidarla
+        assert_eq!(cache.get("/con-etag"), None);
+        assert_eq!(cache.etag("/con-etag").as_deref(), Some("\"v1\""));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn invalidate_removes_by_prefix() {
+        let mut cache = ResponseCache::new();
+        cache.insert("/playlists/a/tracks?offset=0", "a0".to_string(), None, TTL);
+        cache.insert("/playlists/a/tracks?offset=100", "a1".to_string(), None, TTL);
+        cache.insert("/playlists/b/tracks?offset=0", "b0".to_string(), None, TTL);
+
+        cache.invalidate("/playlists/a/");
+        assert_eq!(cache.get("/playlists/a/tracks?offset=0"), None);
+        assert_eq!(cache.get("/playlists/a/tracks?offset=100"), None);
+        assert_eq!(cache.get("/playlists/b/tracks?offset=0").as_deref(), Some("b0"));
+    }
+}
@@ -0,0 +1,204 @@
+use anyhow::{anyhow, Result};
+use axum::extract::{Query, State};
+use axum::http::{StatusCode, Uri};
+use axum::response::Html;
+use axum::routing::get;
+use axum::Router;
+use std::collections::HashMap;
+use std::net::TcpListener as StdTcpListener;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+use url::Url;
+
+use crate::i18n::{self, Locale};
+use crate::tr;
+
+/// Puerto local en el que se espera la redirección de Spotify tras autorizar
+pub struct CallbackListener {
+    listener: StdTcpListener,
+    redirect_uri: String,
+    expected_state: Option<String>,
+    timeout: Duration,
+}
+
+/// Estado compartido con el manejador de la redirección
+struct Shared {
+    base: Url,
+    expected_state: Option<String>,
+    result: Mutex<Option<oneshot::Sender<Result<String>>>>,
+}
+
+impl CallbackListener {
+    /// Abre el puerto de la URI de redirección (o el de `port` si se indica; 0 elige uno libre)
+    /// en `host` o, si no se indica, en el host de la propia URI
+    pub fn bind(redirect_uri: &str, host: Option<&str>, port: Option<u16>) -> Result<Self> {
+        let mut url = Url::parse(redirect_uri).map_err(|e| anyhow!(tr!("redirect_uri no válida ({}): {}", redirect_uri, e)))?;
+        let host = match host.or(url.host_str()) {
+            Some("localhost") | None => "127.0.0.1".to_string(),
+            Some(host) => host.to_string(),
+        };
+        let port = port.or(url.port_or_known_default()).unwrap_or(80);
+
+        let listener = StdTcpListener::bind((host.as_str(), port)).map_err(|e| {
+            anyhow!(tr!(
+                "No se pudo abrir {}:{} para recibir la redirección ({}). Cambia callback_port o pon 0 para usar uno libre",
+                host,
+                port,
+                e
+            ))
+        })?;
+        listener.set_nonblocking(true)?;
+
+        // Con puerto 0 el sistema elige uno: se anuncia en la URI enviada a Spotify
+        let bound_port = listener.local_addr()?.port();
+        url.set_port(Some(bound_port)).map_err(|_| anyhow!(tr!("redirect_uri no admite puerto: {}", redirect_uri)))?;
+        Ok(Self {
+            listener,
+            redirect_uri: url.to_string(),
+            expected_state: None,
+            timeout: Duration::from_secs(300),
+        })
+    }
+
+    /// URI de redirección que hay que usar en la autorización y en el canje del código
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+
+    /// Solo acepta redirecciones con este `state`; las demás se rechazan y se sigue esperando
+    pub fn expect_state(mut self, state: &str) -> Self {
+        self.expected_state = Some(state.to_string());
+        self
+    }
+
+    /// Tiempo máximo de espera antes de abandonar la autorización
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Espera a que Spotify redirija al navegador y devuelve la URL completa (con `code` y `state`)
+    pub async fn wait(self) -> Result<String> {
+        let base = Url::parse(&self.redirect_uri)?;
+        let (sender, receiver) = oneshot::channel();
+        let shared = Arc::new(Shared {
+            base: base.clone(),
+            expected_state: self.expected_state,
+            result: Mutex::new(Some(sender)),
+        });
+        let app = Router::new()
+            .route(base.path(), get(handle_redirect))
+            .with_state(shared);
+
+        // El servidor se cierra al recibir el resultado, después de enviar la página al navegador
+        let outcome = Arc::new(Mutex::new(None));
+        let slot = outcome.clone();
+        let timeout = self.timeout;
+        let shutdown = async move {
+            let result = tokio::time::timeout(timeout, receiver).await;
+            *slot.lock().unwrap() = Some(result);
+        };
+        let listener = TcpListener::from_std(self.listener)?;
+        axum::serve(listener, app).with_graceful_shutdown(shutdown).await?;
+
+        let outcome = outcome.lock().unwrap().take();
+        match outcome {
+            Some(Ok(Ok(result))) => result,
+            Some(Err(_)) => Err(anyhow!(tr!(
+                "No se recibió la autorización de Spotify en {} segundos",
+                timeout.as_secs()
+            ))),
+            _ => Err(anyhow!(tr!("El servidor de redirección se cerró antes de recibir la autorización"))),
+        }
+    }
+}
+
+async fn handle_redirect(
+    State(shared): State<Arc<Shared>>,
+    uri: Uri,
+    Query(params): Query<HashMap<String, String>>,
+) -> (StatusCode, Html<String>) {
+    if let Some(expected) = &shared.expected_state {
+        if params.get("state") != Some(expected) {
+            // Puede ser una pestaña antigua: se ignora y se sigue esperando la buena
+            return (
+                StatusCode::BAD_REQUEST,
+                page(
+                    tr!("⚠️ Autorización no reconocida"),
+                    tr!("Esta redirección no corresponde a la autorización en curso. Vuelve a la terminal e inténtalo de nuevo."),
+                ),
+            );
+        }
+    }
+
+    let (status, html, result) = if let Some(error) = params.get("error") {
+        let message = if error == "access_denied" {
+            tr!("Has denegado el acceso a SpotiGod en Spotify").to_string()
+        } else {
+            tr!("Spotify rechazó la autorización: {}", error)
+        };
+        (
+            StatusCode::OK,
+            page(
+                tr!("❌ Autorización cancelada"),
+                &tr!("{}. Puedes cerrar esta ventana y volver a intentarlo desde la terminal.", escape_html(&message)),
+            ),
+            Err(anyhow!(message)),
+        )
+    } else if params.contains_key("code") {
+        let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+        (
+            StatusCode::OK,
+            page(tr!("✅ ¡Autenticación exitosa!"), tr!("Puedes cerrar esta ventana y volver a la terminal.")),
+            shared.base.join(path).map(|url| url.to_string()).map_err(Into::into),
+        )
+    } else {
+        return (
+            StatusCode::BAD_REQUEST,
+            page(tr!("⚠️ Falta el código"), tr!("La redirección no incluye el código de autorización.")),
+        );
+    };
+
+    if let Some(sender) = shared.result.lock().unwrap().take() {
+        let _ = sender.send(result);
+    }
+    (status, html)
+}
+
+/// `error` llega en la URL, así que cualquiera puede poner HTML en él
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+/// Página que se muestra en el navegador al terminar la redirección
+fn page(title: &str, message: &str) -> Html<String> {
+    Html(format!(
+        r#"<!DOCTYPE html>
+<html lang="{}">
+<head>
+<meta charset="utf-8">
+<title>SpotiGod</title>
+<style>
+body {{ background: #121212; color: #fff; font-family: sans-serif; display: flex; align-items: center; justify-content: center; height: 100vh; margin: 0; }}
+.card {{ background: #181818; border-radius: 12px; padding: 2rem 3rem; text-align: center; box-shadow: 0 8px 24px rgba(0, 0, 0, 0.5); }}
+h1 {{ color: #1db954; }}
+p {{ color: #b3b3b3; }}
+</style>
+</head>
+<body>
+<div class="card">
+<h1>{}</h1>
+<p>{}</p>
+</div>
+</body>
+</html>"#,
+        match i18n::locale() {
+            Locale::Es => "es",
+            Locale::En => "en",
+        },
+        title,
+        message
+    ))
+}
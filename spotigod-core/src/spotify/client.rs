@@ -0,0 +1,1206 @@
+use super::models::{TokenResponse, PlaybackState, SearchResults, PlaylistsResponse, Track, SavedTracksResponse, SavedAlbumsResponse, QueueResponse, Device, DevicesResponse, FullArtist, TimeRange, TopTracksResponse, TopArtistsResponse, FollowedArtistsResponse, RecommendationsResponse, UserProfile, Playlist, AudioAnalysis, Album, SearchType, PlaylistTracksResponse, Episode, SavedEpisodesResponse, AlbumSearchResult, NewReleasesResponse, FeaturedPlaylistsResponse, Category, CategoriesResponse, CategoryPlaylistsResponse, SnapshotResponse, ArtistTopTracksResponse, RelatedArtistsResponse, AlbumTracksResponse, PlaybackContext, PlaybackOffset};
+use super::api::{ApiCall, Reauthorization, SpotifyApi};
+use super::cache::ResponseCache;
+use super::callback::CallbackListener;
+use super::rate_limiter::RateLimiter;
+use crate::config::Config;
+use crate::tr;
+use super::error::{Result, SpotifyError};
+use anyhow::anyhow;
+use base64::{Engine as _, engine::general_purpose::{STANDARD as Base64, URL_SAFE_NO_PAD}};
+use reqwest::header::{ETAG, IF_NONE_MATCH, RETRY_AFTER};
+use reqwest::{Client, Method, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::VecDeque;
+use std::io::prelude::*;
+use std::time::{Duration, Instant};
+use url::Url;
+use uuid::Uuid;
+
+pub struct SpotifyClient {
+    client: Client,
+    config: Config,
+    base_url: String,
+    /// País del perfil (o `from_token` si no se pudo saber), usado como mercado cuando la configuración no fija uno
+    profile_market: Option<String>,
+    rate_limit_waits: Vec<u64>,
+    /// Peticiones pendientes de recoger con `take_api_calls`; solo se guardan las últimas
+    api_calls: VecDeque<ApiCall>,
+    rate_limiter: RateLimiter,
+    cache: ResponseCache,
+    /// Autorización OAuth iniciada y pendiente de recibir el código
+    pending_auth: Option<PendingAuthorization>,
+    /// Último dispositivo en el que se reprodujo, para reintentar si deja de estar activo
+    last_device_id: Option<String>,
+    /// Dispositivo elegido automáticamente en el último reintento, pendiente de avisar
+    auto_selected_device: Option<String>,
+}
+
+struct PendingAuthorization {
+    state: String,
+    code_verifier: Option<String>,
+    /// La misma URI de redirección tiene que ir en la autorización y en el canje del código
+    redirect_uri: String,
+}
+
+/// Permisos que pide SpotiGod al autorizar
+pub const SCOPES: &str = "user-read-playback-state user-modify-playback-state user-read-currently-playing playlist-read-private playlist-read-collaborative user-library-read user-library-modify user-top-read user-read-private playlist-modify-public playlist-modify-private user-read-playback-position user-follow-read";
+
+/// Reintentos ante respuestas 429 antes de devolver el error
+const MAX_RATE_LIMIT_RETRIES: u32 = 5;
+/// Espera máxima por un 429: si Spotify pide más, se devuelve el error en vez de bloquear el
+/// cliente tanto tiempo
+const MAX_RATE_LIMIT_WAIT: u64 = 30;
+/// Peticiones que se guardan mientras nadie las recoge (subcomandos, sondeo del demonio...)
+const MAX_PENDING_API_CALLS: usize = 100;
+
+impl SpotifyClient {
+    pub fn new(config: Config) -> Self {
+        let rate_limiter = RateLimiter::new(config.requests_per_second);
+        Self {
+            client: Client::new(),
+            config,
+            base_url: "https://api.spotify.com/v1".to_string(),
+            profile_market: None,
+            rate_limit_waits: Vec::new(),
+            api_calls: VecDeque::new(),
+            rate_limiter,
+            cache: ResponseCache::new(),
+            pending_auth: None,
+            last_device_id: None,
+            auto_selected_device: None,
+        }
+    }
+
+    pub async fn is_authenticated(&self) -> bool {
+        self.config.is_token_valid()
+    }
+
+    pub async fn authenticate(&mut self) -> anyhow::Result<()> {
+        // Si el puerto no se puede abrir (contenedor, WSL, firewall...) se sigue con el flujo manual
+        let callback = if self.uses_manual_auth() {
+            None
+        } else {
+            match self.bind_callback() {
+                Ok(callback) => Some(callback),
+                Err(e) => {
+                    println!("⚠️  {}", e);
+                    println!("{}", tr!("⚠️  Continuando con la autorización manual"));
+                    None
+                }
+            }
+        };
+        let redirect_uri = callback
+            .as_ref()
+            .map_or_else(|| self.config.redirect_uri.clone(), |c| c.redirect_uri().to_string());
+        let auth_url = self.authorization_url(&redirect_uri);
+        let state = self.pending_auth.as_ref().map(|p| p.state.clone()).unwrap_or_default();
+        let callback = callback.map(|c| c.expect_state(&state));
+
+        let redirect = if let Some(callback) = callback {
+            println!("{}", tr!("🌐 Abriendo navegador para autenticación..."));
+            println!("{}", tr!("📋 Si no se abre automáticamente, copia esta URL:"));
+            println!("{}", &auth_url);
+
+            // Intentar abrir el navegador
+            if webbrowser::open(&auth_url).is_err() {
+                println!("{}", tr!("⚠️  No se pudo abrir el navegador automáticamente"));
+            }
+
+            // Iniciar servidor temporal para recibir el callback
+            println!("{}", tr!("🔄 Esperando callback de Spotify en {}...", redirect_uri));
+            callback.wait().await?
+        } else {
+            Self::read_redirect_manually(&auth_url)?
+        };
+        let code = Self::code_from_input(&redirect, &state)?;
+
+        // Intercambiar código por token
+        self.exchange_pending_code(&code).await
+    }
+
+    /// Abre el puerto local para la redirección según redirect_uri, callback_host y callback_port
+    fn bind_callback(&self) -> anyhow::Result<CallbackListener> {
+        let callback = CallbackListener::bind(
+            &self.config.redirect_uri,
+            self.config.callback_host.as_deref(),
+            self.config.callback_port,
+        )?;
+        Ok(callback.timeout(Duration::from_secs(self.config.auth_timeout_secs)))
+    }
+
+    /// Genera una autorización nueva (state y, con PKCE, code_verifier) y devuelve su URL
+    fn authorization_url(&mut self, redirect_uri: &str) -> String {
+        // Generar state para OAuth
+        let state = Uuid::new_v4().to_string();
+
+        // Sin client secret se usa PKCE: el verifier se guarda para el intercambio del código
+        let code_verifier = (!self.uses_client_secret()).then(Self::generate_code_verifier);
+
+        // Construir URL de autorización
+        let mut auth_url = format!(
+            "https://accounts.spotify.com/authorize?response_type=code&client_id={}&scope={}&redirect_uri={}&state={}",
+            self.config.client_id,
+            SCOPES,
+            urlencoding::encode(redirect_uri),
+            state
+        );
+        if let Some(ref verifier) = code_verifier {
+            auth_url.push_str(&format!("&code_challenge_method=S256&code_challenge={}", Self::code_challenge(verifier)));
+        }
+
+        self.pending_auth = Some(PendingAuthorization {
+            state,
+            code_verifier,
+            redirect_uri: redirect_uri.to_string(),
+        });
+        auth_url
+    }
+
+    /// Intercambia el código usando el verifier de la autorización en curso
+    async fn exchange_pending_code(&mut self, code: &str) -> anyhow::Result<()> {
+        let pending = self
+            .pending_auth
+            .take()
+            .ok_or_else(|| anyhow!(tr!("No hay ninguna autorización en curso")))?;
+        self.exchange_code_for_token(code, &pending.redirect_uri, pending.code_verifier.as_deref()).await
+    }
+
+    /// Sin navegador local (servidores, SSH) o si se pide en la configuración
+    fn uses_manual_auth(&self) -> bool {
+        self.config.manual_auth || std::env::var_os("SSH_CONNECTION").is_some()
+    }
+
+    /// Flujo manual: el usuario autoriza en otro equipo y pega la URL de redirección o el código
+    fn read_redirect_manually(auth_url: &str) -> anyhow::Result<String> {
+        println!("{}", tr!("🔗 Abre esta URL en cualquier navegador y autoriza el acceso:"));
+        println!("{}", auth_url);
+        println!("{}", tr!("📋 Después pega aquí la URL a la que te redirigió Spotify (aunque la página no cargue) o solo el código:"));
+        print!("> ");
+        std::io::stdout().flush()?;
+
+        let mut input = String::new();
+        std::io::stdin().read_line(&mut input)?;
+        Ok(input)
+    }
+
+    /// Extrae el código de una URL de redirección (comprobando el state) o lo toma tal cual
+    fn code_from_input(input: &str, state: &str) -> anyhow::Result<String> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Err(anyhow!(tr!("No se introdujo ningún código")));
+        }
+        // Lo que no es una URL es el código pegado solo
+        let Ok(parsed_url) = Url::parse(input) else {
+            return Ok(input.to_string());
+        };
+        let param = |name: &str| {
+            parsed_url
+                .query_pairs()
+                .find(|(key, _)| key == name)
+                .map(|(_, value)| value.to_string())
+        };
+        if let Some(error) = param("error") {
+            return Err(anyhow!(if error == "access_denied" {
+                tr!("Has denegado el acceso a SpotiGod en Spotify").to_string()
+            } else {
+                tr!("Spotify rechazó la autorización: {}", error)
+            }));
+        }
+        if param("state").as_deref() != Some(state) {
+            return Err(anyhow!(tr!("El parámetro state no coincide; vuelve a iniciar la autenticación")));
+        }
+        param("code").ok_or_else(|| anyhow!(tr!("No se encontró el código en la URL")))
+    }
+
+    /// Hay client secret configurado: se usa el flujo clásico en lugar de PKCE
+    fn uses_client_secret(&self) -> bool {
+        self.config.client_secret.as_deref().is_some_and(|s| !s.is_empty())
+    }
+
+    /// code_verifier de PKCE: 64 caracteres aleatorios del alfabeto permitido
+    fn generate_code_verifier() -> String {
+        format!("{}{}", Uuid::new_v4().simple(), Uuid::new_v4().simple())
+    }
+
+    fn code_challenge(code_verifier: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+    }
+
+    /// Petición al endpoint de tokens: con Basic auth si hay client secret, o con client_id (PKCE)
+    async fn request_token(&self, mut params: Vec<(&str, &str)>) -> Result<Response> {
+        let mut request = self.client
+            .post("https://accounts.spotify.com/api/token")
+            .header("Content-Type", "application/x-www-form-urlencoded");
+        match self.config.client_secret {
+            Some(ref secret) if !secret.is_empty() => {
+                let auth_header = Base64.encode(format!("{}:{}", self.config.client_id, secret));
+                request = request.header("Authorization", format!("Basic {}", auth_header));
+            }
+            _ => params.push(("client_id", &self.config.client_id)),
+        }
+        Ok(request.form(&params).send().await?)
+    }
+
+    async fn exchange_code_for_token(&mut self, code: &str, redirect_uri: &str, code_verifier: Option<&str>) -> anyhow::Result<()> {
+        let mut params = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", redirect_uri),
+        ];
+        if let Some(verifier) = code_verifier {
+            params.push(("code_verifier", verifier));
+        }
+
+        let response = self.request_token(params).await?;
+
+        if response.status().is_success() {
+            let token_response: TokenResponse = response.json().await?;
+            
+            self.config.access_token = Some(token_response.access_token);
+            self.config.refresh_token = token_response.refresh_token;
+            self.config.token_scope = Some(token_response.scope);
+            self.config.token_expires_at = Some(
+                chrono::Utc::now().timestamp() + token_response.expires_in
+            );
+            tracing::info!("autorización completada");
+            
+            self.config.save().await?;
+            Ok(())
+        } else {
+            let error_text = response.text().await?;
+            tracing::warn!(error = %error_text, "Spotify rechazó el código de autorización");
+            Err(anyhow!(tr!("Error al obtener token: {}", error_text)))
+        }
+    }
+
+    async fn ensure_valid_token(&mut self) -> Result<()> {
+        if !self.config.is_token_valid() {
+            if let Some(refresh_token) = self.config.refresh_token.clone() {
+                self.refresh_access_token(&refresh_token).await?;
+            } else {
+                return Err(SpotifyError::Unauthorized);
+            }
+        }
+        Ok(())
+    }
+
+    async fn refresh_access_token(&mut self, refresh_token: &str) -> Result<()> {
+        let params = vec![
+            ("grant_type", "refresh_token"),
+            ("refresh_token", refresh_token),
+        ];
+
+        let started = Instant::now();
+        let response = self.request_token(params).await;
+        let status = response.as_ref().ok().map(|r| r.status().as_u16());
+        self.record_call(&Method::POST, "accounts.spotify.com/api/token", status, started);
+        let response = response.inspect_err(|e| {
+            tracing::warn!(error = %e, "no se pudo renovar el token");
+        })?;
+
+        if response.status().is_success() {
+            let token_response: TokenResponse = response.json().await?;
+            tracing::info!(
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                expires_in = token_response.expires_in,
+                "token renovado"
+            );
+
+            self.config.access_token = Some(token_response.access_token);
+            if let Some(new_refresh_token) = token_response.refresh_token {
+                self.config.refresh_token = Some(new_refresh_token);
+            }
+            self.config.token_scope = Some(token_response.scope);
+            self.config.token_expires_at = Some(
+                chrono::Utc::now().timestamp() + token_response.expires_in
+            );
+            
+            self.config
+                .save()
+                .await
+                .map_err(|e| SpotifyError::Storage(e.to_string()))?;
+            Ok(())
+        } else {
+            tracing::warn!(status = response.status().as_u16(), "Spotify rechazó la renovación del token");
+            Err(SpotifyError::Unauthorized)
+        }
+    }
+
+    async fn get_auth_header(&mut self) -> Result<String> {
+        self.ensure_valid_token().await?;
+        let token = self.config.access_token.as_ref()
+            .ok_or(SpotifyError::Unauthorized)?;
+        Ok(format!("Bearer {}", token))
+    }
+
+    /// Envía una petición autenticada a la API. Si Spotify responde 429 espera lo que indique
+    /// Retry-After y reintenta, de forma transparente para quien llama
+    async fn send(&mut self, method: Method, path: &str, body: Option<&serde_json::Value>) -> Result<Response> {
+        self.send_conditional(method, path, body, None).await
+    }
+
+    /// Como `send`, pero con `If-None-Match` si se indica un ETag
+    async fn send_conditional(
+        &mut self,
+        method: Method,
+        path: &str,
+        body: Option<&serde_json::Value>,
+        etag: Option<&str>,
+    ) -> Result<Response> {
+        let mut retries = 0;
+        loop {
+            self.rate_limiter.acquire().await;
+            let auth_header = self.get_auth_header().await?;
+            let mut request = self.client
+                .request(method.clone(), format!("{}{}", self.base_url, path))
+                .header("Authorization", auth_header);
+            if let Some(etag) = etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            request = match body {
+                Some(body) => request.json(body),
+                // PUT/POST sin cuerpo necesitan Content-Length explícito
+                None if method == Method::PUT || method == Method::POST => request.header("Content-Length", "0").body(""),
+                None => request,
+            };
+
+            let started = Instant::now();
+            let response = request.send().await;
+            let status = response.as_ref().ok().map(|r| r.status().as_u16());
+            self.record_call(&method, path, status, started);
+            let response = response.inspect_err(|e| {
+                tracing::warn!(%method, path, error = %e, "error de red");
+            })?;
+            let elapsed_ms = started.elapsed().as_millis() as u64;
+            let status = response.status();
+            if status.is_server_error() {
+                tracing::warn!(%method, path, status = status.as_u16(), elapsed_ms, "petición a la API");
+            } else {
+                tracing::debug!(%method, path, status = status.as_u16(), elapsed_ms, "petición a la API");
+            }
+            if status != StatusCode::TOO_MANY_REQUESTS || retries >= MAX_RATE_LIMIT_RETRIES {
+                return Ok(response);
+            }
+
+            let wait = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok())
+                .unwrap_or(1);
+            if wait > MAX_RATE_LIMIT_WAIT {
+                tracing::warn!(path, wait, "límite de peticiones: espera demasiado larga");
+                return Err(SpotifyError::RateLimited { retry_after: wait });
+            }
+            tracing::warn!(path, wait, retries, "límite de peticiones: esperando");
+            self.rate_limit_waits.push(wait);
+            retries += 1;
+            tokio::time::sleep(Duration::from_secs(wait)).await;
+        }
+    }
+
+    /// Apunta una petición para `take_api_calls`
+    fn record_call(&mut self, method: &Method, path: &str, status: Option<u16>, started: Instant) {
+        if self.api_calls.len() == MAX_PENDING_API_CALLS {
+            self.api_calls.pop_front();
+        }
+        self.api_calls.push_back(ApiCall {
+            method: method.to_string(),
+            path: path.to_string(),
+            status,
+            duration_ms: started.elapsed().as_millis() as u64,
+            at: chrono::Utc::now(),
+        });
+    }
+
+    /// Petición de control de reproducción: si Spotify responde que no hay dispositivo activo y
+    /// el último usado sigue disponible, se reintenta una vez dirigida a él
+    async fn send_player(&mut self, method: Method, path: &str, body: Option<&serde_json::Value>) -> Result<Response> {
+        let response = self.send(method.clone(), path, body).await?;
+        if response.status() != StatusCode::NOT_FOUND {
+            return Ok(response);
+        }
+        let error = SpotifyError::from_response(response).await;
+        let (SpotifyError::NoActiveDevice, Some(device_id)) = (&error, self.last_device_id.clone()) else {
+            return Err(error);
+        };
+        let devices = self.get_devices().await?;
+        let Some(device) = devices.into_iter().find(|d| d.id.as_deref() == Some(device_id.as_str())) else {
+            return Err(error);
+        };
+
+        let separator = if path.contains('?') { '&' } else { '?' };
+        let response = self
+            .send(method, &format!("{}{}device_id={}", path, separator, device_id), body)
+            .await?;
+        if response.status().is_success() {
+            self.auto_selected_device = Some(device.name);
+        }
+        Ok(response)
+    }
+
+    /// GET servido desde la caché mientras no caduque; si no, se pide (condicionada al ETag
+    /// guardado, de modo que un 304 reutiliza la copia) y se guarda
+    async fn get_cached<T: DeserializeOwned>(&mut self, path: &str, ttl: Duration) -> Result<T> {
+        if let Some(body) = self.cache.get(path) {
+            return Ok(serde_json::from_str(&body)?);
+        }
+        let etag = self.cache.etag(path);
+        let response = self.send_conditional(Method::GET, path, None, etag.as_deref()).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(body) = self.cache.revalidate(path, ttl) {
+                return Ok(serde_json::from_str(&body)?);
+            }
+        }
+        if !response.status().is_success() {
+            return Err(SpotifyError::from_response(response).await);
+        }
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(|v| v.to_string());
+        let body = response.text().await?;
+        let value = serde_json::from_str(&body)?;
+        self.cache.insert(path, body, etag, ttl);
+        Ok(value)
+    }
+
+    /// TTL de la biblioteca del usuario (playlists, favoritos, episodios)
+    fn library_ttl(&self) -> Duration {
+        Duration::from_secs(self.config.library_cache_ttl_secs)
+    }
+
+    /// TTL del catálogo (artistas y álbumes), que cambia mucho menos
+    fn catalog_ttl(&self) -> Duration {
+        Duration::from_secs(self.config.catalog_cache_ttl_secs)
+    }
+
+    /// Mercado para las peticiones de catálogo: el configurado, el país del perfil o, si no se
+    /// conoce, `from_token` para que Spotify lo deduzca del token
+    pub async fn market(&mut self) -> String {
+        if let Some(market) = self.config.market.clone().or_else(|| self.profile_market.clone()) {
+            return market;
+        }
+        // También se guarda `from_token`, para no volver a pedir el perfil en cada petición
+        let market = self
+            .get_user_profile()
+            .await
+            .ok()
+            .and_then(|profile| profile.country)
+            .unwrap_or_else(|| "from_token".to_string());
+        self.profile_market = Some(market.clone());
+        market
+    }
+}
+
+impl SpotifyApi for SpotifyClient {
+    async fn save_session(&mut self) -> Result<()> {
+        self.config
+            .save()
+            .await
+            .map_err(|e| SpotifyError::Storage(e.to_string()))
+    }
+
+    async fn logout(&mut self) -> Result<()> {
+        self.config.clear_tokens();
+        self.cache = ResponseCache::new();
+        self.pending_auth = None;
+        self.profile_market = None;
+        self.last_device_id = None;
+        self.auto_selected_device = None;
+        self.save_session().await
+    }
+
+    fn take_auto_selected_device(&mut self) -> Option<String> {
+        self.auto_selected_device.take()
+    }
+
+    fn begin_reauthorization(&mut self) -> Reauthorization {
+        // Si el puerto no está disponible se sigue con el flujo manual: pegar la URL en la interfaz
+        let (callback, callback_error) = if self.uses_manual_auth() {
+            (None, None)
+        } else {
+            match self.bind_callback() {
+                Ok(callback) => (Some(callback), None),
+                Err(e) => (None, Some(e.to_string())),
+            }
+        };
+        let redirect_uri = callback
+            .as_ref()
+            .map_or_else(|| self.config.redirect_uri.clone(), |c| c.redirect_uri().to_string());
+        let url = self.authorization_url(&redirect_uri);
+        let state = self.pending_auth.as_ref().map(|p| p.state.clone()).unwrap_or_default();
+        let callback = callback.map(|c| c.expect_state(&state));
+        if callback.is_some() {
+            // Si no se abre, la interfaz muestra la URL para copiarla
+            let _ = webbrowser::open(&url);
+        }
+        Reauthorization { url, callback, callback_error }
+    }
+
+    async fn complete_reauthorization(&mut self, input: &str) -> Result<()> {
+        let state = self.pending_auth.as_ref().map(|p| p.state.clone()).unwrap_or_default();
+        let code = Self::code_from_input(input, &state).map_err(|e| SpotifyError::Auth(e.to_string()))?;
+        self.exchange_pending_code(&code)
+            .await
+            .map_err(|e| SpotifyError::Auth(e.to_string()))
+    }
+
+    fn take_rate_limit_waits(&mut self) -> Vec<u64> {
+        std::mem::take(&mut self.rate_limit_waits)
+    }
+
+    fn take_api_calls(&mut self) -> Vec<ApiCall> {
+        self.api_calls.drain(..).collect()
+    }
+
+
+    async fn get_current_playback(&mut self) -> Result<Option<PlaybackState>> {
+        // Sin additional_types Spotify devuelve item nulo cuando suena un episodio
+        let response = self.send(Method::GET, "/me/player?additional_types=track,episode", None).await?;
+
+        if response.status() == 204 {
+            // No hay reproducción activa
+            return Ok(None);
+        }
+
+        if response.status().is_success() {
+            let playback_state: PlaybackState = response.json().await?;
+            if let Some(ref id) = playback_state.device.id {
+                self.last_device_id = Some(id.clone());
+            }
+            Ok(Some(playback_state))
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn play(&mut self) -> Result<()> {
+        let response = self.send_player(Method::PUT, "/me/player/play", None).await?;
+
+        if response.status().is_success() || response.status() == 204 {
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn pause(&mut self) -> Result<()> {
+        let response = self.send_player(Method::PUT, "/me/player/pause", None).await?;
+
+        if response.status().is_success() || response.status() == 204 {
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn next_track(&mut self) -> Result<()> {
+        let response = self.send_player(Method::POST, "/me/player/next", None).await?;
+
+        if response.status().is_success() || response.status() == 204 {
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn previous_track(&mut self) -> Result<()> {
+        let response = self.send_player(Method::POST, "/me/player/previous", None).await?;
+
+        if response.status().is_success() || response.status() == 204 {
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn set_volume(&mut self, volume: u8) -> Result<()> {
+        let response = self.send_player(Method::PUT, &format!("/me/player/volume?volume_percent={}", volume), None).await?;
+
+        if response.status().is_success() || response.status() == 204 {
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn seek(&mut self, position_ms: u64) -> Result<()> {
+        let response = self.send_player(Method::PUT, &format!("/me/player/seek?position_ms={}", position_ms), None).await?;
+
+        if response.status().is_success() || response.status() == 204 {
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_devices(&mut self) -> Result<Vec<Device>> {
+        let response = self.send(Method::GET, "/me/player/devices", None).await?;
+
+        if response.status().is_success() {
+            let devices: DevicesResponse = response.json().await?;
+            Ok(devices.devices)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn transfer_playback(&mut self, device_id: &str, play: bool) -> Result<()> {
+        let body = json!({
+            "device_ids": [device_id],
+            "play": play
+        });
+
+        let response = self.send(Method::PUT, "/me/player", Some(&body)).await?;
+
+        if response.status().is_success() || response.status() == 204 {
+            self.last_device_id = Some(device_id.to_string());
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    /// Busca varios tipos a la vez; Spotify devuelve una página por cada tipo pedido
+    async fn search(&mut self, query: &str, types: &[SearchType], limit: u8, offset: u32) -> Result<SearchResults> {
+        let encoded_query = urlencoding::encode(query);
+        let types = types.iter().map(|t| t.as_str()).collect::<Vec<_>>().join(",");
+        let market = self.market().await;
+
+        let path = format!(
+            "/search?q={}&type={}&market={}&limit={}&offset={}",
+            encoded_query, types, market, limit, offset
+        );
+        let response = self.send(Method::GET, &path, None).await?;
+
+        if response.status().is_success() {
+            let search_results: SearchResults = response.json().await?;
+            Ok(search_results)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn add_to_queue(&mut self, uri: &str) -> Result<()> {
+        let encoded_uri = urlencoding::encode(uri);
+
+        let response = self.send_player(Method::POST, &format!("/me/player/queue?uri={}", encoded_uri), None).await?;
+
+        if response.status().is_success() || response.status() == 204 {
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_queue(&mut self) -> Result<QueueResponse> {
+        let response = self.send(Method::GET, "/me/player/queue", None).await?;
+
+        if response.status().is_success() {
+            let queue: QueueResponse = response.json().await?;
+            Ok(queue)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_user_playlists(&mut self, limit: u8, offset: u32) -> Result<PlaylistsResponse> {
+        let path = format!("/me/playlists?limit={}&offset={}", limit, offset);
+        self.get_cached(&path, self.library_ttl()).await
+    }
+
+    async fn get_saved_tracks(&mut self, limit: u8, offset: u32) -> Result<SavedTracksResponse> {
+        let market = self.market().await;
+
+        let path = format!("/me/tracks?market={}&limit={}&offset={}", market, limit, offset);
+        self.get_cached(&path, self.library_ttl()).await
+    }
+
+    async fn get_saved_albums(&mut self, limit: u8, offset: u32) -> Result<SavedAlbumsResponse> {
+        let market = self.market().await;
+
+        let path = format!("/me/albums?market={}&limit={}&offset={}", market, limit, offset);
+        self.get_cached(&path, self.library_ttl()).await
+    }
+
+    async fn get_top_tracks(&mut self, time_range: TimeRange, limit: u8) -> Result<Vec<Track>> {
+        let response = self.send(Method::GET, &format!("/me/top/tracks?time_range={}&limit={}", time_range.as_str(), limit), None).await?;
+
+        if response.status().is_success() {
+            let top_tracks: TopTracksResponse = response.json().await?;
+            Ok(top_tracks.items)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_top_artists(&mut self, time_range: TimeRange, limit: u8) -> Result<Vec<FullArtist>> {
+        let response = self.send(Method::GET, &format!("/me/top/artists?time_range={}&limit={}", time_range.as_str(), limit), None).await?;
+
+        if response.status().is_success() {
+            let top_artists: TopArtistsResponse = response.json().await?;
+            Ok(top_artists.items)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_followed_artists(&mut self) -> Result<Vec<FullArtist>> {
+        let mut artists = Vec::new();
+        let mut after: Option<String> = None;
+        loop {
+            let mut path = "/me/following?type=artist&limit=50".to_string();
+            if let Some(ref cursor) = after {
+                path.push_str(&format!("&after={}", urlencoding::encode(cursor)));
+            }
+            let page: FollowedArtistsResponse = self.get_cached(&path, self.library_ttl()).await?;
+            let page = page.artists;
+            let received = page.items.len();
+            artists.extend(page.items);
+            after = page.cursors.and_then(|c| c.after);
+            if page.next.is_none() || after.is_none() || received == 0 {
+                return Ok(artists);
+            }
+        }
+    }
+
+    async fn get_artist_top_tracks(&mut self, artist_id: &str) -> Result<Vec<Track>> {
+        let market = self.market().await;
+
+        let path = format!("/artists/{}/top-tracks?market={}", artist_id, market);
+        let top_tracks: ArtistTopTracksResponse = self.get_cached(&path, self.catalog_ttl()).await?;
+        Ok(top_tracks.tracks)
+    }
+
+    async fn get_artist_albums(&mut self, artist_id: &str, limit: u8) -> Result<Vec<Album>> {
+        let market = self.market().await;
+
+        let path = format!(
+            "/artists/{}/albums?include_groups=album,single&market={}&limit={}",
+            artist_id, market, limit
+        );
+        let albums: AlbumSearchResult = self.get_cached(&path, self.catalog_ttl()).await?;
+        Ok(albums.items)
+    }
+
+    async fn get_related_artists(&mut self, artist_id: &str) -> Result<Vec<FullArtist>> {
+        let path = format!("/artists/{}/related-artists", artist_id);
+        let related: RelatedArtistsResponse = self.get_cached(&path, self.catalog_ttl()).await?;
+        Ok(related.artists)
+    }
+
+    async fn get_recommendations(&mut self, seed_tracks: &[String], seed_artists: &[String], limit: u8) -> Result<Vec<Track>> {
+        let market = self.market().await;
+
+        let path = format!(
+            "/recommendations?seed_tracks={}&seed_artists={}&market={}&limit={}",
+            seed_tracks.join(","),
+            seed_artists.join(","),
+            market,
+            limit
+        );
+        let response = self.send(Method::GET, &path, None).await?;
+
+        if response.status().is_success() {
+            let recommendations: RecommendationsResponse = response.json().await?;
+            Ok(recommendations.tracks)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_audio_analysis(&mut self, track_id: &str) -> Result<AudioAnalysis> {
+        let response = self.send(Method::GET, &format!("/audio-analysis/{}", track_id), None).await?;
+
+        if response.status().is_success() {
+            let analysis: AudioAnalysis = response.json().await?;
+            Ok(analysis)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_new_releases(&mut self, limit: u8, offset: u32) -> Result<AlbumSearchResult> {
+        let response = self.send(Method::GET, &format!("/browse/new-releases?limit={}&offset={}", limit, offset), None).await?;
+
+        if response.status().is_success() {
+            let new_releases: NewReleasesResponse = response.json().await?;
+            Ok(new_releases.albums)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_featured_playlists(&mut self, limit: u8) -> Result<(Option<String>, Vec<Playlist>)> {
+        let response = self.send(Method::GET, &format!("/browse/featured-playlists?limit={}", limit), None).await?;
+
+        if response.status().is_success() {
+            let featured: FeaturedPlaylistsResponse = response.json().await?;
+            let playlists = featured.playlists.items.into_iter().flatten().collect();
+            Ok((featured.message, playlists))
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_categories(&mut self, limit: u8) -> Result<Vec<Category>> {
+        let response = self.send(Method::GET, &format!("/browse/categories?limit={}", limit), None).await?;
+
+        if response.status().is_success() {
+            let categories: CategoriesResponse = response.json().await?;
+            Ok(categories.categories.items)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_category_playlists(&mut self, category_id: &str, limit: u8) -> Result<Vec<Playlist>> {
+        let response = self.send(Method::GET, &format!("/browse/categories/{}/playlists?limit={}", category_id, limit), None).await?;
+
+        if response.status().is_success() {
+            let category_playlists: CategoryPlaylistsResponse = response.json().await?;
+            Ok(category_playlists.playlists.items.into_iter().flatten().collect())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn get_user_profile(&mut self) -> Result<UserProfile> {
+        let response = self.send(Method::GET, "/me", None).await?;
+
+        if response.status().is_success() {
+            let profile: UserProfile = response.json().await?;
+            Ok(profile)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn create_playlist(&mut self, user_id: &str, name: &str, public: bool) -> Result<Playlist> {
+        let body = json!({
+            "name": name,
+            "public": public
+        });
+
+        let response = self.send(Method::POST, &format!("/users/{}/playlists", user_id), Some(&body)).await?;
+
+        if response.status().is_success() {
+            self.cache.invalidate("/me/playlists");
+            let playlist: Playlist = response.json().await?;
+            Ok(playlist)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn add_tracks_to_playlist(&mut self, playlist_id: &str, uris: &[String]) -> Result<()> {
+        // La API admite como máximo 100 URIs por petición
+        for chunk in uris.chunks(100) {
+            let body = json!({
+                "uris": chunk
+            });
+
+            let response = self.send(Method::POST, &format!("/playlists/{}/tracks", playlist_id), Some(&body)).await?;
+
+            if !response.status().is_success() {
+                return Err(SpotifyError::from_response(response).await);
+            }
+        }
+        // Cambian las canciones y el contador de la playlist
+        self.cache.invalidate(&format!("/playlists/{}/tracks", playlist_id));
+        self.cache.invalidate("/me/playlists");
+        Ok(())
+    }
+
+    async fn get_playlist_tracks(&mut self, playlist_id: &str, limit: u8, offset: u32) -> Result<PlaylistTracksResponse> {
+        let market = self.market().await;
+
+        let path = format!(
+            "/playlists/{}/tracks?market={}&limit={}&offset={}",
+            playlist_id, market, limit, offset
+        );
+        self.get_cached(&path, self.library_ttl()).await
+    }
+
+    async fn get_album_tracks(&mut self, album_id: &str, limit: u8, offset: u32) -> Result<AlbumTracksResponse> {
+        let market = self.market().await;
+
+        let path = format!("/albums/{}/tracks?market={}&limit={}&offset={}", album_id, market, limit, offset);
+        self.get_cached(&path, self.catalog_ttl()).await
+    }
+
+    async fn reorder_playlist_tracks(&mut self, playlist_id: &str, range_start: usize, insert_before: usize) -> Result<String> {
+        let body = json!({
+            "range_start": range_start,
+            "insert_before": insert_before,
+            "range_length": 1
+        });
+
+        let response = self.send(Method::PUT, &format!("/playlists/{}/tracks", playlist_id), Some(&body)).await?;
+
+        if response.status().is_success() {
+            self.cache.invalidate(&format!("/playlists/{}/tracks", playlist_id));
+            let snapshot: SnapshotResponse = response.json().await?;
+            Ok(snapshot.snapshot_id)
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn remove_playlist_tracks(&mut self, playlist_id: &str, uris: &[String]) -> Result<()> {
+        // La API admite como máximo 100 canciones por petición
+        for chunk in uris.chunks(100) {
+            let tracks: Vec<_> = chunk.iter().map(|uri| json!({ "uri": uri })).collect();
+            let body = json!({
+                "tracks": tracks
+            });
+
+            let response = self.send(Method::DELETE, &format!("/playlists/{}/tracks", playlist_id), Some(&body)).await?;
+
+            if !response.status().is_success() {
+                return Err(SpotifyError::from_response(response).await);
+            }
+        }
+        self.cache.invalidate(&format!("/playlists/{}/tracks", playlist_id));
+        self.cache.invalidate("/me/playlists");
+        Ok(())
+    }
+
+    async fn update_playlist_details(&mut self, playlist_id: &str, name: &str, description: &str) -> Result<()> {
+        let body = json!({
+            "name": name,
+            "description": description
+        });
+
+        let response = self.send(Method::PUT, &format!("/playlists/{}", playlist_id), Some(&body)).await?;
+
+        if response.status().is_success() {
+            self.cache.invalidate("/me/playlists");
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn follow_playlist(&mut self, playlist_id: &str) -> Result<()> {
+        let response = self.send(Method::PUT, &format!("/playlists/{}/followers", playlist_id), None).await?;
+
+        if response.status().is_success() {
+            self.cache.invalidate("/me/playlists");
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn save_tracks(&mut self, ids: &[String]) -> Result<()> {
+        // La API admite como máximo 50 ids por petición
+        for chunk in ids.chunks(50) {
+            let response = self.send(Method::PUT, &format!("/me/tracks?ids={}", chunk.join(",")), None).await?;
+
+            if !response.status().is_success() {
+                return Err(SpotifyError::from_response(response).await);
+            }
+        }
+        self.cache.invalidate("/me/tracks");
+        Ok(())
+    }
+
+    async fn remove_saved_tracks(&mut self, ids: &[String]) -> Result<()> {
+        // La API admite como máximo 50 ids por petición
+        for chunk in ids.chunks(50) {
+            let response = self.send(Method::DELETE, &format!("/me/tracks?ids={}", chunk.join(",")), None).await?;
+
+            if !response.status().is_success() {
+                return Err(SpotifyError::from_response(response).await);
+            }
+        }
+        self.cache.invalidate("/me/tracks");
+        Ok(())
+    }
+
+    async fn check_saved_tracks(&mut self, ids: &[String]) -> Result<Vec<bool>> {
+        let mut saved = Vec::with_capacity(ids.len());
+
+        // La API admite como máximo 50 ids por petición
+        for chunk in ids.chunks(50) {
+            let response = self.send(Method::GET, &format!("/me/tracks/contains?ids={}", chunk.join(",")), None).await?;
+
+            if response.status().is_success() {
+                let chunk_saved: Vec<bool> = response.json().await?;
+                saved.extend(chunk_saved);
+            } else {
+                return Err(SpotifyError::from_response(response).await);
+            }
+        }
+        Ok(saved)
+    }
+
+    async fn get_saved_episodes(&mut self) -> Result<Vec<Episode>> {
+        let saved_episodes: SavedEpisodesResponse = self.get_cached("/me/episodes?limit=50", self.library_ttl()).await?;
+        Ok(saved_episodes.items.into_iter().map(|item| item.episode).collect())
+    }
+
+    async fn save_episodes(&mut self, ids: &[String]) -> Result<()> {
+        // La API admite como máximo 50 ids por petición
+        for chunk in ids.chunks(50) {
+            let response = self.send(Method::PUT, &format!("/me/episodes?ids={}", chunk.join(",")), None).await?;
+
+            if !response.status().is_success() {
+                return Err(SpotifyError::from_response(response).await);
+            }
+        }
+        self.cache.invalidate("/me/episodes");
+        Ok(())
+    }
+
+    async fn remove_saved_episodes(&mut self, ids: &[String]) -> Result<()> {
+        // La API admite como máximo 50 ids por petición
+        for chunk in ids.chunks(50) {
+            let response = self.send(Method::DELETE, &format!("/me/episodes?ids={}", chunk.join(",")), None).await?;
+
+            if !response.status().is_success() {
+                return Err(SpotifyError::from_response(response).await);
+            }
+        }
+        self.cache.invalidate("/me/episodes");
+        Ok(())
+    }
+
+    async fn unfollow_playlist(&mut self, playlist_id: &str) -> Result<()> {
+        let response = self.send(Method::DELETE, &format!("/playlists/{}/followers", playlist_id), None).await?;
+
+        if response.status().is_success() {
+            self.cache.invalidate("/me/playlists");
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    /// Inicia la reproducción de un contexto o lista de URIs, opcionalmente desde un elemento y una posición
+    async fn start_playback(&mut self, context: PlaybackContext, offset: Option<PlaybackOffset>, position_ms: Option<i64>) -> Result<()> {
+        let mut body = match context {
+            PlaybackContext::Context(context_uri) => json!({ "context_uri": context_uri }),
+            PlaybackContext::Uris(uris) => json!({ "uris": uris }),
+        };
+        match offset {
+            Some(PlaybackOffset::Position(position)) => body["offset"] = json!({ "position": position }),
+            Some(PlaybackOffset::Uri(uri)) => body["offset"] = json!({ "uri": uri }),
+            None => {}
+        }
+        if let Some(position_ms) = position_ms {
+            body["position_ms"] = json!(position_ms);
+        }
+
+        let response = self.send_player(Method::PUT, "/me/player/play", Some(&body)).await?;
+
+        if response.status().is_success() || response.status() == 204 {
+            Ok(())
+        } else {
+            Err(SpotifyError::from_response(response).await)
+        }
+    }
+
+    async fn toggle_shuffle(&mut self) -> Result<()> {
+        // Primero obtenemos el estado actual
+        if let Some(current_state) = self.get_current_playback().await? {
+            let new_shuffle_state = !current_state.shuffle_state;
+            let response = self.send_player(Method::PUT, &format!("/me/player/shuffle?state={}", new_shuffle_state), None).await?;
+
+            if response.status().is_success() || response.status() == 204 {
+                Ok(())
+            } else {
+                Err(SpotifyError::from_response(response).await)
+            }
+        } else {
+            Err(SpotifyError::NoActiveDevice)
+        }
+    }
+
+    async fn toggle_repeat(&mut self) -> Result<()> {
+        // Ciclar entre off -> context -> track -> off
+        if let Some(current_state) = self.get_current_playback().await? {
+            let new_repeat_state = match current_state.repeat_state.as_str() {
+                "off" => "context",
+                "context" => "track", 
+                "track" => "off",
+                _ => "off",
+            };
+            
+            let response = self.send_player(Method::PUT, &format!("/me/player/repeat?state={}", new_repeat_state), None).await?;
+
+            if response.status().is_success() || response.status() == 204 {
+                Ok(())
+            } else {
+                Err(SpotifyError::from_response(response).await)
+            }
+        } else {
+            Err(SpotifyError::NoActiveDevice)
+        }
+    }
+} 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REDIRECT: &str = "http://127.0.0.1:8888/callback";
+
+    fn error_text(result: anyhow::Result<String>) -> String {
+        result.unwrap_err().to_string()
+    }
+
+    #[test]
+    fn bare_code_is_taken_as_is() {
+        assert_eq!(SpotifyClient::code_from_input("  AQBx-123 \n", "s1").unwrap(), "AQBx-123");
+    }
+
+    #[test]
+    fn empty_input_fails() {
+        assert_eq!(error_text(SpotifyClient::code_from_input("   ", "s1")), "No se introdujo ningún código");
+    }
+
+    #[test]
+    fn code_from_redirect_url() {
+        let url = format!("{}?code=AQBx%2D123&state=s1", REDIRECT);
+        assert_eq!(SpotifyClient::code_from_input(&url, "s1").unwrap(), "AQBx-123");
+    }
+
+    #[test]
+    fn state_must_match() {
+        let wrong = format!("{}?code=abc&state=otro", REDIRECT);
+        let missing = format!("{}?code=abc", REDIRECT);
+        for url in [wrong, missing] {
+            assert_eq!(
+                error_text(SpotifyClient::code_from_input(&url, "s1")),
+                "El parámetro state no coincide; vuelve a iniciar la autenticación"
+            );
+        }
+    }
+
+    #[test]
+    fn url_without_code_fails() {
+        let url = format!("{}?state=s1", REDIRECT);
+        assert_eq!(error_text(SpotifyClient::code_from_input(&url, "s1")), "No se encontró el código en la URL");
+    }
+
+    #[test]
+    fn spotify_errors_are_reported() {
+        let denied = format!("{}?error=access_denied&state=s1", REDIRECT);
+        assert_eq!(
+            error_text(SpotifyClient::code_from_input(&denied, "s1")),
+            "Has denegado el acceso a SpotiGod en Spotify"
+        );
+        let other = format!("{}?error=invalid_scope&state=s1", REDIRECT);
+        assert_eq!(
+            error_text(SpotifyClient::code_from_input(&other, "s1")),
+            "Spotify rechazó la autorización: invalid_scope"
+        );
+    }
+}
@@ -0,0 +1,99 @@
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Response, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use crate::tr;
+
+pub type Result<T> = std::result::Result<T, SpotifyError>;
+
+/// Errores del cliente de Spotify, clasificados para que la interfaz pueda reaccionar a cada uno.
+/// Se serializan tal cual para que el demonio se los pase a sus clientes
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "detail", rename_all = "snake_case")]
+pub enum SpotifyError {
+    /// Token ausente, caducado o rechazado
+    Unauthorized,
+    NoActiveDevice,
+    RateLimited { retry_after: u64 },
+    PremiumRequired,
+    Forbidden(String),
+    Network(String),
+    Parse(String),
+    /// No se pudo guardar la configuración con los tokens renovados
+    Storage(String),
+    /// Falló el flujo OAuth al volver a autorizar
+    Auth(String),
+    ApiError { status: u16, message: String },
+    /// Se perdió la conexión con el demonio (`spotigod daemon`)
+    Daemon(String),
+}
+
+impl SpotifyError {
+    /// Clasifica una respuesta no exitosa a partir del código y del cuerpo de error de Spotify
+    pub async fn from_response(response: Response) -> Self {
+        let status = response.status();
+        let retry_after = response
+            .headers()
+            .get(RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse::<u64>().ok())
+            .unwrap_or(1);
+        let body: serde_json::Value = response.json().await.unwrap_or_default();
+        let message = body["error"]["message"]
+            .as_str()
+            .or_else(|| body["error_description"].as_str())
+            .unwrap_or_else(|| status.canonical_reason().unwrap_or(""))
+            .to_string();
+        let reason = body["error"]["reason"].as_str().unwrap_or_default();
+
+        match status {
+            StatusCode::UNAUTHORIZED => SpotifyError::Unauthorized,
+            StatusCode::TOO_MANY_REQUESTS => SpotifyError::RateLimited { retry_after },
+            StatusCode::FORBIDDEN if reason == "PREMIUM_REQUIRED" || message.contains("Premium") => SpotifyError::PremiumRequired,
+            StatusCode::FORBIDDEN => SpotifyError::Forbidden(message),
+            StatusCode::NOT_FOUND if reason == "NO_ACTIVE_DEVICE" || message.contains("No active device") => {
+                SpotifyError::NoActiveDevice
+            }
+            _ => SpotifyError::ApiError { status: status.as_u16(), message },
+        }
+    }
+}
+
+impl fmt::Display for SpotifyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpotifyError::Unauthorized => write!(f, "{}", tr!("No autorizado: la sesión ha caducado")),
+            SpotifyError::NoActiveDevice => write!(f, "{}", tr!("No hay ningún dispositivo activo")),
+            SpotifyError::RateLimited { retry_after } => {
+                write!(f, "{}", tr!("Límite de peticiones alcanzado (reintentar en {}s)", retry_after))
+            }
+            SpotifyError::PremiumRequired => write!(f, "{}", tr!("Se requiere Spotify Premium")),
+            SpotifyError::Forbidden(message) => write!(f, "{}", tr!("Acceso denegado: {}", message)),
+            SpotifyError::Network(e) => write!(f, "{}", tr!("Error de red: {}", e)),
+            SpotifyError::Parse(message) => write!(f, "{}", tr!("Respuesta inesperada de Spotify: {}", message)),
+            SpotifyError::Storage(message) => write!(f, "{}", tr!("Error al guardar la configuración: {}", message)),
+            SpotifyError::Auth(message) => write!(f, "{}", tr!("Error de autenticación: {}", message)),
+            SpotifyError::ApiError { status, message } => write!(f, "{}", tr!("Error de la API ({}): {}", status, message)),
+            SpotifyError::Daemon(message) => write!(f, "{}", tr!("Error de conexión con el demonio: {}", message)),
+        }
+    }
+}
+
+impl std::error::Error for SpotifyError {}
+
+impl From<reqwest::Error> for SpotifyError {
+    fn from(e: reqwest::Error) -> Self {
+        if e.is_decode() {
+            SpotifyError::Parse(e.to_string())
+        } else {
+            SpotifyError::Network(e.to_string())
+        }
+    }
+}
+
+impl From<serde_json::Error> for SpotifyError {
+    fn from(e: serde_json::Error) -> Self {
+        SpotifyError::Parse(e.to_string())
+    }
+}
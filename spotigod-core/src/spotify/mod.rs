@@ -0,0 +1,12 @@
+pub mod models;
+pub mod api;
+pub mod callback;
+pub mod client;
+pub mod error;
+mod cache;
+mod rate_limiter;
+
+pub use api::{ApiCall, Reauthorization, SpotifyApi};
+pub use client::SpotifyClient;
+pub use error::SpotifyError;
+pub use models::*; 
\ No newline at end of file
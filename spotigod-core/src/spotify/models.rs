@@ -0,0 +1,680 @@
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Trata `null` como el valor por defecto (los archivos locales llegan con ids nulos)
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    pub refresh_token: Option<String>,
+    pub scope: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Track {
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub id: String,
+    pub name: String,
+    pub artists: Vec<Artist>,
+    pub album: Album,
+    pub duration_ms: i64,
+    #[serde(default)]
+    pub explicit: bool,
+    pub external_urls: ExternalUrls,
+    #[serde(default)]
+    pub popularity: i32,
+    /// Archivo local del usuario: no tiene id ni se puede reproducir por la API
+    #[serde(default)]
+    pub is_local: bool,
+}
+
+impl Track {
+    /// URI `spotify:track:`, o `None` si es un archivo local
+    pub fn uri(&self) -> Option<String> {
+        if self.is_local || self.id.is_empty() {
+            None
+        } else {
+            Some(format!("spotify:track:{}", self.id))
+        }
+    }
+
+    pub fn is_playable(&self) -> bool {
+        self.uri().is_some()
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Artist {
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub id: String,
+    pub name: String,
+    pub external_urls: ExternalUrls,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FullArtist {
+    pub id: String,
+    pub name: String,
+    pub genres: Vec<String>,
+    pub followers: Followers,
+    pub popularity: i32,
+    pub images: Vec<Image>,
+    pub external_urls: ExternalUrls,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Album {
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub id: String,
+    pub name: String,
+    pub artists: Vec<Artist>,
+    #[serde(default)]
+    pub images: Vec<Image>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub release_date: String,
+    pub external_urls: ExternalUrls,
+    #[serde(default)]
+    pub album_type: String,
+    #[serde(default)]
+    pub total_tracks: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Image {
+    pub height: Option<i32>,
+    pub url: String,
+    pub width: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ExternalUrls {
+    #[serde(default)]
+    pub spotify: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlaybackState {
+    pub device: Device,
+    pub repeat_state: String,
+    pub shuffle_state: bool,
+    pub context: Option<Context>,
+    pub timestamp: i64,
+    pub progress_ms: Option<i64>,
+    pub is_playing: bool,
+    pub item: Option<PlayableItem>,
+    pub currently_playing_type: String,
+    pub actions: Actions,
+}
+
+impl PlaybackState {
+    /// Canción que suena, o `None` si es un episodio o no hay nada
+    pub fn track(&self) -> Option<&Track> {
+        self.item.as_ref().and_then(PlayableItem::track)
+    }
+}
+
+/// Lo que está sonando: una canción o un episodio de podcast
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PlayableItem {
+    Track(Track),
+    Episode(Episode),
+}
+
+impl PlayableItem {
+    pub fn track(&self) -> Option<&Track> {
+        match self {
+            PlayableItem::Track(track) => Some(track),
+            PlayableItem::Episode(_) => None,
+        }
+    }
+
+    pub fn duration_ms(&self) -> i64 {
+        match self {
+            PlayableItem::Track(track) => track.duration_ms,
+            PlayableItem::Episode(episode) => episode.duration_ms,
+        }
+    }
+
+    pub fn id(&self) -> &str {
+        match self {
+            PlayableItem::Track(track) => &track.id,
+            PlayableItem::Episode(episode) => &episode.id,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            PlayableItem::Track(track) => &track.name,
+            PlayableItem::Episode(episode) => &episode.name,
+        }
+    }
+
+    /// URI `spotify:track:` o `spotify:episode:`; los archivos locales no tienen
+    pub fn uri(&self) -> Option<String> {
+        match self {
+            PlayableItem::Track(track) => track.uri(),
+            PlayableItem::Episode(episode) => Some(format!("spotify:episode:{}", episode.id)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Device {
+    pub id: Option<String>,
+    pub is_active: bool,
+    pub is_private_session: bool,
+    pub is_restricted: bool,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub device_type: String,
+    pub volume_percent: Option<i32>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DevicesResponse {
+    pub devices: Vec<Device>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Context {
+    pub external_urls: ExternalUrls,
+    pub href: String,
+    #[serde(rename = "type")]
+    pub context_type: String,
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Actions {
+    pub interrupting_playback: Option<bool>,
+    pub pausing: Option<bool>,
+    pub resuming: Option<bool>,
+    pub seeking: Option<bool>,
+    pub skipping_next: Option<bool>,
+    pub skipping_prev: Option<bool>,
+    pub toggling_repeat_context: Option<bool>,
+    pub toggling_shuffle: Option<bool>,
+    pub toggling_repeat_track: Option<bool>,
+    pub transferring_playback: Option<bool>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct QueueResponse {
+    /// La cola puede mezclar canciones y episodios
+    pub currently_playing: Option<PlayableItem>,
+    pub queue: Vec<PlayableItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchType {
+    Track,
+    Album,
+    Artist,
+    Playlist,
+    Show,
+}
+
+impl SearchType {
+    pub const ALL: [SearchType; 5] = [
+        SearchType::Track,
+        SearchType::Album,
+        SearchType::Artist,
+        SearchType::Playlist,
+        SearchType::Show,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SearchType::Track => "track",
+            SearchType::Album => "album",
+            SearchType::Artist => "artist",
+            SearchType::Playlist => "playlist",
+            SearchType::Show => "show",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            SearchType::Track => SearchType::Album,
+            SearchType::Album => SearchType::Artist,
+            SearchType::Artist => SearchType::Playlist,
+            SearchType::Playlist => SearchType::Show,
+            SearchType::Show => SearchType::Track,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SearchResults {
+    pub tracks: Option<TrackSearchResult>,
+    pub albums: Option<AlbumSearchResult>,
+    pub artists: Option<ArtistSearchResult>,
+    pub playlists: Option<PlaylistSearchResult>,
+    pub shows: Option<ShowSearchResult>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TrackSearchResult {
+    pub href: String,
+    pub items: Vec<Track>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AlbumSearchResult {
+    pub href: String,
+    pub items: Vec<Album>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ArtistSearchResult {
+    pub href: String,
+    pub items: Vec<FullArtist>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ShowSearchResult {
+    pub href: String,
+    // Igual que con las playlists, puede haber entradas nulas
+    pub items: Vec<Option<Show>>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlaylistSearchResult {
+    pub href: String,
+    // Spotify puede devolver entradas nulas en las búsquedas de playlists
+    pub items: Vec<Option<Playlist>>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct NewReleasesResponse {
+    pub albums: AlbumSearchResult,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FeaturedPlaylistsResponse {
+    pub message: Option<String>,
+    pub playlists: PlaylistSearchResult,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Category {
+    pub id: String,
+    pub name: String,
+    pub href: String,
+    pub icons: Vec<Image>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CategoriesResponse {
+    pub categories: CategoryPage,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CategoryPage {
+    pub href: String,
+    pub items: Vec<Category>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CategoryPlaylistsResponse {
+    pub playlists: PlaylistSearchResult,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct UserProfile {
+    pub id: String,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub country: Option<String>,
+    pub followers: Followers,
+    pub images: Vec<Image>,
+    pub product: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Followers {
+    pub href: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Playlist {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub images: Vec<Image>,
+    pub owner: PlaylistOwner,
+    pub public: Option<bool>,
+    pub tracks: PlaylistTracks,
+    pub external_urls: ExternalUrls,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlaylistOwner {
+    pub id: String,
+    pub display_name: Option<String>,
+    pub external_urls: ExternalUrls,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlaylistTracks {
+    pub href: String,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlaylistsResponse {
+    pub href: String,
+    pub items: Vec<Playlist>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedTracksResponse {
+    pub href: String,
+    pub items: Vec<SavedTrackItem>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedTrackItem {
+    pub added_at: String,
+    pub track: Track,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedAlbumsResponse {
+    pub href: String,
+    pub items: Vec<SavedAlbumItem>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedAlbumItem {
+    pub added_at: String,
+    pub album: Album,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlaylistTracksResponse {
+    pub href: String,
+    pub items: Vec<PlaylistTrackItem>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PlaylistTrackItem {
+    pub added_at: String,
+    pub track: Option<Track>,
+} 
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeRange {
+    Short,
+    Medium,
+    Long,
+}
+
+impl TimeRange {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TimeRange::Short => "short_term",
+            TimeRange::Medium => "medium_term",
+            TimeRange::Long => "long_term",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        match self {
+            TimeRange::Short => TimeRange::Medium,
+            TimeRange::Medium => TimeRange::Long,
+            TimeRange::Long => TimeRange::Short,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TopTracksResponse {
+    pub href: String,
+    pub items: Vec<Track>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TopArtistsResponse {
+    pub href: String,
+    pub items: Vec<FullArtist>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+/// Respuesta de `/me/following`: los artistas van en una página paginada por cursor
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FollowedArtistsResponse {
+    pub artists: FollowedArtistsPage,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FollowedArtistsPage {
+    pub items: Vec<FullArtist>,
+    pub next: Option<String>,
+    pub cursors: Option<Cursors>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Cursors {
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RecommendationsResponse {
+    pub seeds: Vec<RecommendationSeed>,
+    pub tracks: Vec<Track>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RecommendationSeed {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub seed_type: String,
+    pub href: Option<String>,
+    #[serde(rename = "initialPoolSize")]
+    pub initial_pool_size: i32,
+    #[serde(rename = "afterFilteringSize")]
+    pub after_filtering_size: i32,
+    #[serde(rename = "afterRelinkingSize")]
+    pub after_relinking_size: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AudioAnalysis {
+    pub segments: Vec<AudioSegment>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AudioSegment {
+    pub start: f64,
+    pub duration: f64,
+    pub loudness_start: f64,
+    pub loudness_max: f64,
+    pub loudness_max_time: f64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Episode {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub duration_ms: i64,
+    pub release_date: String,
+    pub resume_point: Option<ResumePoint>,
+    pub show: Show,
+    pub external_urls: ExternalUrls,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ResumePoint {
+    pub fully_played: bool,
+    pub resume_position_ms: i64,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct Show {
+    pub id: String,
+    pub name: String,
+    pub publisher: String,
+    pub external_urls: ExternalUrls,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedEpisodesResponse {
+    pub href: String,
+    pub items: Vec<SavedEpisodeItem>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SavedEpisodeItem {
+    pub added_at: String,
+    pub episode: Episode,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SnapshotResponse {
+    pub snapshot_id: String,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ArtistTopTracksResponse {
+    pub tracks: Vec<Track>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct RelatedArtistsResponse {
+    pub artists: Vec<FullArtist>,
+}
+
+/// Canción simplificada tal como la devuelve /albums/{id}/tracks (sin álbum ni popularidad)
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AlbumTrack {
+    pub id: String,
+    pub name: String,
+    pub artists: Vec<Artist>,
+    pub duration_ms: i64,
+    pub explicit: bool,
+    pub external_urls: ExternalUrls,
+    pub track_number: i32,
+}
+
+impl AlbumTrack {
+    /// Completa la canción con el álbum al que pertenece
+    pub fn with_album(self, album: &Album) -> Track {
+        Track {
+            id: self.id,
+            name: self.name,
+            artists: self.artists,
+            album: album.clone(),
+            duration_ms: self.duration_ms,
+            explicit: self.explicit,
+            external_urls: self.external_urls,
+            popularity: 0,
+            is_local: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AlbumTracksResponse {
+    pub href: String,
+    pub items: Vec<AlbumTrack>,
+    pub limit: i32,
+    pub next: Option<String>,
+    pub offset: i32,
+    pub previous: Option<String>,
+    pub total: i32,
+}
+
+/// Qué reproducir: un contexto (álbum, playlist, artista...) o una lista suelta de URIs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybackContext {
+    Context(String),
+    Uris(Vec<String>),
+}
+
+/// Elemento del contexto por el que empezar la reproducción
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlaybackOffset {
+    Position(usize),
+    Uri(String),
+}
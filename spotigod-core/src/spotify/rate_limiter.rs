@@ -0,0 +1,86 @@
+use tokio::time::{sleep, Duration, Instant};
+
+/// Limitador de peticiones tipo token bucket: admite ráfagas de hasta `capacity`
+/// peticiones y después deja pasar `refill_per_sec` por segundo.
+pub struct RateLimiter {
+    capacity: f64,
+    tokens: f64,
+    refill_per_sec: f64,
+    last_refill: Instant,
+}
+
+impl RateLimiter {
+    pub fn new(requests_per_second: f64) -> Self {
+        // Nunca por debajo de una petición cada diez segundos para no bloquear la interfaz
+        let refill_per_sec = requests_per_second.max(0.1);
+        let capacity = refill_per_sec.max(1.0);
+        Self {
+            capacity,
+            tokens: capacity,
+            refill_per_sec,
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Espera hasta que haya un token disponible y lo consume
+    pub async fn acquire(&mut self) {
+        self.refill();
+        if self.tokens < 1.0 {
+            let missing = 1.0 - self.tokens;
+            sleep(Duration::from_secs_f64(missing / self.refill_per_sec)).await;
+            self.refill();
+        }
+        self.tokens = (self.tokens - 1.0).max(0.0);
+    }
+
+    fn refill(&mut self) {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.refill_per_sec).min(self.capacity);
+        self.last_refill = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(start_paused = true)]
+    async fn burst_then_waits_for_refill() {
+        let mut limiter = RateLimiter::new(2.0);
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn refills_while_idle_up_to_capacity() {
+        let mut limiter = RateLimiter::new(2.0);
+        limiter.acquire().await;
+        limiter.acquire().await;
+
+        // Tras mucho tiempo parado solo se recupera la ráfaga completa, no más
+        tokio::time::advance(Duration::from_secs(10)).await;
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::ZERO);
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::from_millis(500));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn rate_has_a_floor() {
+        let mut limiter = RateLimiter::new(0.0);
+        let start = Instant::now();
+        limiter.acquire().await;
+        limiter.acquire().await;
+        assert_eq!(start.elapsed(), Duration::from_secs(10));
+    }
+}
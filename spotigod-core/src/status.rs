@@ -0,0 +1,168 @@
+use crate::spotify::{PlayableItem, PlaybackState, Track};
+use serde::Serialize;
+
+/// Estado de la reproducción en un formato estable para scripts (`spotigod status --json`).
+/// Los campos no cambian de nombre entre versiones; los que no se conocen salen como `null`
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PlaybackStatus {
+    /// Hay una sesión de reproducción en algún dispositivo
+    pub active: bool,
+    pub playing: bool,
+    /// `track` o `episode`
+    pub kind: Option<String>,
+    pub id: Option<String>,
+    pub title: Option<String>,
+    /// Artistas separados por comas, o el podcast si es un episodio
+    pub artist: Option<String>,
+    pub artists: Vec<String>,
+    /// Álbum, o el podcast si es un episodio
+    pub album: Option<String>,
+    pub progress_ms: Option<i64>,
+    pub duration_ms: Option<i64>,
+    pub device: Option<String>,
+    pub volume: Option<i32>,
+    pub shuffle: bool,
+    /// `off`, `track` o `context`
+    pub repeat: String,
+    /// Carátula más grande del álbum
+    pub art_url: Option<String>,
+    /// Enlace de open.spotify.com
+    pub url: Option<String>,
+}
+
+impl PlaybackStatus {
+    pub fn new(playback: Option<&PlaybackState>) -> Self {
+        let Some(playback) = playback else {
+            return Self { repeat: "off".to_string(), ..Self::default() };
+        };
+        let mut status = Self {
+            active: true,
+            playing: playback.is_playing,
+            progress_ms: playback.progress_ms,
+            device: Some(playback.device.name.clone()),
+            volume: playback.device.volume_percent,
+            shuffle: playback.shuffle_state,
+            repeat: playback.repeat_state.clone(),
+            ..Self::default()
+        };
+        match playback.item.as_ref() {
+            Some(PlayableItem::Track(track)) => status.set_track(track),
+            Some(PlayableItem::Episode(episode)) => {
+                status.kind = Some("episode".to_string());
+                status.id = Some(episode.id.clone());
+                status.title = Some(episode.name.clone());
+                status.artists = vec![episode.show.name.clone()];
+                status.artist = Some(episode.show.name.clone());
+                status.album = Some(episode.show.name.clone());
+                status.duration_ms = Some(episode.duration_ms);
+                status.url = Some(episode.external_urls.spotify.clone()).filter(|url| !url.is_empty());
+            }
+            None => {}
+        }
+        status
+    }
+
+    /// Datos de una canción que no tiene por qué estar sonando (p. ej. la que se marca con "me gusta")
+    pub fn for_track(track: &Track) -> Self {
+        let mut status = Self { repeat: "off".to_string(), ..Self::default() };
+        status.set_track(track);
+        status
+    }
+
+    fn set_track(&mut self, track: &Track) {
+        self.kind = Some("track".to_string());
+        self.id = Some(track.id.clone()).filter(|id| !id.is_empty());
+        self.title = Some(track.name.clone());
+        self.artists = track.artists.iter().map(|a| a.name.clone()).collect();
+        self.artist = Some(self.artists.join(", "));
+        self.album = Some(track.album.name.clone());
+        self.duration_ms = Some(track.duration_ms);
+        // Spotify las ordena de mayor a menor
+        self.art_url = track.album.images.first().map(|image| image.url.clone());
+        self.url = Some(track.external_urls.spotify.clone()).filter(|url| !url.is_empty());
+    }
+
+    /// Variables de entorno `SPOTIGOD_<CAMPO>` con los mismos valores que la plantilla de `render`
+    pub fn env(&self) -> Vec<(String, String)> {
+        let mut env = vec![("SPOTIGOD_KIND".to_string(), self.kind.clone().unwrap_or_default())];
+        for name in ENV_FIELDS {
+            let value = self.field(name).unwrap_or_default();
+            env.push((format!("SPOTIGOD_{}", name.to_uppercase()), value));
+        }
+        env
+    }
+
+    /// Una línea de JSON con todos los campos
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+
+    /// Sustituye en la plantilla `{title}`, `{artist}`, `{album}`, `{progress}`, `{duration}`,
+    /// `{progress_ms}`, `{duration_ms}`, `{state}` (▶/⏸), `{status}` (playing, paused o stopped),
+    /// `{device}`, `{volume}`, `{shuffle}`, `{repeat}`, `{art_url}`, `{url}` e `{id}`. Lo que no
+    /// se conoce queda vacío y las llaves que no son un campo se dejan tal cual
+    pub fn render(&self, template: &str) -> String {
+        let mut output = String::new();
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            output.push_str(&rest[..start]);
+            let field = rest[start + 1..].find('}').and_then(|end| {
+                let name = &rest[start + 1..start + 1 + end];
+                self.field(name).map(|value| (value, end))
+            });
+            match field {
+                Some((value, end)) => {
+                    output.push_str(&value);
+                    rest = &rest[start + end + 2..];
+                }
+                None => {
+                    output.push('{');
+                    rest = &rest[start + 1..];
+                }
+            }
+        }
+        output.push_str(rest);
+        output
+    }
+
+    fn field(&self, name: &str) -> Option<String> {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        let number = |value: Option<i64>| value.map(|v| v.to_string()).unwrap_or_default();
+        Some(match name {
+            "title" => text(&self.title),
+            "artist" => text(&self.artist),
+            "album" => text(&self.album),
+            "progress" => self.progress_ms.map(format_duration).unwrap_or_default(),
+            "duration" => self.duration_ms.map(format_duration).unwrap_or_default(),
+            "progress_ms" => number(self.progress_ms),
+            "duration_ms" => number(self.duration_ms),
+            "state" => if self.playing { "▶" } else { "⏸" }.to_string(),
+            "status" => match (self.active, self.playing) {
+                (false, _) => "stopped",
+                (true, true) => "playing",
+                (true, false) => "paused",
+            }
+            .to_string(),
+            "device" => text(&self.device),
+            "volume" => number(self.volume.map(i64::from)),
+            "shuffle" => self.shuffle.to_string(),
+            "repeat" => self.repeat.clone(),
+            "art_url" => text(&self.art_url),
+            "url" => text(&self.url),
+            "id" => text(&self.id),
+            _ => return None,
+        })
+    }
+}
+
+/// Campos de `render` que se pasan como variables de entorno
+const ENV_FIELDS: &[&str] = &[
+    "id", "title", "artist", "album", "progress_ms", "duration_ms", "status", "device", "volume", "shuffle", "repeat",
+    "art_url", "url",
+];
+
+/// "m:ss"
+pub fn format_duration(ms: i64) -> String {
+    let seconds = ms.max(0) / 1000;
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,47 @@
+use crate::config::Config;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+/// Estado de la interfaz que se recuerda entre sesiones, en `~/.local/state/spotigod/state.json`
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct UiState {
+    /// Vista activa al salir (`playlists`, `favorites`...)
+    #[serde(default)]
+    pub view: Option<String>,
+    /// Id de la playlist abierta al salir
+    #[serde(default)]
+    pub open_playlist: Option<String>,
+    /// Última búsqueda
+    #[serde(default)]
+    pub search_query: Option<String>,
+    /// Fila seleccionada en cada lista
+    #[serde(default)]
+    pub selections: HashMap<String, usize>,
+}
+
+impl UiState {
+    /// Lee el estado guardado; si no existe o no se puede leer se empieza de cero
+    pub fn load() -> Self {
+        Self::path()
+            .and_then(|path| Ok(fs::read_to_string(path)?))
+            .ok()
+            .and_then(|content| serde_json::from_str(&content).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    fn path() -> Result<PathBuf> {
+        Ok(Config::state_dir()?.join("state.json"))
+    }
+}
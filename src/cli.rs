@@ -0,0 +1,211 @@
+use anyhow::Result;
+use clap::{Parser, Subcommand};
+use spotigod_core::config::Config;
+use spotigod_core::spotify::SpotifyApi;
+use spotigod_core::status::PlaybackStatus;
+use spotigod_core::tr;
+use std::io::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use crate::ui::truncate;
+
+/// Plantilla de `statusline` si no hay ni `--format` ni `statusline_format` en el config
+const STATUSLINE_FORMAT: &str = "{state} {artist} - {title}";
+
+/// Cliente de Spotify para la terminal. Sin subcomando abre la interfaz
+#[derive(Debug, Parser)]
+#[command(name = "spotigod", version)]
+pub struct Cli {
+    /// Directorio para todos los archivos (configuración, sesión, estado y cachés) en lugar de
+    /// los de XDG; también con SPOTIGOD_CONFIG_DIR
+    #[arg(long, global = true, value_name = "DIR")]
+    pub config: Option<PathBuf>,
+    /// Abre la interfaz con el panel de las últimas peticiones a la API (también con F12)
+    #[arg(long)]
+    pub debug: bool,
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Clone, Subcommand)]
+pub enum Command {
+    /// Reanuda la reproducción
+    Play,
+    /// Pausa la reproducción
+    Pause,
+    /// Pasa a la siguiente canción
+    Next,
+    /// Vuelve a la canción anterior
+    Prev,
+    /// Pausa o reanuda según lo que esté haciendo
+    Toggle,
+    /// Cambia el volumen (0-100)
+    Volume {
+        #[arg(value_parser = clap::value_parser!(u8).range(0..=100))]
+        percent: u8,
+    },
+    /// Muestra lo que suena
+    Status {
+        /// Todo el estado en una línea de JSON
+        #[arg(long, conflicts_with = "format")]
+        json: bool,
+        /// Plantilla de una línea, como "{artist} - {title} [{progress}/{duration}]"
+        #[arg(long)]
+        format: Option<String>,
+    },
+    /// Línea corta para barras de estado (waybar, polybar, tmux...); vacía si no suena nada
+    Statusline {
+        /// Plantilla con los mismos campos que `status --format`
+        #[arg(long)]
+        format: Option<String>,
+        /// Columnas como mucho; lo que sobra se corta con "…"
+        #[arg(long)]
+        max_width: Option<usize>,
+        /// No termina: escribe una línea nueva cada vez que cambia
+        #[arg(long)]
+        follow: bool,
+        /// Segundos entre consultas a Spotify con --follow
+        #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+    /// Se queda en segundo plano con la sesión abierta; la interfaz y los subcomandos lo usan
+    /// en lugar de hablar cada uno con Spotify
+    #[cfg(unix)]
+    Daemon,
+    /// Comprueba la configuración, la sesión, la red, los dispositivos y la terminal, y
+    /// explica cómo arreglar lo que falle
+    Doctor,
+    /// Borra los tokens y la biblioteca guardada
+    Logout {
+        /// Autoriza otra cuenta a continuación
+        #[arg(long)]
+        login: bool,
+    },
+}
+
+impl Command {
+    /// Las órdenes de reproducción se hacen con una sola petición, sin abrir la interfaz
+    pub fn is_remote(&self) -> bool {
+        match self {
+            #[cfg(unix)]
+            Command::Daemon => false,
+            Command::Logout { .. } | Command::Doctor => false,
+            _ => true,
+        }
+    }
+}
+
+/// Ejecuta una orden de reproducción y escribe el resultado en una línea
+pub async fn run(command: Command, client: &mut impl SpotifyApi, config: &Config) -> Result<()> {
+    match command {
+        Command::Play => client.play().await?,
+        Command::Pause => client.pause().await?,
+        Command::Next => client.next_track().await?,
+        Command::Prev => client.previous_track().await?,
+        Command::Toggle => {
+            let playing = client.get_current_playback().await?.is_some_and(|playback| playback.is_playing);
+            if playing {
+                client.pause().await?;
+            } else {
+                client.play().await?;
+            }
+        }
+        Command::Volume { percent } => client.set_volume(percent).await?,
+        Command::Status { json, format } => {
+            let playback = client.get_current_playback().await?;
+            let status = PlaybackStatus::new(playback.as_ref());
+            match format {
+                _ if json => println!("{}", status.to_json()),
+                Some(template) => println!("{}", status.render(&template)),
+                None if status.title.is_none() => println!("{}", tr!("No hay nada sonando")),
+                None => println!("{}", status.render("{state} {title} - {artist} ({progress} / {duration})")),
+            }
+        }
+        Command::Statusline { format, max_width, follow, interval } => {
+            let template = format
+                .or_else(|| config.statusline_format.clone())
+                .unwrap_or_else(|| STATUSLINE_FORMAT.to_string());
+            let max_width = max_width.or(config.statusline_max_width);
+            if follow {
+                follow_statusline(client, &template, max_width, Duration::from_secs(interval)).await?;
+            } else {
+                let playback = client.get_current_playback().await?;
+                let status = PlaybackStatus::new(playback.as_ref());
+                println!("{}", statusline(&status, &template, max_width));
+            }
+        }
+        #[cfg(unix)]
+        Command::Daemon => unreachable!("el demonio se atiende antes de conectar"),
+        Command::Logout { .. } => unreachable!("logout se atiende antes de conectar"),
+        Command::Doctor => unreachable!("doctor se atiende antes de conectar"),
+    }
+    Ok(())
+}
+
+fn statusline(status: &PlaybackStatus, template: &str, max_width: Option<usize>) -> String {
+    if status.title.is_none() {
+        return String::new();
+    }
+    let line = status.render(template);
+    match max_width {
+        Some(width) => truncate(&line, width).into_owned(),
+        None => line,
+    }
+}
+
+/// Consulta el estado cada `interval` y entre medias adelanta el progreso cada segundo; solo
+/// escribe cuando la línea cambia, para que la barra no se redibuje sin motivo
+async fn follow_statusline(
+    client: &mut impl SpotifyApi,
+    template: &str,
+    max_width: Option<usize>,
+    interval: Duration,
+) -> Result<()> {
+    let mut stdout = std::io::stdout();
+    let mut ticker = tokio::time::interval(Duration::from_secs(1));
+    let mut fetched: Option<(PlaybackStatus, Instant)> = None;
+    let mut last_line: Option<String> = None;
+    loop {
+        ticker.tick().await;
+        let current = fetched.as_ref().map(|(status, at)| advance(status, at.elapsed()));
+        // Al acabar la canción se pregunta sin esperar al intervalo, para no quedarse en la anterior
+        let stale = match (&current, &fetched) {
+            (Some(status), Some((_, at))) => {
+                let ended = status.playing && status.duration_ms.is_some() && status.progress_ms >= status.duration_ms;
+                at.elapsed() >= interval || ended
+            }
+            _ => true,
+        };
+        let status = if stale {
+            // Un corte de red no cierra la barra: la línea queda vacía hasta la siguiente consulta
+            let status = match client.get_current_playback().await {
+                Ok(playback) => PlaybackStatus::new(playback.as_ref()),
+                Err(_) => PlaybackStatus::default(),
+            };
+            fetched = Some((status.clone(), Instant::now()));
+            status
+        } else {
+            current.unwrap_or_default()
+        };
+        let line = statusline(&status, template, max_width);
+        if last_line.as_ref() != Some(&line) {
+            // Si quien lee cierra la tubería se termina sin más
+            writeln!(stdout, "{line}")?;
+            stdout.flush()?;
+            last_line = Some(line);
+        }
+    }
+}
+
+/// El estado consultado hace `elapsed`, con el progreso adelantado si está sonando
+fn advance(status: &PlaybackStatus, elapsed: Duration) -> PlaybackStatus {
+    let mut status = status.clone();
+    if status.playing {
+        status.progress_ms = status.progress_ms.map(|progress| {
+            let progress = progress + elapsed.as_millis() as i64;
+            status.duration_ms.map_or(progress, |duration| progress.min(duration))
+        });
+    }
+    status
+}
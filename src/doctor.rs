@@ -0,0 +1,377 @@
+//! `spotigod doctor`: comprueba la configuración, la sesión, la red, los dispositivos y la
+//! terminal, y dice cómo arreglar lo que falle.
+
+use anyhow::{anyhow, Result};
+use colored::Colorize;
+use spotigod_core::config::Config;
+#[cfg(unix)]
+use spotigod_core::daemon::DaemonClient;
+use spotigod_core::i18n::{self, Locale};
+use spotigod_core::spotify::callback::CallbackListener;
+use spotigod_core::spotify::client::SCOPES;
+use spotigod_core::spotify::{SpotifyApi, SpotifyClient, SpotifyError};
+use spotigod_core::tr;
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
+use tracing_subscriber::filter::LevelFilter;
+
+use crate::ui::{Keymap, ScriptEngine, Theme};
+
+/// Servicios de Spotify a los que tiene que llegar SpotiGod
+const ENDPOINTS: &[&str] = &["https://accounts.spotify.com", "https://api.spotify.com/v1/"];
+
+/// Tamaño a partir del cual caben todas las columnas de las tablas
+const MIN_TERMINAL_SIZE: (u16, u16) = (80, 24);
+
+/// Resultados de las comprobaciones, que se escriben según se hacen
+#[derive(Default)]
+struct Report {
+    problems: usize,
+    warnings: usize,
+}
+
+impl Report {
+    fn ok(&mut self, check: &str, detail: &str) {
+        println!("✅ {}: {}", check.bold(), detail);
+    }
+
+    fn info(&mut self, check: &str, detail: &str) {
+        println!("ℹ️  {}: {}", check.bold(), detail);
+    }
+
+    fn warn(&mut self, check: &str, detail: &str, fix: &str) {
+        self.warnings += 1;
+        println!("⚠️  {}: {}", check.bold(), detail.yellow());
+        println!("   → {}", fix);
+    }
+
+    fn fail(&mut self, check: &str, detail: &str, fix: &str) {
+        self.problems += 1;
+        println!("❌ {}: {}", check.bold(), detail.red());
+        println!("   → {}", fix);
+    }
+}
+
+/// Hace todas las comprobaciones; termina con error si alguna falla
+pub async fn run() -> Result<()> {
+    let mut report = Report::default();
+    println!("{}", tr!("🩺 Comprobando SpotiGod...").bright_green().bold());
+
+    let config = check_config(&mut report).await;
+    let online = check_network(&mut report).await;
+    if let Some(config) = &config {
+        check_session(&mut report, config, online).await;
+        check_redirect_uri(&mut report, config);
+    }
+    let theme = config
+        .as_ref()
+        .and_then(|config| Theme::from_config(config.theme_preset.as_deref(), &config.theme, config.accessible).ok());
+    check_terminal(&mut report, config.as_ref(), theme.as_ref());
+
+    println!();
+    match (report.problems, report.warnings) {
+        (0, 0) => {
+            println!("{}", tr!("✅ Todo en orden").green().bold());
+            Ok(())
+        }
+        (0, warnings) => {
+            println!("{}", tr!("Sin problemas; {} avisos", warnings).yellow().bold());
+            Ok(())
+        }
+        (problems, _) => Err(anyhow!(tr!("{} comprobaciones fallaron", problems))),
+    }
+}
+
+/// config.toml se puede leer y sus teclas, tema, idioma y nivel de log son válidos
+async fn check_config(report: &mut Report) -> Option<Config> {
+    let check = tr!("Configuración");
+    let path = Config::get_config_path().ok()?;
+    let legacy = path.with_file_name("config.json");
+    // Sin archivo `Config::load` pide SPOTIFY_CLIENT_ID y termina: se comprueba antes
+    if !path.exists() && !legacy.exists() && std::env::var("SPOTIFY_CLIENT_ID").is_err() {
+        report.fail(
+            check,
+            &tr!("no existe {}", path.display()),
+            tr!("Exporta SPOTIFY_CLIENT_ID con el Client ID de tu aplicación y abre spotigod para crearlo"),
+        );
+        return None;
+    }
+    let config = match Config::load().await {
+        Ok(config) => config,
+        Err(e) => {
+            report.fail(check, &e.to_string(), tr!("Corrige config.toml; el mensaje indica la línea del error"));
+            return None;
+        }
+    };
+    if let Ok(locale) = Locale::detect(config.language.as_deref()) {
+        i18n::set_locale(locale);
+    }
+
+    let mut valid = true;
+    if config.client_id.trim().is_empty() {
+        report.fail(check, tr!("client_id está vacío"), tr!("Copia el Client ID de https://developer.spotify.com/dashboard en [auth]"));
+        valid = false;
+    }
+    match Keymap::from_config(&config.keymap, config.keymap_preset) {
+        Ok(mut keymap) => {
+            if let Err(e) = ScriptEngine::load(&mut keymap) {
+                report.fail(check, &e.to_string(), tr!("Corrige el script o quítalo de scripts/ en el directorio del config"));
+                valid = false;
+            }
+        }
+        Err(e) => {
+            report.fail(check, &e.to_string(), tr!("Revisa la sección [keybindings]"));
+            valid = false;
+        }
+    }
+    if let Err(e) = Theme::from_config(config.theme_preset.as_deref(), &config.theme, config.accessible) {
+        report.fail(check, &e.to_string(), tr!("Revisa la sección [theme]"));
+        valid = false;
+    }
+    if let Err(e) = Locale::detect(config.language.as_deref()) {
+        report.fail(check, &e.to_string(), tr!("Pon language = \"es\" o \"en\" en [ui], o quítalo"));
+        valid = false;
+    }
+    if config.log_level.parse::<LevelFilter>().is_err() {
+        report.fail(
+            check,
+            &tr!("nivel de log no válido: \"{}\"", config.log_level),
+            tr!("Pon level = \"off\", \"error\", \"warn\", \"info\", \"debug\" o \"trace\" en [log]"),
+        );
+        valid = false;
+    }
+    if valid {
+        report.ok(check, &path.display().to_string());
+    }
+    Some(config)
+}
+
+/// Se llega a Spotify por HTTPS; devuelve si hay conexión
+async fn check_network(report: &mut Report) -> bool {
+    let check = tr!("Red");
+    let http = reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap_or_default();
+    let mut online = true;
+    for url in ENDPOINTS {
+        let started = Instant::now();
+        // Cualquier respuesta, aunque sea un error de la API, indica que hay conexión
+        match http.get(*url).send().await {
+            Ok(_) => report.ok(check, &tr!("{} responde en {} ms", url, started.elapsed().as_millis())),
+            Err(e) => {
+                report.fail(
+                    check,
+                    &tr!("no se pudo conectar con {}: {}", url, e),
+                    tr!("Comprueba la conexión, el cortafuegos y el proxy (HTTPS_PROXY)"),
+                );
+                online = false;
+            }
+        }
+    }
+    online
+}
+
+/// Tokens guardados, permisos concedidos y, con conexión, la cuenta y los dispositivos
+async fn check_session(report: &mut Report, config: &Config, online: bool) {
+    let check = tr!("Sesión");
+    let relogin = tr!("Ejecuta spotigod logout --login para volver a autorizar");
+    if config.access_token.is_none() && config.refresh_token.is_none() {
+        report.fail(check, tr!("no hay sesión guardada"), tr!("Abre spotigod sin argumentos para autorizarlo"));
+        return;
+    }
+    match config.token_expires_at {
+        Some(expires_at) if config.is_token_valid() => {
+            let expires = chrono::DateTime::from_timestamp(expires_at, 0).map(|at| at.with_timezone(&chrono::Local));
+            let expires = expires.map(|at| at.format("%H:%M").to_string()).unwrap_or_default();
+            report.ok(check, &tr!("token válido hasta las {}", expires));
+        }
+        _ if config.refresh_token.is_some() => report.info(check, tr!("el token caducó; se renovará con el refresh token")),
+        _ => {
+            report.fail(check, tr!("el token caducó y no hay refresh token"), relogin);
+            return;
+        }
+    }
+
+    if online {
+        // Con un demonio en marcha se le pregunta a él: renovar aquí el token dejaría el suyo sin validez
+        #[cfg(unix)]
+        if let Some(mut daemon) = DaemonClient::connect(config.clone()).await {
+            report.info(check, tr!("hay un demonio en marcha; las peticiones pasan por él"));
+            check_account(report, &mut daemon).await;
+        } else {
+            check_account(report, &mut SpotifyClient::new(config.clone())).await;
+        }
+        #[cfg(not(unix))]
+        check_account(report, &mut SpotifyClient::new(config.clone())).await;
+    }
+
+    // La comprobación de la cuenta puede haber renovado el token y guardado sus permisos
+    let scope = match Config::load().await {
+        Ok(reloaded) => reloaded.token_scope,
+        Err(_) => config.token_scope.clone(),
+    };
+    let check = tr!("Permisos");
+    match scope {
+        None => report.warn(check, tr!("la sesión es anterior a esta versión y no se sabe qué permisos concedió"), relogin),
+        Some(scope) => {
+            let granted: Vec<&str> = scope.split_whitespace().collect();
+            let missing: Vec<&str> = SCOPES.split_whitespace().filter(|s| !granted.contains(s)).collect();
+            if missing.is_empty() {
+                report.ok(check, &tr!("concedidos los {} que usa SpotiGod", granted.len()));
+            } else {
+                report.fail(check, &tr!("faltan {}", missing.join(", ")), relogin);
+            }
+        }
+    }
+}
+
+/// Perfil de la cuenta y dispositivos disponibles
+async fn check_account(report: &mut Report, client: &mut impl SpotifyApi) {
+    let check = tr!("Cuenta");
+    match client.get_user_profile().await {
+        Ok(profile) => {
+            let name = profile.display_name.unwrap_or(profile.id);
+            let product = profile.product.unwrap_or_default();
+            report.ok(check, &format!("{} ({})", name, product));
+            if product != "premium" {
+                report.warn(
+                    check,
+                    tr!("Spotify solo permite controlar la reproducción con Premium"),
+                    tr!("Con esta cuenta puedes navegar, pero play, pausa, volumen y cola fallarán con 403"),
+                );
+            }
+        }
+        Err(SpotifyError::Unauthorized) => {
+            report.fail(check, tr!("Spotify rechazó la sesión"), tr!("Ejecuta spotigod logout --login para volver a autorizar"));
+            return;
+        }
+        Err(e) => {
+            report.fail(check, &e.to_string(), tr!("Vuelve a probar; si sigue fallando, mira el log con [log] level = \"debug\""));
+            return;
+        }
+    }
+
+    let check = tr!("Dispositivos");
+    match client.get_devices().await {
+        Ok(devices) if devices.is_empty() => report.warn(
+            check,
+            tr!("no hay ningún dispositivo disponible"),
+            tr!("Abre Spotify en el ordenador, el móvil o un altavoz (o usa spotifyd/librespot)"),
+        ),
+        Ok(devices) => {
+            let names: Vec<&str> = devices.iter().map(|d| d.name.as_str()).collect();
+            match devices.iter().find(|d| d.is_active) {
+                Some(active) => report.ok(check, &tr!("activo «{}» (disponibles: {})", active.name, names.join(", "))),
+                None => report.warn(
+                    check,
+                    &tr!("ninguno activo (disponibles: {})", names.join(", ")),
+                    tr!("Pon algo a sonar en uno o elígelo con d en la interfaz"),
+                ),
+            }
+        }
+        Err(e) => report.fail(check, &e.to_string(), tr!("Vuelve a probar; si sigue fallando, mira el log con [log] level = \"debug\"")),
+    }
+}
+
+/// La URI de redirección la acepta Spotify y su puerto se puede abrir
+fn check_redirect_uri(report: &mut Report, config: &Config) {
+    let check = tr!("Redirección");
+    if config.redirect_uri.contains("://localhost") {
+        report.warn(
+            check,
+            tr!("Spotify ya no acepta localhost en las Redirect URI"),
+            tr!("Usa http://127.0.0.1:8888/callback en redirect_uri y en el dashboard de tu aplicación"),
+        );
+    }
+    if config.manual_auth {
+        report.info(check, tr!("modo manual: se pega la URL de redirección, no se abre ningún puerto"));
+        return;
+    }
+    match CallbackListener::bind(&config.redirect_uri, config.callback_host.as_deref(), config.callback_port) {
+        Ok(listener) => report.ok(
+            check,
+            &tr!("{} se puede abrir; debe coincidir con la Redirect URI del dashboard", listener.redirect_uri()),
+        ),
+        Err(e) => report.fail(
+            check,
+            &e.to_string(),
+            tr!("Si no puedes abrir ningún puerto (contenedor, WSL), pon manual = true en [auth]"),
+        ),
+    }
+}
+
+/// Tamaño, colores, símbolos e imágenes de la terminal
+fn check_terminal(report: &mut Report, config: Option<&Config>, theme: Option<&Theme>) {
+    let check = tr!("Terminal");
+    if !std::io::stdout().is_terminal() {
+        report.info(check, tr!("la salida no es una terminal; lo siguiente puede no corresponder a la que uses"));
+    }
+
+    let term = std::env::var("TERM").unwrap_or_default();
+    if term.is_empty() || term == "dumb" {
+        report.fail(check, &tr!("TERM=\"{}\" no admite la interfaz", term), tr!("Exporta TERM, por ejemplo TERM=xterm-256color"));
+    } else {
+        report.ok(check, &format!("TERM={}", term));
+    }
+
+    if let Ok((columns, rows)) = crossterm::terminal::size() {
+        if columns < MIN_TERMINAL_SIZE.0 || rows < MIN_TERMINAL_SIZE.1 {
+            report.warn(
+                check,
+                &tr!("{}x{} es pequeña: se ocultan columnas y paneles", columns, rows),
+                &tr!("Agranda la ventana al menos a {}x{}", MIN_TERMINAL_SIZE.0, MIN_TERMINAL_SIZE.1),
+            );
+        } else {
+            report.ok(check, &format!("{}x{}", columns, rows));
+        }
+    }
+
+    let colorterm = std::env::var("COLORTERM").unwrap_or_default();
+    if matches!(colorterm.as_str(), "truecolor" | "24bit") {
+        report.ok(check, tr!("color de 24 bits"));
+    } else if theme.is_some_and(Theme::uses_rgb) {
+        let fix = if std::env::var_os("TMUX").is_some() {
+            tr!("En tmux añade set -as terminal-features ',*:RGB' y exporta COLORTERM=truecolor, o usa el tema default")
+        } else {
+            tr!("Usa una terminal con color de 24 bits y exporta COLORTERM=truecolor, o usa el tema default")
+        };
+        report.warn(check, tr!("el tema usa colores #rrggbb y la terminal no anuncia color de 24 bits (COLORTERM)"), fix);
+    } else {
+        report.info(check, tr!("sin color de 24 bits (COLORTERM); los temas con #rrggbb se verán aproximados"));
+    }
+
+    let utf8 = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|var| std::env::var(var).ok().filter(|value| !value.is_empty()))
+        .is_some_and(|locale| locale.to_uppercase().replace('-', "").contains("UTF8"));
+    if !utf8 && !config.is_some_and(|config| config.ascii_symbols) {
+        report.warn(
+            check,
+            tr!("el idioma del sistema no es UTF-8: los emoji pueden verse mal"),
+            tr!("Exporta LANG con UTF-8 (p. ej. es_ES.UTF-8) o pon ascii_symbols = true en [ui]"),
+        );
+    }
+
+    match image_protocol() {
+        Some(protocol) => report.info(check, &tr!("imágenes con el protocolo {}", protocol)),
+        None => report.info(check, tr!("no se detecta ningún protocolo de imágenes (kitty, iTerm2 o sixel)")),
+    }
+}
+
+/// Protocolo de imágenes de la terminal, según sus variables de entorno
+fn image_protocol() -> Option<&'static str> {
+    let var = |name: &str| std::env::var(name).unwrap_or_default();
+    let term = var("TERM");
+    let program = var("TERM_PROGRAM");
+    if term.contains("kitty") || std::env::var_os("KITTY_WINDOW_ID").is_some() || program == "ghostty" {
+        Some("kitty")
+    } else if program == "WezTerm" {
+        Some("kitty / iTerm2")
+    } else if program == "iTerm.app" || var("LC_TERMINAL") == "iTerm2" {
+        Some("iTerm2")
+    } else if std::env::var_os("KONSOLE_VERSION").is_some() {
+        Some("kitty")
+    } else if term.starts_with("foot") || term.contains("mlterm") || term.contains("sixel") {
+        Some("sixel")
+    } else {
+        None
+    }
+}
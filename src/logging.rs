@@ -0,0 +1,50 @@
+//! Registro en archivo con `tracing`. Mientras la interfaz ocupa la pantalla alternativa no se
+//! puede escribir en la terminal, así que las peticiones a la API, sus tiempos, las renovaciones
+//! del token y los errores van a `logs/` en el directorio de estado, un archivo por día.
+
+use anyhow::{anyhow, Result};
+use spotigod_core::config::Config;
+use spotigod_core::tr;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::EnvFilter;
+
+/// Archivos de log que se conservan; los más antiguos se borran al cambiar de día
+const MAX_LOG_FILES: usize = 7;
+
+/// Empieza a registrar con el nivel de `[log] level`. `SPOTIGOD_LOG` lo sustituye y admite
+/// también directivas por módulo (p. ej. `spotigod_core::spotify=trace`)
+pub fn init(config: &Config) -> Result<()> {
+    let filter = match std::env::var("SPOTIGOD_LOG") {
+        Ok(directives) if !directives.trim().is_empty() => EnvFilter::try_new(&directives)
+            .map_err(|e| anyhow!(tr!("SPOTIGOD_LOG no válido \"{}\": {}", directives, e)))?,
+        _ => {
+            let level: LevelFilter = config.log_level.parse()
+                .map_err(|_| anyhow!(tr!("Nivel de log no válido: \"{}\"", config.log_level)))?;
+            if level == LevelFilter::OFF {
+                return Ok(());
+            }
+            // Lo de otras bibliotecas (reqwest, hyper...) solo si son avisos o errores
+            EnvFilter::new(format!("warn,spotigod={0},spotigod_core={0}", level))
+        }
+    };
+
+    let dir = Config::log_dir()?;
+    std::fs::create_dir_all(&dir)?;
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix("spotigod")
+        .filename_suffix("log")
+        .max_log_files(MAX_LOG_FILES)
+        .build(&dir)
+        .map_err(|e| anyhow!(tr!("No se pudo abrir el log en {}: {}", dir.display(), e)))?;
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(appender)
+        .with_ansi(false)
+        .try_init()
+        .map_err(|e| anyhow!(e))?;
+    tracing::info!(version = env!("CARGO_PKG_VERSION"), "inicio");
+    Ok(())
+}
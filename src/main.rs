@@ -1,36 +1,156 @@
-mod spotify;
+mod cli;
+mod doctor;
+mod logging;
 mod ui;
-mod config;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
+use clap::Parser;
 use colored::Colorize;
 
-use config::Config;
-use spotify::SpotifyClient;
-use ui::App;
+use spotigod_core::config::Config;
+#[cfg(unix)]
+use spotigod_core::daemon::{self, DaemonClient};
+use spotigod_core::i18n::{self, Locale};
+use spotigod_core::library::LibraryStore;
+#[cfg(unix)]
+use spotigod_core::scrobble;
+use spotigod_core::spotify::{SpotifyApi, SpotifyClient};
+use spotigod_core::tr;
+use cli::{Cli, Command};
+use ui::{App, Keymap, ScriptEngine, Theme};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    println!("{}", "🎵 Bienvenido a SpotiGod - Tu cliente de Spotify en terminal 🎵".bright_green().bold());
+    let cli = Cli::parse();
+    if let Some(dir) = cli.config.clone() {
+        Config::set_dir_override(dir);
+    }
+    // Hasta leer el config el idioma sale de las variables de entorno
+    i18n::set_locale(Locale::detect(None)?);
+
+    // `spotigod doctor`: antes de cargar el config, que puede ser justo lo que falla
+    if let Some(Command::Doctor) = cli.command {
+        return doctor::run().await;
+    }
+
+    // `spotigod play|pause|next|...`: una sola petición, sin interfaz ni mensajes de bienvenida
+    if let Some(command) = cli.command.as_ref().filter(|command| command.is_remote()) {
+        let config = Config::load().await?;
+        i18n::set_locale(Locale::detect(config.language.as_deref())?);
+        init_logging(&config);
+        #[cfg(unix)]
+        if let Some(mut daemon) = DaemonClient::connect(config.clone()).await {
+            return cli::run(command.clone(), &mut daemon, &config).await;
+        }
+        let mut spotify_client = SpotifyClient::new(config.clone());
+        if !spotify_client.is_authenticated().await {
+            return Err(anyhow!(tr!("No hay sesión: abre spotigod sin argumentos para autorizarlo")));
+        }
+        return cli::run(command.clone(), &mut spotify_client, &config).await;
+    }
+
+    println!("{}", tr!("🎵 Bienvenido a SpotiGod - Tu cliente de Spotify en terminal 🎵").bright_green().bold());
     
     // Cargar configuración
     let config = Config::load().await?;
+    i18n::set_locale(Locale::detect(config.language.as_deref())?);
+    init_logging(&config);
+    let mut keymap = Keymap::from_config(&config.keymap, config.keymap_preset)?;
+    let scripts = ScriptEngine::load(&mut keymap)?;
+    let theme = Theme::from_config(config.theme_preset.as_deref(), &config.theme, config.accessible)?;
     
     // Inicializar cliente de Spotify
     let mut spotify_client = SpotifyClient::new(config.clone());
+
+    // Con un demonio en marcha la interfaz le hace a él las peticiones
+    #[cfg(unix)]
+    let mut daemon = match cli.command {
+        Some(Command::Daemon) => None,
+        _ => DaemonClient::connect(config.clone()).await,
+    };
+
+    // `spotigod logout [--login]`: borra la sesión y, con --login, autoriza otra cuenta
+    if let Some(Command::Logout { login }) = cli.command {
+        #[cfg(unix)]
+        if let Some(daemon) = daemon.as_mut() {
+            logout(daemon).await?;
+        } else {
+            logout(&mut spotify_client).await?;
+        }
+        #[cfg(not(unix))]
+        logout(&mut spotify_client).await?;
+        if !login {
+            return Ok(());
+        }
+    }
+
+    // Si la sesión caducó o se cerró, la propia interfaz pide volver a autorizar
+    #[cfg(unix)]
+    if let Some(daemon) = daemon {
+        let mut app = App::new(daemon, keymap, scripts, theme, &config);
+        app.defer_to_daemon();
+        if cli.debug {
+            app.show_debug_overlay();
+        }
+        return app.run().await;
+    }
     
     // Verificar si ya tenemos un token válido
     if !spotify_client.is_authenticated().await {
-        println!("{}", "🔐 Necesitas autenticarte con Spotify...".yellow());
+        println!("{}", tr!("🔐 Necesitas autenticarte con Spotify...").yellow());
         spotify_client.authenticate().await?;
-        println!("{}", "✅ Autenticación exitosa!".green());
+        println!("{}", tr!("✅ Autenticación exitosa!").green());
+    }
+
+    #[cfg(unix)]
+    if let Some(Command::Daemon) = cli.command {
+        return run_daemon(spotify_client, &config).await;
     }
     
     // Inicializar la aplicación TUI
-    let mut app = App::new(spotify_client);
+    let mut app = App::new(spotify_client, keymap, scripts, theme, &config);
+    if cli.debug {
+        app.show_debug_overlay();
+    }
     
     // Ejecutar la aplicación
     app.run().await?;
     
+    Ok(())
+}
+
+/// Sin log se sigue funcionando: solo se avisa
+fn init_logging(config: &Config) {
+    if let Err(e) = logging::init(config) {
+        eprintln!("{}", format!("⚠️  {}", e).yellow());
+    }
+}
+
+/// `spotigod daemon`: atiende el socket hasta recibir Ctrl+C o SIGTERM y lo borra al salir
+#[cfg(unix)]
+async fn run_daemon(spotify_client: SpotifyClient, config: &Config) -> Result<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let listener = daemon::bind().await?;
+    let path = daemon::socket_path()?;
+    println!("{}", tr!("🎧 Demonio escuchando en {}", path.display()).green());
+    let mut terminate = signal(SignalKind::terminate())?;
+    let result = tokio::select! {
+        result = daemon::serve(spotify_client, scrobble::scrobblers(config), config.hooks.clone(), listener) => result,
+        _ = tokio::signal::ctrl_c() => Ok(()),
+        _ = terminate.recv() => Ok(()),
+    };
+    if let Err(e) = &result {
+        tracing::error!(error = %e, "el demonio terminó con un error");
+    }
+    let _ = std::fs::remove_file(path);
+    result
+}
+
+/// Borra los tokens y la biblioteca guardada en disco
+async fn logout(spotify_client: &mut impl SpotifyApi) -> Result<()> {
+    spotify_client.logout().await?;
+    LibraryStore::open_default()?.clear()?;
+    println!("{}", tr!("👋 Sesión cerrada: se han borrado los tokens y la biblioteca guardada").green());
     Ok(())
 } 
\ No newline at end of file
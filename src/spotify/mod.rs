@@ -1,5 +0,0 @@
-pub mod models;
-pub mod client;
-
-pub use client::SpotifyClient;
-pub use models::*; 
\ No newline at end of file
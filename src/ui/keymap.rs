@@ -0,0 +1,374 @@
+use anyhow::{anyhow, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use spotigod_core::config::{KeyList, KeymapPreset};
+use spotigod_core::tr;
+use std::collections::HashMap;
+use std::fmt;
+
+/// Acciones globales que se pueden reasignar desde la sección `keymap` del config
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Quit,
+    PlayPause,
+    Next,
+    Previous,
+    Shuffle,
+    Repeat,
+    Seek,
+    ViewPlayer,
+    ViewSearch,
+    ViewPlaylists,
+    ViewFavorites,
+    ViewQueue,
+    ViewTop,
+    ViewDiscover,
+    ViewEpisodes,
+    ViewBrowse,
+    ViewSavedAlbums,
+    ViewLyrics,
+    ViewArtists,
+    ViewLog,
+    DebugOverlay,
+    Search,
+    Command,
+    Launcher,
+    Volume,
+    VolumeUp,
+    VolumeDown,
+    Mute,
+    ToggleFavorite,
+    Devices,
+    AddToQueue,
+    Radio,
+    AddToPlaylist,
+    ShowArtist,
+    RelatedArtists,
+    ContextMenu,
+    CopyLink,
+    OpenExternal,
+    ToggleMark,
+    JumpToPlaying,
+    Sort,
+    Filter,
+    FocusSidebar,
+    NextView,
+    PreviousView,
+    Reauthorize,
+    Logout,
+}
+
+/// Movimientos por las listas que añade el preset de navegación
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Motion {
+    Up,
+    Down,
+    /// Volver atrás, como Esc
+    Back,
+    /// Abrir o reproducir, como Enter
+    Enter,
+    /// Ir al principio; hay que pulsar la tecla dos veces (gg)
+    Top,
+    Bottom,
+    HalfPageDown,
+    HalfPageUp,
+}
+
+/// Teclas del preset "vim"
+const VIM_MOTIONS: &[(Motion, &str)] = &[
+    (Motion::Down, "j"),
+    (Motion::Up, "k"),
+    (Motion::Back, "h"),
+    (Motion::Enter, "l"),
+    (Motion::Top, "g"),
+    (Motion::Bottom, "G"),
+    (Motion::HalfPageDown, "ctrl+d"),
+    (Motion::HalfPageUp, "ctrl+u"),
+];
+
+/// Nombre en el config y teclas por defecto de cada acción
+const ACTIONS: &[(Action, &str, &[&str])] = &[
+    (Action::Quit, "quit", &["q"]),
+    (Action::PlayPause, "play_pause", &["space"]),
+    (Action::Next, "next", &["right", "n"]),
+    (Action::Previous, "previous", &["left", "p"]),
+    (Action::Shuffle, "shuffle", &["s"]),
+    (Action::Repeat, "repeat", &["r"]),
+    (Action::Seek, "seek", &["%"]),
+    (Action::ViewPlayer, "view_player", &["1"]),
+    (Action::ViewSearch, "view_search", &["2"]),
+    (Action::ViewPlaylists, "view_playlists", &["3"]),
+    (Action::ViewFavorites, "view_favorites", &["4"]),
+    (Action::ViewQueue, "view_queue", &["5"]),
+    (Action::ViewTop, "view_top", &["6"]),
+    (Action::ViewDiscover, "view_discover", &["7"]),
+    (Action::ViewEpisodes, "view_episodes", &["8"]),
+    (Action::ViewBrowse, "view_browse", &["9"]),
+    (Action::ViewSavedAlbums, "view_saved_albums", &["0"]),
+    (Action::ViewLyrics, "view_lyrics", &["l"]),
+    (Action::ViewArtists, "view_artists", &[]),
+    (Action::ViewLog, "view_log", &["E"]),
+    (Action::DebugOverlay, "debug_overlay", &["F12"]),
+    (Action::Search, "search", &["/"]),
+    (Action::Command, "command", &[":"]),
+    (Action::Launcher, "launcher", &["ctrl+p"]),
+    (Action::Volume, "volume", &["v"]),
+    (Action::VolumeUp, "volume_up", &["ctrl+up"]),
+    (Action::VolumeDown, "volume_down", &["ctrl+down"]),
+    (Action::Mute, "mute", &["m"]),
+    (Action::ToggleFavorite, "toggle_favorite", &["f"]),
+    (Action::Devices, "devices", &["d"]),
+    (Action::AddToQueue, "add_to_queue", &["a"]),
+    (Action::Radio, "radio", &["w"]),
+    (Action::AddToPlaylist, "add_to_playlist", &["P"]),
+    (Action::ShowArtist, "show_artist", &["A"]),
+    (Action::RelatedArtists, "related_artists", &["R"]),
+    (Action::ContextMenu, "context_menu", &["o"]),
+    (Action::CopyLink, "copy_link", &["y"]),
+    (Action::OpenExternal, "open_external", &["b"]),
+    (Action::ToggleMark, "toggle_mark", &["x"]),
+    (Action::JumpToPlaying, "jump_to_playing", &["."]),
+    (Action::Sort, "sort", &["S"]),
+    (Action::Filter, "filter", &["ctrl+f"]),
+    (Action::FocusSidebar, "focus_sidebar", &["ctrl+w"]),
+    (Action::NextView, "next_view", &["tab"]),
+    (Action::PreviousView, "previous_view", &["shift+tab"]),
+    (Action::Reauthorize, "reauthorize", &["L"]),
+    (Action::Logout, "logout", &["O"]),
+];
+
+/// Teclas que la interfaz usa para navegar y no se pueden reasignar
+const RESERVED: &[&str] = &["up", "down", "enter", "esc", "backspace", "ctrl+c"];
+
+/// Tecla con modificadores, tal como se compara con los eventos de crossterm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct KeyBinding {
+    code: KeyCode,
+    modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// Interpreta "q", "space", "ctrl+n", "alt+left", "F5"...
+    fn parse(spec: &str) -> Result<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut parts: Vec<&str> = spec.split('+').collect();
+        // "+" solo o "ctrl++" se refieren a la propia tecla +
+        if spec.ends_with('+') {
+            parts.retain(|p| !p.is_empty());
+            parts.push("+");
+        }
+        let Some((key, mods)) = parts.split_last() else {
+            return Err(anyhow!(tr!("tecla vacía")));
+        };
+        for m in mods {
+            modifiers |= match m.to_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                other => return Err(anyhow!(tr!("modificador desconocido \"{}\" en \"{}\"", other, spec))),
+            };
+        }
+
+        let code = match key.to_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "tab" => KeyCode::Tab,
+            "backspace" => KeyCode::Backspace,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" => KeyCode::PageUp,
+            "pagedown" => KeyCode::PageDown,
+            "delete" => KeyCode::Delete,
+            "insert" => KeyCode::Insert,
+            name if name.len() > 1 && name.starts_with('f') => match name[1..].parse() {
+                Ok(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(anyhow!(tr!("tecla desconocida \"{}\"", spec))),
+            },
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(anyhow!(tr!("tecla desconocida \"{}\"", spec))),
+                }
+            }
+        };
+        Ok(Self::normalized(code, modifiers))
+    }
+
+    fn from_event(key: &KeyEvent) -> Self {
+        Self::normalized(key.code, key.modifiers)
+    }
+
+    /// En los caracteres Shift ya va implícito en la letra (A frente a a), así que se ignora.
+    /// Shift+Tab llega como BackTab y se trata como Tab con Shift
+    fn normalized(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        let mut modifiers = modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT);
+        if matches!(code, KeyCode::Char(_)) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        let code = match code {
+            KeyCode::BackTab => {
+                modifiers.insert(KeyModifiers::SHIFT);
+                KeyCode::Tab
+            }
+            code => code,
+        };
+        Self { code, modifiers }
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "SPACE"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+/// Asignación de teclas a acciones: la predeterminada con los cambios del config
+pub struct Keymap {
+    bindings: HashMap<Action, Vec<KeyBinding>>,
+    actions: HashMap<KeyBinding, Action>,
+    motions: HashMap<KeyBinding, Motion>,
+    /// Teclas de los scripts de Lua y la posición de su función en el `ScriptEngine`
+    scripts: HashMap<KeyBinding, usize>,
+}
+
+impl Action {
+    /// Nombre de la acción en el config
+    pub fn name(self) -> &'static str {
+        ACTIONS.iter().find(|(action, _, _)| *action == self).map_or("", |(_, name, _)| name)
+    }
+}
+
+impl Keymap {
+    /// Aplica la sección `keymap` del config sobre las teclas por defecto y comprueba que
+    /// no haya acciones desconocidas, teclas reservadas ni teclas repetidas (tampoco con las del preset;
+    /// una tecla por defecto que use el preset se queda sin asignar)
+    pub fn from_config(overrides: &HashMap<String, KeyList>, preset: KeymapPreset) -> Result<Self> {
+        let reserved = RESERVED
+            .iter()
+            .map(|spec| KeyBinding::parse(spec))
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut bindings = HashMap::new();
+        for (action, name, defaults) in ACTIONS {
+            let specs: Vec<&str> = match overrides.get(*name) {
+                Some(keys) => keys.keys(),
+                None => defaults.to_vec(),
+            };
+            let keys = specs
+                .iter()
+                .map(|spec| KeyBinding::parse(spec))
+                .collect::<Result<Vec<_>>>()
+                .map_err(|e| anyhow!("keybindings.{}: {}", name, e))?;
+            if let Some(key) = keys.iter().find(|k| reserved.contains(k)) {
+                return Err(anyhow!(tr!("keybindings.{}: la tecla {} está reservada para navegar", name, key)));
+            }
+            bindings.insert(*action, keys);
+        }
+
+        if let Some(unknown) = overrides.keys().find(|name| !ACTIONS.iter().any(|(_, n, _)| n == name)) {
+            let valid: Vec<&str> = ACTIONS.iter().map(|(_, n, _)| *n).collect();
+            return Err(anyhow!(tr!(
+                "keybindings: acción desconocida \"{}\" (válidas: {})",
+                unknown,
+                valid.join(", ")
+            )));
+        }
+
+        let mut actions = HashMap::new();
+        for (action, name, _) in ACTIONS {
+            for key in &bindings[action] {
+                if let Some(other) = actions.insert(*key, *action) {
+                    let other_name = ACTIONS.iter().find(|(a, _, _)| *a == other).map_or("", |(_, n, _)| *n);
+                    return Err(anyhow!(tr!("keybindings: la tecla {} está asignada a {} y a {}", key, other_name, name)));
+                }
+            }
+        }
+
+        let mut motions = HashMap::new();
+        if preset == KeymapPreset::Vim {
+            for (motion, spec) in VIM_MOTIONS {
+                let key = KeyBinding::parse(spec)?;
+                if let Some(action) = actions.get(&key).copied() {
+                    let name = ACTIONS.iter().find(|(a, _, _)| *a == action).map_or("", |(_, n, _)| *n);
+                    if overrides.contains_key(name) {
+                        return Err(anyhow!(tr!("keybindings: la tecla {} de {} la usa el preset vim", key, name)));
+                    }
+                    actions.remove(&key);
+                    if let Some(keys) = bindings.get_mut(&action) {
+                        keys.retain(|k| *k != key);
+                    }
+                }
+                motions.insert(key, *motion);
+            }
+        }
+
+        Ok(Self { bindings, actions, motions, scripts: HashMap::new() })
+    }
+
+    /// Acción asignada a la tecla pulsada, si la hay
+    pub fn action_for(&self, key: &KeyEvent) -> Option<Action> {
+        self.actions.get(&KeyBinding::from_event(key)).copied()
+    }
+
+    /// Asigna una tecla a la función `index` de los scripts; no puede estar reservada ni
+    /// usada ya por una acción, el preset u otro script
+    pub fn bind_script(&mut self, spec: &str, index: usize) -> Result<()> {
+        let key = KeyBinding::parse(spec)?;
+        if RESERVED.iter().any(|spec| KeyBinding::parse(spec).ok() == Some(key)) {
+            return Err(anyhow!(tr!("la tecla {} está reservada para navegar", key)));
+        }
+        if let Some(action) = self.actions.get(&key) {
+            return Err(anyhow!(tr!("la tecla {} ya está asignada a {}", key, action.name())));
+        }
+        if self.motions.contains_key(&key) {
+            return Err(anyhow!(tr!("la tecla {} la usa el preset vim", key)));
+        }
+        if self.scripts.insert(key, index).is_some() {
+            return Err(anyhow!(tr!("la tecla {} ya está asignada a otro script", key)));
+        }
+        Ok(())
+    }
+
+    /// Función de los scripts asignada a la tecla pulsada, si la hay
+    pub fn script_for(&self, key: &KeyEvent) -> Option<usize> {
+        self.scripts.get(&KeyBinding::from_event(key)).copied()
+    }
+
+    /// Movimiento del preset de navegación asignado a la tecla pulsada, si lo hay
+    pub fn motion_for(&self, key: &KeyEvent) -> Option<Motion> {
+        self.motions.get(&KeyBinding::from_event(key)).copied()
+    }
+
+    /// Teclas de una acción para mostrarlas en la ayuda ("←/p")
+    pub fn label(&self, action: Action) -> String {
+        let keys: Vec<String> = self.bindings[&action].iter().map(ToString::to_string).collect();
+        if keys.is_empty() {
+            "-".to_string()
+        } else {
+            keys.join("/")
+        }
+    }
+}
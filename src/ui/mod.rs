@@ -1,90 +1,1432 @@
-use crate::spotify::{SpotifyClient, PlaybackState, Track, Playlist};
+mod keymap;
+mod script;
+mod symbols;
+mod theme;
+mod visualizer;
+mod worker;
+#[cfg(test)]
+mod tests;
+
+pub use keymap::Keymap;
+pub use script::ScriptEngine;
+pub use theme::Theme;
+
+use spotigod_core::config::Config;
+use spotigod_core::i18n::tr;
+use spotigod_core::tr;
+use spotigod_core::library::LibraryStore;
+use spotigod_core::lyrics::{Lyrics, LyricsProvider, LyricsQuery};
+use spotigod_core::hooks::{HookEvent, HookTracker, Hooks};
+use spotigod_core::status::PlaybackStatus;
+use spotigod_core::scrobble::{self, ScrobbleEvent, ScrobbleTracker, Scrobbler};
+use spotigod_core::ui_state::UiState;
+use spotigod_core::spotify::error::Result as ApiResult;
+use spotigod_core::spotify::{ApiCall, SpotifyApi, PlaybackState, Track, Playlist, Device, FullArtist, TimeRange, AudioAnalysis, Album, SearchType, Episode, Category, UserProfile, PlaylistTrackItem, Artist, PlaybackContext, PlaybackOffset, SearchResults, Show, SpotifyError, PlayableItem, PlaylistsResponse, SavedTracksResponse, SavedAlbumsResponse, PlaylistTracksResponse, AlbumSearchResult, AlbumTracksResponse, QueueResponse, Reauthorization};
 use anyhow::Result;
 use crossterm::{
-    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers},
+    cursor,
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Alignment, Constraint, Direction, Layout, Rect},
-    style::{Color, Modifier, Style},
+    layout::{Alignment, Constraint, Direction, Layout, Margin, Rect},
+    style::{Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Clear, Gauge, List, ListItem, ListState, Paragraph, Wrap},
+    widgets::{
+        block::{Position, Title},
+        Block, BorderType, Borders, Cell as TableCell, Clear, Gauge, LineGauge, List, ListItem, ListState, Paragraph, Row,
+        Scrollbar, ScrollbarOrientation, ScrollbarState, Table, TableState, Tabs, Wrap,
+    },
     Frame, Terminal,
 };
+use std::borrow::Cow;
+use std::cmp::Reverse;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet, VecDeque};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 use std::io;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::rc::Rc;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Mutex;
+use tokio::task::{JoinHandle, LocalSet};
 use tokio::time::{Duration, Instant};
+use keymap::{Action, Motion};
+use script::{ScriptCommand, ScriptRun};
+use visualizer::Visualizer;
+use worker::{Command, Request, Response};
 
 #[derive(Debug, PartialEq)]
 enum InputMode {
     Normal,
     Search,
     Volume,
+    Devices,
+    CreatePlaylist,
+    PlaylistPicker,
+    EditPlaylist,
+    Reauth,
+    Command,
+    ContextMenu,
+    Filter,
+    Launcher,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum AppState {
     Player,
     Search,
     Playlists,
     Favorites,
+    Queue,
+    Top,
+    Discover,
+    Episodes,
+    Browse,
+    PlaylistTracks,
+    Artist,
+    AlbumTracks,
+    SavedAlbums,
+    Lyrics,
+    Artists,
+    Log,
+}
+
+#[derive(Debug, Clone)]
+enum DiscoverSeed {
+    Track { id: String, name: String },
+    Artist { id: String, name: String },
+}
+
+impl DiscoverSeed {
+    fn id(&self) -> &str {
+        match self {
+            DiscoverSeed::Track { id, .. } | DiscoverSeed::Artist { id, .. } => id,
+        }
+    }
+
+    fn name(&self) -> &str {
+        match self {
+            DiscoverSeed::Track { name, .. } | DiscoverSeed::Artist { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BrowseTab {
+    NewReleases,
+    Featured,
+    Categories,
+}
+
+impl BrowseTab {
+    fn next(&self) -> Self {
+        match self {
+            BrowseTab::NewReleases => BrowseTab::Featured,
+            BrowseTab::Featured => BrowseTab::Categories,
+            BrowseTab::Categories => BrowseTab::NewReleases,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArtistSection {
+    TopTracks,
+    Albums,
+    Related,
+}
+
+impl ArtistSection {
+    fn next(&self) -> Self {
+        match self {
+            ArtistSection::TopTracks => ArtistSection::Albums,
+            ArtistSection::Albums => ArtistSection::Related,
+            ArtistSection::Related => ArtistSection::TopTracks,
+        }
+    }
+}
+
+/// Vista a la que vuelve Esc. Las vistas principales conservan su propio estado; las de detalle
+/// se guardan enteras porque abrir otra playlist, artista o álbum las sustituye
+struct NavEntry {
+    state: AppState,
+    detail: Option<Detail>,
+    /// Filtro y marcas de la lista de la vista de detalle
+    filter: Option<String>,
+    marks: Option<HashSet<String>>,
+}
+
+enum Detail {
+    Playlist {
+        playlist: Playlist,
+        items: Vec<PlaylistTrackItem>,
+        total: usize,
+        list_state: ListState,
+    },
+    Artist {
+        artist: Artist,
+        section: ArtistSection,
+        top_tracks: Vec<Track>,
+        albums: Vec<Album>,
+        related: Vec<FullArtist>,
+        list_states: [ListState; 3],
+    },
+    Album {
+        album: Album,
+        tracks: Vec<Track>,
+        total: usize,
+        list_state: ListState,
+    },
+}
+
+impl Detail {
+    fn list(&self) -> ViewList {
+        match self {
+            Detail::Playlist { .. } => ViewList::PlaylistItems,
+            Detail::Artist { .. } => ViewList::ArtistTopTracks,
+            Detail::Album { .. } => ViewList::AlbumTracks,
+        }
+    }
+}
+
+/// Listas que se pueden filtrar; en las de canciones también se pueden marcar varias para
+/// actuar sobre todas a la vez
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ViewList {
+    Search,
+    Favorites,
+    Queue,
+    Top,
+    Discover,
+    PlaylistItems,
+    AlbumTracks,
+    ArtistTopTracks,
+    Playlists,
+}
+
+/// Carga en segundo plano cuyo aviso se muestra en su panel hasta que llega la respuesta
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Loading {
+    Search,
+    Playlist,
+    Playlists,
+    Favorites,
+    SavedAlbums,
+    Album,
+    Artist,
+    Artists,
+    Queue,
+    Top,
+    Discover,
+    Episodes,
+    NewReleases,
+    Featured,
+    /// Las categorías o las playlists de la que se abre
+    Categories,
+}
+
+/// Criterio de orden de las listas de canciones; `Default` es el orden en que las devuelve Spotify
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum SortKey {
+    #[default]
+    Default,
+    Title,
+    Artist,
+    Album,
+    Duration,
+    Popularity,
+    /// Solo en playlists, que guardan cuándo se añadió cada canción
+    Added,
+}
+
+impl SortKey {
+    fn next(&self, has_added: bool) -> Self {
+        match self {
+            SortKey::Default => SortKey::Title,
+            SortKey::Title => SortKey::Artist,
+            SortKey::Artist => SortKey::Album,
+            SortKey::Album => SortKey::Duration,
+            SortKey::Duration => SortKey::Popularity,
+            SortKey::Popularity if has_added => SortKey::Added,
+            SortKey::Popularity | SortKey::Added => SortKey::Default,
+        }
+    }
+}
+
+/// Elemento sobre el que se abre el menú contextual
+#[derive(Debug, Clone)]
+enum MenuTarget {
+    Track(Track),
+    Album(Album),
+    Playlist(Playlist),
+    /// Canciones marcadas en la lista activa
+    Marked(Vec<Track>),
+}
+
+impl MenuTarget {
+    fn name(&self) -> String {
+        match self {
+            MenuTarget::Track(track) => track.name.clone(),
+            MenuTarget::Album(album) => album.name.clone(),
+            MenuTarget::Playlist(playlist) => playlist.name.clone(),
+            MenuTarget::Marked(tracks) => tr!("{} canciones marcadas", tracks.len()),
+        }
+    }
+
+    fn link(&self) -> &str {
+        match self {
+            MenuTarget::Track(track) => &track.external_urls.spotify,
+            MenuTarget::Album(album) => &album.external_urls.spotify,
+            MenuTarget::Playlist(playlist) => &playlist.external_urls.spotify,
+            MenuTarget::Marked(_) => "",
+        }
+    }
+
+    fn uri(&self) -> Option<String> {
+        match self {
+            MenuTarget::Track(track) => track.uri(),
+            MenuTarget::Album(album) if !album.id.is_empty() => Some(format!("spotify:album:{}", album.id)),
+            MenuTarget::Playlist(playlist) => Some(format!("spotify:playlist:{}", playlist.id)),
+            MenuTarget::Album(_) | MenuTarget::Marked(_) => None,
+        }
+    }
+}
+
+/// Opciones del menú contextual
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MenuEntry {
+    Play,
+    AddToQueue,
+    AddToPlaylist,
+    ToggleFavorite,
+    ShowArtist,
+    ShowAlbum,
+    CopyLink,
+    OpenExternal,
+    RemoveFromPlaylist,
+    ClearMarks,
+}
+
+/// Lo que hace la interfaz cuando termina una orden encargada al trabajador. Las que dejan
+/// marcas las quitan de `marks`, la lista en la que estaban al dar la orden
+#[derive(Debug)]
+enum AfterCommand {
+    /// Muestra el aviso y vuelve a consultar la reproducción
+    Refresh(String),
+    /// Como `Refresh`, y además recarga la cola
+    RefreshQueue(String),
+    Message(String),
+    Volume(u8),
+    Seek(i64),
+    TrackSaved { track: Track, saved: bool },
+    TracksSaved { tracks: Vec<Track>, marks: Option<ViewList> },
+    EpisodeSaved { id: String, name: String, saved: bool },
+    Queued(Track),
+    /// Sigue con la siguiente canción del lote
+    Queue(QueueBatch),
+    PlaylistTracksRemoved { playlist: Playlist, count: usize },
+    AddedToPlaylist { playlist: Playlist, count: usize, marks: Option<ViewList> },
+    /// La canción ya se movió en la lista; si falla se recarga la playlist
+    PlaylistItemMoved { playlist_id: String },
+    Unfollowed(Playlist),
+    PlaylistEdited { playlist_id: String, name: String, description: String },
+    PlaylistCreated(String),
+    DiscoverSaved(String),
+    OwnQueueResumed,
+}
+
+/// Canciones que se añaden a la cola de una en una, cada una cuando termina la anterior para
+/// que queden en orden
+#[derive(Debug)]
+struct QueueBatch {
+    tracks: VecDeque<Track>,
+    queued: usize,
+    /// Canción de la que sale la radio, si lo es; la radio no pasa por la cola propia
+    radio: Option<String>,
+    marks: Option<ViewList>,
+}
+
+/// Máximo de semillas que admite /recommendations
+const MAX_DISCOVER_SEEDS: usize = 5;
+/// Resultados pedidos por página de búsqueda
+const SEARCH_PAGE_SIZE: u8 = 20;
+/// Órdenes que puede ejecutar un script por tecla, para que un `spotigod.search` que busca
+/// de nuevo en su función no se repita sin fin
+const MAX_SCRIPT_COMMANDS: usize = 100;
+/// Ancho de la columna de duración en las tablas de canciones
+const DURATION_WIDTH: u16 = 6;
+/// Ancho de la barra de popularidad en las tablas de canciones
+const POPULARITY_WIDTH: u16 = 5;
+
+/// Fila de una tabla de canciones; en la cola también puede haber episodios
+#[derive(Clone, Copy)]
+enum TrackRow<'a> {
+    Track(&'a Track),
+    Episode(&'a Episode),
+    /// Elemento que ya no está disponible
+    Unavailable,
+}
+
+impl<'a> From<Option<&'a Track>> for TrackRow<'a> {
+    fn from(track: Option<&'a Track>) -> Self {
+        track.map_or(TrackRow::Unavailable, TrackRow::Track)
+    }
+}
+
+impl<'a> From<&'a PlayableItem> for TrackRow<'a> {
+    fn from(item: &'a PlayableItem) -> Self {
+        match item {
+            PlayableItem::Track(track) => TrackRow::Track(track),
+            PlayableItem::Episode(episode) => TrackRow::Episode(episode),
+        }
+    }
+}
+
+/// Columnas opcionales de una tabla de canciones
+#[derive(Debug, Clone, Copy)]
+struct TrackColumns {
+    /// Sobra en las canciones de un álbum
+    album: bool,
+    /// En las listas donde se elige qué escuchar: búsqueda, favoritas y playlists
+    popularity: bool,
 }
+/// Canciones que se encolan al iniciar una radio
+const RADIO_SIZE: u8 = 30;
+/// Análisis de audio que se mantienen en memoria
+const MAX_CACHED_ANALYSES: usize = 20;
+/// Tiempo máximo entre dos clics sobre la misma fila para contarlos como doble clic
+const DOUBLE_CLICK_TIME: Duration = Duration::from_millis(400);
+/// Vistas de la barra lateral, en su orden; bajo Playlists se despliegan las del usuario
+const SIDEBAR_VIEWS: &[(Action, &str)] = &[
+    (Action::ViewPlayer, "Reproductor"),
+    (Action::ViewSearch, "Búsqueda"),
+    (Action::ViewQueue, "Cola"),
+    (Action::ViewPlaylists, "Playlists"),
+    (Action::ViewFavorites, "Favoritos"),
+    (Action::ViewSavedAlbums, "Álbumes"),
+    (Action::ViewArtists, "Artistas"),
+    (Action::ViewTop, "Top"),
+    (Action::ViewDiscover, "Descubrir"),
+    (Action::ViewEpisodes, "Episodios"),
+    (Action::ViewBrowse, "Explorar"),
+    (Action::ViewLyrics, "Letra"),
+    (Action::ViewLog, "Registro"),
+];
+/// Ancho de la barra lateral
+const SIDEBAR_WIDTH: u16 = 24;
 
-pub struct App {
-    spotify_client: SpotifyClient,
+/// Fila de la barra lateral: una vista o una de las playlists desplegadas
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SidebarEntry {
+    View(Action),
+    Playlist(usize),
+}
+
+/// Resultado del lanzador (Ctrl+P): posición en la lista cargada de la biblioteca
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LauncherItem {
+    Playlist(usize),
+    Album(usize),
+    Artist(usize),
+    Track(usize),
+}
+
+/// Tipo de una entrada del registro
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum LogKind {
+    Error,
+    Info,
+    Action,
+}
+
+/// Entrada del registro: un error, un aviso o una acción ejecutada
+#[derive(Debug, Clone)]
+struct LogEntry {
+    time: chrono::DateTime<chrono::Local>,
+    kind: LogKind,
+    text: String,
+}
+
+/// Entradas que guarda el registro; al llenarse se descartan las más antiguas
+const LOG_SIZE: usize = 500;
+
+/// Peticiones a la API que guarda el panel de depuración
+const API_CALLS_SIZE: usize = 50;
+
+/// Máximo de resultados que muestra el lanzador; para el resto hay que afinar la búsqueda
+const LAUNCHER_LIMIT: usize = 200;
+
+/// Pestañas de la cabecera: acción que abre cada vista y su nombre
+const HEADER_TABS: &[(Action, &str)] = &[
+    (Action::ViewPlayer, "Reproductor"),
+    (Action::ViewSearch, "Búsqueda"),
+    (Action::ViewPlaylists, "Playlists"),
+    (Action::ViewFavorites, "Favoritos"),
+    (Action::ViewQueue, "Cola"),
+    (Action::ViewTop, "Top"),
+    (Action::ViewDiscover, "Descubrir"),
+    (Action::ViewEpisodes, "Episodios"),
+    (Action::ViewBrowse, "Explorar"),
+    (Action::ViewSavedAlbums, "Álbumes"),
+    (Action::ViewLyrics, "Letra"),
+];
+
+pub struct App<C: SpotifyApi> {
+    /// Compartido con las peticiones en segundo plano (ver `worker`)
+    spotify_client: Rc<Mutex<C>>,
+    /// Canal hacia el trabajador; existe mientras corre el bucle principal
+    requests: Option<UnboundedSender<Request>>,
+    keymap: Keymap,
+    /// Funciones de los scripts de Lua asignadas a teclas
+    scripts: ScriptEngine,
+    /// Llamada a un script cuyas órdenes se están ejecutando
+    script_run: Option<ScriptRun>,
+    theme: Theme,
+    /// Colores de la sección `theme` del config, que se aplican también al cambiar de tema con `:theme`
+    theme_overrides: HashMap<String, String>,
+    /// Emoji y símbolos sustituidos por ASCII (`ascii_symbols` en el config)
+    ascii_symbols: bool,
+    /// Estados marcados siempre con algo más que el color (`accessible` en el config), también tras `:theme`
+    accessible: bool,
     current_playback: Option<PlaybackState>,
     input_mode: InputMode,
     app_state: AppState,
     search_input: String,
+    search_type: SearchType,
+    /// Consulta de la última búsqueda y, por tipo, desplazamiento y si Spotify tiene más páginas
+    search_query: String,
+    search_pages: HashMap<SearchType, (u32, bool)>,
+    /// Resultados que Spotify dice tener de cada tipo, aunque no estén todos cargados
+    search_totals: HashMap<SearchType, usize>,
     search_results: Vec<Track>,
     search_list_state: ListState,
+    search_albums: Vec<Album>,
+    search_albums_list_state: ListState,
+    search_artists: Vec<FullArtist>,
+    search_artists_list_state: ListState,
+    search_playlists: Vec<Playlist>,
+    search_playlists_list_state: ListState,
+    search_shows: Vec<Show>,
+    search_shows_list_state: ListState,
+    /// Vista previa (id de playlist y primeras canciones) en la búsqueda de playlists
+    playlist_preview: Option<(String, Vec<Track>)>,
     volume_input: String,
+    command_input: String,
+    /// Texto y resultados del lanzador
+    launcher_input: String,
+    launcher_results: Vec<LauncherItem>,
+    launcher_list_state: ListState,
     error_message: Option<String>,
+    /// Errores, avisos y acciones recientes, del más antiguo al más reciente
+    log: VecDeque<LogEntry>,
+    log_list_state: ListState,
+    /// Último error y último aviso apuntados, para no repetirlos mientras siguen en pantalla
+    logged_messages: (Option<String>, Option<String>),
+    /// Se abre el selector de dispositivos en el siguiente ciclo porque no había ninguno activo
+    device_picker_requested: bool,
+    /// Spotify rechazó la sesión: se ofrece volver a autorizar en el siguiente ciclo
+    reauth_requested: bool,
+    /// El usuario cerró el aviso de reautorización; no se vuelve a abrir solo
+    reauth_dismissed: bool,
+    /// Se ha pedido una autorización nueva y todavía no ha llegado su URL
+    reauth_pending: bool,
+    reauth_url: String,
+    reauth_input: String,
+    /// Espera en segundo plano de la redirección de Spotify al puerto local
+    reauth_callback: Option<JoinHandle<anyhow::Result<String>>>,
+    /// Copia local de la biblioteca; `None` si no se pudo abrir la base de datos
+    library_store: Option<LibraryStore>,
+    /// La última petición falló por red: se muestra la biblioteca guardada en solo lectura
+    offline: bool,
+    /// La biblioteca se refresca tras pintar la primera pantalla con los datos guardados
+    library_refresh_pending: bool,
+    /// Se está recargando la biblioteca entera: al terminar se avisa en el pie
+    library_refreshing: bool,
+    loading: HashSet<Loading>,
+    /// Consultas de la reproducción encargadas que aún no han vuelto; solo se aplica la última,
+    /// porque las anteriores pueden ser de antes de una orden
+    playback_pending: u32,
+    /// Órdenes encargadas al trabajador, que sirven de id a la siguiente
+    commands_sent: u64,
+    /// Estado de la sesión anterior; se aplica tras la primera carga de la biblioteca para que
+    /// esta no deshaga la selección recuperada
+    restored_state: Option<UiState>,
+    /// Selecciones recuperadas, que se aplican cuando terminan las cargas de la vista restaurada
+    restored_selections: Option<HashMap<String, usize>>,
     success_message: Option<String>,
     last_update: Instant,
     should_quit: bool,
     playlists: Vec<Playlist>,
     playlist_list_state: ListState,
+    playlists_total: usize,
     favorites: Vec<Track>,
     favorites_list_state: ListState,
+    favorites_total: usize,
+    saved_albums: Vec<Album>,
+    saved_albums_list_state: ListState,
+    followed_artists: Vec<FullArtist>,
+    followed_artists_list_state: ListState,
+    /// Barra lateral con las vistas (`sidebar` en el config)
+    sidebar: bool,
+    /// Las teclas de navegación mueven la barra lateral en lugar de la lista de la vista
+    sidebar_focused: bool,
+    sidebar_state: ListState,
+    /// Playlists del usuario desplegadas bajo la entrada Playlists
+    sidebar_playlists_open: bool,
+    /// Zona de la barra lateral y su desplazamiento, para los clics
+    sidebar_area: Option<(Rect, usize)>,
+    saved_albums_total: usize,
+    queue_playing: Option<PlayableItem>,
+    /// Canciones y episodios
+    queue: Vec<PlayableItem>,
+    queue_list_state: ListState,
+    /// Ids de la cola de Spotify cuando el usuario movió o quitó canciones. Mientras haya,
+    /// `queue` es una cola propia que no se recarga de Spotify (la API no permite reordenar
+    /// la suya) y se hace cumplir al cambiar de canción
+    own_queue: Option<HashSet<String>>,
+    devices: Vec<Device>,
+    devices_list_state: ListState,
+    top_time_range: TimeRange,
+    top_tracks: Vec<Track>,
+    top_tracks_list_state: ListState,
+    top_artists: Vec<FullArtist>,
+    discover_seeds: Vec<DiscoverSeed>,
+    discover_results: Vec<Track>,
+    discover_list_state: ListState,
+    saved_episodes: Vec<Episode>,
+    saved_episodes_list_state: ListState,
+    /// Episodios quitados de la biblioteca en esta sesión (se pueden volver a guardar)
+    removed_episodes: HashSet<String>,
+    browse_tab: BrowseTab,
+    new_releases: Vec<Album>,
+    new_releases_list_state: ListState,
+    new_releases_total: usize,
+    featured_message: Option<String>,
+    featured_playlists: Vec<Playlist>,
+    featured_list_state: ListState,
+    categories: Vec<Category>,
+    categories_list_state: ListState,
+    /// Categoría abierta y sus playlists (segundo nivel de navegación)
+    open_category: Option<(Category, Vec<Playlist>)>,
+    category_playlists_list_state: ListState,
+    /// Vistas a las que vuelve Esc, la más reciente al final
+    nav_stack: Vec<NavEntry>,
+    /// Playlist abierta en la vista de canciones de playlist
+    open_playlist: Option<Playlist>,
+    playlist_items: Vec<PlaylistTrackItem>,
+    playlist_items_list_state: ListState,
+    playlist_items_total: usize,
+    /// Artista abierto en la vista de detalle
+    open_artist: Option<Artist>,
+    artist_section: ArtistSection,
+    artist_top_tracks: Vec<Track>,
+    artist_top_tracks_list_state: ListState,
+    artist_albums: Vec<Album>,
+    artist_albums_list_state: ListState,
+    related_artists: Vec<FullArtist>,
+    related_artists_list_state: ListState,
+    /// Álbum abierto en la vista de canciones de álbum
+    open_album: Option<Album>,
+    album_tracks: Vec<Track>,
+    album_tracks_list_state: ListState,
+    album_tracks_total: usize,
+    /// Playlist en edición (id) con los campos del formulario
+    editing_playlist_id: Option<String>,
+    edit_playlist_name: String,
+    edit_playlist_description: String,
+    editing_description: bool,
+    /// Playlist marcada para dejar de seguir a la espera de confirmación
+    pending_unfollow: Option<String>,
+    /// Se pulsó O una vez: la siguiente pulsación cierra la sesión
+    pending_logout: bool,
+    /// Se pulsó g una vez (preset vim): la siguiente pulsación va al principio de la lista
+    pending_top: bool,
+    /// Se ha pulsado `seek`: la siguiente cifra salta a esa décima parte de la canción
+    pending_seek: bool,
+    /// Filas visibles del área de contenido, para los saltos de media página
+    list_height: u16,
+    /// Zona y desplazamiento de la lista de la vista activa en el último dibujo, para traducir clics a filas
+    list_area: Cell<Option<(Rect, usize)>>,
+    /// Zona de la barra de progreso en el último dibujo, para saltar con un clic
+    progress_area: Cell<Option<Rect>>,
+    /// Zona de cada pestaña de la cabecera en el último dibujo
+    header_tabs: Vec<(Rect, Action)>,
+    /// Último clic sobre una fila, para detectar el doble clic
+    last_click: Option<(Instant, usize)>,
+    /// Filas por paso de la rueda del ratón
+    scroll_step: usize,
+    /// Abrir enlaces en la aplicación oficial en lugar de en el navegador (`open_in_app` en el config)
+    open_in_app: bool,
+    /// Puntos que cambia el volumen con cada pulsación de subir o bajar
+    volume_step: u8,
+    /// Volumen que había antes de silenciar, para recuperarlo al quitar el silencio
+    muted_volume: Option<u8>,
+    /// Espera sin teclear antes de lanzar la búsqueda mientras se escribe; `None` busca solo con Enter
+    search_debounce: Option<Duration>,
+    /// Última tecla de una búsqueda que todavía no se ha lanzado
+    search_typed_at: Option<Instant>,
+    user_profile: Option<UserProfile>,
+    /// Playlists propias ofrecidas en el selector y URIs pendientes de añadir
+    picker_playlists: Vec<Playlist>,
+    picker_list_state: ListState,
+    picker_uris: Vec<String>,
+    new_playlist_name: String,
+    new_playlist_public: bool,
+    /// Estado de "me gusta" conocido por id de canción
+    saved_tracks: HashMap<String, bool>,
+    /// Canción que suena cuyo "me gusta" se está consultando
+    saved_pending: Option<String>,
+    /// Análisis de audio por id de canción (None si la API no lo ofrece)
+    audio_analyses: HashMap<String, Option<AudioAnalysis>>,
+    /// Servicio de letras (None si `lyrics_provider` es "none")
+    lyrics_provider: Option<Arc<dyn LyricsProvider>>,
+    /// Canción cuya letra se está descargando
+    lyrics_pending: Option<String>,
+    /// Letra de la última canción consultada: "título - artistas" y la letra (None si no hay)
+    lyrics: Option<(String, Option<Lyrics>)>,
+    /// Desplazamiento de las letras sin sincronizar
+    lyrics_scroll: u16,
+    /// Servicios a los que se envían las canciones escuchadas
+    scrobblers: Vec<Arc<dyn Scrobbler>>,
+    scrobble_tracker: ScrobbleTracker,
+    /// Órdenes de `[hooks]` que se ejecutan con los eventos de la reproducción
+    hooks: Hooks,
+    hook_tracker: HookTracker,
+    /// Elemento y opciones del menú contextual abierto
+    context_menu: Option<(MenuTarget, Vec<MenuEntry>)>,
+    context_menu_state: ListState,
+    /// Ids de las canciones marcadas en cada lista
+    marked_tracks: HashMap<ViewList, HashSet<String>>,
+    /// Orden elegido para búsqueda, favoritos y canciones de playlist
+    sort_keys: HashMap<ViewList, SortKey>,
+    /// Texto con el que se filtra cada lista
+    filters: HashMap<ViewList, String>,
+    /// Últimas peticiones a la API, para el panel de depuración
+    api_calls: VecDeque<ApiCall>,
+    /// Panel de depuración visible (`--debug` o `debug_overlay`)
+    debug_overlay: bool,
 }
 
-impl App {
-    pub fn new(spotify_client: SpotifyClient) -> Self {
+impl<C: SpotifyApi + 'static> App<C> {
+    pub fn new(spotify_client: C, keymap: Keymap, scripts: ScriptEngine, theme: Theme, config: &Config) -> Self {
         let mut search_list_state = ListState::default();
         search_list_state.select(Some(0));
         
-        Self {
-            spotify_client,
+        let mut app = Self {
+            spotify_client: Rc::new(Mutex::new(spotify_client)),
+            requests: None,
+            keymap,
+            scripts,
+            script_run: None,
+            theme,
+            theme_overrides: config.theme.clone(),
+            ascii_symbols: config.ascii_symbols,
+            accessible: config.accessible,
             current_playback: None,
             input_mode: InputMode::Normal,
             app_state: AppState::Player,
             search_input: String::new(),
+            search_type: SearchType::Track,
+            search_query: String::new(),
+            search_pages: HashMap::new(),
+            search_totals: HashMap::new(),
             search_results: Vec::new(),
             search_list_state,
+            search_albums: Vec::new(),
+            search_albums_list_state: ListState::default(),
+            search_artists: Vec::new(),
+            search_artists_list_state: ListState::default(),
+            search_playlists: Vec::new(),
+            search_playlists_list_state: ListState::default(),
+            search_shows: Vec::new(),
+            search_shows_list_state: ListState::default(),
+            playlist_preview: None,
             volume_input: String::new(),
+            command_input: String::new(),
+            launcher_input: String::new(),
+            launcher_results: Vec::new(),
+            launcher_list_state: ListState::default(),
+            log: VecDeque::new(),
+            log_list_state: ListState::default(),
+            logged_messages: (None, None),
             error_message: None,
+            device_picker_requested: false,
+            reauth_requested: false,
+            reauth_dismissed: false,
+            reauth_pending: false,
+            reauth_url: String::new(),
+            reauth_input: String::new(),
+            reauth_callback: None,
+            library_store: LibraryStore::open_default().ok(),
+            offline: false,
+            library_refresh_pending: true,
+            library_refreshing: false,
+            loading: HashSet::new(),
+            playback_pending: 0,
+            commands_sent: 0,
+            restored_state: Some(UiState::load()),
+            restored_selections: None,
             success_message: None,
             last_update: Instant::now(),
             should_quit: false,
             playlists: Vec::new(),
             playlist_list_state: ListState::default(),
+            playlists_total: 0,
             favorites: Vec::new(),
             favorites_list_state: ListState::default(),
+            favorites_total: 0,
+            saved_albums: Vec::new(),
+            saved_albums_list_state: ListState::default(),
+            followed_artists: Vec::new(),
+            followed_artists_list_state: ListState::default(),
+            sidebar: config.sidebar,
+            sidebar_focused: false,
+            sidebar_state: ListState::default(),
+            sidebar_playlists_open: false,
+            sidebar_area: None,
+            saved_albums_total: 0,
+            queue_playing: None,
+            queue: Vec::new(),
+            queue_list_state: ListState::default(),
+            own_queue: None,
+            devices: Vec::new(),
+            devices_list_state: ListState::default(),
+            top_time_range: TimeRange::Medium,
+            top_tracks: Vec::new(),
+            top_tracks_list_state: ListState::default(),
+            top_artists: Vec::new(),
+            discover_seeds: Vec::new(),
+            discover_results: Vec::new(),
+            discover_list_state: ListState::default(),
+            saved_episodes: Vec::new(),
+            saved_episodes_list_state: ListState::default(),
+            removed_episodes: HashSet::new(),
+            browse_tab: BrowseTab::NewReleases,
+            new_releases: Vec::new(),
+            new_releases_list_state: ListState::default(),
+            new_releases_total: 0,
+            featured_message: None,
+            featured_playlists: Vec::new(),
+            featured_list_state: ListState::default(),
+            categories: Vec::new(),
+            categories_list_state: ListState::default(),
+            open_category: None,
+            category_playlists_list_state: ListState::default(),
+            nav_stack: Vec::new(),
+            open_playlist: None,
+            playlist_items: Vec::new(),
+            playlist_items_list_state: ListState::default(),
+            playlist_items_total: 0,
+            open_artist: None,
+            artist_section: ArtistSection::TopTracks,
+            artist_top_tracks: Vec::new(),
+            artist_top_tracks_list_state: ListState::default(),
+            artist_albums: Vec::new(),
+            artist_albums_list_state: ListState::default(),
+            related_artists: Vec::new(),
+            related_artists_list_state: ListState::default(),
+            open_album: None,
+            album_tracks: Vec::new(),
+            album_tracks_list_state: ListState::default(),
+            album_tracks_total: 0,
+            editing_playlist_id: None,
+            edit_playlist_name: String::new(),
+            edit_playlist_description: String::new(),
+            editing_description: false,
+            pending_unfollow: None,
+            pending_logout: false,
+            pending_top: false,
+            pending_seek: false,
+            list_height: 0,
+            list_area: Cell::new(None),
+            progress_area: Cell::new(None),
+            header_tabs: Vec::new(),
+            last_click: None,
+            scroll_step: config.scroll_step.max(1),
+            volume_step: config.volume_step.clamp(1, 100),
+            open_in_app: config.open_in_app,
+            muted_volume: None,
+            search_debounce: (config.search_debounce_ms > 0).then(|| Duration::from_millis(config.search_debounce_ms)),
+            search_typed_at: None,
+            user_profile: None,
+            picker_playlists: Vec::new(),
+            picker_list_state: ListState::default(),
+            picker_uris: Vec::new(),
+            new_playlist_name: String::new(),
+            new_playlist_public: true,
+            saved_tracks: HashMap::new(),
+            saved_pending: None,
+            audio_analyses: HashMap::new(),
+            lyrics_provider: config.lyrics_provider.provider().map(Arc::from),
+            lyrics_pending: None,
+            lyrics: None,
+            lyrics_scroll: 0,
+            scrobblers: scrobble::scrobblers(config),
+            scrobble_tracker: ScrobbleTracker::new(),
+            hooks: config.hooks.clone(),
+            hook_tracker: HookTracker::new(),
+            context_menu: None,
+            context_menu_state: ListState::default(),
+            marked_tracks: HashMap::new(),
+            sort_keys: HashMap::new(),
+            filters: HashMap::new(),
+            api_calls: VecDeque::new(),
+            debug_overlay: false,
+        };
+        app.restore_library();
+        app
+    }
+
+    /// Rellena playlists, favoritos y álbumes con la copia local para no arrancar vacío
+    fn restore_library(&mut self) {
+        let Some(store) = self.library_store.as_ref() else {
+            return;
+        };
+        if let Ok(playlists) = store.playlists() {
+            self.playlists = playlists.items;
+            self.playlists_total = playlists.total;
+        }
+        if let Ok(favorites) = store.saved_tracks() {
+            self.saved_tracks.extend(favorites.items.iter().map(|t| (t.id.clone(), true)));
+            self.favorites = favorites.items;
+            self.favorites_total = favorites.total;
+        }
+        if let Ok(albums) = store.saved_albums() {
+            self.saved_albums = albums.items;
+            self.saved_albums_total = albums.total;
+        }
+        select_first_if_none(&mut self.playlist_list_state, self.playlists.len());
+        select_first_if_none(&mut self.favorites_list_state, self.favorites.len());
+        select_first_if_none(&mut self.saved_albums_list_state, self.saved_albums.len());
+    }
+
+    /// Cierra la sesión (borrando tokens, caché y biblioteca guardada) y pide autorizar otra cuenta;
+    /// la primera pulsación solo pide confirmación
+    fn logout(&mut self) {
+        if !self.pending_logout {
+            self.pending_logout = true;
+            self.success_message = Some(tr!(
+                "Pulsa {} de nuevo para cerrar sesión y borrar los datos guardados",
+                self.keymap.label(Action::Logout)
+            ));
+            return;
+        }
+
+        self.pending_logout = false;
+        self.request(Request::Logout);
+    }
+
+    /// Deja la interfaz sin rastro de la cuenta cerrada y empieza a autorizar otra
+    fn finish_logout(&mut self, result: ApiResult<()>) {
+        if let Err(e) = result {
+            self.show_error(tr!("Error al cerrar sesión"), e);
+            return;
+        }
+        if let Some(store) = self.library_store.as_mut() {
+            if let Err(e) = store.clear() {
+                self.error_message = Some(tr!("No se pudo borrar la biblioteca guardada: {}", e));
+            }
+        }
+
+        self.current_playback = None;
+        self.playlists.clear();
+        self.playlists_total = 0;
+        self.playlist_list_state.select(None);
+        self.favorites.clear();
+        self.favorites_total = 0;
+        self.favorites_list_state.select(None);
+        self.saved_albums.clear();
+        self.saved_albums_total = 0;
+        self.saved_albums_list_state.select(None);
+        self.followed_artists.clear();
+        self.followed_artists_list_state.select(None);
+        self.user_profile = None;
+        self.saved_tracks.clear();
+        self.nav_stack.clear();
+        self.own_queue = None;
+        self.app_state = AppState::Player;
+
+        // Se empieza directamente una autorización nueva; con Esc se puede salir con q
+        self.reauth_dismissed = false;
+        self.open_reauth_popup();
+        self.success_message = Some(tr!("Sesión cerrada: autoriza una cuenta para continuar").to_string());
+    }
+
+    /// Encarga una carga y la anuncia en su panel hasta que llegue la respuesta
+    fn load(&mut self, loading: Loading, request: Request) {
+        self.loading.insert(loading);
+        self.request(request);
+    }
+
+    /// Descarga de nuevo la primera página de cada colección de la biblioteca
+    fn refresh_library(&mut self) {
+        self.load(Loading::Playlists, Request::Playlists { offset: 0 });
+        self.load(Loading::Favorites, Request::Favorites { offset: 0 });
+        self.load(Loading::SavedAlbums, Request::SavedAlbums { offset: 0 });
+        self.library_refreshing = true;
+    }
+
+    /// Avisa de que la biblioteca está al día cuando llega la última de sus tres colecciones
+    fn finish_library_refresh(&mut self) {
+        let pending = [Loading::Playlists, Loading::Favorites, Loading::SavedAlbums]
+            .iter()
+            .any(|loading| self.loading.contains(loading));
+        if pending || !std::mem::take(&mut self.library_refreshing) {
+            return;
+        }
+        if !self.offline && self.error_message.is_none() {
+            self.success_message = Some(tr!("Biblioteca actualizada").to_string());
+        }
+    }
+
+    /// Aplica la respuesta de una petición hecha en segundo plano
+    fn handle_response(&mut self, response: Response) {
+        match response {
+            Response::Playback(result) => {
+                self.playback_pending = self.playback_pending.saturating_sub(1);
+                if self.playback_pending == 0 {
+                    self.apply_playback(result);
+                }
+            }
+            Response::Search { query, live, result } => {
+                self.loading.remove(&Loading::Search);
+                // Una búsqueda en vivo que ya no corresponde a lo escrito se descarta
+                if live && query != self.search_input.trim() {
+                    return;
+                }
+                self.apply_search(query, live, result);
+            }
+            Response::SearchPage { query, search_type, result } => {
+                self.loading.remove(&Loading::Search);
+                // Si entretanto se buscó otra cosa la página ya no sirve
+                if query == self.search_query {
+                    self.add_search_page(search_type, result);
+                }
+            }
+            Response::Playlists { offset, result } => {
+                self.loading.remove(&Loading::Playlists);
+                if offset == 0 {
+                    self.replace_playlists(result);
+                } else if offset as usize == self.playlists.len() {
+                    match result {
+                        Ok(page) => self.add_playlists_page(page),
+                        Err(e) => self.show_error(tr!("Error al cargar playlists"), e),
+                    }
+                }
+                self.finish_library_refresh();
+            }
+            Response::Favorites { offset, result } => {
+                self.loading.remove(&Loading::Favorites);
+                if offset == 0 {
+                    self.replace_favorites(result);
+                } else if offset as usize == self.favorites.len() {
+                    match result {
+                        Ok(page) => self.add_favorites_page(page),
+                        Err(e) => self.show_error(tr!("Error al cargar favoritos"), e),
+                    }
+                }
+                self.finish_library_refresh();
+            }
+            Response::SavedAlbums { offset, result } => {
+                self.loading.remove(&Loading::SavedAlbums);
+                if offset == 0 {
+                    self.replace_saved_albums(result);
+                } else if offset as usize == self.saved_albums.len() {
+                    match result {
+                        Ok(page) => self.add_saved_albums_page(page),
+                        Err(e) => self.show_error(tr!("Error al cargar álbumes guardados"), e),
+                    }
+                }
+                self.finish_library_refresh();
+            }
+            Response::PlaylistPicker { offset, uris, result } => {
+                self.loading.remove(&Loading::Playlists);
+                match result {
+                    Ok(pages) => {
+                        // Si entretanto se recargaron las playlists, las páginas se suman a las nuevas
+                        if offset == 0 || offset as usize == self.playlists.len() {
+                            if offset == 0 {
+                                self.playlists.clear();
+                                self.playlist_list_state.select(None);
+                            }
+                            for page in pages {
+                                self.add_playlists_page(page);
+                            }
+                        }
+                        self.show_playlist_picker(uris);
+                    }
+                    Err(e) => self.show_error(tr!("Error al cargar playlists"), e),
+                }
+                self.finish_library_refresh();
+            }
+            Response::PlaylistItems { playlist_id, offset, result } => {
+                let Some(playlist) = self.open_playlist.clone() else {
+                    self.loading.remove(&Loading::Playlist);
+                    return;
+                };
+                // Si entretanto se abrió otra playlist o se recargó esta, la respuesta ya no sirve
+                if playlist.id != playlist_id || offset as usize != self.playlist_items.len() {
+                    return;
+                }
+                self.loading.remove(&Loading::Playlist);
+                self.add_playlist_items_page(&playlist, result);
+            }
+            Response::PlaylistPreview { playlist, result } => match result {
+                Ok(page) => {
+                    let tracks: Vec<Track> = page.items.into_iter().filter_map(|item| item.track).collect();
+                    self.refresh_saved_tracks(&tracks);
+                    self.success_message = Some(tr!("Vista previa: {} ({} canciones)", playlist.name, page.total));
+                    self.playlist_preview = Some((playlist.id, tracks));
+                }
+                Err(e) => self.show_error(tr!("Error al cargar vista previa"), e),
+            },
+            Response::AlbumTracks { album, offset, result } => {
+                let Some(open) = self.open_album.as_ref() else {
+                    self.loading.remove(&Loading::Album);
+                    return;
+                };
+                if open.id != album.id || offset as usize != self.album_tracks.len() {
+                    return;
+                }
+                self.loading.remove(&Loading::Album);
+                self.add_album_tracks_page(&album, result);
+            }
+            Response::Artist { artist, section, top_tracks, albums, related } => {
+                self.loading.remove(&Loading::Artist);
+                self.show_artist(artist, section, top_tracks, albums, related);
+            }
+            Response::FollowedArtists(result) => {
+                self.loading.remove(&Loading::Artists);
+                match result {
+                    Ok(artists) => {
+                        self.followed_artists = artists;
+                        self.followed_artists_list_state.select(None);
+                        select_first_if_none(&mut self.followed_artists_list_state, self.followed_artists.len());
+                        self.success_message = Some(tr!("Sigues a {} artistas", self.followed_artists.len()));
+                    }
+                    Err(e) => self.show_error(tr!("Error al cargar los artistas que sigues"), e),
+                }
+            }
+            Response::Queue(result) => {
+                self.loading.remove(&Loading::Queue);
+                self.apply_queue(result);
+            }
+            Response::Top { time_range, tracks, artists } => {
+                self.loading.remove(&Loading::Top);
+                // Si entretanto se cambió de periodo ya viene otra respuesta
+                if time_range == self.top_time_range {
+                    self.apply_top_items(tracks, artists);
+                }
+            }
+            Response::Recommendations(result) => {
+                self.loading.remove(&Loading::Discover);
+                match result {
+                    Ok(tracks) => {
+                        self.refresh_saved_tracks(&tracks);
+                        self.discover_results = tracks;
+                        self.reset_view_list(ViewList::Discover);
+                        self.discover_list_state.select(if self.discover_results.is_empty() { None } else { Some(0) });
+                        self.success_message = Some(tr!("{} recomendaciones", self.discover_results.len()));
+                    }
+                    Err(e) => self.show_error(tr!("Error al cargar recomendaciones"), e),
+                }
+            }
+            Response::Radio { track, result } => match result {
+                Ok(tracks) => {
+                    let tracks = tracks.into_iter().filter(|t| t.uri().is_some()).collect();
+                    self.queue_next(QueueBatch { tracks, queued: 0, radio: Some(track.name), marks: None });
+                }
+                Err(e) => self.show_error(tr!("Error al iniciar la radio"), e),
+            },
+            Response::SavedEpisodes(result) => {
+                self.loading.remove(&Loading::Episodes);
+                match result {
+                    Ok(episodes) => {
+                        self.saved_episodes = episodes;
+                        self.removed_episodes.clear();
+                        self.saved_episodes_list_state.select(if self.saved_episodes.is_empty() { None } else { Some(0) });
+                        self.success_message = Some(tr!("Cargados {} episodios guardados", self.saved_episodes.len()));
+                    }
+                    Err(e) => self.show_error(tr!("Error al cargar episodios"), e),
+                }
+            }
+            Response::NewReleases { offset, result } => {
+                self.loading.remove(&Loading::NewReleases);
+                if offset as usize == self.new_releases.len() {
+                    self.add_new_releases_page(result);
+                }
+            }
+            Response::FeaturedPlaylists(result) => {
+                self.loading.remove(&Loading::Featured);
+                match result {
+                    Ok((message, playlists)) => {
+                        self.featured_message = message;
+                        self.featured_playlists = playlists;
+                        self.featured_list_state.select(if self.featured_playlists.is_empty() { None } else { Some(0) });
+                        self.success_message = Some(tr!("Cargadas {} playlists destacadas", self.featured_playlists.len()));
+                    }
+                    Err(e) => self.show_error(tr!("Error al cargar playlists destacadas"), e),
+                }
+            }
+            Response::Categories(result) => {
+                self.loading.remove(&Loading::Categories);
+                match result {
+                    Ok(categories) => {
+                        self.categories = categories;
+                        self.categories_list_state.select(if self.categories.is_empty() { None } else { Some(0) });
+                        self.success_message = Some(tr!("Cargadas {} categorías", self.categories.len()));
+                    }
+                    Err(e) => self.show_error(tr!("Error al cargar categorías"), e),
+                }
+            }
+            Response::CategoryPlaylists { category, result } => {
+                self.loading.remove(&Loading::Categories);
+                match result {
+                    Ok(playlists) => {
+                        self.category_playlists_list_state.select(if playlists.is_empty() { None } else { Some(0) });
+                        self.success_message = Some(tr!("{}: {} playlists", category.name, playlists.len()));
+                        self.open_category = Some((category, playlists));
+                    }
+                    Err(e) => self.show_error(tr!("Error al cargar la categoría"), e),
+                }
+            }
+            Response::Devices(result) => match result {
+                Ok(devices) => {
+                    if devices.is_empty() {
+                        self.error_message = Some(tr!("No se encontraron dispositivos. Abre Spotify en algún dispositivo").to_string());
+                        return;
+                    }
+                    let active = devices.iter().position(|d| d.is_active).unwrap_or(0);
+                    self.devices = devices;
+                    self.devices_list_state.select(Some(active));
+                    self.input_mode = InputMode::Devices;
+                }
+                Err(e) => self.show_error(tr!("Error al cargar dispositivos"), e),
+            },
+            // Si falla, la cabecera simplemente no muestra la cuenta y `user_id` lo vuelve a pedir
+            Response::Profile(result) => {
+                if let Ok(profile) = result {
+                    self.user_profile = Some(profile);
+                }
+            }
+            Response::Reauthorization(reauth) => {
+                self.reauth_pending = false;
+                self.show_reauth_popup(reauth);
+            }
+            Response::Reauthorized(result) => self.finish_reauth(result),
+            Response::LoggedOut(result) => self.finish_logout(result),
+            Response::AudioAnalysis { track_id, analysis } => {
+                self.audio_analyses.insert(track_id, analysis);
+            }
+            Response::Lyrics { key, result } => {
+                if self.lyrics_pending.as_ref() == Some(&key) {
+                    self.lyrics_pending = None;
+                }
+                // Si falla no se reintenta para esta canción, igual que con el análisis de audio
+                let lyrics = result.unwrap_or_else(|e| {
+                    self.error_message = Some(tr!("No se pudo descargar la letra: {}", e));
+                    None
+                });
+                self.lyrics = Some((key, lyrics));
+                self.lyrics_scroll = 0;
+            }
+            // Solo van al registro: un servicio caído no debe tapar los mensajes de la reproducción
+            Response::Scrobbled { event, errors } => {
+                if errors.is_empty() {
+                    if let ScrobbleEvent::Listened(listen) = event {
+                        self.log(LogKind::Info, tr!("Escucha enviada: {}", listen.track));
+                    }
+                }
+                for (service, e) in errors {
+                    self.log(LogKind::Error, tr!("No se pudo enviar la escucha a {}: {}", service, e));
+                }
+            }
+            Response::Saved { ids, result } => {
+                // La comprobación de la canción actual se repite sola: su error no se muestra
+                let current = self.saved_pending.as_ref().is_some_and(|id| ids.contains(id));
+                if current {
+                    self.saved_pending = None;
+                }
+                match result {
+                    Ok(saved) => self.saved_tracks.extend(ids.into_iter().zip(saved)),
+                    Err(e) if !current => self.show_error(tr!("Error al comprobar favoritos"), e),
+                    Err(_) => {}
+                }
+            }
+            Response::Command { id, then, result } => {
+                let failed = result.is_err();
+                self.finish_command(*then, result);
+                if self.script_run.as_ref().is_some_and(|run| run.waiting == Some(id)) {
+                    if failed {
+                        self.abort_script();
+                    } else {
+                        self.continue_script();
+                    }
+                }
+            }
+            Response::ScriptSearch { id, result } => {
+                if self.script_run.as_ref().is_some_and(|run| run.waiting == Some(id)) {
+                    self.finish_script_search(result);
+                }
+            }
         }
     }
 
     pub async fn run(&mut self) -> Result<()> {
         // Setup terminal
+        install_panic_hook();
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
         let backend = CrosstermBackend::new(stdout);
         let mut terminal = Terminal::new(backend)?;
 
+        // Las señales (cerrar la pestaña, Ctrl+C fuera del bucle, systemd) también terminan el bucle
+        let shutdown = Arc::new(AtomicBool::new(false));
+        spawn_signal_listener(shutdown.clone());
+
+        // El terminal se restaura también si el bucle termina con error. Las peticiones en segundo
+        // plano viven en el LocalSet: al soltarlo se cancelan las que queden y liberan el cliente
+        let result = LocalSet::new().run_until(self.event_loop(&mut terminal, &shutdown)).await;
+        self.requests = None;
+        let flushed = self.flush_state().await;
+        restore_terminal()?;
+        result.and(flushed)
+    }
+
+    /// Guarda lo pendiente antes de salir: la biblioteca local y la configuración con los tokens
+    async fn flush_state(&mut self) -> Result<()> {
+        if let Some(store) = self.library_store.as_mut() {
+            store.save_playlists(&self.playlists, self.playlists_total)?;
+            store.save_saved_tracks(&self.favorites, self.favorites_total)?;
+            store.save_saved_albums(&self.saved_albums, self.saved_albums_total)?;
+        }
+        // El trabajador ya se ha parado: el cliente está libre
+        self.spotify_client.lock().await.save_session().await?;
+        self.ui_state().save()?;
+        Ok(())
+    }
+
+    /// Listas cuya selección se recuerda entre sesiones, con su número de elementos
+    fn remembered_lists(&mut self) -> [(&'static str, &mut ListState, usize); 5] {
+        [
+            ("search", &mut self.search_list_state, self.search_results.len()),
+            ("playlists", &mut self.playlist_list_state, self.playlists.len()),
+            ("playlist", &mut self.playlist_items_list_state, self.playlist_items.len()),
+            ("favorites", &mut self.favorites_list_state, self.favorites.len()),
+            ("albums", &mut self.saved_albums_list_state, self.saved_albums.len()),
+        ]
+    }
+
+    /// Nombre con el que se guarda la vista activa; las de detalle (artista, álbum) no se recuerdan
+    fn view_name(&self) -> Option<&'static str> {
+        Some(match self.app_state {
+            AppState::Player => "player",
+            AppState::Search => "search",
+            AppState::Playlists => "playlists",
+            AppState::PlaylistTracks => "playlist",
+            AppState::Favorites => "favorites",
+            AppState::Queue => "queue",
+            AppState::Top => "top",
+            AppState::Discover => "discover",
+            AppState::Episodes => "episodes",
+            AppState::Browse => "browse",
+            AppState::SavedAlbums => "albums",
+            AppState::Artists => "artists",
+            AppState::Log => "log",
+            AppState::Lyrics => "lyrics",
+            AppState::Artist | AppState::AlbumTracks => return None,
+        })
+    }
+
+    /// Estado de la interfaz para la próxima sesión. Las selecciones se guardan como posición en
+    /// los datos, porque el orden y el filtro no se recuerdan
+    fn ui_state(&mut self) -> UiState {
+        let data_lists = [
+            ("search", ViewList::Search),
+            ("playlists", ViewList::Playlists),
+            ("playlist", ViewList::PlaylistItems),
+            ("favorites", ViewList::Favorites),
+        ];
+        let mut selections = HashMap::new();
+        for (name, state, _) in self.remembered_lists() {
+            if let Some(i) = state.selected() {
+                selections.insert(name.to_string(), i);
+            }
+        }
+        for (name, list) in data_lists {
+            if let Some(i) = selections.get_mut(name) {
+                *i = self.data_index(list, *i);
+            }
+        }
+        UiState {
+            view: self.view_name().map(str::to_string),
+            open_playlist: self.open_playlist.as_ref().map(|playlist| playlist.id.clone()),
+            search_query: Some(self.search_query.clone()).filter(|query| !query.is_empty()),
+            selections,
+        }
+    }
+
+    /// Vuelve a la vista, la búsqueda y las selecciones de la sesión anterior. Las selecciones
+    /// se limitan a lo cargado en la primera página
+    async fn restore_ui_state(&mut self, state: UiState) {
+        if let Some(query) = state.search_query {
+            self.search_input = query;
+            self.perform_search();
+        }
+        let playlist = state
+            .open_playlist
+            .and_then(|id| self.playlists.iter().find(|playlist| playlist.id == id).cloned());
+        let action = match state.view.as_deref() {
+            Some("playlist") => match playlist {
+                Some(playlist) => {
+                    self.show_playlist(playlist);
+                    self.nav_stack.clear();
+                    None
+                }
+                None => Some(Action::ViewPlaylists),
+            },
+            Some("search") => Some(Action::ViewSearch),
+            Some("playlists") => Some(Action::ViewPlaylists),
+            Some("favorites") => Some(Action::ViewFavorites),
+            Some("queue") => Some(Action::ViewQueue),
+            Some("top") => Some(Action::ViewTop),
+            Some("discover") => Some(Action::ViewDiscover),
+            Some("episodes") => Some(Action::ViewEpisodes),
+            Some("browse") => Some(Action::ViewBrowse),
+            Some("albums") => Some(Action::ViewSavedAlbums),
+            Some("artists") => Some(Action::ViewArtists),
+            Some("log") => Some(Action::ViewLog),
+            Some("lyrics") => Some(Action::ViewLyrics),
+            _ => None,
+        };
+        if let Some(action) = action {
+            self.run_action(action).await;
+        }
+        self.restored_selections = Some(state.selections);
+    }
+
+    /// Aplica las selecciones recuperadas una vez cargado lo que abrió `restore_ui_state`, para
+    /// que la carga no las deshaga
+    fn restore_selections(&mut self, selections: HashMap<String, usize>) {
+        for (name, list_state, len) in self.remembered_lists() {
+            if let (Some(&i), true) = (selections.get(name), len > 0) {
+                list_state.select(Some(i.min(len - 1)));
+            }
+        }
+    }
+
+    async fn event_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, shutdown: &AtomicBool) -> Result<()> {
+        let (requests, mut responses) =
+            worker::spawn(self.spotify_client.clone(), self.lyrics_provider.clone(), self.scrobblers.clone());
+        self.requests = Some(requests);
+
         // Actualizar estado inicial
-        self.update_playback_state().await;
+        self.request(Request::Profile);
+        self.refresh_playback();
 
         let mut last_tick = Instant::now();
         let tick_rate = Duration::from_millis(250);
@@ -92,472 +1434,3858 @@ impl App {
         loop {
             terminal.draw(|f| self.ui(f))?;
 
-            let timeout = tick_rate
+            let mut timeout = tick_rate
                 .checked_sub(last_tick.elapsed())
                 .unwrap_or_else(|| Duration::from_secs(0));
+            // Despertar a tiempo para la búsqueda pendiente aunque no llegue ninguna tecla
+            if let Some(pending) = self.search_pending_for() {
+                timeout = timeout.min(pending);
+            }
 
-            if crossterm::event::poll(timeout)? {
-                if let Event::Key(key) = event::read()? {
-                    if self.handle_key_event(key).await? {
-                        break;
-                    }
+            // Lo primero que llegue: una tecla, la respuesta de una petición o el siguiente tick.
+            // Mientras las peticiones esperan en segundo plano se sigue dibujando y leyendo teclas
+            let terminal_event = tokio::select! {
+                _ = key_pending() => Some(event::read()?),
+                Some(response) = responses.recv() => {
+                    self.handle_response(response);
+                    None
                 }
+                _ = tokio::time::sleep(timeout) => None,
+            };
+            match terminal_event {
+                Some(Event::Key(key)) if self.handle_key_event(key).await? => break,
+                Some(Event::Mouse(mouse)) => self.handle_mouse_event(mouse).await,
+                _ => {}
+            }
+
+            if self.search_pending_for() == Some(Duration::ZERO) {
+                self.search_typed_at = None;
+                self.live_search();
             }
 
             if last_tick.elapsed() >= tick_rate {
                 // Actualizar estado de reproducción cada segundo aproximadamente
-                if self.last_update.elapsed() >= Duration::from_secs(1) {
-                    self.update_playback_state().await;
+                if self.last_update.elapsed() >= Duration::from_secs(1) && self.playback_pending == 0 {
+                    self.refresh_playback();
                     self.last_update = Instant::now();
                 }
                 last_tick = Instant::now();
             }
 
-            if self.should_quit {
+            self.show_rate_limit_notice();
+            self.show_auto_selected_device_notice();
+            self.collect_api_calls();
+            self.log_messages();
+
+            if std::mem::take(&mut self.device_picker_requested) {
+                self.open_device_picker();
+            }
+
+            if std::mem::take(&mut self.reauth_requested) {
+                self.open_reauth_popup();
+            }
+
+            if self.reauth_callback.as_ref().is_some_and(|handle| handle.is_finished()) {
+                self.finish_reauth_from_callback().await;
+            }
+
+            if std::mem::take(&mut self.library_refresh_pending) {
+                self.refresh_library();
+            }
+
+            // La sesión anterior se recupera cuando la biblioteca ya está cargada, y sus
+            // selecciones cuando también lo está la vista que se abrió
+            if self.loading.is_empty() {
+                if let Some(state) = self.restored_state.take() {
+                    self.restore_ui_state(state).await;
+                } else if let Some(selections) = self.restored_selections.take() {
+                    self.restore_selections(selections);
+                }
+            }
+
+            if self.should_quit || shutdown.load(Ordering::SeqCst) {
                 break;
             }
         }
 
-        // Restore terminal
-        disable_raw_mode()?;
-        execute!(
-            terminal.backend_mut(),
-            LeaveAlternateScreen,
-            DisableMouseCapture
-        )?;
-        terminal.show_cursor()?;
-
         Ok(())
     }
 
-    async fn update_playback_state(&mut self) {
-        match self.spotify_client.get_current_playback().await {
-            Ok(playback) => {
-                self.current_playback = playback;
-                self.error_message = None;
-            }
-            Err(e) => {
-                self.error_message = Some(format!("Error al actualizar reproducción: {}", e));
+    /// Avisa en el pie si el cliente tuvo que esperar por el límite de peticiones de Spotify
+    fn show_rate_limit_notice(&mut self) {
+        // Si el cliente está ocupado con una petición, el aviso sale en la siguiente vuelta
+        let Ok(mut client) = self.spotify_client.try_lock() else {
+            return;
+        };
+        let waits = client.take_rate_limit_waits();
+        drop(client);
+        if !waits.is_empty() {
+            self.success_message = Some(tr!(
+                "⏳ Spotify limitó las peticiones: reintentado tras esperar {}s",
+                waits.iter().sum::<u64>()
+            ));
+        }
+    }
+
+    /// Recoge las peticiones que hizo el cliente para el panel de depuración
+    fn collect_api_calls(&mut self) {
+        let calls = self.spotify_client.try_lock().map(|mut client| client.take_api_calls()).unwrap_or_default();
+        for call in calls {
+            if self.api_calls.len() == API_CALLS_SIZE {
+                self.api_calls.pop_front();
             }
+            self.api_calls.push_back(call);
         }
     }
 
-    async fn handle_key_event(&mut self, key: KeyEvent) -> Result<bool> {
-        // Clear messages after key press
-        self.success_message = None;
-        
-        match self.input_mode {
-            InputMode::Normal => self.handle_normal_key_event(key).await,
-            InputMode::Search => self.handle_search_key_event(key).await,
-            InputMode::Volume => self.handle_volume_key_event(key).await,
+    /// Avisa si una orden se redirigió al último dispositivo porque no había ninguno activo
+    fn show_auto_selected_device_notice(&mut self) {
+        let device = self.spotify_client.try_lock().ok().and_then(|mut client| client.take_auto_selected_device());
+        if let Some(device) = device {
+            self.success_message = Some(tr!(
+                "🔈 No había ningún dispositivo activo: se ha usado el último, «{}»",
+                device
+            ));
         }
     }
 
-    async fn handle_normal_key_event(&mut self, key: KeyEvent) -> Result<bool> {
-        match key.code {
-            KeyCode::Char('q') => return Ok(true),
-            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return Ok(true),
-            
-            // Controles de reproducción
-            KeyCode::Char(' ') => self.toggle_playback().await,
-            KeyCode::Char('n') | KeyCode::Right => self.next_track().await,
-            KeyCode::Char('p') | KeyCode::Left => self.previous_track().await,
-            KeyCode::Char('s') => self.toggle_shuffle().await,
-            KeyCode::Char('r') => self.toggle_repeat().await,
-            
-            // Navegación entre vistas
-            KeyCode::Char('1') => self.app_state = AppState::Player,
-            KeyCode::Char('2') => self.app_state = AppState::Search,
-            KeyCode::Char('3') => {
-                self.app_state = AppState::Playlists;
-                self.load_playlists().await;
+    /// Encarga una petición al trabajador sin esperar la respuesta
+    fn request(&self, request: Request) {
+        if let Some(requests) = &self.requests {
+            let _ = requests.send(request);
+        }
+    }
+
+    /// Apunta una entrada en el registro
+    fn log(&mut self, kind: LogKind, text: String) {
+        match kind {
+            LogKind::Error => tracing::error!("{}", text),
+            LogKind::Info => tracing::info!("{}", text),
+            LogKind::Action => tracing::debug!("{}", text),
+        }
+        if self.log.len() == LOG_SIZE {
+            self.log.pop_front();
+        }
+        self.log.push_back(LogEntry { time: chrono::Local::now(), kind, text });
+    }
+
+    /// Apunta en el registro los mensajes nuevos del pie, que desaparecen con la siguiente tecla
+    fn log_messages(&mut self) {
+        if self.error_message.is_some() && self.error_message != self.logged_messages.0 {
+            let text = self.error_message.clone().unwrap_or_default();
+            self.log(LogKind::Error, text);
+        }
+        if self.success_message.is_some() && self.success_message != self.logged_messages.1 {
+            let text = self.success_message.clone().unwrap_or_default();
+            self.log(LogKind::Info, text);
+        }
+        self.logged_messages = (self.error_message.clone(), self.success_message.clone());
+    }
+
+    /// Muestra un error de Spotify reaccionando según su tipo
+    fn show_error(&mut self, context: &str, error: SpotifyError) {
+        self.error_message = Some(match error {
+            SpotifyError::NoActiveDevice => {
+                self.device_picker_requested = true;
+                tr!("No hay ningún dispositivo activo: elige uno en la lista (si no aparece, abre Spotify en él)").to_string()
             }
-            KeyCode::Char('4') => {
-                self.app_state = AppState::Favorites;
-                self.load_favorites().await;
+            SpotifyError::PremiumRequired => tr!("Esta acción requiere una cuenta de Spotify Premium").to_string(),
+            SpotifyError::Unauthorized => {
+                if !self.reauth_dismissed && !self.reauth_pending && !matches!(self.input_mode, InputMode::Reauth) {
+                    self.reauth_requested = true;
+                }
+                tr!(
+                    "La sesión ha caducado: pulsa {} para volver a autorizar SpotiGod",
+                    self.keymap.label(Action::Reauthorize)
+                )
             }
-            
-            // Búsqueda
-            KeyCode::Char('/') => {
-                self.input_mode = InputMode::Search;
-                self.search_input.clear();
+            SpotifyError::RateLimited { retry_after } => {
+                tr!("⏳ Spotify está limitando las peticiones, vuelve a intentarlo en {}s", retry_after)
             }
-            
-            // Control de volumen
-            KeyCode::Char('v') => {
-                self.input_mode = InputMode::Volume;
-                self.volume_input.clear();
+            SpotifyError::Network(_) => {
+                self.offline = true;
+                tr!("Sin conexión con Spotify: mostrando la biblioteca guardada (solo lectura)").to_string()
             }
-            
-            // Navegación en resultados de búsqueda
-            KeyCode::Up => {
-                match self.app_state {
-                    AppState::Search => self.previous_search_result(),
-                    AppState::Playlists => self.previous_playlist(),
-                    AppState::Favorites => self.previous_favorite(),
-                    _ => {}
+            error => format!("{}: {}", context, error),
+        });
+    }
+
+    /// Encarga una consulta de la reproducción; las que estén en curso ya no reflejan la última orden
+    fn refresh_playback(&mut self) {
+        self.playback_pending += 1;
+        self.request(Request::Playback);
+    }
+
+    /// Encarga una orden al trabajador; el resultado se aplica con `finish_command` cuando llega
+    fn send_command(&mut self, command: Command, then: AfterCommand) {
+        self.commands_sent += 1;
+        self.request(Request::Command { id: self.commands_sent, command, then: Box::new(then) });
+    }
+
+    /// Aplica el resultado de una orden
+    fn finish_command(&mut self, then: AfterCommand, result: ApiResult<()>) {
+        if let Err(e) = result {
+            let context = match then {
+                AfterCommand::Queue(batch) => {
+                    if let Some(track) = batch.radio {
+                        self.show_error(tr!("Error al encolar la radio"), e);
+                        if batch.queued > 0 {
+                            self.success_message = Some(tr!("Radio de {}: {} canciones en cola", track, batch.queued));
+                        }
+                        return;
+                    }
+                    tr!("Error tras añadir {} canciones a la cola", batch.queued)
+                }
+                AfterCommand::PlaylistCreated(_) => tr!("Error al crear playlist").to_string(),
+                AfterCommand::DiscoverSaved(_) => tr!("Error al guardar playlist").to_string(),
+                AfterCommand::OwnQueueResumed => {
+                    self.own_queue = None;
+                    tr!("Error al reanudar la cola propia").to_string()
                 }
+                AfterCommand::PlaylistItemMoved { playlist_id } => {
+                    self.reload_open_playlist(&playlist_id);
+                    tr!("Error al mover la canción").to_string()
+                }
+                _ => "Error".to_string(),
+            };
+            self.show_error(&context, e);
+            return;
+        }
+
+        match then {
+            AfterCommand::Refresh(message) => {
+                self.success_message = Some(message);
+                self.refresh_playback();
             }
-            KeyCode::Down => {
-                match self.app_state {
-                    AppState::Search => self.next_search_result(),
-                    AppState::Playlists => self.next_playlist(),
-                    AppState::Favorites => self.next_favorite(),
-                    _ => {}
+            AfterCommand::RefreshQueue(message) => {
+                self.success_message = Some(message);
+                self.refresh_playback();
+                self.load_queue();
+            }
+            AfterCommand::Message(message) => self.success_message = Some(message),
+            AfterCommand::Volume(volume) => {
+                if volume > 0 {
+                    self.muted_volume = None;
                 }
+                self.success_message = Some(tr!("Volumen: {}%", volume));
+                self.refresh_playback();
             }
-            KeyCode::Enter => {
-                match self.app_state {
-                    AppState::Search => self.play_selected_track().await,
-                    AppState::Playlists => self.play_selected_playlist().await,
-                    AppState::Favorites => self.play_selected_favorite().await,
-                    _ => {}
+            AfterCommand::Seek(position_ms) => {
+                // Se adelanta la barra sin esperar a la siguiente consulta
+                if let Some(playback) = self.current_playback.as_mut() {
+                    playback.progress_ms = Some(position_ms);
                 }
+                self.last_update = Instant::now();
+                self.success_message = Some(tr!("Saltando a {}", Self::format_duration(position_ms)));
             }
-            _ => {}
-        }
-        Ok(false)
-    }
-
-    async fn handle_search_key_event(&mut self, key: KeyEvent) -> Result<bool> {
-        match key.code {
-            KeyCode::Enter => {
-                if !self.search_input.is_empty() {
-                    self.perform_search().await;
+            AfterCommand::TrackSaved { track, saved } => {
+                if saved {
+                    self.hooks.run(HookEvent::TrackLiked, &PlaybackStatus::for_track(&track));
                 }
-                self.input_mode = InputMode::Normal;
-                self.app_state = AppState::Search;
+                self.saved_tracks.insert(track.id, saved);
+                self.success_message = Some(if saved {
+                    tr!("Añadida a favoritos: {}", track.name)
+                } else {
+                    tr!("Eliminada de favoritos: {}", track.name)
+                });
             }
-            KeyCode::Esc => {
-                self.input_mode = InputMode::Normal;
+            AfterCommand::TracksSaved { tracks, marks } => {
+                for track in &tracks {
+                    self.hooks.run(HookEvent::TrackLiked, &PlaybackStatus::for_track(track));
+                    self.saved_tracks.insert(track.id.clone(), true);
+                }
+                self.success_message = Some(tr!("{} canciones añadidas a favoritos", tracks.len()));
+                self.unmark(marks);
             }
-            KeyCode::Char(c) => {
-                self.search_input.push(c);
+            AfterCommand::EpisodeSaved { id, name, saved } => {
+                if saved {
+                    self.removed_episodes.remove(&id);
+                    self.success_message = Some(tr!("Episodio guardado: {}", name));
+                } else {
+                    self.removed_episodes.insert(id);
+                    self.success_message = Some(tr!("Episodio eliminado: {} (x para deshacer)", name));
+                }
             }
-            KeyCode::Backspace => {
-                self.search_input.pop();
+            AfterCommand::Queued(track) => {
+                self.add_to_own_queue(&track);
+                self.success_message = Some(tr!("Añadida a la cola: {}", track.name));
             }
-            _ => {}
-        }
-        Ok(false)
-    }
-
-    async fn handle_volume_key_event(&mut self, key: KeyEvent) -> Result<bool> {
-        match key.code {
-            KeyCode::Enter => {
-                if let Ok(volume) = self.volume_input.parse::<u8>() {
-                    if volume <= 100 {
-                        self.set_volume(volume).await;
-                    } else {
-                        self.error_message = Some("El volumen debe estar entre 0 y 100".to_string());
+            AfterCommand::Queue(mut batch) => {
+                if let Some(track) = batch.tracks.pop_front() {
+                    if batch.radio.is_none() {
+                        self.add_to_own_queue(&track);
                     }
-                } else {
-                    self.error_message = Some("Volumen inválido".to_string());
+                    batch.queued += 1;
                 }
-                self.input_mode = InputMode::Normal;
+                self.queue_next(batch);
             }
-            KeyCode::Esc => {
-                self.input_mode = InputMode::Normal;
+            AfterCommand::PlaylistTracksRemoved { playlist, count } => {
+                self.marked_tracks.remove(&ViewList::PlaylistItems);
+                self.reload_open_playlist(&playlist.id);
+                self.success_message = Some(tr!("{} canciones quitadas de {}", count, playlist.name));
+            }
+            AfterCommand::AddedToPlaylist { playlist, count, marks } => {
+                self.success_message = Some(if count == 1 {
+                    tr!("Añadida a {}", playlist.name)
+                } else {
+                    tr!("{} canciones añadidas a {}", count, playlist.name)
+                });
+                self.unmark(marks);
             }
-            KeyCode::Char(c) if c.is_numeric() => {
-                if self.volume_input.len() < 3 {
-                    self.volume_input.push(c);
+            AfterCommand::PlaylistItemMoved { .. } => {}
+            AfterCommand::Unfollowed(playlist) => {
+                self.playlists.retain(|p| p.id != playlist.id);
+                self.playlists_total = self.playlists_total.saturating_sub(1);
+                let len = self.playlists.len();
+                if let Some(i) = self.playlist_list_state.selected() {
+                    self.playlist_list_state.select(if len == 0 { None } else { Some(i.min(len - 1)) });
                 }
+                self.success_message = Some(tr!("Has dejado de seguir: {}", playlist.name));
             }
-            KeyCode::Backspace => {
-                self.volume_input.pop();
+            AfterCommand::PlaylistEdited { playlist_id, name, description } => {
+                if let Some(playlist) = self.playlists.iter_mut().find(|p| p.id == playlist_id) {
+                    playlist.name = name.clone();
+                    playlist.description = Some(description);
+                }
+                self.success_message = Some(tr!("Playlist actualizada: {}", name));
             }
-            _ => {}
+            AfterCommand::PlaylistCreated(name) => {
+                self.load_playlists();
+                self.success_message = Some(tr!("Playlist creada: {}", name));
+            }
+            AfterCommand::DiscoverSaved(name) => self.success_message = Some(tr!("Playlist creada: {}", name)),
+            AfterCommand::OwnQueueResumed => {}
         }
-        Ok(false)
     }
 
-    async fn toggle_playback(&mut self) {
-        if let Some(ref playback) = self.current_playback {
-            let result = if playback.is_playing {
-                self.spotify_client.pause().await
-            } else {
-                self.spotify_client.play().await
-            };
-            
-            match result {
-                Ok(_) => {
-                    self.success_message = Some(if playback.is_playing { "Pausado" } else { "Reproduciendo" }.to_string());
-                    // Actualizar estado inmediatamente
-                    self.update_playback_state().await;
-                }
-                Err(e) => self.error_message = Some(format!("Error: {}", e)),
-            }
-        } else {
-            self.error_message = Some("No hay reproducción activa".to_string());
+    /// Vuelve a cargar desde el principio la playlist, si sigue abierta
+    fn reload_open_playlist(&mut self, playlist_id: &str) {
+        if self.open_playlist.as_ref().is_some_and(|open| open.id == playlist_id) {
+            self.playlist_items.clear();
+            self.playlist_items_list_state.select(None);
+            // La página que estuviera en camino ya no sirve
+            self.loading.remove(&Loading::Playlist);
+            self.load_playlist_items();
         }
     }
 
-    async fn next_track(&mut self) {
-        match self.spotify_client.next_track().await {
-            Ok(_) => {
-                self.success_message = Some("Siguiente canción".to_string());
-                tokio::time::sleep(Duration::from_millis(500)).await;
-                self.update_playback_state().await;
+    /// Aplica el estado de reproducción y pide lo que depende de la canción actual
+    fn apply_playback(&mut self, result: ApiResult<Option<PlaybackState>>) {
+        match result {
+            Ok(playback) => {
+                let previous = self.current_playback.as_ref().and_then(|p| p.item.as_ref()).map(|item| item.id().to_string());
+                self.current_playback = playback;
+                self.error_message = None;
+                self.offline = false;
+                self.scrobble();
+                self.run_playback_hooks();
+                if previous.is_some() {
+                    self.follow_own_queue(previous);
+                }
+            }
+            Err(e) => {
+                self.show_error(tr!("Error al actualizar reproducción"), e);
             }
-            Err(e) => self.error_message = Some(format!("Error: {}", e)),
         }
+        self.load_current_track_saved();
+        self.load_audio_analysis();
+        self.load_lyrics();
     }
 
-    async fn previous_track(&mut self) {
-        match self.spotify_client.previous_track().await {
-            Ok(_) => {
-                self.success_message = Some("Canción anterior".to_string());
-                tokio::time::sleep(Duration::from_millis(500)).await;
-                self.update_playback_state().await;
-            }
-            Err(e) => self.error_message = Some(format!("Error: {}", e)),
+    /// Envía a los servicios de scrobbling lo que toque según la última consulta
+    fn scrobble(&mut self) {
+        if self.scrobblers.is_empty() {
+            return;
+        }
+        for event in self.scrobble_tracker.update(self.current_playback.as_ref()) {
+            self.request(Request::Scrobble { event });
         }
     }
 
-    async fn toggle_shuffle(&mut self) {
-        match self.spotify_client.toggle_shuffle().await {
-            Ok(_) => {
-                self.success_message = Some("Shuffle cambiado".to_string());
-                self.update_playback_state().await;
-            }
-            Err(e) => self.error_message = Some(format!("Error: {}", e)),
+    /// Ejecuta los hooks de los cambios desde la última consulta
+    fn run_playback_hooks(&mut self) {
+        if !self.hooks.watches_playback() {
+            return;
+        }
+        let status = PlaybackStatus::new(self.current_playback.as_ref());
+        for event in self.hook_tracker.update(&status) {
+            self.hooks.run(event, &status);
         }
     }
 
-    async fn toggle_repeat(&mut self) {
-        match self.spotify_client.toggle_repeat().await {
-            Ok(_) => {
-                self.success_message = Some("Modo repetición cambiado".to_string());
-                self.update_playback_state().await;
-            }
-            Err(e) => self.error_message = Some(format!("Error: {}", e)),
-        }
+    /// Con un demonio en marcha es él quien envía las escuchas y ejecuta los hooks de la
+    /// reproducción, aunque la interfaz esté cerrada
+    pub fn defer_to_daemon(&mut self) {
+        self.scrobblers.clear();
+        self.hooks = self.hooks.without_playback();
     }
 
-    async fn set_volume(&mut self, volume: u8) {
-        match self.spotify_client.set_volume(volume).await {
-            Ok(_) => {
-                self.success_message = Some(format!("Volumen: {}%", volume));
-                self.update_playback_state().await;
-            }
-            Err(e) => self.error_message = Some(format!("Error: {}", e)),
+    /// Arranca con el panel de depuración abierto (`--debug`)
+    pub fn show_debug_overlay(&mut self) {
+        self.debug_overlay = true;
+    }
+
+    fn load_current_track_saved(&mut self) {
+        let current = self.current_playback.as_ref().and_then(|p| p.track()).filter(|t| t.is_playable());
+        let Some(track_id) = current.map(|t| t.id.clone()) else {
+            return;
+        };
+        if self.saved_tracks.contains_key(&track_id) || self.saved_pending.as_ref() == Some(&track_id) {
+            return;
         }
+        self.saved_pending = Some(track_id.clone());
+        self.request(Request::CheckSaved { ids: vec![track_id] });
     }
 
-    async fn perform_search(&mut self) {
-        match self.spotify_client.search_tracks(&self.search_input, 20).await {
-            Ok(tracks) => {
-                self.search_results = tracks;
-                self.search_list_state.select(Some(0));
-                self.success_message = Some(format!("Encontradas {} canciones", self.search_results.len()));
-            }
-            Err(e) => self.error_message = Some(format!("Error en búsqueda: {}", e)),
+    /// Consulta el estado de "me gusta" de las canciones que aún no están en caché
+    fn refresh_saved_tracks(&mut self, tracks: &[Track]) {
+        let mut ids: Vec<String> = tracks
+            .iter()
+            .filter(|t| t.is_playable())
+            .map(|t| t.id.clone())
+            .filter(|id| !self.saved_tracks.contains_key(id))
+            .collect();
+        ids.sort();
+        ids.dedup();
+        if ids.is_empty() {
+            return;
         }
+
+        self.request(Request::CheckSaved { ids });
     }
 
-    fn previous_search_result(&mut self) {
-        if !self.search_results.is_empty() {
-            let i = match self.search_list_state.selected() {
-                Some(i) => {
-                    if i == 0 {
-                        self.search_results.len() - 1
-                    } else {
-                        i - 1
-                    }
-                }
-                None => 0,
-            };
-            self.search_list_state.select(Some(i));
+    fn toggle_current_track_saved(&mut self) {
+        let Some(track) = self.current_playback.as_ref().and_then(|p| p.track().cloned()) else {
+            self.error_message = Some(tr!("No hay canción reproduciéndose").to_string());
+            return;
+        };
+        self.toggle_track_saved(track);
+    }
+
+    fn toggle_track_saved(&mut self, track: Track) {
+        if !track.is_playable() {
+            self.show_local_file_error(&track);
+            return;
         }
+        let ids = vec![track.id.clone()];
+        let saved = self.saved_tracks.get(&track.id).copied().unwrap_or(false);
+        let command = if saved { Command::RemoveSavedTracks(ids) } else { Command::SaveTracks(ids) };
+        self.send_command(command, AfterCommand::TrackSaved { track, saved: !saved });
     }
 
-    fn next_search_result(&mut self) {
-        if !self.search_results.is_empty() {
-            let i = match self.search_list_state.selected() {
-                Some(i) => {
-                    if i >= self.search_results.len() - 1 {
-                        0
-                    } else {
-                        i + 1
-                    }
-                }
-                None => 0,
-            };
-            self.search_list_state.select(Some(i));
+    fn load_audio_analysis(&mut self) {
+        let current = self.current_playback.as_ref().and_then(|p| p.track()).filter(|t| t.is_playable());
+        let Some(track_id) = current.map(|t| t.id.clone()) else {
+            return;
+        };
+        if self.audio_analyses.contains_key(&track_id) {
+            return;
+        }
+        if self.audio_analyses.len() >= MAX_CACHED_ANALYSES {
+            self.audio_analyses.clear();
         }
+
+        // Hasta que llegue (y si falla, para siempre) el visualizador simplemente no se muestra
+        self.audio_analyses.insert(track_id.clone(), None);
+        self.request(Request::AudioAnalysis { track_id });
     }
 
-    async fn play_selected_track(&mut self) {
-        if let Some(i) = self.search_list_state.selected() {
-            if let Some(track) = self.search_results.get(i) {
-                let track_uri = format!("spotify:track:{}", track.id);
-                match self.spotify_client.play_track(&track_uri).await {
-                    Ok(_) => {
-                        self.success_message = Some(format!("Reproduciendo: {}", track.name));
-                        tokio::time::sleep(Duration::from_millis(500)).await;
-                        self.update_playback_state().await;
-                    }
-                    Err(e) => self.error_message = Some(format!("Error: {}", e)),
-                }
-            }
+    /// Descarga la letra de la canción actual si la vista Letra está abierta y aún no se tiene
+    fn load_lyrics(&mut self) {
+        if !matches!(self.app_state, AppState::Lyrics) || self.lyrics_provider.is_none() {
+            return;
         }
+        let Some(track) = self.current_playback.as_ref().and_then(|p| p.track()) else {
+            return;
+        };
+        let key = Self::lyrics_key(track);
+        if self.lyrics.as_ref().is_some_and(|(k, _)| *k == key) || self.lyrics_pending.as_ref() == Some(&key) {
+            return;
+        }
+
+        let query = LyricsQuery {
+            track: track.name.clone(),
+            artist: track.artists.first().map(|a| a.name.clone()).unwrap_or_default(),
+            album: track.album.name.clone(),
+            duration_ms: track.duration_ms.max(0) as u64,
+        };
+        self.lyrics_pending = Some(key.clone());
+        self.request(Request::Lyrics { key, query });
     }
 
-    async fn load_playlists(&mut self) {
-        match self.spotify_client.get_user_playlists().await {
-            Ok(playlists) => {
-                self.playlists = playlists;
-                self.playlist_list_state.select(Some(0));
-                self.success_message = Some(format!("Cargadas {} playlists", self.playlists.len()));
-            }
-            Err(e) => self.error_message = Some(format!("Error al cargar playlists: {}", e)),
+    /// Identifica la canción de una letra; vale también para archivos locales, que no tienen id
+    fn lyrics_key(track: &Track) -> String {
+        let artists: Vec<&str> = track.artists.iter().map(|a| a.name.as_str()).collect();
+        format!("{} - {}", track.name, artists.join(", "))
+    }
+
+    /// Salta a esa fracción (de 0 a 1) de lo que suena
+    fn seek_to_fraction(&mut self, fraction: f64) {
+        let Some(duration_ms) = self.current_playback.as_ref().and_then(|p| p.item.as_ref()).map(|item| item.duration_ms()) else {
+            self.error_message = Some(tr!("No hay nada sonando").to_string());
+            return;
+        };
+        let position_ms = (duration_ms.max(0) as f64 * fraction.clamp(0.0, 1.0)) as i64;
+        self.send_command(Command::Seek { position_ms: position_ms as u64 }, AfterCommand::Seek(position_ms));
+    }
+
+    /// Progreso estimado de la canción actual, interpolado desde la última actualización
+    fn estimated_progress_ms(&self, playback: &PlaybackState) -> Option<i64> {
+        let progress_ms = playback.progress_ms?;
+        if playback.is_playing {
+            Some(progress_ms + self.last_update.elapsed().as_millis() as i64)
+        } else {
+            Some(progress_ms)
         }
     }
 
-    async fn load_favorites(&mut self) {
-        match self.spotify_client.get_saved_tracks().await {
-            Ok(tracks) => {
-                self.favorites = tracks;
-                self.favorites_list_state.select(Some(0));
-                self.success_message = Some(format!("Cargadas {} canciones favoritas", self.favorites.len()));
+    async fn handle_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        // Clear messages after key press
+        self.success_message = None;
+        if key.code != KeyCode::Char('x') {
+            self.pending_unfollow = None;
+        }
+        if self.keymap.action_for(&key) != Some(Action::Logout) {
+            self.pending_logout = false;
+        }
+        if self.keymap.motion_for(&key) != Some(Motion::Top) {
+            self.pending_top = false;
+        }
+        // Tras `seek` las cifras saltan en la canción en lugar de cambiar de vista
+        if std::mem::take(&mut self.pending_seek) && self.input_mode == InputMode::Normal {
+            if let KeyCode::Char(c @ '0'..='9') = key.code {
+                self.seek_to_fraction((c as u8 - b'0') as f64 / 10.0);
+                return Ok(false);
             }
-            Err(e) => self.error_message = Some(format!("Error al cargar favoritos: {}", e)),
+        }
+        
+        match self.input_mode {
+            InputMode::Normal => self.handle_normal_key_event(key).await,
+            InputMode::Search => self.handle_search_key_event(key).await,
+            InputMode::Volume => self.handle_volume_key_event(key),
+            InputMode::Devices => self.handle_devices_key_event(key),
+            InputMode::CreatePlaylist => self.handle_create_playlist_key_event(key),
+            InputMode::PlaylistPicker => self.handle_playlist_picker_key_event(key),
+            InputMode::EditPlaylist => self.handle_edit_playlist_key_event(key),
+            InputMode::Reauth => self.handle_reauth_key_event(key),
+            InputMode::Command => self.handle_command_key_event(key).await,
+            InputMode::ContextMenu => self.handle_context_menu_key_event(key).await,
+            InputMode::Filter => self.handle_filter_key_event(key).await,
+            InputMode::Launcher => self.handle_launcher_key_event(key),
         }
     }
 
-    fn previous_playlist(&mut self) {
-        if !self.playlists.is_empty() {
-            let i = match self.playlist_list_state.selected() {
-                Some(i) => {
-                    if i == 0 {
-                        self.playlists.len() - 1
+    async fn handle_normal_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        if key.code == KeyCode::Char('c') && key.modifiers.contains(KeyModifiers::CONTROL) {
+            return Ok(true);
+        }
+        // Con el preset vim, h/j/k/l equivalen a Esc, ↓, ↑ y Enter en todas las vistas
+        let key = match self.keymap.motion_for(&key) {
+            Some(Motion::Up) => KeyEvent::new(KeyCode::Up, KeyModifiers::NONE),
+            Some(Motion::Down) => KeyEvent::new(KeyCode::Down, KeyModifiers::NONE),
+            Some(Motion::Back) => KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE),
+            Some(Motion::Enter) => KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE),
+            _ => key,
+        };
+        if self.sidebar_focused && self.handle_sidebar_key_event(key).await {
+            return Ok(false);
+        }
+        // Las teclas propias de cada vista tienen prioridad sobre las acciones globales
+        if self.handle_view_key_event(key) {
+            return Ok(false);
+        }
+        if let Some(action) = self.keymap.action_for(&key) {
+            return Ok(self.run_action(action).await);
+        }
+        if let Some(index) = self.keymap.script_for(&key) {
+            self.run_script(index);
+            return Ok(false);
+        }
+        if let Some(motion) = self.keymap.motion_for(&key) {
+            self.run_motion(motion);
+            return Ok(false);
+        }
+
+        match key.code {
+            // Navegación en resultados de búsqueda
+            KeyCode::Up => {
+                if let Some((state, len)) = self.current_list() {
+                    select_previous(state, len);
+                }
+            }
+            KeyCode::Down => {
+                if self.at_end_of_loaded_page() {
+                    self.load_next_page();
+                }
+                if let Some((state, len)) = self.current_list() {
+                    select_next(state, len);
+                }
+            }
+            // Saltos por páginas del alto de la lista y a los extremos de lo cargado
+            KeyCode::PageUp => {
+                let page = self.list_height.max(1) as isize;
+                if let Some((state, len)) = self.current_list() {
+                    move_selection(state, len, -page);
+                }
+            }
+            KeyCode::PageDown => {
+                if self.at_end_of_loaded_page() {
+                    self.load_next_page();
+                }
+                let page = self.list_height.max(1) as isize;
+                if let Some((state, len)) = self.current_list() {
+                    move_selection(state, len, page);
+                }
+            }
+            KeyCode::Home => {
+                if let Some((state, len)) = self.current_list() {
+                    move_selection(state, len, isize::MIN);
+                }
+            }
+            KeyCode::End => {
+                if let Some((state, len)) = self.current_list() {
+                    move_selection(state, len, isize::MAX);
+                }
+            }
+            KeyCode::Enter => {
+                match self.app_state {
+                    AppState::Search => match self.search_type {
+                        SearchType::Track => self.play_selected_track(),
+                        SearchType::Album => self.play_selected_album(),
+                        SearchType::Artist => self.play_selected_artist(),
+                        SearchType::Playlist => self.play_selected_search_playlist(),
+                        SearchType::Show => self.play_selected_show(),
+                    },
+                    AppState::Playlists => self.open_selected_playlist(),
+                    AppState::Favorites => self.play_selected_favorite(),
+                    AppState::Queue => self.play_selected_queue_item(),
+                    AppState::Top => self.play_selected_top_track(),
+                    AppState::Discover => self.play_selected_discover_track(),
+                    AppState::Episodes => self.play_selected_episode(),
+                    AppState::PlaylistTracks => self.play_selected_playlist_item(),
+                    AppState::AlbumTracks => self.play_selected_album_track(),
+                    AppState::SavedAlbums => self.open_selected_album(),
+                    AppState::Artists => {
+                        if let Some(artist) = self.followed_artists_list_state.selected().and_then(|i| self.followed_artists.get(i)) {
+                            let artist = Self::simple_artist(artist);
+                            self.open_artist_detail(artist);
+                        }
+                    }
+                    AppState::Artist => match self.artist_section {
+                        ArtistSection::TopTracks => self.play_selected_artist_top_track(),
+                        ArtistSection::Albums => self.play_selected_artist_album(),
+                        ArtistSection::Related => {
+                            if let Some(artist) = self.related_artists_list_state.selected().and_then(|i| self.related_artists.get(i)) {
+                                let artist = Self::simple_artist(artist);
+                                self.open_artist_detail(artist);
+                            }
+                        }
+                    },
+                    AppState::Browse => match self.browse_tab {
+                        BrowseTab::NewReleases => self.play_selected_new_release(),
+                        BrowseTab::Featured => self.play_selected_featured_playlist(),
+                        BrowseTab::Categories if self.open_category.is_some() => self.play_selected_category_playlist(),
+                        BrowseTab::Categories => self.open_selected_category(),
+                    },
+                    _ => {}
+                }
+            }
+            KeyCode::Char('+') => {
+                if let Some(track) = self.selected_track().cloned() {
+                    if track.is_playable() {
+                        self.add_discover_seed(DiscoverSeed::Track { id: track.id, name: track.name });
                     } else {
-                        i - 1
+                        self.show_local_file_error(&track);
                     }
                 }
-                None => 0,
-            };
-            self.playlist_list_state.select(Some(i));
+            }
+            _ => {}
         }
+        Ok(false)
     }
 
-    fn next_playlist(&mut self) {
-        if !self.playlists.is_empty() {
-            let i = match self.playlist_list_state.selected() {
-                Some(i) => {
-                    if i >= self.playlists.len() - 1 {
-                        0
-                    } else {
-                        i + 1
-                    }
+    /// Teclas que solo tienen sentido en una vista concreta; devuelve si se ha usado la tecla
+    fn handle_view_key_event(&mut self, key: KeyEvent) -> bool {
+        match key.code {
+            // Pestañas de resultados de búsqueda (Alt+1..5)
+            KeyCode::Char(c @ '1'..='5') if key.modifiers.contains(KeyModifiers::ALT) && matches!(self.app_state, AppState::Search) => {
+                self.search_type = SearchType::ALL[c as usize - '1' as usize];
+            }
+            KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) && matches!(self.app_state, AppState::PlaylistTracks) => {
+                self.move_selected_playlist_item(true);
+            }
+            KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) && matches!(self.app_state, AppState::PlaylistTracks) => {
+                self.move_selected_playlist_item(false);
+            }
+            KeyCode::Up if key.modifiers.contains(KeyModifiers::SHIFT) && matches!(self.app_state, AppState::Queue) => {
+                self.move_selected_queue_item(true);
+            }
+            KeyCode::Down if key.modifiers.contains(KeyModifiers::SHIFT) && matches!(self.app_state, AppState::Queue) => {
+                self.move_selected_queue_item(false);
+            }
+            KeyCode::Delete | KeyCode::Char('X') if matches!(self.app_state, AppState::Queue) => self.remove_selected_queue_item(),
+            KeyCode::Esc | KeyCode::Backspace
+                if matches!(self.app_state, AppState::PlaylistTracks | AppState::Artist | AppState::AlbumTracks) =>
+            {
+                self.go_back();
+            }
+            KeyCode::Char('R') if matches!(self.app_state, AppState::AlbumTracks) => self.play_open_album(),
+            KeyCode::Char('i') if self.selected_album().is_some() => self.open_selected_album(),
+            KeyCode::Tab if matches!(self.app_state, AppState::Artist) => self.artist_section = self.artist_section.next(),
+            KeyCode::Char('i') if matches!(self.app_state, AppState::Search) && self.search_type == SearchType::Artist => {
+                if let Some(artist) = self.search_artists_list_state.selected().and_then(|i| self.search_artists.get(i)) {
+                    let artist = Self::simple_artist(artist);
+                    self.open_artist_detail(artist);
                 }
-                None => 0,
-            };
-            self.playlist_list_state.select(Some(i));
+            }
+            KeyCode::Char('x') if matches!(self.app_state, AppState::Playlists) => self.unfollow_selected_playlist(),
+            KeyCode::Char('F') if matches!(self.app_state, AppState::Browse) && self.browse_tab == BrowseTab::Categories => {
+                self.follow_selected_category_playlist();
+            }
+            KeyCode::Char('e') if matches!(self.app_state, AppState::Playlists) => self.start_editing_selected_playlist(),
+            KeyCode::Char('c') if matches!(self.app_state, AppState::Playlists) => {
+                self.new_playlist_name.clear();
+                self.new_playlist_public = true;
+                self.input_mode = InputMode::CreatePlaylist;
+            }
+            KeyCode::Tab if matches!(self.app_state, AppState::Browse) => {
+                self.browse_tab = self.browse_tab.next();
+                self.load_browse_tab();
+            }
+            KeyCode::Char('F') if matches!(self.app_state, AppState::Browse) && self.browse_tab == BrowseTab::Featured => {
+                self.follow_selected_featured_playlist();
+            }
+            KeyCode::Esc | KeyCode::Backspace
+                if matches!(self.app_state, AppState::Browse) && self.browse_tab == BrowseTab::Categories =>
+            {
+                self.open_category = None;
+            }
+            KeyCode::Char('c') if matches!(self.app_state, AppState::Log) => {
+                self.log.clear();
+                self.log_list_state.select(None);
+            }
+            KeyCode::Char('x') if matches!(self.app_state, AppState::Episodes) => self.toggle_selected_episode_saved(),
+            KeyCode::Char('c') if matches!(self.app_state, AppState::Discover) => self.seed_from_current_track(),
+            KeyCode::Char('C') if matches!(self.app_state, AppState::Discover) => self.seed_from_current_artists(),
+            KeyCode::Char('x') if matches!(self.app_state, AppState::Discover) => {
+                self.discover_seeds.clear();
+                self.success_message = Some(tr!("Semillas eliminadas").to_string());
+            }
+            KeyCode::Char('g') if matches!(self.app_state, AppState::Discover) => self.load_recommendations(),
+            KeyCode::Char('S') if matches!(self.app_state, AppState::Discover) => self.save_discover_as_playlist(),
+            KeyCode::Tab if matches!(self.app_state, AppState::Search) => self.search_type = self.search_type.next(),
+            KeyCode::Char('m') if matches!(self.app_state, AppState::Search) && self.search_has_more() => {
+                self.load_more_search_results();
+            }
+            KeyCode::Char('i') if self.is_searching_playlists() => self.preview_selected_search_playlist(),
+            KeyCode::Char('F') if self.is_searching_playlists() => self.follow_selected_search_playlist(),
+            KeyCode::Up if key.modifiers.is_empty() && matches!(self.app_state, AppState::Lyrics) => self.lyrics_scroll = self.lyrics_scroll.saturating_sub(1),
+            KeyCode::Down if key.modifiers.is_empty() && matches!(self.app_state, AppState::Lyrics) => self.lyrics_scroll = self.lyrics_scroll.saturating_add(1),
+            KeyCode::PageUp if matches!(self.app_state, AppState::Lyrics) => {
+                self.lyrics_scroll = self.lyrics_scroll.saturating_sub(self.list_height)
+            }
+            KeyCode::PageDown if matches!(self.app_state, AppState::Lyrics) => {
+                self.lyrics_scroll = self.lyrics_scroll.saturating_add(self.list_height)
+            }
+            KeyCode::Home if matches!(self.app_state, AppState::Lyrics) => self.lyrics_scroll = 0,
+            KeyCode::Char('t') if matches!(self.app_state, AppState::Top) => {
+                self.top_time_range = self.top_time_range.next();
+                self.load_top_items();
+            }
+            _ => return false,
         }
+        true
     }
 
-    fn previous_favorite(&mut self) {
-        if !self.favorites.is_empty() {
-            let i = match self.favorites_list_state.selected() {
-                Some(i) => {
-                    if i == 0 {
-                        self.favorites.len() - 1
-                    } else {
-                        i - 1
-                    }
-                }
-                None => 0,
-            };
-            self.favorites_list_state.select(Some(i));
+    /// Llama a la función de un script y ejecuta después, en orden, las órdenes que haya
+    /// apuntado; un error corta las que quedan
+    fn run_script(&mut self, index: usize) {
+        self.log(LogKind::Action, format!("script {}", self.scripts.name(index)));
+        if self.script_run.is_some() {
+            self.error_message = Some(tr!("Todavía se están ejecutando las órdenes de otro script").to_string());
+            return;
         }
+        let status = PlaybackStatus::new(self.current_playback.as_ref());
+        let commands = match self.scripts.call(index, status) {
+            Ok(commands) => commands,
+            Err(e) => {
+                self.error_message = Some(tr!("Error en el script: {}", e));
+                return;
+            }
+        };
+        self.script_run = Some(ScriptRun { commands: commands.into(), ..ScriptRun::default() });
+        self.continue_script();
     }
 
-    fn next_favorite(&mut self) {
-        if !self.favorites.is_empty() {
-            let i = match self.favorites_list_state.selected() {
-                Some(i) => {
-                    if i >= self.favorites.len() - 1 {
-                        0
+    /// Ejecuta las órdenes pendientes del script hasta la primera que pasa por Spotify, que
+    /// sigue cuando llega su respuesta
+    fn continue_script(&mut self) {
+        let Some(mut run) = self.script_run.take() else {
+            return;
+        };
+        run.waiting = None;
+        while let Some(command) = run.commands.pop_front() {
+            run.executed += 1;
+            if run.executed > MAX_SCRIPT_COMMANDS {
+                self.error_message = Some(tr!("El script ha apuntado más de {} órdenes", MAX_SCRIPT_COMMANDS));
+                self.scripts.discard([command]);
+                break;
+            }
+            // Lo que pasó mientras se esperaba a la orden anterior no corta las siguientes
+            self.error_message = None;
+            let sent = self.commands_sent;
+            match command {
+                ScriptCommand::PlayPause => self.toggle_playback(),
+                ScriptCommand::Play(None) => {
+                    self.send_command(Command::Play, AfterCommand::Refresh(tr!("Reproduciendo").to_string()))
+                }
+                ScriptCommand::Play(Some(uri)) => {
+                    let context = if uri.starts_with("spotify:track:") || uri.starts_with("spotify:episode:") {
+                        PlaybackContext::Uris(vec![uri])
                     } else {
-                        i + 1
-                    }
+                        PlaybackContext::Context(uri)
+                    };
+                    self.start_playback(context, None, tr!("Reproduciendo").to_string());
                 }
-                None => 0,
-            };
-            self.favorites_list_state.select(Some(i));
+                ScriptCommand::Pause => self.send_command(Command::Pause, AfterCommand::Refresh(tr!("Pausado").to_string())),
+                ScriptCommand::Next => self.next_track(),
+                ScriptCommand::Previous => self.previous_track(),
+                ScriptCommand::Volume(volume) => self.set_volume(volume),
+                ScriptCommand::Queue(uri) => {
+                    self.send_command(Command::AddToQueue(uri), AfterCommand::Message(tr!("Añadida a la cola").to_string()))
+                }
+                ScriptCommand::AddToPlaylist { playlist, uri } => {
+                    let found = self
+                        .playlists
+                        .iter()
+                        .find(|p| p.id == playlist || p.name.eq_ignore_ascii_case(&playlist))
+                        .cloned();
+                    let Some(found) = found else {
+                        self.error_message = Some(tr!("No hay ninguna playlist «{}» en tu biblioteca", playlist));
+                        break;
+                    };
+                    let command = Command::AddTracksToPlaylist { playlist_id: found.id.clone(), uris: vec![uri] };
+                    self.send_command(command, AfterCommand::AddedToPlaylist { playlist: found, count: 1, marks: None });
+                }
+                ScriptCommand::Search { query, callback } => {
+                    self.commands_sent += 1;
+                    self.request(Request::ScriptSearch { id: self.commands_sent, query });
+                    run.search = Some(callback);
+                }
+                ScriptCommand::Message(text) => self.success_message = Some(text),
+                ScriptCommand::Error(text) => self.error_message = Some(text),
+            }
+            if self.error_message.is_some() {
+                break;
+            }
+            if self.commands_sent != sent {
+                run.waiting = Some(self.commands_sent);
+                self.script_run = Some(run);
+                return;
+            }
         }
+        self.scripts.abort(run);
     }
 
-    async fn play_selected_playlist(&mut self) {
-        if let Some(i) = self.playlist_list_state.selected() {
-            if let Some(playlist) = self.playlists.get(i) {
-                let playlist_uri = format!("spotify:playlist:{}", playlist.id);
-                match self.spotify_client.play_playlist(&playlist_uri).await {
-                    Ok(_) => {
-                        self.success_message = Some(format!("Reproduciendo playlist: {}", playlist.name));
-                        tokio::time::sleep(Duration::from_millis(500)).await;
-                        self.update_playback_state().await;
-                    }
-                    Err(e) => self.error_message = Some(format!("Error: {}", e)),
-                }
-            }
+    /// Deja sin ejecutar lo que quedaba del script en marcha
+    fn abort_script(&mut self) {
+        if let Some(run) = self.script_run.take() {
+            self.scripts.abort(run);
         }
     }
 
-    async fn play_selected_favorite(&mut self) {
-        if let Some(i) = self.favorites_list_state.selected() {
-            if let Some(track) = self.favorites.get(i) {
-                let track_uri = format!("spotify:track:{}", track.id);
-                match self.spotify_client.play_track(&track_uri).await {
-                    Ok(_) => {
-                        self.success_message = Some(format!("Reproduciendo: {}", track.name));
-                        tokio::time::sleep(Duration::from_millis(500)).await;
-                        self.update_playback_state().await;
-                    }
-                    Err(e) => self.error_message = Some(format!("Error: {}", e)),
+    /// Llama a la función de `spotigod.search` con las canciones encontradas y sigue con el
+    /// script; lo que apunte la función va antes que lo que quedaba
+    fn finish_script_search(&mut self, result: ApiResult<SearchResults>) {
+        let Some(mut run) = self.script_run.take() else {
+            return;
+        };
+        let Some(callback) = run.search.take() else {
+            self.scripts.abort(run);
+            return;
+        };
+        let tracks = match result {
+            Ok(results) => results.tracks.map(|t| t.items).unwrap_or_default(),
+            Err(e) => {
+                self.show_error(tr!("Error en búsqueda"), e);
+                self.scripts.release(callback);
+                self.scripts.abort(run);
+                return;
+            }
+        };
+        let status = PlaybackStatus::new(self.current_playback.as_ref());
+        match self.scripts.call_search(callback, &tracks, status) {
+            Ok(commands) => {
+                for command in commands.into_iter().rev() {
+                    run.commands.push_front(command);
                 }
+                self.script_run = Some(run);
+                self.continue_script();
+            }
+            Err(e) => {
+                self.error_message = Some(tr!("Error en el script: {}", e));
+                self.scripts.abort(run);
             }
         }
     }
 
-    fn ui(&mut self, f: &mut Frame) {
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints([
-                Constraint::Length(3), // Header
-                Constraint::Min(0),    // Content
-                Constraint::Length(3), // Footer
-            ])
-            .split(f.size());
+    /// Ejecuta una acción global del keymap; devuelve si hay que salir
+    async fn run_action(&mut self, action: Action) -> bool {
+        self.log(LogKind::Action, action.name().to_string());
+        // Las vistas principales empiezan una navegación nueva
+        if matches!(action, Action::ViewArtists | Action::ViewLog) || HEADER_TABS.iter().any(|(tab, _)| *tab == action) {
+            self.nav_stack.clear();
+        }
+        match action {
+            Action::Quit => return true,
 
-        self.render_header(f, chunks[0]);
-        self.render_content(f, chunks[1]);
-        self.render_footer(f, chunks[2]);
+            // Controles de reproducción
+            Action::PlayPause => self.toggle_playback(),
+            Action::Next => self.next_track(),
+            Action::Previous => self.previous_track(),
+            Action::Shuffle => self.toggle_shuffle(),
+            Action::Repeat => self.toggle_repeat(),
+            Action::Seek => {
+                self.pending_seek = true;
+                self.success_message = Some(tr!("Pulsa una cifra del 0 al 9 para saltar a esa parte de la canción").to_string());
+            }
+
+            // Navegación entre vistas
+            Action::ViewPlayer => self.app_state = AppState::Player,
+            Action::ViewSearch => self.app_state = AppState::Search,
+            Action::ViewPlaylists => {
+                self.app_state = AppState::Playlists;
+                self.load(Loading::Playlists, Request::Playlists { offset: 0 });
+            }
+            Action::ViewFavorites => {
+                self.app_state = AppState::Favorites;
+                self.load(Loading::Favorites, Request::Favorites { offset: 0 });
+            }
+            Action::ViewQueue => {
+                self.app_state = AppState::Queue;
+                self.load_queue();
+            }
+            Action::ViewTop => {
+                self.app_state = AppState::Top;
+                self.load_top_items();
+            }
+            Action::ViewDiscover => {
+                self.app_state = AppState::Discover;
+                if self.discover_seeds.is_empty() {
+                    self.seed_from_current_track();
+                }
+                if self.discover_results.is_empty() && !self.discover_seeds.is_empty() {
+                    self.load_recommendations();
+                }
+            }
+            Action::ViewEpisodes => {
+                self.app_state = AppState::Episodes;
+                self.load_saved_episodes();
+            }
+            Action::ViewBrowse => {
+                self.app_state = AppState::Browse;
+                self.load_browse_tab();
+            }
+            Action::ViewSavedAlbums => {
+                self.app_state = AppState::SavedAlbums;
+                self.load(Loading::SavedAlbums, Request::SavedAlbums { offset: 0 });
+            }
+            Action::ViewLyrics => {
+                self.app_state = AppState::Lyrics;
+                self.load_lyrics();
+            }
+            Action::ViewArtists => {
+                self.app_state = AppState::Artists;
+                self.load_followed_artists();
+            }
+            Action::ViewLog => self.app_state = AppState::Log,
+            Action::DebugOverlay => self.debug_overlay = !self.debug_overlay,
+            Action::FocusSidebar if self.sidebar => {
+                self.sidebar_focused = !self.sidebar_focused;
+                if self.sidebar_focused {
+                    self.select_current_sidebar_entry();
+                }
+            }
+            Action::FocusSidebar => {}
+            Action::NextView => {
+                let action = self.adjacent_view(1);
+                return Box::pin(self.run_action(action)).await;
+            }
+            Action::PreviousView => {
+                let action = self.adjacent_view(-1);
+                return Box::pin(self.run_action(action)).await;
+            }
+
+            // Búsqueda
+            Action::Search => {
+                self.input_mode = InputMode::Search;
+                self.search_input.clear();
+            }
+
+            // Línea de órdenes (":theme nord")
+            Action::Command => {
+                self.input_mode = InputMode::Command;
+                self.command_input.clear();
+            }
+            Action::Launcher => self.open_launcher(),
+
+            // Control de volumen
+            Action::Volume => {
+                self.input_mode = InputMode::Volume;
+                self.volume_input.clear();
+            }
+            Action::VolumeUp => self.nudge_volume(i32::from(self.volume_step)),
+            Action::VolumeDown => self.nudge_volume(-i32::from(self.volume_step)),
+            Action::Mute => self.toggle_mute(),
+
+            // Me gusta de la canción actual
+            Action::ToggleFavorite => self.toggle_current_track_saved(),
+
+            // Selector de dispositivos
+            Action::Devices => self.open_device_picker(),
+
+            Action::AddToQueue => {
+                let marked = self.marked_tracks_in_view();
+                if !marked.is_empty() {
+                    self.queue_tracks(&marked);
+                } else if let Some(track) = self.selected_track().cloned() {
+                    self.queue_track(&track);
+                }
+            }
+            Action::AddToPlaylist => {
+                let marked = self.marked_tracks_in_view();
+                if !marked.is_empty() {
+                    self.open_playlist_picker(track_uris(&marked));
+                } else if let Some(track) = self.selected_track().cloned() {
+                    match track.uri() {
+                        Some(uri) => self.open_playlist_picker(vec![uri]),
+                        None => self.show_local_file_error(&track),
+                    }
+                }
+            }
+            Action::Radio => self.start_track_radio(),
+            Action::ShowArtist => {
+                if let Some(artist) = self.current_track_artist() {
+                    self.open_artist_detail(artist);
+                }
+            }
+            Action::RelatedArtists => self.open_current_artist_related(),
+            Action::ContextMenu => self.open_context_menu(),
+            Action::CopyLink => {
+                if let Some(target) = self.selected_or_playing_target() {
+                    self.copy_link(&target);
+                }
+            }
+            Action::OpenExternal => {
+                if let Some(target) = self.selected_or_playing_target() {
+                    self.open_externally(&target);
+                }
+            }
+            Action::ToggleMark => self.toggle_selected_mark(),
+            Action::JumpToPlaying => self.jump_to_playing(),
+            Action::Sort => self.cycle_sort(),
+            Action::Filter => {
+                if let Some(list) = self.current_filter_list() {
+                    self.filters.entry(list).or_default();
+                    self.input_mode = InputMode::Filter;
+                }
+            }
+            Action::Reauthorize => {
+                self.reauth_dismissed = false;
+                self.open_reauth_popup();
+            }
+            Action::Logout => self.logout(),
+        }
+        false
+    }
+
+    /// Clic en una pestaña de la cabecera o en una fila de la lista activa; doble clic la reproduce.
+    /// La rueda mueve la selección de la lista activa
+    async fn handle_mouse_event(&mut self, mouse: MouseEvent) {
+        if self.input_mode != InputMode::Normal {
+            return;
+        }
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left | MouseButton::Right) => {}
+            MouseEventKind::ScrollDown => {
+                if self.at_end_of_loaded_page() {
+                    self.load_next_page();
+                }
+                let step = self.scroll_step as isize;
+                if let Some((state, len)) = self.current_list() {
+                    move_selection(state, len, step);
+                }
+                return;
+            }
+            MouseEventKind::ScrollUp => {
+                let step = self.scroll_step as isize;
+                if let Some((state, len)) = self.current_list() {
+                    move_selection(state, len, -step);
+                }
+                return;
+            }
+            _ => return,
+        }
+        let contains = |area: &Rect| {
+            (area.left()..area.right()).contains(&mouse.column) && (area.top()..area.bottom()).contains(&mouse.row)
+        };
+
+        if let Some(area) = self.progress_area.get().filter(|area| contains(area) && area.width > 0) {
+            if mouse.kind == MouseEventKind::Down(MouseButton::Left) {
+                self.seek_to_fraction((mouse.column - area.x) as f64 / area.width as f64);
+            }
+            return;
+        }
+
+        if let Some(action) = self.header_tabs.iter().find(|(area, _)| contains(area)).map(|(_, action)| *action) {
+            self.success_message = None;
+            self.run_action(action).await;
+            return;
+        }
+
+        if let Some((area, offset)) = self.sidebar_area {
+            let inner = Rect::new(area.x + 1, area.y + 1, area.width.saturating_sub(2), area.height.saturating_sub(2));
+            if contains(&inner) {
+                let index = offset + (mouse.row - inner.y) as usize;
+                if index < self.sidebar_entries().len() {
+                    self.sidebar_focused = true;
+                    self.sidebar_state.select(Some(index));
+                    self.activate_sidebar_entry().await;
+                }
+                return;
+            }
+        }
+
+        let Some((area, offset)) = self.list_area.get() else {
+            return;
+        };
+        // Se descuenta el borde del bloque que rodea la lista
+        let inner = Rect::new(area.x + 1, area.y + 1, area.width.saturating_sub(2), area.height.saturating_sub(2));
+        if !contains(&inner) {
+            return;
+        }
+        let index = offset + (mouse.row - inner.y) as usize;
+        let Some((state, len)) = self.current_list() else {
+            return;
+        };
+        if index >= len {
+            return;
+        }
+        state.select(Some(index));
+
+        if mouse.kind == MouseEventKind::Down(MouseButton::Right) {
+            self.open_context_menu();
+            return;
+        }
+        let double_click = self
+            .last_click
+            .is_some_and(|(at, last)| last == index && at.elapsed() <= DOUBLE_CLICK_TIME);
+        if double_click {
+            self.last_click = None;
+            let _ = self.handle_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)).await;
+        } else {
+            self.last_click = Some((Instant::now(), index));
+        }
+    }
+
+    /// Con la barra lateral enfocada, ↑/↓ la recorren, Enter abre la fila y Esc vuelve a la vista;
+    /// el resto de teclas siguen siendo las globales. Devuelve si se ha usado la tecla
+    async fn handle_sidebar_key_event(&mut self, key: KeyEvent) -> bool {
+        let len = self.sidebar_entries().len();
+        match key.code {
+            KeyCode::Up => select_previous(&mut self.sidebar_state, len),
+            KeyCode::Down => select_next(&mut self.sidebar_state, len),
+            KeyCode::Home | KeyCode::PageUp => move_selection(&mut self.sidebar_state, len, isize::MIN),
+            KeyCode::End | KeyCode::PageDown => move_selection(&mut self.sidebar_state, len, isize::MAX),
+            KeyCode::Enter => self.activate_sidebar_entry().await,
+            KeyCode::Esc => self.sidebar_focused = false,
+            _ => return false,
+        }
+        true
+    }
+
+    /// Filas de la barra lateral, con las playlists si están desplegadas
+    fn sidebar_entries(&self) -> Vec<SidebarEntry> {
+        let mut entries = Vec::new();
+        for (action, _) in SIDEBAR_VIEWS {
+            entries.push(SidebarEntry::View(*action));
+            if *action == Action::ViewPlaylists && self.sidebar_playlists_open {
+                entries.extend((0..self.playlists.len()).map(SidebarEntry::Playlist));
+            }
+        }
+        entries
+    }
+
+    /// Coloca la selección de la barra lateral en la vista activa
+    fn select_current_sidebar_entry(&mut self) {
+        let current = self.current_tab().map(SidebarEntry::View);
+        if let Some(i) = self.sidebar_entries().iter().position(|entry| Some(*entry) == current) {
+            self.sidebar_state.select(Some(i));
+        } else {
+            select_first_if_none(&mut self.sidebar_state, SIDEBAR_VIEWS.len());
+        }
+    }
+
+    /// Abre la vista o la playlist seleccionada en la barra lateral. Playlists además despliega
+    /// o pliega las del usuario y deja el foco en la barra para poder elegir una
+    async fn activate_sidebar_entry(&mut self) {
+        let Some(entry) = self.sidebar_state.selected().and_then(|i| self.sidebar_entries().get(i).copied()) else {
+            return;
+        };
+        match entry {
+            SidebarEntry::View(Action::ViewPlaylists) => {
+                self.sidebar_playlists_open = !self.sidebar_playlists_open;
+                self.run_action(Action::ViewPlaylists).await;
+            }
+            SidebarEntry::View(action) => {
+                self.sidebar_focused = false;
+                self.run_action(action).await;
+            }
+            SidebarEntry::Playlist(i) => {
+                let Some(playlist) = self.playlists.get(i).cloned() else {
+                    return;
+                };
+                self.sidebar_focused = false;
+                self.show_playlist(playlist);
+            }
+        }
+    }
+
+    /// Saltos del preset de navegación (gg, G, Ctrl+d, Ctrl+u) en la lista de la vista activa
+    fn run_motion(&mut self, motion: Motion) {
+        let half_page = (self.list_height as isize / 2).max(1);
+        match motion {
+            Motion::Top if !self.pending_top => self.pending_top = true,
+            Motion::Top => {
+                self.pending_top = false;
+                if let Some((state, len)) = self.current_list() {
+                    move_selection(state, len, isize::MIN);
+                }
+            }
+            Motion::Bottom => {
+                if let Some((state, len)) = self.current_list() {
+                    move_selection(state, len, isize::MAX);
+                }
+            }
+            Motion::HalfPageDown => {
+                if self.at_end_of_loaded_page() {
+                    self.load_next_page();
+                }
+                if let Some((state, len)) = self.current_list() {
+                    move_selection(state, len, half_page);
+                }
+            }
+            Motion::HalfPageUp => {
+                if let Some((state, len)) = self.current_list() {
+                    move_selection(state, len, -half_page);
+                }
+            }
+            Motion::Up | Motion::Down | Motion::Back | Motion::Enter => {}
+        }
+    }
+
+    async fn handle_search_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Enter => {
+                self.search_typed_at = None;
+                // Si la búsqueda en vivo ya trajo estos resultados no se repite
+                if !self.search_input.is_empty() && self.search_input != self.search_query {
+                    self.perform_search();
+                }
+                self.input_mode = InputMode::Normal;
+                self.app_state = AppState::Search;
+            }
+            KeyCode::Esc => {
+                self.search_typed_at = None;
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Tab => {
+                self.search_type = self.search_type.next();
+            }
+            KeyCode::Char(c) => {
+                self.search_input.push(c);
+                self.search_typed_at = Some(Instant::now());
+            }
+            KeyCode::Backspace => {
+                self.search_input.pop();
+                self.search_typed_at = Some(Instant::now());
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    async fn handle_command_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Enter => {
+                let command = std::mem::take(&mut self.command_input);
+                self.input_mode = InputMode::Normal;
+                self.run_command(&command);
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Char(c) => {
+                self.command_input.push(c);
+            }
+            KeyCode::Backspace => {
+                self.command_input.pop();
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn handle_launcher_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Up => select_previous(&mut self.launcher_list_state, self.launcher_results.len()),
+            KeyCode::Down => select_next(&mut self.launcher_list_state, self.launcher_results.len()),
+            KeyCode::Enter | KeyCode::Tab => {
+                self.input_mode = InputMode::Normal;
+                let item = self.launcher_list_state.selected().and_then(|i| self.launcher_results.get(i)).copied();
+                if let Some(item) = item {
+                    self.launch(item, key.code == KeyCode::Tab);
+                }
+            }
+            KeyCode::Esc => self.input_mode = InputMode::Normal,
+            KeyCode::Char(c) => {
+                self.launcher_input.push(c);
+                self.update_launcher_results();
+            }
+            KeyCode::Backspace => {
+                self.launcher_input.pop();
+                self.update_launcher_results();
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Ejecuta una orden escrita tras `:`
+    fn run_command(&mut self, command: &str) {
+        let mut words = command.split_whitespace();
+        match (words.next(), words.next()) {
+            (None, _) => {}
+            (Some("theme"), None) => {
+                self.success_message = Some(tr!("Temas disponibles: {}", theme::PRESETS.join(", ")));
+            }
+            (Some("theme"), Some(name)) => match Theme::from_config(Some(name), &self.theme_overrides, self.accessible) {
+                Ok(theme) => {
+                    self.theme = theme;
+                    self.success_message = Some(tr!("Tema: {}", name));
+                }
+                Err(e) => self.error_message = Some(e.to_string()),
+            },
+            (Some(other), _) => self.error_message = Some(tr!("Orden desconocida: {}", other)),
+        }
+    }
+
+    fn handle_create_playlist_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Enter => {
+                if self.new_playlist_name.trim().is_empty() {
+                    self.error_message = Some(tr!("El nombre de la playlist no puede estar vacío").to_string());
+                } else {
+                    self.create_playlist();
+                }
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Tab => {
+                self.new_playlist_public = !self.new_playlist_public;
+            }
+            KeyCode::Char(c) => {
+                self.new_playlist_name.push(c);
+            }
+            KeyCode::Backspace => {
+                self.new_playlist_name.pop();
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn handle_reauth_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Enter => {
+                let input = std::mem::take(&mut self.reauth_input);
+                self.complete_reauth(&input);
+            }
+            KeyCode::Esc => {
+                self.close_reauth_popup();
+                self.reauth_dismissed = true;
+            }
+            KeyCode::Char(c) => {
+                self.reauth_input.push(c);
+            }
+            KeyCode::Backspace => {
+                self.reauth_input.pop();
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Inicia una nueva autorización OAuth sin salir de la interfaz
+    fn open_reauth_popup(&mut self) {
+        if !self.reauth_pending {
+            self.reauth_pending = true;
+            self.request(Request::BeginReauthorization);
+        }
+    }
+
+    fn show_reauth_popup(&mut self, reauth: Reauthorization) {
+        self.close_reauth_popup();
+        self.reauth_url = reauth.url;
+        if let Some(callback) = reauth.callback {
+            self.reauth_callback = Some(tokio::spawn(callback.wait()));
+        }
+        if let Some(e) = reauth.callback_error {
+            self.error_message = Some(tr!("{}. Pega la URL de redirección a mano", e));
+        }
+        self.input_mode = InputMode::Reauth;
+    }
+
+    fn close_reauth_popup(&mut self) {
+        if let Some(handle) = self.reauth_callback.take() {
+            handle.abort();
+        }
+        self.reauth_input.clear();
+        if matches!(self.input_mode, InputMode::Reauth) {
+            self.input_mode = InputMode::Normal;
+        }
+    }
+
+    async fn finish_reauth_from_callback(&mut self) {
+        let Some(handle) = self.reauth_callback.take() else {
+            return;
+        };
+        match handle.await {
+            Ok(Ok(redirect)) => self.complete_reauth(&redirect),
+            Ok(Err(e)) => {
+                self.error_message = Some(tr!("No se pudo recibir la redirección de Spotify: {}. Pega la URL a mano", e));
+            }
+            Err(_) => {}
+        }
+    }
+
+    /// Canjea el código; el aviso se cierra mientras tanto para no enviarlo dos veces
+    fn complete_reauth(&mut self, input: &str) {
+        self.close_reauth_popup();
+        self.success_message = Some(tr!("Comprobando la autorización...").to_string());
+        self.request(Request::CompleteReauthorization { input: input.to_string() });
+    }
+
+    /// Si la sesión se renovó, vuelve a la vista en la que estaba el usuario
+    fn finish_reauth(&mut self, result: ApiResult<()>) {
+        match result {
+            Ok(()) => {
+                self.reauth_dismissed = false;
+                self.error_message = None;
+                self.success_message = Some(tr!("Sesión renovada").to_string());
+                self.request(Request::Profile);
+                self.refresh_playback();
+                self.library_refresh_pending = true;
+            }
+            Err(e) => {
+                // Cada intento usa un state nuevo: se vuelve a empezar la autorización
+                self.error_message = Some(tr!("{}. Vuelve a autorizar", e));
+                self.open_reauth_popup();
+            }
+        }
+    }
+
+    /// El filtro se aplica mientras se escribe; Enter lo deja puesto y Esc lo quita
+    async fn handle_filter_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        let Some(list) = self.current_filter_list() else {
+            self.input_mode = InputMode::Normal;
+            return Ok(false);
+        };
+        match key.code {
+            KeyCode::Enter => {
+                if self.filters.get(&list).is_some_and(|text| text.is_empty()) {
+                    self.filters.remove(&list);
+                }
+                self.input_mode = InputMode::Normal;
+                return Ok(false);
+            }
+            KeyCode::Esc => {
+                self.filters.remove(&list);
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Up => {
+                if let Some((state, len)) = self.current_list() {
+                    select_previous(state, len);
+                }
+                return Ok(false);
+            }
+            KeyCode::Down => {
+                if let Some((state, len)) = self.current_list() {
+                    select_next(state, len);
+                }
+                return Ok(false);
+            }
+            KeyCode::Char(c) => self.filters.entry(list).or_default().push(c),
+            KeyCode::Backspace => {
+                self.filters.entry(list).or_default().pop();
+            }
+            _ => return Ok(false),
+        }
+        if let Some((state, len)) = self.current_list() {
+            state.select(if len == 0 { None } else { Some(0) });
+        }
+        Ok(false)
+    }
+
+    async fn handle_context_menu_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        let len = self.context_menu.as_ref().map_or(0, |(_, entries)| entries.len());
+        match key.code {
+            KeyCode::Up => select_previous(&mut self.context_menu_state, len),
+            KeyCode::Down => select_next(&mut self.context_menu_state, len),
+            KeyCode::Enter => {
+                self.input_mode = InputMode::Normal;
+                let menu = self.context_menu.take();
+                let entry = menu.as_ref().zip(self.context_menu_state.selected()).and_then(|((_, entries), i)| entries.get(i).copied());
+                if let (Some((target, _)), Some(entry)) = (menu, entry) {
+                    self.run_menu_entry(target, entry).await;
+                }
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+                self.context_menu = None;
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn handle_playlist_picker_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Up => select_previous(&mut self.picker_list_state, self.picker_playlists.len()),
+            KeyCode::Down => select_next(&mut self.picker_list_state, self.picker_playlists.len()),
+            KeyCode::Enter => {
+                self.add_to_picked_playlist();
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn handle_edit_playlist_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        let field = if self.editing_description {
+            &mut self.edit_playlist_description
+        } else {
+            &mut self.edit_playlist_name
+        };
+        match key.code {
+            KeyCode::Enter => {
+                if self.edit_playlist_name.trim().is_empty() {
+                    self.error_message = Some(tr!("El nombre de la playlist no puede estar vacío").to_string());
+                } else {
+                    self.save_playlist_details();
+                }
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Tab => {
+                self.editing_description = !self.editing_description;
+            }
+            KeyCode::Char(c) => {
+                field.push(c);
+            }
+            KeyCode::Backspace => {
+                field.pop();
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn handle_volume_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Enter => {
+                if let Ok(volume) = self.volume_input.parse::<u8>() {
+                    if volume <= 100 {
+                        self.set_volume(volume);
+                    } else {
+                        self.error_message = Some(tr!("El volumen debe estar entre 0 y 100").to_string());
+                    }
+                } else {
+                    self.error_message = Some(tr!("Volumen inválido").to_string());
+                }
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Char(c) if c.is_numeric() && self.volume_input.len() < 3 => {
+                self.volume_input.push(c);
+            }
+            KeyCode::Backspace => {
+                self.volume_input.pop();
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    fn handle_devices_key_event(&mut self, key: KeyEvent) -> Result<bool> {
+        match key.code {
+            KeyCode::Up => select_previous(&mut self.devices_list_state, self.devices.len()),
+            KeyCode::Down => select_next(&mut self.devices_list_state, self.devices.len()),
+            KeyCode::Enter => {
+                self.transfer_to_selected_device();
+                self.input_mode = InputMode::Normal;
+            }
+            KeyCode::Esc => {
+                self.input_mode = InputMode::Normal;
+            }
+            _ => {}
+        }
+        Ok(false)
+    }
+
+    /// Pide los dispositivos; la lista se abre al llegar
+    fn open_device_picker(&mut self) {
+        self.request(Request::Devices);
+    }
+
+    fn transfer_to_selected_device(&mut self) {
+        let Some(device) = self.devices_list_state.selected().and_then(|i| self.devices.get(i)) else {
+            return;
+        };
+        let Some(device_id) = device.id.clone() else {
+            self.error_message = Some(tr!("El dispositivo {} no admite transferencias", device.name));
+            return;
+        };
+        let device_name = device.name.clone();
+        let keep_playing = self.current_playback.as_ref().map(|p| p.is_playing).unwrap_or(false);
+        self.send_command(
+            Command::TransferPlayback { device_id, play: keep_playing },
+            AfterCommand::Refresh(tr!("Reproducción transferida a {}", device_name)),
+        );
+    }
+
+    fn toggle_playback(&mut self) {
+        let Some(playing) = self.current_playback.as_ref().map(|p| p.is_playing) else {
+            self.error_message = Some(tr!("No hay reproducción activa").to_string());
+            return;
+        };
+        if playing {
+            self.send_command(Command::Pause, AfterCommand::Refresh(tr!("Pausado").to_string()));
+        } else {
+            self.send_command(Command::Play, AfterCommand::Refresh(tr!("Reproduciendo").to_string()));
+        }
+    }
+
+    fn next_track(&mut self) {
+        self.send_command(Command::Next, AfterCommand::Refresh(tr!("Siguiente canción").to_string()));
+    }
+
+    fn previous_track(&mut self) {
+        self.send_command(Command::Previous, AfterCommand::Refresh(tr!("Canción anterior").to_string()));
+    }
+
+    fn toggle_shuffle(&mut self) {
+        self.send_command(Command::ToggleShuffle, AfterCommand::Refresh(tr!("Shuffle cambiado").to_string()));
+    }
+
+    fn toggle_repeat(&mut self) {
+        self.send_command(Command::ToggleRepeat, AfterCommand::Refresh(tr!("Modo repetición cambiado").to_string()));
+    }
+
+    fn set_volume(&mut self, volume: u8) {
+        self.send_command(Command::SetVolume(volume), AfterCommand::Volume(volume));
+    }
+
+    /// Empieza a reproducir; `message` se muestra cuando Spotify acepta la orden
+    fn start_playback(&mut self, context: PlaybackContext, offset: Option<PlaybackOffset>, message: String) {
+        self.send_command(Command::StartPlayback { context, offset, position_ms: None }, AfterCommand::Refresh(message));
+    }
+
+    /// Sube o baja el volumen del dispositivo activo partiendo del último conocido
+    fn nudge_volume(&mut self, delta: i32) {
+        let Some(current) = self.current_playback.as_ref().and_then(|p| p.device.volume_percent) else {
+            self.error_message = Some(tr!("El dispositivo no informa de su volumen").to_string());
+            return;
+        };
+        let volume = (current + delta).clamp(0, 100) as u8;
+        if i32::from(volume) != current {
+            self.set_volume(volume);
+        } else {
+            self.success_message = Some(tr!("Volumen: {}%", volume));
+        }
+    }
+
+    /// Silencia guardando el volumen actual o, si ya está silenciado, lo recupera
+    fn toggle_mute(&mut self) {
+        let Some(current) = self.current_playback.as_ref().and_then(|p| p.device.volume_percent) else {
+            self.error_message = Some(tr!("El dispositivo no informa de su volumen").to_string());
+            return;
+        };
+        match self.muted_volume {
+            // Si el volumen se cambió desde otro sitio el silencio ya no vale
+            Some(previous) if current == 0 => self.set_volume(previous),
+            _ if current == 0 => self.success_message = Some(tr!("El volumen ya está a 0").to_string()),
+            _ => {
+                self.muted_volume = Some(current.clamp(0, 100) as u8);
+                self.set_volume(0);
+            }
+        }
+    }
+
+    /// Tiempo que falta para lanzar la búsqueda en vivo, si hay una pendiente
+    fn search_pending_for(&self) -> Option<Duration> {
+        let (debounce, typed_at) = (self.search_debounce?, self.search_typed_at?);
+        Some(debounce.saturating_sub(typed_at.elapsed()))
+    }
+
+    /// Búsqueda lanzada mientras se escribe. Si al llegar la respuesta lo escrito ya es otra
+    /// cosa, se descarta (ver `handle_response`)
+    fn live_search(&mut self) {
+        let query = self.search_input.trim().to_string();
+        if query.is_empty() || query == self.search_query {
+            return;
+        }
+        self.load(Loading::Search, Request::Search { query, live: true });
+    }
+
+    /// Vacía los resultados de todas las pestañas para una búsqueda nueva
+    fn clear_search_results(&mut self, query: String) {
+        self.search_query = query;
+        self.search_pages.clear();
+        self.search_totals.clear();
+        self.search_results.clear();
+        self.reset_view_list(ViewList::Search);
+        self.search_albums.clear();
+        self.search_artists.clear();
+        self.search_playlists.clear();
+        self.search_shows.clear();
+        for state in [
+            &mut self.search_list_state,
+            &mut self.search_albums_list_state,
+            &mut self.search_artists_list_state,
+            &mut self.search_playlists_list_state,
+            &mut self.search_shows_list_state,
+        ] {
+            state.select(None);
+        }
+        self.playlist_preview = None;
+    }
+
+    /// Busca todos los tipos a la vez; cada pestaña guarda sus resultados y su selección
+    fn perform_search(&mut self) {
+        let query = self.search_input.clone();
+        self.load(Loading::Search, Request::Search { query, live: false });
+    }
+
+    /// Sustituye los resultados por los de una búsqueda recién llegada
+    fn apply_search(&mut self, query: String, live: bool, result: ApiResult<SearchResults>) {
+        match result {
+            Ok(results) => {
+                self.clear_search_results(query);
+                self.apply_search_results(results);
+                if live {
+                    // Mientras se escribe se pasa a ver los resultados
+                    if self.input_mode == InputMode::Search {
+                        self.app_state = AppState::Search;
+                    }
+                    return;
+                }
+                self.success_message = Some(tr!(
+                    "Encontrados: {} canciones, {} álbumes, {} artistas, {} playlists, {} podcasts",
+                    self.search_results.len(),
+                    self.search_albums.len(),
+                    self.search_artists.len(),
+                    self.search_playlists.len(),
+                    self.search_shows.len()
+                ));
+            }
+            Err(e) => self.show_error(tr!("Error en búsqueda"), e),
+        }
+    }
+
+    /// Pide la siguiente página de la pestaña activa
+    fn load_more_search_results(&mut self) {
+        if self.loading.contains(&Loading::Search) {
+            return;
+        }
+        let query = self.search_query.clone();
+        let search_type = self.search_type;
+        let offset = self.search_pages.get(&search_type).map(|(offset, _)| *offset).unwrap_or(0);
+        self.load(Loading::Search, Request::SearchPage { query, search_type, offset });
+    }
+
+    /// Añade a sus resultados la página recibida de un tipo
+    fn add_search_page(&mut self, search_type: SearchType, result: ApiResult<SearchResults>) {
+        match result {
+            Ok(results) => {
+                self.apply_search_results(results);
+                let loaded = match search_type {
+                    SearchType::Track => self.search_results.len(),
+                    SearchType::Album => self.search_albums.len(),
+                    SearchType::Artist => self.search_artists.len(),
+                    SearchType::Playlist => self.search_playlists.len(),
+                    SearchType::Show => self.search_shows.len(),
+                };
+                self.success_message = Some(tr!("{}: {} resultados", Self::search_type_label(search_type), loaded));
+            }
+            Err(e) => self.show_error(tr!("Error en búsqueda"), e),
+        }
+    }
+
+    /// Añade cada página recibida a su pestaña y actualiza su paginación.
+    /// Las entradas nulas cuentan para el desplazamiento aunque no se muestren
+    fn apply_search_results(&mut self, results: SearchResults) {
+        if let Some(page) = results.tracks {
+            self.refresh_saved_tracks(&page.items);
+            self.advance_search_page(SearchType::Track, page.items.len(), page.next.is_some(), page.total);
+            self.search_results.extend(page.items);
+            select_first_if_none(&mut self.search_list_state, self.search_results.len());
+        }
+        if let Some(page) = results.albums {
+            self.advance_search_page(SearchType::Album, page.items.len(), page.next.is_some(), page.total);
+            self.search_albums.extend(page.items);
+            select_first_if_none(&mut self.search_albums_list_state, self.search_albums.len());
+        }
+        if let Some(page) = results.artists {
+            self.advance_search_page(SearchType::Artist, page.items.len(), page.next.is_some(), page.total);
+            self.search_artists.extend(page.items);
+            select_first_if_none(&mut self.search_artists_list_state, self.search_artists.len());
+        }
+        if let Some(page) = results.playlists {
+            self.advance_search_page(SearchType::Playlist, page.items.len(), page.next.is_some(), page.total);
+            self.search_playlists.extend(page.items.into_iter().flatten());
+            select_first_if_none(&mut self.search_playlists_list_state, self.search_playlists.len());
+        }
+        if let Some(page) = results.shows {
+            self.advance_search_page(SearchType::Show, page.items.len(), page.next.is_some(), page.total);
+            self.search_shows.extend(page.items.into_iter().flatten());
+            select_first_if_none(&mut self.search_shows_list_state, self.search_shows.len());
+        }
+    }
+
+    fn advance_search_page(&mut self, search_type: SearchType, received: usize, has_next: bool, total: i32) {
+        let page = self.search_pages.entry(search_type).or_insert((0, false));
+        page.0 += received as u32;
+        page.1 = has_next && received > 0;
+        self.search_totals.insert(search_type, total.max(0) as usize);
+    }
+
+    fn search_has_more(&self) -> bool {
+        self.search_pages.get(&self.search_type).is_some_and(|(_, has_more)| *has_more)
+    }
+
+    fn play_selected_show(&mut self) {
+        let Some(show) = self.search_shows_list_state.selected().and_then(|i| self.search_shows.get(i)) else {
+            return;
+        };
+        let show_uri = format!("spotify:show:{}", show.id);
+        let name = show.name.clone();
+        self.start_playback(PlaybackContext::Context(show_uri), None, tr!("Reproduciendo podcast: {}", name));
+    }
+
+    fn is_searching_playlists(&self) -> bool {
+        matches!(self.app_state, AppState::Search) && self.search_type == SearchType::Playlist
+    }
+
+    fn selected_search_playlist(&self) -> Option<&Playlist> {
+        self.search_playlists_list_state
+            .selected()
+            .and_then(|i| self.search_playlists.get(i))
+    }
+
+    fn preview_selected_search_playlist(&mut self) {
+        let Some(playlist) = self.selected_search_playlist().cloned() else {
+            return;
+        };
+        self.request(Request::PlaylistPreview { playlist });
+    }
+
+    fn follow_selected_search_playlist(&mut self) {
+        let Some(playlist) = self.selected_search_playlist().cloned() else {
+            return;
+        };
+        let message = tr!("Siguiendo playlist: {}", playlist.name);
+        self.send_command(Command::FollowPlaylist(playlist.id), AfterCommand::Message(message));
+    }
+
+    fn play_selected_search_playlist(&mut self) {
+        let Some(playlist) = self.selected_search_playlist().cloned() else {
+            return;
+        };
+        let playlist_uri = format!("spotify:playlist:{}", playlist.id);
+        self.start_playback(PlaybackContext::Context(playlist_uri), None, tr!("Reproduciendo playlist: {}", playlist.name));
+    }
+
+    fn play_selected_artist(&mut self) {
+        if let Some(i) = self.search_artists_list_state.selected() {
+            if let Some(artist) = self.search_artists.get(i) {
+                // El contexto de artista reproduce sus canciones más populares
+                let artist_uri = format!("spotify:artist:{}", artist.id);
+                self.start_playback(PlaybackContext::Context(artist_uri), None, tr!("Reproduciendo artista: {}", artist.name));
+            }
+        }
+    }
+
+    fn play_selected_album(&mut self) {
+        if let Some(i) = self.search_albums_list_state.selected() {
+            if let Some(album) = self.search_albums.get(i) {
+                let album_uri = format!("spotify:album:{}", album.id);
+                self.start_playback(PlaybackContext::Context(album_uri), None, tr!("Reproduciendo álbum: {}", album.name));
+            }
+        }
+    }
+
+    fn play_selected_track(&mut self) {
+        let Some(track) = self.selected_track().cloned() else {
+            return;
+        };
+        // Se reproduce dentro de su álbum para que al terminar siga con las siguientes canciones
+        let album_uri = format!("spotify:album:{}", track.album.id);
+        let track_uri = format!("spotify:track:{}", track.id);
+        self.start_playback(PlaybackContext::Context(album_uri), Some(PlaybackOffset::Uri(track_uri)), tr!("Reproduciendo: {}", track.name));
+    }
+
+    /// Canción seleccionada en la lista de canciones de la vista activa
+    fn selected_track(&self) -> Option<&Track> {
+        let (tracks, state) = match self.app_state {
+            AppState::PlaylistTracks => return self.selected_playlist_item_track(),
+            AppState::Search if self.search_type == SearchType::Track => (&self.search_results, &self.search_list_state),
+            AppState::Favorites => (&self.favorites, &self.favorites_list_state),
+            AppState::Queue => {
+                let i = self.queue_list_state.selected().map(|i| self.data_index(ViewList::Queue, i))?;
+                return self.queue.get(i).and_then(PlayableItem::track);
+            }
+            AppState::Top => (&self.top_tracks, &self.top_tracks_list_state),
+            AppState::Discover => (&self.discover_results, &self.discover_list_state),
+            AppState::AlbumTracks => (&self.album_tracks, &self.album_tracks_list_state),
+            AppState::Artist if self.artist_section == ArtistSection::TopTracks => {
+                (&self.artist_top_tracks, &self.artist_top_tracks_list_state)
+            }
+            AppState::Player
+            | AppState::Search
+            | AppState::Playlists
+            | AppState::Episodes
+            | AppState::Browse
+            | AppState::Artist
+            | AppState::SavedAlbums
+            | AppState::Lyrics
+            | AppState::Artists
+            | AppState::Log => return None,
+        };
+        let i = state.selected()?;
+        let i = self.current_track_list().map_or(i, |list| self.data_index(list, i));
+        tracks.get(i)
+    }
+
+    fn selected_playlist_item_track(&self) -> Option<&Track> {
+        self.playlist_items_list_state
+            .selected()
+            .map(|i| self.data_index(ViewList::PlaylistItems, i))
+            .and_then(|i| self.playlist_items.get(i))
+            .and_then(|item| item.track.as_ref())
+    }
+
+    fn sort_key(&self, list: ViewList) -> SortKey {
+        self.sort_keys.get(&list).copied().unwrap_or_default()
+    }
+
+    /// Posiciones de la lista que pasan el filtro, en el orden elegido; a igualdad se mantiene
+    /// el orden original
+    fn view_order(&self, list: ViewList) -> Vec<usize> {
+        let filter = self.filters.get(&list).map(String::as_str).unwrap_or("");
+        if list == ViewList::Playlists {
+            return (0..self.playlists.len())
+                .filter(|&i| {
+                    let playlist = &self.playlists[i];
+                    let owner = playlist.owner.display_name.as_deref().unwrap_or(&playlist.owner.id);
+                    fuzzy_match(filter, &format!("{} {}", playlist.name, owner))
+                })
+                .collect();
+        }
+
+        let rows: Vec<(Option<&Track>, &str)> = match list {
+            ViewList::Search => self.search_results.iter().map(|t| (Some(t), "")).collect(),
+            ViewList::Favorites => self.favorites.iter().map(|t| (Some(t), "")).collect(),
+            ViewList::Queue => self.queue.iter().map(|item| (item.track(), "")).collect(),
+            ViewList::Top => self.top_tracks.iter().map(|t| (Some(t), "")).collect(),
+            ViewList::Discover => self.discover_results.iter().map(|t| (Some(t), "")).collect(),
+            ViewList::PlaylistItems => self.playlist_items.iter().map(|item| (item.track.as_ref(), item.added_at.as_str())).collect(),
+            ViewList::AlbumTracks => self.album_tracks.iter().map(|t| (Some(t), "")).collect(),
+            ViewList::ArtistTopTracks => self.artist_top_tracks.iter().map(|t| (Some(t), "")).collect(),
+            ViewList::Playlists => Vec::new(),
+        };
+        let track = |i: usize| rows[i].0;
+        let mut order: Vec<usize> = (0..rows.len())
+            .filter(|&i| {
+                filter.trim().is_empty()
+                    || track(i).is_some_and(|t| {
+                        let artists: Vec<&str> = t.artists.iter().map(|a| a.name.as_str()).collect();
+                        fuzzy_match(filter, &format!("{} {} {}", t.name, artists.join(" "), t.album.name))
+                    })
+            })
+            .collect();
+        match self.sort_key(list) {
+            SortKey::Default => {}
+            SortKey::Title => order.sort_by_cached_key(|&i| track(i).map(|t| t.name.to_lowercase())),
+            SortKey::Artist => {
+                order.sort_by_cached_key(|&i| track(i).and_then(|t| t.artists.first()).map(|a| a.name.to_lowercase()))
+            }
+            SortKey::Album => order.sort_by_cached_key(|&i| track(i).map(|t| t.album.name.to_lowercase())),
+            SortKey::Duration => order.sort_by_key(|&i| track(i).map(|t| t.duration_ms)),
+            SortKey::Popularity => order.sort_by_key(|&i| Reverse(track(i).map(|t| t.popularity))),
+            SortKey::Added => order.sort_by_key(|&i| Reverse(rows[i].1)),
+        }
+        order
+    }
+
+    /// Posición en los datos de la fila `i` tal como se muestra la lista (ordenada y filtrada)
+    fn data_index(&self, list: ViewList, i: usize) -> usize {
+        if self.sort_key(list) == SortKey::Default && !self.filters.contains_key(&list) {
+            return i;
+        }
+        self.view_order(list).get(i).copied().unwrap_or(usize::MAX)
+    }
+
+    /// Lista de la vista activa que se puede filtrar
+    fn current_filter_list(&self) -> Option<ViewList> {
+        match self.app_state {
+            AppState::Playlists => Some(ViewList::Playlists),
+            AppState::Artist if self.artist_section != ArtistSection::TopTracks => None,
+            _ => self.current_track_list(),
+        }
+    }
+
+    /// Quita las marcas y el filtro de una lista cuyo contenido se sustituye
+    fn reset_view_list(&mut self, list: ViewList) {
+        self.marked_tracks.remove(&list);
+        self.filters.remove(&list);
+    }
+
+    /// Título de una lista con el filtro aplicado, si lo hay
+    fn with_filter(&self, list: ViewList, title: String) -> String {
+        match self.filters.get(&list).filter(|text| !text.is_empty()) {
+            Some(text) if title.is_empty() => tr!("Filtro: {}", text),
+            Some(text) => format!("{} · {}", title, tr!("Filtro: {}", text)),
+            None => title,
+        }
+    }
+
+    /// Aviso de carga si hay una petición pendiente para la lista de la vista activa
+    fn loading_notice(&self) -> Option<String> {
+        let loading = match self.app_state {
+            AppState::Search => Loading::Search,
+            AppState::PlaylistTracks => Loading::Playlist,
+            AppState::Playlists => Loading::Playlists,
+            AppState::Favorites => Loading::Favorites,
+            AppState::SavedAlbums => Loading::SavedAlbums,
+            AppState::AlbumTracks => Loading::Album,
+            AppState::Artists => Loading::Artists,
+            AppState::Queue => Loading::Queue,
+            AppState::Top => Loading::Top,
+            AppState::Discover => Loading::Discover,
+            AppState::Episodes => Loading::Episodes,
+            AppState::Browse => match self.browse_tab {
+                BrowseTab::NewReleases => Loading::NewReleases,
+                BrowseTab::Featured => Loading::Featured,
+                BrowseTab::Categories => Loading::Categories,
+            },
+            _ => return None,
+        };
+        self.loading
+            .contains(&loading)
+            .then(|| format!("{} {}", self.glyphs("⏳"), tr!("Cargando...")))
+    }
+
+    /// Añade el aviso de carga al título de una lista que ya muestra datos (que pueden estar viejos)
+    fn with_loading(&self, title: String) -> String {
+        match self.loading_notice() {
+            Some(notice) if title.is_empty() => notice,
+            Some(notice) => format!("{} · {}", title, notice),
+            None => title,
+        }
+    }
+
+    /// Pasa al siguiente orden en la lista de canciones activa
+    fn cycle_sort(&mut self) {
+        let Some(list) = self.current_track_list().filter(|list| {
+            matches!(list, ViewList::Search | ViewList::Favorites | ViewList::PlaylistItems)
+        }) else {
+            return;
+        };
+        let key = self.sort_key(list).next(list == ViewList::PlaylistItems);
+        self.sort_keys.insert(list, key);
+        if let Some((state, len)) = self.current_list() {
+            state.select(if len == 0 { None } else { Some(0) });
+        }
+        self.success_message = Some(self.sort_title(list));
+    }
+
+    /// "Orden: título", para el título de la lista
+    fn sort_title(&self, list: ViewList) -> String {
+        let label = match self.sort_key(list) {
+            SortKey::Default => match list {
+                ViewList::Search => tr!("relevancia"),
+                ViewList::Favorites => tr!("añadidas recientemente"),
+                _ => tr!("orden de la playlist"),
+            },
+            SortKey::Title => tr!("título"),
+            SortKey::Artist => tr!("artista"),
+            SortKey::Album => tr!("álbum"),
+            SortKey::Duration => tr!("duración"),
+            SortKey::Popularity => tr!("popularidad"),
+            SortKey::Added => tr!("añadidas recientemente"),
+        };
+        tr!("Orden: {} ({}: cambiar)", label, self.keymap.label(Action::Sort))
+    }
+
+    fn show_local_file_error(&mut self, track: &Track) {
+        self.error_message = Some(tr!("«{}» es un archivo local: Spotify no permite usarlo desde la API", track.name));
+    }
+
+    /// Playlist seleccionada en las listas de playlists (biblioteca, búsqueda y Explorar)
+    fn selected_playlist(&self) -> Option<&Playlist> {
+        match self.app_state {
+            AppState::Playlists => self.selected_library_playlist(),
+            AppState::Search => self.selected_search_playlist(),
+            AppState::Browse if self.browse_tab == BrowseTab::Featured => {
+                self.featured_list_state.selected().and_then(|i| self.featured_playlists.get(i))
+            }
+            AppState::Browse if self.browse_tab == BrowseTab::Categories => self
+                .open_category
+                .as_ref()
+                .and_then(|(_, playlists)| self.category_playlists_list_state.selected().and_then(|i| playlists.get(i))),
+            _ => None,
+        }
+    }
+
+    /// Abre el menú de acciones de la canción, el álbum o la playlist seleccionados
+    fn open_context_menu(&mut self) {
+        let marked = self.marked_tracks_in_view();
+        let target = if !marked.is_empty() {
+            MenuTarget::Marked(marked)
+        } else if let Some(target) = self.selected_menu_target() {
+            target
+        } else {
+            return;
+        };
+        let entries = match target {
+            MenuTarget::Track(_) => vec![
+                MenuEntry::Play,
+                MenuEntry::AddToQueue,
+                MenuEntry::AddToPlaylist,
+                MenuEntry::ToggleFavorite,
+                MenuEntry::ShowArtist,
+                MenuEntry::ShowAlbum,
+                MenuEntry::CopyLink,
+                MenuEntry::OpenExternal,
+            ],
+            MenuTarget::Album(_) => vec![
+                MenuEntry::Play,
+                MenuEntry::ShowAlbum,
+                MenuEntry::ShowArtist,
+                MenuEntry::CopyLink,
+                MenuEntry::OpenExternal,
+            ],
+            MenuTarget::Playlist(_) => vec![MenuEntry::Play, MenuEntry::CopyLink, MenuEntry::OpenExternal],
+            MenuTarget::Marked(_) => {
+                let mut entries = vec![MenuEntry::AddToQueue, MenuEntry::AddToPlaylist, MenuEntry::ToggleFavorite];
+                if matches!(self.app_state, AppState::PlaylistTracks) {
+                    entries.push(MenuEntry::RemoveFromPlaylist);
+                }
+                entries.push(MenuEntry::ClearMarks);
+                entries
+            }
+        };
+        self.context_menu = Some((target, entries));
+        self.context_menu_state.select(Some(0));
+        self.input_mode = InputMode::ContextMenu;
+    }
+
+    /// Canción, álbum o playlist seleccionados en la vista activa
+    fn selected_menu_target(&self) -> Option<MenuTarget> {
+        if let Some(track) = self.selected_track() {
+            Some(MenuTarget::Track(track.clone()))
+        } else if let Some(album) = self.selected_album() {
+            Some(MenuTarget::Album(album.clone()))
+        } else {
+            self.selected_playlist().map(|playlist| MenuTarget::Playlist(playlist.clone()))
+        }
+    }
+
+    /// Lo seleccionado o, si no hay nada seleccionado, la canción que suena
+    fn selected_or_playing_target(&mut self) -> Option<MenuTarget> {
+        let target = self.selected_menu_target().or_else(|| {
+            self.current_playback
+                .as_ref()
+                .and_then(PlaybackState::track)
+                .map(|track| MenuTarget::Track(track.clone()))
+        });
+        if target.is_none() {
+            self.error_message = Some(tr!("No hay nada seleccionado ni sonando").to_string());
+        }
+        target
+    }
+
+    /// Nombre de una opción del menú contextual para el elemento
+    fn menu_entry_label(&self, target: &MenuTarget, entry: MenuEntry) -> &'static str {
+        if let MenuTarget::Marked(_) = target {
+            match entry {
+                MenuEntry::AddToQueue => return tr!("Añadir todas a la cola"),
+                MenuEntry::AddToPlaylist => return tr!("Añadir todas a una playlist"),
+                MenuEntry::ToggleFavorite => return tr!("Me gusta a todas"),
+                _ => {}
+            }
+        }
+        match entry {
+            MenuEntry::Play => tr!("Reproducir"),
+            MenuEntry::AddToQueue => tr!("Añadir a la cola"),
+            MenuEntry::AddToPlaylist => tr!("Añadir a playlist"),
+            MenuEntry::ToggleFavorite => match target {
+                MenuTarget::Track(track) if self.saved_tracks.get(&track.id).copied().unwrap_or(false) => tr!("Quitar de favoritos"),
+                _ => tr!("Me gusta"),
+            },
+            MenuEntry::ShowArtist => tr!("Ir al artista"),
+            MenuEntry::ShowAlbum => tr!("Ir al álbum"),
+            MenuEntry::CopyLink => tr!("Copiar enlace"),
+            MenuEntry::OpenExternal => tr!("Abrir en Spotify"),
+            MenuEntry::RemoveFromPlaylist => tr!("Quitar todas de la playlist"),
+            MenuEntry::ClearMarks => tr!("Desmarcar todas"),
+        }
+    }
+
+    async fn run_menu_entry(&mut self, target: MenuTarget, entry: MenuEntry) {
+        match (entry, target) {
+            // La canción sigue seleccionada: se reproduce igual que con Enter, en su contexto
+            (MenuEntry::Play, MenuTarget::Track(_)) => {
+                let _ = self.handle_normal_key_event(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE)).await;
+            }
+            (MenuEntry::Play, MenuTarget::Album(album)) => {
+                self.play_context(format!("spotify:album:{}", album.id), tr!("Reproduciendo álbum: {}", album.name));
+            }
+            (MenuEntry::Play, MenuTarget::Playlist(playlist)) => {
+                self.play_context(format!("spotify:playlist:{}", playlist.id), tr!("Reproduciendo playlist: {}", playlist.name))
+                    ;
+            }
+            (MenuEntry::AddToQueue, MenuTarget::Track(track)) => self.queue_track(&track),
+            (MenuEntry::AddToPlaylist, MenuTarget::Track(track)) => match track.uri() {
+                Some(uri) => self.open_playlist_picker(vec![uri]),
+                None => self.show_local_file_error(&track),
+            },
+            (MenuEntry::ToggleFavorite, MenuTarget::Track(track)) => self.toggle_track_saved(track),
+            (MenuEntry::ShowArtist, MenuTarget::Track(Track { artists, .. }) | MenuTarget::Album(Album { artists, .. })) => {
+                if let Some(artist) = artists.into_iter().next() {
+                    self.open_artist_detail(artist);
+                }
+            }
+            (MenuEntry::ShowAlbum, MenuTarget::Track(Track { album, .. }) | MenuTarget::Album(album)) => {
+                self.open_album_view(album);
+            }
+            (MenuEntry::CopyLink, target) => self.copy_link(&target),
+            (MenuEntry::OpenExternal, target) => self.open_externally(&target),
+            (MenuEntry::AddToQueue, MenuTarget::Marked(tracks)) => self.queue_tracks(&tracks),
+            (MenuEntry::AddToPlaylist, MenuTarget::Marked(tracks)) => self.open_playlist_picker(track_uris(&tracks)),
+            (MenuEntry::ToggleFavorite, MenuTarget::Marked(tracks)) => self.save_marked_tracks(&tracks),
+            (MenuEntry::RemoveFromPlaylist, MenuTarget::Marked(tracks)) => self.remove_marked_from_playlist(&tracks),
+            (MenuEntry::ClearMarks, _) => self.clear_marks(),
+            _ => {}
+        }
+    }
+
+    /// Reproduce un álbum o una playlist desde el principio
+    fn play_context(&mut self, uri: String, message: String) {
+        self.start_playback(PlaybackContext::Context(uri), None, message);
+    }
+
+    /// Abre el lanzador sobre lo que hay cargado de la biblioteca. Los artistas seguidos no se
+    /// cargan al arrancar, así que se piden la primera vez
+    fn open_launcher(&mut self) {
+        if self.followed_artists.is_empty() {
+            self.load_followed_artists();
+        }
+        self.launcher_input.clear();
+        self.update_launcher_results();
+        self.input_mode = InputMode::Launcher;
+    }
+
+    /// Icono, nombre y detalle (dueño o artistas) de un resultado del lanzador
+    fn launcher_entry(&self, item: LauncherItem) -> (&'static str, &str, String) {
+        let artists = |artists: &[Artist]| artists.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ");
+        match item {
+            LauncherItem::Playlist(i) => {
+                let playlist = &self.playlists[i];
+                let owner = playlist.owner.display_name.as_deref().unwrap_or(&playlist.owner.id);
+                ("📋 ", &playlist.name, owner.to_string())
+            }
+            LauncherItem::Album(i) => ("💿 ", &self.saved_albums[i].name, artists(&self.saved_albums[i].artists)),
+            LauncherItem::Artist(i) => ("🎤 ", &self.followed_artists[i].name, String::new()),
+            LauncherItem::Track(i) => ("♥ ", &self.favorites[i].name, artists(&self.favorites[i].artists)),
+        }
+    }
+
+    fn update_launcher_results(&mut self) {
+        let items = (0..self.playlists.len())
+            .map(LauncherItem::Playlist)
+            .chain((0..self.saved_albums.len()).map(LauncherItem::Album))
+            .chain((0..self.followed_artists.len()).map(LauncherItem::Artist))
+            .chain((0..self.favorites.len()).map(LauncherItem::Track));
+        self.launcher_results = items
+            .filter(|&item| {
+                let (_, name, detail) = self.launcher_entry(item);
+                fuzzy_match(&self.launcher_input, &format!("{} {}", name, detail))
+            })
+            .take(LAUNCHER_LIMIT)
+            .collect();
+        self.launcher_list_state.select(if self.launcher_results.is_empty() { None } else { Some(0) });
+    }
+
+    /// Abre el resultado elegido en su vista o, con `play`, lo reproduce. Las canciones
+    /// favoritas se seleccionan en Favoritos
+    fn launch(&mut self, item: LauncherItem, play: bool) {
+        match item {
+            LauncherItem::Playlist(i) => {
+                let playlist = self.playlists[i].clone();
+                if play {
+                    self.play_context(format!("spotify:playlist:{}", playlist.id), tr!("Reproduciendo playlist: {}", playlist.name))
+                        ;
+                } else {
+                    self.show_playlist(playlist);
+                }
+            }
+            LauncherItem::Album(i) => {
+                let album = self.saved_albums[i].clone();
+                if play {
+                    self.play_context(format!("spotify:album:{}", album.id), tr!("Reproduciendo álbum: {}", album.name));
+                } else {
+                    self.open_album_view(album);
+                }
+            }
+            LauncherItem::Artist(i) => {
+                let artist = Self::simple_artist(&self.followed_artists[i]);
+                if play {
+                    self.play_context(format!("spotify:artist:{}", artist.id), tr!("Reproduciendo artista: {}", artist.name));
+                } else {
+                    self.open_artist_detail(artist);
+                }
+            }
+            LauncherItem::Track(i) => {
+                self.nav_stack.clear();
+                self.app_state = AppState::Favorites;
+                self.filters.remove(&ViewList::Favorites);
+                let position = self.view_order(ViewList::Favorites).into_iter().position(|j| j == i);
+                self.favorites_list_state.select(position);
+                if play {
+                    self.play_selected_favorite();
+                }
+            }
+        }
+    }
+
+    /// Copia al portapapeles el enlace de open.spotify.com del elemento
+    fn copy_link(&mut self, target: &MenuTarget) {
+        let link = target.link();
+        if link.is_empty() {
+            self.error_message = Some(tr!("«{}» no tiene enlace de Spotify", target.name()));
+            return;
+        }
+        match arboard::Clipboard::new().and_then(|mut clipboard| clipboard.set_text(link)) {
+            Ok(_) => self.success_message = Some(tr!("Enlace copiado: {}", link)),
+            Err(e) => self.error_message = Some(tr!("No se pudo copiar al portapapeles: {}", e)),
+        }
+    }
+
+    /// Abre el elemento en el navegador o, con `open_in_app`, en la aplicación oficial de Spotify
+    fn open_externally(&mut self, target: &MenuTarget) {
+        let address = if self.open_in_app {
+            target.uri()
+        } else {
+            Some(target.link().to_string()).filter(|link| !link.is_empty())
+        };
+        let Some(address) = address else {
+            self.error_message = Some(tr!("«{}» no tiene enlace de Spotify", target.name()));
+            return;
+        };
+        match webbrowser::open(&address) {
+            Ok(_) => self.success_message = Some(tr!("Abriendo: {}", address)),
+            Err(e) => self.error_message = Some(tr!("No se pudo abrir {}: {}", address, e)),
+        }
+    }
+
+    /// Lista de canciones de la vista activa en la que se pueden marcar canciones
+    fn current_track_list(&self) -> Option<ViewList> {
+        match self.app_state {
+            AppState::Search if self.search_type == SearchType::Track => Some(ViewList::Search),
+            AppState::Favorites => Some(ViewList::Favorites),
+            AppState::Queue => Some(ViewList::Queue),
+            AppState::Top => Some(ViewList::Top),
+            AppState::Discover => Some(ViewList::Discover),
+            AppState::PlaylistTracks => Some(ViewList::PlaylistItems),
+            AppState::AlbumTracks => Some(ViewList::AlbumTracks),
+            AppState::Artist => Some(ViewList::ArtistTopTracks),
+            _ => None,
+        }
+    }
+
+    /// Ids marcados en la lista activa, si hay alguno
+    fn current_marks(&self) -> Option<&HashSet<String>> {
+        self.current_track_list()
+            .and_then(|list| self.marked_tracks.get(&list))
+            .filter(|marks| !marks.is_empty())
+    }
+
+    /// Canciones marcadas de la lista activa, en el orden de la lista
+    fn marked_tracks_in_view(&self) -> Vec<Track> {
+        let (Some(list), Some(marks)) = (self.current_track_list(), self.current_marks()) else {
+            return Vec::new();
+        };
+        let tracks: Vec<&Track> = match list {
+            ViewList::Search => self.search_results.iter().collect(),
+            ViewList::Favorites => self.favorites.iter().collect(),
+            ViewList::Queue => self.queue.iter().filter_map(PlayableItem::track).collect(),
+            ViewList::Top => self.top_tracks.iter().collect(),
+            ViewList::Discover => self.discover_results.iter().collect(),
+            ViewList::PlaylistItems => self.playlist_items.iter().filter_map(|item| item.track.as_ref()).collect(),
+            ViewList::AlbumTracks => self.album_tracks.iter().collect(),
+            ViewList::ArtistTopTracks => self.artist_top_tracks.iter().collect(),
+            ViewList::Playlists => Vec::new(),
+        };
+        let mut seen = HashSet::new();
+        tracks
+            .into_iter()
+            .filter(|track| marks.contains(&track.id) && seen.insert(&track.id))
+            .cloned()
+            .collect()
+    }
+
+    /// Marca o desmarca la canción seleccionada y pasa a la siguiente
+    fn toggle_selected_mark(&mut self) {
+        let (Some(list), Some(track)) = (self.current_track_list(), self.selected_track().cloned()) else {
+            return;
+        };
+        if !track.is_playable() {
+            self.show_local_file_error(&track);
+            return;
+        }
+        let marks = self.marked_tracks.entry(list).or_default();
+        if !marks.remove(&track.id) {
+            marks.insert(track.id);
+        }
+        let count = marks.len();
+        self.success_message = Some(tr!("{} canciones marcadas ({}: acciones)", count, self.keymap.label(Action::ContextMenu)));
+        if let Some((state, len)) = self.current_list() {
+            select_next(state, len);
+        }
+    }
+
+    /// Canción en la posición `i` de los datos de una lista, sin tener en cuenta orden ni filtro
+    fn list_track(&self, list: ViewList, i: usize) -> Option<&Track> {
+        match list {
+            ViewList::Search => self.search_results.get(i),
+            ViewList::Favorites => self.favorites.get(i),
+            ViewList::Queue => self.queue.get(i).and_then(PlayableItem::track),
+            ViewList::Top => self.top_tracks.get(i),
+            ViewList::Discover => self.discover_results.get(i),
+            ViewList::PlaylistItems => self.playlist_items.get(i).and_then(|item| item.track.as_ref()),
+            ViewList::AlbumTracks => self.album_tracks.get(i),
+            ViewList::ArtistTopTracks => self.artist_top_tracks.get(i),
+            ViewList::Playlists => None,
+        }
+    }
+
+    /// Lleva la selección de la lista activa a la canción que suena, si está en ella
+    fn jump_to_playing(&mut self) {
+        let list = match self.app_state {
+            AppState::Artist if self.artist_section != ArtistSection::TopTracks => None,
+            _ => self.current_track_list(),
+        };
+        let Some(list) = list else {
+            return;
+        };
+        let Some(id) = self.current_playback.as_ref().and_then(|p| p.track()).map(|t| t.id.clone()) else {
+            self.error_message = Some(tr!("No hay nada sonando").to_string());
+            return;
+        };
+        let position = self
+            .view_order(list)
+            .into_iter()
+            .position(|i| self.list_track(list, i).is_some_and(|track| track.id == id));
+        match (position, self.current_list()) {
+            (Some(position), Some((state, _))) => state.select(Some(position)),
+            _ => self.error_message = Some(tr!("La canción que suena no está en esta lista").to_string()),
+        }
+    }
+
+    fn clear_marks(&mut self) {
+        self.unmark(self.current_track_list());
+    }
+
+    /// Quita las marcas de una lista, que puede no ser ya la activa
+    fn unmark(&mut self, list: Option<ViewList>) {
+        if let Some(list) = list {
+            self.marked_tracks.remove(&list);
+        }
+    }
+
+    fn queue_tracks(&mut self, tracks: &[Track]) {
+        let tracks = tracks.iter().filter(|t| t.uri().is_some()).cloned().collect();
+        let marks = self.current_track_list();
+        self.queue_next(QueueBatch { tracks, queued: 0, radio: None, marks });
+    }
+
+    /// Encola la siguiente canción del lote o, si no quedan, avisa de cuántas se han añadido
+    fn queue_next(&mut self, batch: QueueBatch) {
+        if let Some(uri) = batch.tracks.front().and_then(|t| t.uri()) {
+            self.send_command(Command::AddToQueue(uri), AfterCommand::Queue(batch));
+            return;
+        }
+        match batch.radio {
+            Some(track) => {
+                if batch.queued > 0 {
+                    self.success_message = Some(tr!("Radio de {}: {} canciones en cola", track, batch.queued));
+                }
+            }
+            None => {
+                self.success_message = Some(tr!("{} canciones añadidas a la cola", batch.queued));
+                self.unmark(batch.marks);
+            }
+        }
+    }
+
+    fn save_marked_tracks(&mut self, tracks: &[Track]) {
+        let ids: Vec<String> = tracks.iter().map(|t| t.id.clone()).collect();
+        let marks = self.current_track_list();
+        self.send_command(Command::SaveTracks(ids), AfterCommand::TracksSaved { tracks: tracks.to_vec(), marks });
+    }
+
+    /// Quita las canciones marcadas de la playlist abierta y la vuelve a cargar
+    fn remove_marked_from_playlist(&mut self, tracks: &[Track]) {
+        let Some(playlist) = self.open_playlist.clone() else {
+            return;
+        };
+        let command = Command::RemovePlaylistTracks { playlist_id: playlist.id.clone(), uris: track_uris(tracks) };
+        self.send_command(command, AfterCommand::PlaylistTracksRemoved { playlist, count: tracks.len() });
+    }
+
+    fn queue_track(&mut self, track: &Track) {
+        let Some(track_uri) = track.uri() else {
+            self.show_local_file_error(track);
+            return;
+        };
+        self.send_command(Command::AddToQueue(track_uri), AfterCommand::Queued(track.clone()));
+    }
+
+    /// Con la cola propia activa, lo que se encola va al final de ella
+    fn add_to_own_queue(&mut self, track: &Track) {
+        if let Some(known) = self.own_queue.as_mut() {
+            known.insert(track.id.clone());
+            self.queue.push(PlayableItem::Track(track.clone()));
+        }
+    }
+
+    /// Pasa a la cola propia para poder cambiarla; no se puede con filtro u otro orden
+    fn take_over_queue(&mut self) -> bool {
+        if self.sort_key(ViewList::Queue) != SortKey::Default || self.filters.contains_key(&ViewList::Queue) {
+            self.error_message = Some(tr!("Quita el filtro y vuelve al orden de la cola para cambiarla").to_string());
+            return false;
+        }
+        if self.own_queue.is_none() {
+            self.own_queue = Some(self.queue.iter().map(|item| item.id().to_string()).collect());
+        }
+        true
+    }
+
+    /// Sube o baja en la cola la canción seleccionada
+    fn move_selected_queue_item(&mut self, up: bool) {
+        let Some(i) = self.queue_list_state.selected() else {
+            return;
+        };
+        let target = if up { i.checked_sub(1) } else { Some(i + 1).filter(|&j| j < self.queue.len()) };
+        let Some(target) = target else {
+            return;
+        };
+        if !self.take_over_queue() {
+            return;
+        }
+        self.queue.swap(i, target);
+        self.queue_list_state.select(Some(target));
+    }
+
+    /// Quita de la cola la canción seleccionada
+    fn remove_selected_queue_item(&mut self) {
+        let Some(i) = self.queue_list_state.selected().filter(|&i| i < self.queue.len()) else {
+            return;
+        };
+        if !self.take_over_queue() {
+            return;
+        }
+        let item = self.queue.remove(i);
+        self.queue_list_state.select(if self.queue.is_empty() { None } else { Some(i.min(self.queue.len() - 1)) });
+        self.success_message = Some(tr!("Quitada de la cola: {}", item.name()));
+    }
+
+    /// Hace cumplir la cola propia cuando cambia la canción. Si suena la siguiente de la cola,
+    /// se descuenta; si Spotify sigue con una de su cola (quitada o en otro orden), se vuelve a
+    /// lanzar la cola propia; y si suena cualquier otra cosa, se deja y vuelve la de Spotify
+    fn follow_own_queue(&mut self, previous: Option<String>) {
+        let Some(known) = self.own_queue.as_ref() else {
+            return;
+        };
+        let Some(current) = self.current_playback.as_ref().and_then(|p| p.item.as_ref()).map(|item| item.id().to_string()) else {
+            return;
+        };
+        if previous.as_ref() == Some(&current) {
+            return;
+        }
+        if self.queue.first().is_some_and(|item| item.id() == current) {
+            self.queue.remove(0);
+            if let Some(i) = self.queue_list_state.selected() {
+                self.queue_list_state.select(if self.queue.is_empty() { None } else { Some(i.saturating_sub(1)) });
+            }
+            if self.queue.is_empty() {
+                self.own_queue = None;
+            }
+            return;
+        }
+        if !known.contains(&current) {
+            self.own_queue = None;
+            self.success_message = Some(tr!("Se está reproduciendo otra cosa: vuelve la cola de Spotify").to_string());
+            if matches!(self.app_state, AppState::Queue) {
+                self.load_queue();
+            }
+            return;
+        }
+        let context = PlaybackContext::Uris(self.queue.iter().filter_map(PlayableItem::uri).collect());
+        self.send_command(Command::StartPlayback { context, offset: None, position_ms: None }, AfterCommand::OwnQueueResumed);
+    }
+
+    /// Recarga las playlists desde la primera página; si falla se conserva lo que hubiera
+    fn load_playlists(&mut self) {
+        self.load(Loading::Playlists, Request::Playlists { offset: 0 });
+    }
+
+    /// Sustituye las playlists por la primera página recibida; si falla se conserva lo que hubiera
+    fn replace_playlists(&mut self, result: ApiResult<PlaylistsResponse>) {
+        match result {
+            Ok(page) => {
+                self.playlists.clear();
+                self.playlist_list_state.select(None);
+                self.add_playlists_page(page);
+            }
+            Err(e) => self.show_error(tr!("Error al cargar playlists"), e),
+        }
+    }
+
+    /// Carga la siguiente página de playlists del usuario
+    fn load_more_playlists(&mut self) {
+        if !self.loading.contains(&Loading::Playlists) {
+            self.load(Loading::Playlists, Request::Playlists { offset: self.playlists.len() as u32 });
+        }
+    }
+
+    fn add_playlists_page(&mut self, page: PlaylistsResponse) {
+        self.playlists.extend(page.items);
+        self.playlists_total = page.total.max(0) as usize;
+        select_first_if_none(&mut self.playlist_list_state, self.playlists.len());
+        self.success_message = Some(tr!(
+            "Playlists: {}/{} cargadas",
+            self.playlists.len(),
+            self.playlists_total
+        ));
+        if let Some(store) = self.library_store.as_mut() {
+            if let Err(e) = store.save_playlists(&self.playlists, self.playlists_total) {
+                self.error_message = Some(tr!("Error al guardar la biblioteca local: {}", e));
+            }
+        }
+    }
+
+    /// Recarga los favoritos desde la primera página; si falla se conserva lo que hubiera
+    fn replace_favorites(&mut self, result: ApiResult<SavedTracksResponse>) {
+        match result {
+            Ok(page) => {
+                self.favorites.clear();
+                self.favorites_list_state.select(None);
+                self.add_favorites_page(page);
+            }
+            Err(e) => self.show_error(tr!("Error al cargar favoritos"), e),
+        }
+    }
+
+    /// Carga la siguiente página de canciones favoritas
+    fn load_more_favorites(&mut self) {
+        if !self.loading.contains(&Loading::Favorites) {
+            self.load(Loading::Favorites, Request::Favorites { offset: self.favorites.len() as u32 });
+        }
+    }
+
+    fn add_favorites_page(&mut self, page: SavedTracksResponse) {
+        let tracks: Vec<Track> = page.items.into_iter().map(|item| item.track).collect();
+        // Todo lo que está en favoritos está guardado por definición
+        self.saved_tracks.extend(tracks.iter().map(|t| (t.id.clone(), true)));
+        self.favorites.extend(tracks);
+        self.favorites_total = page.total.max(0) as usize;
+        select_first_if_none(&mut self.favorites_list_state, self.favorites.len());
+        self.success_message = Some(tr!(
+            "Favoritos: {}/{} cargadas",
+            self.favorites.len(),
+            self.favorites_total
+        ));
+        if let Some(store) = self.library_store.as_mut() {
+            if let Err(e) = store.save_saved_tracks(&self.favorites, self.favorites_total) {
+                self.error_message = Some(tr!("Error al guardar la biblioteca local: {}", e));
+            }
+        }
+    }
+
+    /// Sustituye los álbumes guardados por la primera página; si falla se conserva lo que hubiera
+    fn replace_saved_albums(&mut self, result: ApiResult<SavedAlbumsResponse>) {
+        match result {
+            Ok(page) => {
+                self.saved_albums.clear();
+                self.saved_albums_list_state.select(None);
+                self.add_saved_albums_page(page);
+            }
+            Err(e) => self.show_error(tr!("Error al cargar álbumes guardados"), e),
+        }
+    }
+
+    fn load_followed_artists(&mut self) {
+        self.load(Loading::Artists, Request::FollowedArtists);
+    }
+
+    /// Carga la siguiente página de álbumes guardados
+    fn load_more_saved_albums(&mut self) {
+        if !self.loading.contains(&Loading::SavedAlbums) {
+            self.load(Loading::SavedAlbums, Request::SavedAlbums { offset: self.saved_albums.len() as u32 });
+        }
+    }
+
+    fn add_saved_albums_page(&mut self, page: SavedAlbumsResponse) {
+        self.saved_albums.extend(page.items.into_iter().map(|item| item.album));
+        self.saved_albums_total = page.total.max(0) as usize;
+        select_first_if_none(&mut self.saved_albums_list_state, self.saved_albums.len());
+        self.success_message = Some(tr!(
+            "Álbumes: {}/{} cargados",
+            self.saved_albums.len(),
+            self.saved_albums_total
+        ));
+        if let Some(store) = self.library_store.as_mut() {
+            if let Err(e) = store.save_saved_albums(&self.saved_albums, self.saved_albums_total) {
+                self.error_message = Some(tr!("Error al guardar la biblioteca local: {}", e));
+            }
+        }
+    }
+
+    fn load_queue(&mut self) {
+        self.load(Loading::Queue, Request::Queue);
+    }
+
+    fn apply_queue(&mut self, result: ApiResult<QueueResponse>) {
+        match result {
+            Ok(_) if self.own_queue.is_some() => {
+                self.queue_playing = self.current_playback.as_ref().and_then(|p| p.item.clone());
+                self.success_message = Some(tr!("{} canciones en tu cola propia", self.queue.len()));
+            }
+            Ok(queue) => {
+                let tracks: Vec<Track> = queue.queue.iter().filter_map(PlayableItem::track).cloned().collect();
+                self.refresh_saved_tracks(&tracks);
+                self.queue_playing = queue.currently_playing;
+                self.queue = queue.queue;
+                self.queue_list_state.select(if self.queue.is_empty() { None } else { Some(0) });
+                self.success_message = Some(tr!("{} canciones en cola", self.queue.len()));
+            }
+            Err(e) => self.show_error(tr!("Error al cargar la cola"), e),
+        }
+    }
+
+    fn load_top_items(&mut self) {
+        self.load(Loading::Top, Request::Top { time_range: self.top_time_range });
+    }
+
+    fn apply_top_items(&mut self, tracks: ApiResult<Vec<Track>>, artists: ApiResult<Vec<FullArtist>>) {
+        let time_range = self.top_time_range;
+        match (tracks, artists) {
+            (Ok(tracks), Ok(artists)) => {
+                self.refresh_saved_tracks(&tracks);
+                self.top_tracks = tracks;
+                self.top_artists = artists;
+                self.top_tracks_list_state.select(if self.top_tracks.is_empty() { None } else { Some(0) });
+                self.success_message = Some(tr!("Tu top: {}", Self::time_range_label(time_range)));
+            }
+            (Err(e), _) | (_, Err(e)) => self.show_error(tr!("Error al cargar tu top"), e),
+        }
+    }
+
+    fn add_discover_seed(&mut self, seed: DiscoverSeed) {
+        if self.discover_seeds.iter().any(|s| s.id() == seed.id()) {
+            return;
+        }
+        if self.discover_seeds.len() >= MAX_DISCOVER_SEEDS {
+            self.error_message = Some(tr!("Máximo {} semillas para Descubrir", MAX_DISCOVER_SEEDS));
+            return;
+        }
+        self.success_message = Some(tr!("Semilla añadida: {}", seed.name()));
+        self.discover_seeds.push(seed);
+    }
+
+    fn seed_from_current_track(&mut self) {
+        match self.current_playback.as_ref().and_then(|p| p.track().cloned()) {
+            Some(track) => self.add_discover_seed(DiscoverSeed::Track { id: track.id, name: track.name }),
+            None => self.error_message = Some(tr!("No hay canción reproduciéndose").to_string()),
+        }
+    }
+
+    fn seed_from_current_artists(&mut self) {
+        match self.current_playback.as_ref().and_then(|p| p.track().cloned()) {
+            Some(track) => {
+                for artist in track.artists {
+                    self.add_discover_seed(DiscoverSeed::Artist { id: artist.id, name: artist.name });
+                }
+            }
+            None => self.error_message = Some(tr!("No hay canción reproduciéndose").to_string()),
+        }
+    }
+
+    fn load_recommendations(&mut self) {
+        if self.discover_seeds.is_empty() {
+            self.error_message = Some(tr!("Añade al menos una semilla (c: canción actual, C: artistas, +: canción seleccionada)").to_string());
+            return;
+        }
+
+        let mut seed_tracks = Vec::new();
+        let mut seed_artists = Vec::new();
+        for seed in &self.discover_seeds {
+            match seed {
+                DiscoverSeed::Track { id, .. } => seed_tracks.push(id.clone()),
+                DiscoverSeed::Artist { id, .. } => seed_artists.push(id.clone()),
+            }
+        }
+
+        self.load(Loading::Discover, Request::Recommendations { seed_tracks, seed_artists });
+    }
+
+    /// Radio de canción: encola canciones parecidas a la seleccionada (o a la actual)
+    fn start_track_radio(&mut self) {
+        let track = self
+            .selected_track()
+            .or_else(|| self.current_playback.as_ref().and_then(|p| p.track()))
+            .cloned();
+        let Some(track) = track else {
+            self.error_message = Some(tr!("No hay ninguna canción seleccionada").to_string());
+            return;
+        };
+        if !track.is_playable() {
+            self.show_local_file_error(&track);
+            return;
+        }
+        self.request(Request::Radio { track });
+    }
+
+    /// Cuenta conectada para la cabecera: nombre, tipo de cuenta y país. Las cuentas Free no
+    /// pueden controlar la reproducción, así que se avisa en color de error
+    fn account_title(&self) -> Option<Span<'static>> {
+        let profile = self.user_profile.as_ref()?;
+        let name = profile.display_name.as_deref().unwrap_or(&profile.id);
+        let premium = profile.product.as_deref() == Some("premium");
+        let tier = if premium {
+            "Premium".to_string()
+        } else {
+            tr!("Free (el control de la reproducción requiere Premium)").to_string()
+        };
+        let mut text = format!("{}{} · {}", self.glyphs("👤 "), name, tier);
+        if let Some(ref country) = profile.country {
+            text.push_str(&format!(" · {}", country));
+        }
+        let style = if premium {
+            Style::default().fg(self.theme.secondary)
+        } else {
+            Style::default().fg(self.theme.error).add_modifier(Modifier::BOLD)
+        };
+        Some(Span::styled(format!(" {} ", text), style))
+    }
+
+    /// Id del usuario autenticado. Si el perfil todavía no ha llegado se vuelve a pedir y hay que
+    /// repetir la acción
+    fn user_id(&mut self) -> Option<String> {
+        if let Some(ref profile) = self.user_profile {
+            return Some(profile.id.clone());
+        }
+        self.request(Request::Profile);
+        self.error_message = Some(tr!("Todavía no se ha cargado tu perfil de Spotify: vuelve a intentarlo en un momento").to_string());
+        None
+    }
+
+    /// El selector necesita todas las playlists propias, no solo las páginas ya vistas: si faltan
+    /// se piden y se abre al llegar
+    fn open_playlist_picker(&mut self, uris: Vec<String>) {
+        if self.playlists.is_empty() || self.playlists.len() < self.playlists_total {
+            let offset = self.playlists.len() as u32;
+            self.load(Loading::Playlists, Request::PlaylistPicker { offset, uris });
+            return;
+        }
+        self.show_playlist_picker(uris);
+    }
+
+    fn show_playlist_picker(&mut self, uris: Vec<String>) {
+        let Some(user_id) = self.user_id() else {
+            return;
+        };
+
+        // Solo se puede añadir canciones a playlists propias
+        self.picker_playlists = self
+            .playlists
+            .iter()
+            .filter(|p| p.owner.id == user_id)
+            .cloned()
+            .collect();
+        if self.picker_playlists.is_empty() {
+            self.error_message = Some(tr!("No tienes playlists propias. Crea una con 'c' en la vista de Playlists").to_string());
+            return;
+        }
+
+        self.picker_uris = uris;
+        self.picker_list_state.select(Some(0));
+        self.input_mode = InputMode::PlaylistPicker;
+    }
+
+    fn add_to_picked_playlist(&mut self) {
+        let Some(playlist) = self.picker_list_state.selected().and_then(|i| self.picker_playlists.get(i)).cloned() else {
+            return;
+        };
+        let uris = std::mem::take(&mut self.picker_uris);
+        let count = uris.len();
+        let marks = self.current_track_list();
+        let command = Command::AddTracksToPlaylist { playlist_id: playlist.id.clone(), uris };
+        self.send_command(command, AfterCommand::AddedToPlaylist { playlist, count, marks });
+    }
+
+    /// Playlist seleccionada en la vista Playlists
+    fn selected_library_playlist(&self) -> Option<&Playlist> {
+        self.playlist_list_state
+            .selected()
+            .map(|i| self.data_index(ViewList::Playlists, i))
+            .and_then(|i| self.playlists.get(i))
+    }
+
+    fn open_selected_playlist(&mut self) {
+        let Some(playlist) = self.selected_library_playlist().cloned() else {
+            return;
+        };
+        self.show_playlist(playlist);
+    }
+
+    /// Abre las canciones de una playlist; la primera página se carga desde el bucle
+    fn show_playlist(&mut self, playlist: Playlist) {
+        self.push_nav();
+        let playlist_id = playlist.id.clone();
+        self.open_playlist = Some(playlist);
+        self.reset_view_list(ViewList::PlaylistItems);
+        self.playlist_items.clear();
+        self.playlist_items_total = 0;
+        self.playlist_items_list_state.select(None);
+        self.app_state = AppState::PlaylistTracks;
+        self.load(Loading::Playlist, Request::PlaylistItems { playlist_id, offset: 0 });
+    }
+
+    /// Pide la siguiente página de canciones de la playlist abierta
+    fn load_playlist_items(&mut self) {
+        let Some(playlist) = self.open_playlist.as_ref() else {
+            return;
+        };
+        if self.loading.contains(&Loading::Playlist) {
+            return;
+        }
+        let (playlist_id, offset) = (playlist.id.clone(), self.playlist_items.len() as u32);
+        self.load(Loading::Playlist, Request::PlaylistItems { playlist_id, offset });
+    }
+
+    /// Añade a la playlist abierta la página de canciones recibida
+    fn add_playlist_items_page(&mut self, playlist: &Playlist, result: ApiResult<PlaylistTracksResponse>) {
+        match result {
+            Ok(page) => {
+                let tracks: Vec<Track> = page.items.iter().filter_map(|item| item.track.clone()).collect();
+                self.refresh_saved_tracks(&tracks);
+                self.playlist_items.extend(page.items);
+                self.playlist_items_total = page.total.max(0) as usize;
+                if self.playlist_items_list_state.selected().is_none() && !self.playlist_items.is_empty() {
+                    self.playlist_items_list_state.select(Some(0));
+                }
+                self.success_message = Some(tr!(
+                    "{}: {}/{} canciones cargadas",
+                    playlist.name,
+                    self.playlist_items.len(),
+                    self.playlist_items_total
+                ));
+            }
+            Err(e) => self.show_error(tr!("Error al cargar la playlist"), e),
+        }
+    }
+
+    /// Reproduce la playlist abierta a partir de la canción seleccionada
+    fn play_selected_playlist_item(&mut self) {
+        let (Some(playlist), Some(i)) = (self.open_playlist.clone(), self.playlist_items_list_state.selected()) else {
+            return;
+        };
+        let i = self.data_index(ViewList::PlaylistItems, i);
+        let Some(track) = self.selected_playlist_item_track().cloned() else {
+            return;
+        };
+        if !track.is_playable() {
+            self.show_local_file_error(&track);
+            return;
+        }
+        let playlist_uri = format!("spotify:playlist:{}", playlist.id);
+        self.start_playback(PlaybackContext::Context(playlist_uri), Some(PlaybackOffset::Position(i)), tr!("Reproduciendo: {} ({})", track.name, playlist.name));
+    }
+
+    /// Mueve la canción seleccionada una posición arriba o abajo y guarda el nuevo orden en Spotify
+    fn move_selected_playlist_item(&mut self, up: bool) {
+        let Some(playlist) = self.open_playlist.clone() else {
+            return;
+        };
+        let Some(i) = self.playlist_items_list_state.selected() else {
+            return;
+        };
+        if self.sort_key(ViewList::PlaylistItems) != SortKey::Default || self.filters.contains_key(&ViewList::PlaylistItems) {
+            self.error_message = Some(tr!("Quita el filtro y vuelve al orden de la playlist para mover canciones").to_string());
+            return;
+        }
+        let Some(user_id) = self.user_id() else {
+            return;
+        };
+        if playlist.owner.id != user_id {
+            self.error_message = Some(tr!("Solo puedes reordenar tus propias playlists").to_string());
+            return;
+        }
+
+        let target = if up {
+            if i == 0 {
+                return;
+            }
+            i - 1
+        } else {
+            if i + 1 >= self.playlist_items.len() {
+                return;
+            }
+            i + 1
+        };
+        // insert_before se refiere a la posición antes de mover: al bajar hay que saltar el siguiente
+        let insert_before = if up { i - 1 } else { i + 2 };
+
+        // Se mueve ya en la lista, para que la siguiente pulsación parta de la nueva posición
+        // aunque Spotify no haya respondido todavía; las órdenes llegan en el mismo orden
+        self.playlist_items.swap(i, target);
+        self.playlist_items_list_state.select(Some(target));
+        let command = Command::ReorderPlaylistTracks { playlist_id: playlist.id.clone(), range_start: i, insert_before };
+        self.send_command(command, AfterCommand::PlaylistItemMoved { playlist_id: playlist.id });
+    }
+
+    /// Deja de seguir la playlist seleccionada; la primera pulsación solo pide confirmación
+    fn unfollow_selected_playlist(&mut self) {
+        let Some(playlist) = self.selected_library_playlist().cloned() else {
+            return;
+        };
+        if self.pending_unfollow.as_deref() != Some(playlist.id.as_str()) {
+            self.pending_unfollow = Some(playlist.id);
+            self.success_message = Some(tr!("Pulsa x de nuevo para quitar \"{}\" de tu biblioteca", playlist.name));
+            return;
+        }
+
+        self.pending_unfollow = None;
+        self.send_command(Command::UnfollowPlaylist(playlist.id.clone()), AfterCommand::Unfollowed(playlist));
+    }
+
+    fn start_editing_selected_playlist(&mut self) {
+        let Some(playlist) = self.selected_library_playlist().cloned() else {
+            return;
+        };
+        let Some(user_id) = self.user_id() else {
+            return;
+        };
+        if playlist.owner.id != user_id {
+            self.error_message = Some(tr!("Solo puedes editar tus propias playlists").to_string());
+            return;
+        }
+
+        self.editing_playlist_id = Some(playlist.id);
+        self.edit_playlist_name = playlist.name;
+        self.edit_playlist_description = playlist.description.unwrap_or_default();
+        self.editing_description = false;
+        self.input_mode = InputMode::EditPlaylist;
+    }
+
+    fn save_playlist_details(&mut self) {
+        let Some(playlist_id) = self.editing_playlist_id.take() else {
+            return;
+        };
+        let name = self.edit_playlist_name.trim().to_string();
+        let description = self.edit_playlist_description.trim().to_string();
+        let command = Command::UpdatePlaylistDetails {
+            playlist_id: playlist_id.clone(),
+            name: name.clone(),
+            description: description.clone(),
+        };
+        self.send_command(command, AfterCommand::PlaylistEdited { playlist_id, name, description });
+    }
+
+    fn create_playlist(&mut self) {
+        let name = self.new_playlist_name.trim().to_string();
+        let public = self.new_playlist_public;
+        let Some(user_id) = self.user_id() else {
+            return;
+        };
+        let command = Command::CreatePlaylist { user_id, name: name.clone(), public, uris: Vec::new() };
+        self.send_command(command, AfterCommand::PlaylistCreated(name));
+    }
+
+    fn save_discover_as_playlist(&mut self) {
+        if self.discover_results.is_empty() {
+            self.error_message = Some(tr!("No hay recomendaciones para guardar").to_string());
+            return;
+        }
+
+        let name = tr!("SpotiGod Descubrir {}", chrono::Local::now().format("%Y-%m-%d %H:%M"));
+        let uris = track_uris(&self.discover_results);
+        let Some(user_id) = self.user_id() else {
+            return;
+        };
+        let command = Command::CreatePlaylist { user_id, name: name.clone(), public: false, uris };
+        self.send_command(command, AfterCommand::DiscoverSaved(name));
+    }
+
+    fn load_saved_episodes(&mut self) {
+        self.load(Loading::Episodes, Request::SavedEpisodes);
+    }
+
+    fn play_selected_episode(&mut self) {
+        let Some(episode) = self.saved_episodes_list_state.selected().and_then(|i| self.saved_episodes.get(i)) else {
+            return;
+        };
+        let episode_uri = format!("spotify:episode:{}", episode.id);
+        // Continuar donde se dejó, salvo que el episodio ya se haya escuchado entero
+        let position_ms = episode
+            .resume_point
+            .as_ref()
+            .filter(|r| !r.fully_played)
+            .map(|r| r.resume_position_ms)
+            .unwrap_or(0);
+        let name = episode.name.clone();
+
+        let command = Command::StartPlayback {
+            context: PlaybackContext::Uris(vec![episode_uri]),
+            offset: None,
+            position_ms: Some(position_ms),
+        };
+        self.send_command(command, AfterCommand::Refresh(tr!("Reproduciendo episodio: {}", name)));
+    }
+
+    fn toggle_selected_episode_saved(&mut self) {
+        let Some(episode) = self.saved_episodes_list_state.selected().and_then(|i| self.saved_episodes.get(i)) else {
+            return;
+        };
+        let id = episode.id.clone();
+        let name = episode.name.clone();
+        let ids = vec![id.clone()];
+
+        // Los quitados siguen en la lista hasta recargarla, para poder deshacerlo
+        let saved = self.removed_episodes.contains(&id);
+        let command = if saved { Command::SaveEpisodes(ids) } else { Command::RemoveSavedEpisodes(ids) };
+        self.send_command(command, AfterCommand::EpisodeSaved { id, name, saved });
+    }
+
+    fn load_new_releases(&mut self) {
+        if !self.loading.contains(&Loading::NewReleases) {
+            self.load(Loading::NewReleases, Request::NewReleases { offset: self.new_releases.len() as u32 });
+        }
+    }
+
+    fn add_new_releases_page(&mut self, result: ApiResult<AlbumSearchResult>) {
+        match result {
+            Ok(page) => {
+                self.new_releases.extend(page.items);
+                self.new_releases_total = page.total.max(0) as usize;
+                if self.new_releases_list_state.selected().is_none() && !self.new_releases.is_empty() {
+                    self.new_releases_list_state.select(Some(0));
+                }
+                self.success_message = Some(tr!("Novedades: {}/{} cargadas", self.new_releases.len(), self.new_releases_total));
+            }
+            Err(e) => self.show_error(tr!("Error al cargar novedades"), e),
+        }
+    }
+
+    fn load_browse_tab(&mut self) {
+        match self.browse_tab {
+            BrowseTab::NewReleases if self.new_releases.is_empty() => self.load_new_releases(),
+            BrowseTab::Featured if self.featured_playlists.is_empty() => self.load_featured_playlists(),
+            BrowseTab::Categories if self.categories.is_empty() => self.load_categories(),
+            _ => {}
+        }
+    }
+
+    fn load_categories(&mut self) {
+        if !self.loading.contains(&Loading::Categories) {
+            self.load(Loading::Categories, Request::Categories);
+        }
+    }
+
+    fn open_selected_category(&mut self) {
+        let Some(category) = self.categories_list_state.selected().and_then(|i| self.categories.get(i)).cloned() else {
+            return;
+        };
+        self.load(Loading::Categories, Request::CategoryPlaylists { category });
+    }
+
+    fn follow_selected_category_playlist(&mut self) {
+        let Some(playlist) = self
+            .open_category
+            .as_ref()
+            .and_then(|(_, playlists)| self.category_playlists_list_state.selected().and_then(|i| playlists.get(i)))
+            .cloned()
+        else {
+            return;
+        };
+        let message = tr!("Siguiendo playlist: {}", playlist.name);
+        self.send_command(Command::FollowPlaylist(playlist.id), AfterCommand::Message(message));
+    }
+
+    fn play_selected_category_playlist(&mut self) {
+        let Some(playlist) = self
+            .open_category
+            .as_ref()
+            .and_then(|(_, playlists)| self.category_playlists_list_state.selected().and_then(|i| playlists.get(i)))
+        else {
+            return;
+        };
+        let playlist_uri = format!("spotify:playlist:{}", playlist.id);
+        let name = playlist.name.clone();
+        self.start_playback(PlaybackContext::Context(playlist_uri), None, tr!("Reproduciendo playlist: {}", name));
+    }
+
+    fn load_featured_playlists(&mut self) {
+        if !self.loading.contains(&Loading::Featured) {
+            self.load(Loading::Featured, Request::FeaturedPlaylists);
+        }
+    }
+
+    fn play_selected_featured_playlist(&mut self) {
+        let Some(playlist) = self.featured_list_state.selected().and_then(|i| self.featured_playlists.get(i)) else {
+            return;
+        };
+        let playlist_uri = format!("spotify:playlist:{}", playlist.id);
+        let name = playlist.name.clone();
+        self.start_playback(PlaybackContext::Context(playlist_uri), None, tr!("Reproduciendo playlist: {}", name));
+    }
+
+    fn follow_selected_featured_playlist(&mut self) {
+        let Some(playlist) = self.featured_list_state.selected().and_then(|i| self.featured_playlists.get(i)).cloned() else {
+            return;
+        };
+        let message = tr!("Siguiendo playlist: {}", playlist.name);
+        self.send_command(Command::FollowPlaylist(playlist.id), AfterCommand::Message(message));
+    }
+
+    fn play_selected_new_release(&mut self) {
+        let Some(album) = self.new_releases_list_state.selected().and_then(|i| self.new_releases.get(i)) else {
+            return;
+        };
+        let album_uri = format!("spotify:album:{}", album.id);
+        let name = album.name.clone();
+        self.start_playback(PlaybackContext::Context(album_uri), None, tr!("Reproduciendo álbum: {}", name));
+    }
+
+    /// Guarda la vista actual para volver a ella con Esc
+    fn push_nav(&mut self) {
+        let entry = self.nav_entry();
+        self.nav_stack.push(entry);
+    }
+
+    fn nav_entry(&self) -> NavEntry {
+        let detail = match self.app_state {
+            AppState::PlaylistTracks => self.open_playlist.clone().map(|playlist| Detail::Playlist {
+                playlist,
+                items: self.playlist_items.clone(),
+                total: self.playlist_items_total,
+                list_state: self.playlist_items_list_state.clone(),
+            }),
+            AppState::Artist => self.open_artist.clone().map(|artist| Detail::Artist {
+                artist,
+                section: self.artist_section,
+                top_tracks: self.artist_top_tracks.clone(),
+                albums: self.artist_albums.clone(),
+                related: self.related_artists.clone(),
+                list_states: [
+                    self.artist_top_tracks_list_state.clone(),
+                    self.artist_albums_list_state.clone(),
+                    self.related_artists_list_state.clone(),
+                ],
+            }),
+            AppState::AlbumTracks => self.open_album.clone().map(|album| Detail::Album {
+                album,
+                tracks: self.album_tracks.clone(),
+                total: self.album_tracks_total,
+                list_state: self.album_tracks_list_state.clone(),
+            }),
+            _ => None,
+        };
+        let list = detail.as_ref().map(Detail::list);
+        NavEntry {
+            state: self.app_state,
+            filter: list.and_then(|list| self.filters.get(&list).cloned()),
+            marks: list.and_then(|list| self.marked_tracks.get(&list).cloned()),
+            detail,
+        }
+    }
+
+    /// Vuelve a la vista anterior con su selección. Sin historial (una playlist abierta al
+    /// arrancar, por ejemplo) se vuelve a la vista principal correspondiente
+    fn go_back(&mut self) {
+        let Some(entry) = self.nav_stack.pop() else {
+            self.app_state = match self.app_state {
+                AppState::PlaylistTracks => AppState::Playlists,
+                _ => AppState::Player,
+            };
+            return;
+        };
+        if let Some(detail) = entry.detail {
+            let list = detail.list();
+            self.reset_view_list(list);
+            self.filters.extend(entry.filter.map(|filter| (list, filter)));
+            self.marked_tracks.extend(entry.marks.map(|marks| (list, marks)));
+            match detail {
+                Detail::Playlist { playlist, items, total, list_state } => {
+                    // Si sigue abierta la misma playlist, lo cargado desde entonces sigue valiendo
+                    if self.open_playlist.as_ref().map(|open| &open.id) != Some(&playlist.id) {
+                        // La página pendiente de la otra playlist ya no sirve; si se salió de esta
+                        // antes de que llegara la primera, se vuelve a pedir
+                        self.loading.remove(&Loading::Playlist);
+                        if items.is_empty() {
+                            let playlist_id = playlist.id.clone();
+                            self.load(Loading::Playlist, Request::PlaylistItems { playlist_id, offset: 0 });
+                        }
+                        self.open_playlist = Some(playlist);
+                        self.playlist_items = items;
+                        self.playlist_items_total = total;
+                    }
+                    self.playlist_items_list_state = list_state;
+                }
+                Detail::Artist { artist, section, top_tracks, albums, related, list_states } => {
+                    let [top_tracks_state, albums_state, related_state] = list_states;
+                    self.open_artist = Some(artist);
+                    self.artist_section = section;
+                    self.artist_top_tracks = top_tracks;
+                    self.artist_albums = albums;
+                    self.related_artists = related;
+                    self.artist_top_tracks_list_state = top_tracks_state;
+                    self.artist_albums_list_state = albums_state;
+                    self.related_artists_list_state = related_state;
+                }
+                Detail::Album { album, tracks, total, list_state } => {
+                    if self.open_album.as_ref().map(|open| &open.id) != Some(&album.id) {
+                        self.loading.remove(&Loading::Album);
+                        if tracks.is_empty() {
+                            self.load(Loading::Album, Request::AlbumTracks { album: album.clone(), offset: 0 });
+                        }
+                    }
+                    self.open_album = Some(album);
+                    self.album_tracks = tracks;
+                    self.album_tracks_total = total;
+                    self.album_tracks_list_state = list_state;
+                }
+            }
+        }
+        self.app_state = entry.state;
+    }
+
+    fn simple_artist(artist: &FullArtist) -> Artist {
+        Artist {
+            id: artist.id.clone(),
+            name: artist.name.clone(),
+            external_urls: artist.external_urls.clone(),
+        }
+    }
+
+    /// Abre la vista de detalle de un artista: canciones populares, álbumes y relacionados.
+    /// Esc vuelve a la vista actual, aunque sea otro artista
+    fn open_artist_detail(&mut self, artist: Artist) {
+        self.load(Loading::Artist, Request::Artist { artist, section: ArtistSection::TopTracks });
+    }
+
+    /// Muestra el artista recibido en `section`, salvo que no llegaran sus canciones populares
+    fn show_artist(
+        &mut self,
+        artist: Artist,
+        section: ArtistSection,
+        top_tracks: ApiResult<Vec<Track>>,
+        albums: ApiResult<Vec<Album>>,
+        related: ApiResult<Vec<FullArtist>>,
+    ) {
+        let top_tracks = match top_tracks {
+            Ok(tracks) => tracks,
+            Err(e) => {
+                self.show_error(tr!("Error al cargar el artista"), e);
+                return;
+            }
+        };
+        // Los álbumes y relacionados son secundarios: si fallan se muestra la vista igualmente
+        let albums = albums.unwrap_or_else(|e| {
+            self.show_error(tr!("Error al cargar álbumes"), e);
+            Vec::new()
+        });
+        let related = related.unwrap_or_else(|e| {
+            self.show_error(tr!("Error al cargar artistas relacionados"), e);
+            Vec::new()
+        });
+
+        let entry = self.nav_entry();
+        self.nav_stack.push(entry);
+        self.app_state = AppState::Artist;
+        self.refresh_saved_tracks(&top_tracks);
+        self.artist_top_tracks = top_tracks;
+        self.reset_view_list(ViewList::ArtistTopTracks);
+        self.artist_albums = albums;
+        self.related_artists = related;
+        for (state, len) in [
+            (&mut self.artist_top_tracks_list_state, self.artist_top_tracks.len()),
+            (&mut self.artist_albums_list_state, self.artist_albums.len()),
+            (&mut self.related_artists_list_state, self.related_artists.len()),
+        ] {
+            state.select(if len == 0 { None } else { Some(0) });
+        }
+        self.artist_section = section;
+        self.success_message = Some(tr!("Artista: {}", artist.name));
+        self.open_artist = Some(artist);
+    }
+
+    /// Abre directamente los artistas relacionados con el artista de la canción actual
+    fn open_current_artist_related(&mut self) {
+        let Some(artist) = self.current_track_artist() else {
+            return;
+        };
+        self.load(Loading::Artist, Request::Artist { artist, section: ArtistSection::Related });
+    }
+
+    fn current_track_artist(&self) -> Option<Artist> {
+        self.current_playback
+            .as_ref()
+            .and_then(|p| p.track())
+            .and_then(|t| t.artists.first())
+            .cloned()
+    }
+
+    fn play_selected_artist_top_track(&mut self) {
+        let Some(i) = self.artist_top_tracks_list_state.selected() else {
+            return;
+        };
+        let i = self.data_index(ViewList::ArtistTopTracks, i);
+        let Some(track) = self.artist_top_tracks.get(i).cloned() else {
+            return;
+        };
+        // El contexto de artista no admite offset: se reproduce la lista de populares desde la elegida
+        let uris = track_uris(&self.artist_top_tracks);
+        self.start_playback(PlaybackContext::Uris(uris), Some(PlaybackOffset::Position(i)), tr!("Reproduciendo: {}", track.name));
+    }
+
+    fn play_selected_artist_album(&mut self) {
+        let Some(album) = self.artist_albums_list_state.selected().and_then(|i| self.artist_albums.get(i)) else {
+            return;
+        };
+        let album_uri = format!("spotify:album:{}", album.id);
+        let name = album.name.clone();
+        self.start_playback(PlaybackContext::Context(album_uri), None, tr!("Reproduciendo álbum: {}", name));
+    }
+
+    /// Álbum seleccionado en las listas de álbumes (búsqueda, novedades y detalle de artista)
+    fn selected_album(&self) -> Option<&Album> {
+        let (albums, state) = match self.app_state {
+            AppState::Search if self.search_type == SearchType::Album => (&self.search_albums, &self.search_albums_list_state),
+            AppState::Browse if self.browse_tab == BrowseTab::NewReleases => (&self.new_releases, &self.new_releases_list_state),
+            AppState::Artist if self.artist_section == ArtistSection::Albums => (&self.artist_albums, &self.artist_albums_list_state),
+            AppState::SavedAlbums => (&self.saved_albums, &self.saved_albums_list_state),
+            _ => return None,
+        };
+        state.selected().and_then(|i| albums.get(i))
+    }
+
+    fn open_selected_album(&mut self) {
+        let Some(album) = self.selected_album().cloned() else {
+            return;
+        };
+        self.open_album_view(album);
+    }
+
+    /// Muestra las canciones de un álbum; Esc vuelve a la vista actual
+    fn open_album_view(&mut self, album: Album) {
+        self.push_nav();
+        self.open_album = Some(album);
+        self.reset_view_list(ViewList::AlbumTracks);
+        self.album_tracks.clear();
+        self.album_tracks_total = 0;
+        self.album_tracks_list_state.select(None);
+        self.app_state = AppState::AlbumTracks;
+        self.loading.remove(&Loading::Album);
+        self.load_album_tracks();
+    }
+
+    /// Pide la siguiente página de canciones del álbum abierto
+    fn load_album_tracks(&mut self) {
+        let Some(album) = self.open_album.clone() else {
+            return;
+        };
+        if !self.loading.contains(&Loading::Album) {
+            self.load(Loading::Album, Request::AlbumTracks { album, offset: self.album_tracks.len() as u32 });
+        }
+    }
+
+    /// Añade al álbum abierto la página de canciones recibida
+    fn add_album_tracks_page(&mut self, album: &Album, result: ApiResult<AlbumTracksResponse>) {
+        match result {
+            Ok(page) => {
+                let tracks: Vec<Track> = page.items.into_iter().map(|track| track.with_album(album)).collect();
+                self.refresh_saved_tracks(&tracks);
+                self.album_tracks.extend(tracks);
+                self.album_tracks_total = page.total.max(0) as usize;
+                if self.album_tracks_list_state.selected().is_none() && !self.album_tracks.is_empty() {
+                    self.album_tracks_list_state.select(Some(0));
+                }
+            }
+            Err(e) => self.show_error(tr!("Error al cargar el álbum"), e),
+        }
+    }
+
+    /// Reproduce el álbum abierto a partir de la canción seleccionada
+    fn play_selected_album_track(&mut self) {
+        let (Some(album), Some(i)) = (self.open_album.clone(), self.album_tracks_list_state.selected()) else {
+            return;
+        };
+        let i = self.data_index(ViewList::AlbumTracks, i);
+        let Some(track) = self.album_tracks.get(i).cloned() else {
+            return;
+        };
+        let album_uri = format!("spotify:album:{}", album.id);
+        self.start_playback(PlaybackContext::Context(album_uri), Some(PlaybackOffset::Position(i)), tr!("Reproduciendo: {} ({})", track.name, album.name));
+    }
+
+    fn play_open_album(&mut self) {
+        let Some(album) = self.open_album.clone() else {
+            return;
+        };
+        let album_uri = format!("spotify:album:{}", album.id);
+        self.start_playback(PlaybackContext::Context(album_uri), None, tr!("Reproduciendo álbum: {}", album.name));
+    }
+
+    /// Indica si la selección está en el último elemento cargado de una lista paginada con más páginas
+    fn at_end_of_loaded_page(&self) -> bool {
+        let (state, loaded, total) = match self.app_state {
+            AppState::Search => {
+                let (state, len) = match self.search_type {
+                    SearchType::Track => (&self.search_list_state, self.search_results.len()),
+                    SearchType::Album => (&self.search_albums_list_state, self.search_albums.len()),
+                    SearchType::Artist => (&self.search_artists_list_state, self.search_artists.len()),
+                    SearchType::Playlist => (&self.search_playlists_list_state, self.search_playlists.len()),
+                    SearchType::Show => (&self.search_shows_list_state, self.search_shows.len()),
+                };
+                return self.search_has_more() && state.selected() == Some(len.saturating_sub(1));
+            }
+            AppState::Browse if self.browse_tab == BrowseTab::NewReleases => {
+                (&self.new_releases_list_state, self.new_releases.len(), self.new_releases_total)
+            }
+            AppState::Playlists => (&self.playlist_list_state, self.playlists.len(), self.playlists_total),
+            AppState::Favorites => (&self.favorites_list_state, self.favorites.len(), self.favorites_total),
+            AppState::PlaylistTracks => (&self.playlist_items_list_state, self.playlist_items.len(), self.playlist_items_total),
+            AppState::AlbumTracks => (&self.album_tracks_list_state, self.album_tracks.len(), self.album_tracks_total),
+            AppState::SavedAlbums => (&self.saved_albums_list_state, self.saved_albums.len(), self.saved_albums_total),
+            _ => return false,
+        };
+        loaded < total && state.selected() == Some(loaded.saturating_sub(1))
+    }
+
+    fn load_next_page(&mut self) {
+        match self.app_state {
+            AppState::Browse => self.load_new_releases(),
+            AppState::Search => self.load_more_search_results(),
+            AppState::Playlists => self.load_more_playlists(),
+            AppState::Favorites => self.load_more_favorites(),
+            AppState::PlaylistTracks => self.load_playlist_items(),
+            AppState::AlbumTracks => self.load_album_tracks(),
+            AppState::SavedAlbums => self.load_more_saved_albums(),
+            _ => {}
+        }
+    }
+
+    /// Lista navegable de la vista activa junto con su número de elementos
+    fn current_list(&mut self) -> Option<(&mut ListState, usize)> {
+        // Con filtro solo se recorren las filas visibles
+        let visible = self
+            .current_filter_list()
+            .filter(|list| self.filters.contains_key(list))
+            .map(|list| self.view_order(list).len());
+        let (state, len) = self.current_list_state()?;
+        Some((state, visible.unwrap_or(len)))
+    }
+
+    /// Lista navegable de la vista activa con el total de elementos cargados
+    fn current_list_state(&mut self) -> Option<(&mut ListState, usize)> {
+        match self.app_state {
+            AppState::Search => match self.search_type {
+                SearchType::Track => Some((&mut self.search_list_state, self.search_results.len())),
+                SearchType::Album => Some((&mut self.search_albums_list_state, self.search_albums.len())),
+                SearchType::Artist => Some((&mut self.search_artists_list_state, self.search_artists.len())),
+                SearchType::Playlist => Some((&mut self.search_playlists_list_state, self.search_playlists.len())),
+                SearchType::Show => Some((&mut self.search_shows_list_state, self.search_shows.len())),
+            },
+            AppState::Playlists => Some((&mut self.playlist_list_state, self.playlists.len())),
+            AppState::Favorites => Some((&mut self.favorites_list_state, self.favorites.len())),
+            AppState::Queue => Some((&mut self.queue_list_state, self.queue.len())),
+            AppState::Top => Some((&mut self.top_tracks_list_state, self.top_tracks.len())),
+            AppState::Discover => Some((&mut self.discover_list_state, self.discover_results.len())),
+            AppState::Episodes => Some((&mut self.saved_episodes_list_state, self.saved_episodes.len())),
+            AppState::PlaylistTracks => Some((&mut self.playlist_items_list_state, self.playlist_items.len())),
+            AppState::Browse => match self.browse_tab {
+                BrowseTab::NewReleases => Some((&mut self.new_releases_list_state, self.new_releases.len())),
+                BrowseTab::Featured => Some((&mut self.featured_list_state, self.featured_playlists.len())),
+                BrowseTab::Categories => match self.open_category {
+                    Some((_, ref playlists)) => Some((&mut self.category_playlists_list_state, playlists.len())),
+                    None => Some((&mut self.categories_list_state, self.categories.len())),
+                },
+            },
+            AppState::AlbumTracks => Some((&mut self.album_tracks_list_state, self.album_tracks.len())),
+            AppState::SavedAlbums => Some((&mut self.saved_albums_list_state, self.saved_albums.len())),
+            AppState::Artists => Some((&mut self.followed_artists_list_state, self.followed_artists.len())),
+            AppState::Artist => match self.artist_section {
+                ArtistSection::TopTracks => Some((&mut self.artist_top_tracks_list_state, self.artist_top_tracks.len())),
+                ArtistSection::Albums => Some((&mut self.artist_albums_list_state, self.artist_albums.len())),
+                ArtistSection::Related => Some((&mut self.related_artists_list_state, self.related_artists.len())),
+            },
+            AppState::Log => Some((&mut self.log_list_state, self.log.len())),
+            AppState::Player | AppState::Lyrics => None,
+        }
+    }
+
+    fn play_selected_favorite(&mut self) {
+        let Some(track) = self.selected_track().cloned() else {
+            return;
+        };
+        let Some(user_id) = self.user_id() else {
+            return;
+        };
+        // Contexto de "Tus me gusta" para seguir con el resto de favoritos
+        let collection_uri = format!("spotify:user:{}:collection", user_id);
+        let track_uri = format!("spotify:track:{}", track.id);
+        self.start_playback(PlaybackContext::Context(collection_uri), Some(PlaybackOffset::Uri(track_uri)), tr!("Reproduciendo: {}", track.name));
+    }
+
+    fn play_selected_queue_item(&mut self) {
+        let Some(mut i) = self.queue_list_state.selected().map(|i| self.data_index(ViewList::Queue, i)) else {
+            return;
+        };
+        // En la cola propia se salta hasta la elegida y se sigue con el resto, en su orden
+        if self.own_queue.is_some() && i < self.queue.len() {
+            self.queue.drain(..i);
+            self.queue_list_state.select(Some(0));
+            i = 0;
+        }
+        let Some(item) = self.queue.get(i).cloned() else {
+            return;
+        };
+        let uris = if self.own_queue.is_some() {
+            self.queue.iter().filter_map(PlayableItem::uri).collect()
+        } else {
+            item.uri().into_iter().collect()
+        };
+        let command = Command::StartPlayback { context: PlaybackContext::Uris(uris), offset: None, position_ms: None };
+        self.send_command(command, AfterCommand::RefreshQueue(tr!("Reproduciendo: {}", item.name())));
+    }
+
+    fn play_selected_top_track(&mut self) {
+        if let Some(i) = self.top_tracks_list_state.selected().map(|i| self.data_index(ViewList::Top, i)) {
+            if let Some(track) = self.top_tracks.get(i) {
+                let uris = track_uris(&self.top_tracks);
+                self.start_playback(PlaybackContext::Uris(uris), Some(PlaybackOffset::Position(i)), tr!("Reproduciendo: {}", track.name));
+            }
+        }
+    }
+
+    fn play_selected_discover_track(&mut self) {
+        if let Some(i) = self.discover_list_state.selected().map(|i| self.data_index(ViewList::Discover, i)) {
+            if let Some(track) = self.discover_results.get(i) {
+                let uris = track_uris(&self.discover_results);
+                self.start_playback(PlaybackContext::Uris(uris), Some(PlaybackOffset::Position(i)), tr!("Reproduciendo: {}", track.name));
+            }
+        }
+    }
+
+    fn ui(&mut self, f: &mut Frame) {
+        // El Reproductor ya muestra lo que suena; en el resto de vistas va una línea fija sobre el pie
+        let now_playing = if matches!(self.app_state, AppState::Player) { 0 } else { 1 };
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3),           // Header
+                Constraint::Min(0),              // Content
+                Constraint::Length(now_playing), // Now playing
+                Constraint::Length(3),           // Footer
+            ])
+            .split(f.size());
+        self.list_height = chunks[1].height.saturating_sub(2);
+        self.list_area.set(None);
+        self.progress_area.set(None);
+
+        self.render_header(f, chunks[0]);
+        if self.sidebar {
+            let panes = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Length(SIDEBAR_WIDTH), Constraint::Min(0)])
+                .split(chunks[1]);
+            self.render_sidebar(f, panes[0]);
+            self.render_content(f, panes[1]);
+        } else {
+            self.render_content(f, chunks[1]);
+        }
+        if now_playing > 0 {
+            self.render_now_playing_bar(f, chunks[2]);
+        }
+        if matches!(self.input_mode, InputMode::Filter) {
+            self.render_filter_prompt(f, chunks[3]);
+        } else {
+            self.render_footer(f, chunks[3]);
+        }
+        if self.debug_overlay {
+            self.render_debug_overlay(f, chunks[1]);
+        }
 
         // Render input popups
         if matches!(self.input_mode, InputMode::Search) {
             self.render_search_popup(f);
         } else if matches!(self.input_mode, InputMode::Volume) {
             self.render_volume_popup(f);
+        } else if matches!(self.input_mode, InputMode::Devices) {
+            self.render_devices_popup(f);
+        } else if matches!(self.input_mode, InputMode::CreatePlaylist) {
+            self.render_create_playlist_popup(f);
+        } else if matches!(self.input_mode, InputMode::PlaylistPicker) {
+            self.render_playlist_picker_popup(f);
+        } else if matches!(self.input_mode, InputMode::EditPlaylist) {
+            self.render_edit_playlist_popup(f);
+        } else if matches!(self.input_mode, InputMode::Reauth) {
+            self.render_reauth_popup(f);
+        } else if matches!(self.input_mode, InputMode::Command) {
+            self.render_command_popup(f);
+        } else if matches!(self.input_mode, InputMode::ContextMenu) {
+            self.render_context_menu_popup(f);
+        } else if matches!(self.input_mode, InputMode::Launcher) {
+            self.render_launcher_popup(f);
         }
     }
 
-    fn render_header(&self, f: &mut Frame, area: Rect) {
+    fn render_header(&mut self, f: &mut Frame, area: Rect) {
         let title = match self.app_state {
-            AppState::Player => "🎵 SpotiGod - Reproductor",
-            AppState::Search => "🔍 SpotiGod - Búsqueda",
-            AppState::Playlists => "📋 SpotiGod - Playlists",
-            AppState::Favorites => "🎶 SpotiGod - Favoritos",
+            AppState::Player => tr!("🎵 SpotiGod - Reproductor"),
+            AppState::Search => tr!("🔍 SpotiGod - Búsqueda"),
+            AppState::Playlists => tr!("📋 SpotiGod - Playlists"),
+            AppState::Favorites => tr!("🎶 SpotiGod - Favoritos"),
+            AppState::Queue => tr!("⏭️  SpotiGod - Cola"),
+            AppState::Top => tr!("📊 SpotiGod - Tu Top"),
+            AppState::Discover => tr!("✨ SpotiGod - Descubrir"),
+            AppState::Episodes => tr!("🎙️  SpotiGod - Episodios guardados"),
+            AppState::Browse => tr!("🧭 SpotiGod - Explorar"),
+            AppState::PlaylistTracks => tr!("📋 SpotiGod - Playlist"),
+            AppState::Artist => tr!("🎤 SpotiGod - Artista"),
+            AppState::AlbumTracks => tr!("💿 SpotiGod - Álbum"),
+            AppState::SavedAlbums => tr!("💿 SpotiGod - Álbumes guardados"),
+            AppState::Artists => tr!("👤 SpotiGod - Artistas que sigues"),
+            AppState::Lyrics => tr!("🎤 SpotiGod - Letra"),
+            AppState::Log => tr!("📜 SpotiGod - Registro"),
         };
+        let title = if self.offline {
+            tr!("{} · 📴 Sin conexión (solo lectura)", title)
+        } else {
+            title.to_string()
+        };
+        let title = self.glyphs(&title).into_owned();
 
-        let header = Paragraph::new(title)
-            .style(Style::default().fg(Color::Green).add_modifier(Modifier::BOLD))
-            .alignment(Alignment::Center)
-            .block(Block::default().borders(Borders::ALL));
+        let mut block = Block::default()
+            .title(Span::styled(format!(" {} ", title), Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD)))
+            .title_alignment(Alignment::Center)
+            .borders(Borders::ALL);
+        if let Some(account) = self.account_title() {
+            block = block.title(Title::from(account).alignment(Alignment::Right).position(Position::Bottom));
+        }
+        let inner = block.inner(area);
+        f.render_widget(block, area);
+
+        // Pestañas centradas; se guarda la zona de cada una para poder pulsarlas con el ratón
+        let current = self.current_tab();
+        let divider = self.glyphs("│").into_owned();
+        let names: Vec<&str> = HEADER_TABS.iter().map(|(_, name)| tr(name)).collect();
+        let width: u16 = names.iter().map(|name| name.chars().count() as u16 + 2).sum::<u16>() + names.len().saturating_sub(1) as u16;
+        let start = inner.x + inner.width.saturating_sub(width) / 2;
+        let mut x = start;
+        self.header_tabs.clear();
+        for ((action, _), name) in HEADER_TABS.iter().zip(&names) {
+            let label_width = name.chars().count() as u16 + 2;
+            self.header_tabs.push((Rect::new(x, inner.y, label_width, 1), *action));
+            x += label_width + 1;
+        }
+        // Sin vista de la cabecera activa (artista, álbum...) no se resalta ninguna
+        let selected = HEADER_TABS.iter().position(|(action, _)| current == Some(*action)).unwrap_or(HEADER_TABS.len());
+        let tabs = Tabs::new(names)
+            .select(selected)
+            .style(Style::default().fg(self.theme.secondary))
+            .highlight_style(self.theme.active_tab())
+            .divider(Span::styled(divider, Style::default().fg(self.theme.dim)));
+        let tabs_area = Rect::new(start, inner.y, width.min(inner.width), inner.height.min(1));
+        f.render_widget(tabs, tabs_area);
+    }
+
+    /// Texto con los emoji tal cual o en ASCII, según el config
+    fn glyphs<'a>(&self, text: &'a str) -> Cow<'a, str> {
+        symbols::glyphs(text, self.ascii_symbols)
+    }
+
+    /// Marca de la fila seleccionada en las listas
+    fn selection_symbol(&self) -> &'static str {
+        if self.ascii_symbols { "> " } else { "► " }
+    }
 
-        f.render_widget(header, area);
+    /// Acción que abre la pestaña de la cabecera situada `step` posiciones más allá de la activa;
+    /// desde una vista sin pestaña (artista, álbum...) se empieza por un extremo
+    fn adjacent_view(&self, step: isize) -> Action {
+        let len = HEADER_TABS.len() as isize;
+        let current = self.current_tab().and_then(|tab| HEADER_TABS.iter().position(|(action, _)| *action == tab));
+        let next = match current {
+            Some(i) => (i as isize + step).rem_euclid(len),
+            None if step > 0 => 0,
+            None => len - 1,
+        };
+        HEADER_TABS[next as usize].0
+    }
+
+    /// Pestaña de la cabecera que corresponde a la vista activa
+    fn current_tab(&self) -> Option<Action> {
+        match self.app_state {
+            AppState::Player => Some(Action::ViewPlayer),
+            AppState::Search => Some(Action::ViewSearch),
+            AppState::Playlists | AppState::PlaylistTracks => Some(Action::ViewPlaylists),
+            AppState::Favorites => Some(Action::ViewFavorites),
+            AppState::Queue => Some(Action::ViewQueue),
+            AppState::Top => Some(Action::ViewTop),
+            AppState::Discover => Some(Action::ViewDiscover),
+            AppState::Episodes => Some(Action::ViewEpisodes),
+            AppState::Browse => Some(Action::ViewBrowse),
+            AppState::SavedAlbums => Some(Action::ViewSavedAlbums),
+            AppState::Artists => Some(Action::ViewArtists),
+            AppState::Lyrics => Some(Action::ViewLyrics),
+            AppState::Log => Some(Action::ViewLog),
+            AppState::Artist | AppState::AlbumTracks => None,
+        }
     }
 
     fn render_content(&mut self, f: &mut Frame, area: Rect) {
@@ -566,9 +5294,67 @@ impl App {
             AppState::Search => self.render_search_view(f, area),
             AppState::Playlists => self.render_playlists_view(f, area),
             AppState::Favorites => self.render_favorites_view(f, area),
+            AppState::Queue => self.render_queue_view(f, area),
+            AppState::Top => self.render_top_view(f, area),
+            AppState::Discover => self.render_discover_view(f, area),
+            AppState::Episodes => self.render_episodes_view(f, area),
+            AppState::Browse => self.render_browse_view(f, area),
+            AppState::PlaylistTracks => self.render_playlist_tracks_view(f, area),
+            AppState::Artist => self.render_artist_view(f, area),
+            AppState::AlbumTracks => self.render_album_tracks_view(f, area),
+            AppState::SavedAlbums => self.render_saved_albums_view(f, area),
+            AppState::Artists => self.render_followed_artists_view(f, area),
+            AppState::Lyrics => self.render_lyrics_view(f, area),
+            AppState::Log => self.render_log_view(f, area),
         }
     }
 
+    /// Línea compacta con lo que suena y su progreso, visible desde cualquier vista
+    fn render_now_playing_bar(&self, f: &mut Frame, area: Rect) {
+        let Some((playback, item)) = self.current_playback.as_ref().and_then(|p| Some((p, p.item.as_ref()?))) else {
+            let idle = Paragraph::new(tr!("No hay canción reproduciéndose")).style(Style::default().fg(self.theme.dim));
+            f.render_widget(idle, area);
+            return;
+        };
+
+        let (name, by) = match item {
+            PlayableItem::Track(track) => (
+                track.name.as_str(),
+                track.artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", "),
+            ),
+            PlayableItem::Episode(episode) => (episode.name.as_str(), episode.show.name.clone()),
+        };
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Min(0), Constraint::Percentage(35)])
+            .split(area);
+
+        let state = if playback.is_playing { "▶ " } else { "⏸ " };
+        let line = Line::from(vec![
+            Span::styled(self.glyphs(state), Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD)),
+            Span::styled(name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+            Span::styled(format!(" · {}", by), Style::default().fg(self.theme.secondary)),
+        ]);
+        f.render_widget(Paragraph::new(line), columns[0]);
+
+        let duration_ms = item.duration_ms();
+        let progress_ms = playback.progress_ms.unwrap_or(0);
+        let label = format!("{} / {} ", Self::format_duration(progress_ms), Self::format_duration(duration_ms));
+        // La línea empieza una columna después de la etiqueta
+        let offset = (label.len() as u16 + 1).min(columns[1].width);
+        self.progress_area.set(Some(Rect {
+            x: columns[1].x + offset,
+            width: columns[1].width - offset,
+            ..columns[1]
+        }));
+        let gauge = LineGauge::default()
+            .ratio((progress_ms as f64 / duration_ms.max(1) as f64).clamp(0.0, 1.0))
+            .label(label)
+            .style(Style::default().fg(self.theme.secondary))
+            .gauge_style(Style::default().fg(self.theme.gauge));
+        f.render_widget(gauge, columns[1]);
+    }
+
     fn render_player_view(&self, f: &mut Frame, area: Rect) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -582,40 +5368,72 @@ impl App {
 
         // Current track info
         if let Some(ref playback) = self.current_playback {
-            if let Some(ref track) = playback.item {
-                let track_info = vec![
-                    Line::from(vec![
-                        Span::styled("🎵 ", Style::default().fg(Color::Green)),
-                        Span::styled(&track.name, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
-                    ]),
-                    Line::from(vec![
-                        Span::styled("👤 ", Style::default().fg(Color::Blue)),
-                        Span::styled(
-                            track.artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", "),
-                            Style::default().fg(Color::Gray),
-                        ),
-                    ]),
-                    Line::from(vec![
-                        Span::styled("💿 ", Style::default().fg(Color::Magenta)),
-                        Span::styled(&track.album.name, Style::default().fg(Color::Gray)),
-                    ]),
+            if let Some(ref item) = playback.item {
+                let mut track_info = match item {
+                    PlayableItem::Track(track) => {
+                        let saved = self.saved_tracks.get(&track.id).copied().unwrap_or(false);
+                        vec![
+                            Line::from(vec![
+                                Span::styled(self.glyphs("🎵 "), Style::default().fg(self.theme.accent)),
+                                Span::styled(&track.name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+                                Span::styled(
+                                    self.glyphs(if saved { "  ♥" } else { "  ♡" }),
+                                    Style::default().fg(if saved { self.theme.accent } else { self.theme.secondary }),
+                                ),
+                            ]),
+                            Line::from(vec![
+                                Span::styled(self.glyphs("👤 "), Style::default().fg(self.theme.info)),
+                                Span::styled(
+                                    track.artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", "),
+                                    Style::default().fg(self.theme.secondary),
+                                ),
+                            ]),
+                            Line::from(vec![
+                                Span::styled(self.glyphs("💿 "), Style::default().fg(self.theme.detail)),
+                                Span::styled(&track.album.name, Style::default().fg(self.theme.secondary)),
+                            ]),
+                        ]
+                    }
+                    PlayableItem::Episode(episode) => vec![
+                        Line::from(vec![
+                            Span::styled(self.glyphs("🎙️  "), Style::default().fg(self.theme.accent)),
+                            Span::styled(&episode.name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+                        ]),
+                        Line::from(vec![
+                            Span::styled(self.glyphs("📻 "), Style::default().fg(self.theme.info)),
+                            Span::styled(&episode.show.name, Style::default().fg(self.theme.secondary)),
+                            Span::styled(format!(" · {}", episode.show.publisher), Style::default().fg(self.theme.dim)),
+                        ]),
+                        Line::from(vec![
+                            Span::styled(self.glyphs("📅 "), Style::default().fg(self.theme.detail)),
+                            Span::styled(&episode.release_date, Style::default().fg(self.theme.secondary)),
+                        ]),
+                    ],
+                };
+                track_info.extend(vec![
                     Line::from(vec![
-                        Span::styled("🎛️  ", Style::default().fg(Color::Yellow)),
-                        Span::styled(&playback.device.name, Style::default().fg(Color::Gray)),
-                        Span::styled(" | ", Style::default().fg(Color::Gray)),
-                        Span::styled(
-                            format!("Vol: {}%", playback.device.volume_percent.unwrap_or(0)),
-                            Style::default().fg(Color::Gray),
-                        ),
+                        Span::styled(self.glyphs("🎛️  "), Style::default().fg(self.theme.title)),
+                        Span::styled(&playback.device.name, Style::default().fg(self.theme.secondary)),
+                        Span::styled(" | ", Style::default().fg(self.theme.secondary)),
+                        match self.muted_volume.filter(|_| playback.device.volume_percent == Some(0)) {
+                            Some(previous) => Span::styled(
+                                self.glyphs(&tr!("🔇 Silenciado ({}%)", previous)).into_owned(),
+                                self.theme.toggle(false),
+                            ),
+                            None => Span::styled(
+                                format!("Vol: {}%", playback.device.volume_percent.unwrap_or(0)),
+                                Style::default().fg(self.theme.secondary),
+                            ),
+                        },
                     ]),
                     Line::from(vec![
-                        Span::styled("🔀 ", Style::default().fg(if playback.shuffle_state { Color::Green } else { Color::Red })),
+                        Span::styled(self.glyphs("🔀 "), self.theme.toggle(playback.shuffle_state)),
                         Span::styled(
                             if playback.shuffle_state { "Shuffle ON" } else { "Shuffle OFF" },
-                            Style::default().fg(if playback.shuffle_state { Color::Green } else { Color::Red }),
+                            self.theme.toggle(playback.shuffle_state),
                         ),
-                        Span::styled(" | ", Style::default().fg(Color::Gray)),
-                        Span::styled("🔁 ", Style::default().fg(Color::Yellow)),
+                        Span::styled(" | ", Style::default().fg(self.theme.secondary)),
+                        Span::styled(self.glyphs("🔁 "), Style::default().fg(self.theme.title)),
                         Span::styled(
                             match playback.repeat_state.as_str() {
                                 "off" => "Repeat OFF",
@@ -623,10 +5441,10 @@ impl App {
                                 "track" => "Repeat TRACK",
                                 _ => "Repeat UNKNOWN",
                             },
-                            Style::default().fg(Color::Yellow),
+                            Style::default().fg(self.theme.title),
                         ),
                     ]),
-                ];
+                ]);
 
                 let track_paragraph = Paragraph::new(track_info)
                     .block(Block::default().title("Now Playing").borders(Borders::ALL))
@@ -636,40 +5454,42 @@ impl App {
 
                 // Progress bar
                 if let Some(progress_ms) = playback.progress_ms {
-                    let progress = (progress_ms as f64 / track.duration_ms as f64).clamp(0.0, 1.0);
+                    let duration_ms = item.duration_ms();
+                    let progress = (progress_ms as f64 / duration_ms as f64).clamp(0.0, 1.0);
                     let progress_text = format!(
                         "{} / {}",
                         Self::format_duration(progress_ms),
-                        Self::format_duration(track.duration_ms)
+                        Self::format_duration(duration_ms)
                     );
 
                     let progress_bar = Gauge::default()
                         .block(Block::default().title("Progress").borders(Borders::ALL))
-                        .gauge_style(Style::default().fg(Color::Green))
+                        .gauge_style(Style::default().fg(self.theme.gauge))
                         .percent((progress * 100.0) as u16)
                         .label(progress_text);
 
                     f.render_widget(progress_bar, chunks[1]);
+                    self.progress_area.set(Some(Block::default().borders(Borders::ALL).inner(chunks[1])));
                 } else {
                     let no_progress = Gauge::default()
                         .block(Block::default().title("Progress").borders(Borders::ALL))
-                        .gauge_style(Style::default().fg(Color::Gray))
+                        .gauge_style(Style::default().fg(self.theme.secondary))
                         .percent(0)
                         .label("-- / --");
 
                     f.render_widget(no_progress, chunks[1]);
                 }
             } else {
-                let no_track = Paragraph::new("No hay canción reproduciéndose")
-                    .style(Style::default().fg(Color::Yellow))
+                let no_track = Paragraph::new(tr!("No hay canción reproduciéndose"))
+                    .style(Style::default().fg(self.theme.title))
                     .alignment(Alignment::Center)
                     .block(Block::default().title("Now Playing").borders(Borders::ALL));
 
                 f.render_widget(no_track, chunks[0]);
             }
         } else {
-            let no_playback = Paragraph::new("No se detectó reproducción activa\n\nAsegúrate de que Spotify esté abierto\ny reproduciendo música en algún dispositivo")
-                .style(Style::default().fg(Color::Red))
+            let no_playback = Paragraph::new(tr!("No se detectó reproducción activa\n\nAsegúrate de que Spotify esté abierto\ny reproduciendo música en algún dispositivo"))
+                .style(Style::default().fg(self.theme.error))
                 .alignment(Alignment::Center)
                 .block(Block::default().title("Now Playing").borders(Borders::ALL));
 
@@ -677,18 +5497,65 @@ impl App {
         }
 
         // Controls info
+        let key = |action| self.keymap.label(action);
         let controls_text = vec![
-            Line::from("Controles:"),
-            Line::from("SPACE: Play/Pause | ←/p: Anterior | →/n: Siguiente"),
-            Line::from("s: Shuffle | r: Repeat | v: Volumen | /: Buscar"),
-            Line::from("1: Reproductor | 2: Búsqueda | 3: Playlists | 4: Favoritos | q: Salir"),
+            Line::from(tr!("Controles:")),
+            Line::from(tr!(
+                "{}: Play/Pause | {}: Anterior | {}: Siguiente | {}: Me gusta | {}: Ver artista | {}: Relacionados",
+                key(Action::PlayPause),
+                key(Action::Previous),
+                key(Action::Next),
+                key(Action::ToggleFavorite),
+                key(Action::ShowArtist),
+                key(Action::RelatedArtists)
+            )),
+            Line::from(tr!(
+                "{}: Shuffle | {}: Repeat | {}: Volumen | {}: Dispositivos | {}: Buscar | {}: Añadir a la cola | {}: Radio",
+                key(Action::Shuffle),
+                key(Action::Repeat),
+                key(Action::Volume),
+                key(Action::Devices),
+                key(Action::Search),
+                key(Action::AddToQueue),
+                key(Action::Radio)
+            )),
+            Line::from(tr!(
+                "{}: Reproductor | {}: Búsqueda | {}: Playlists | {}: Favoritos | {}: Cola | {}: Tu Top | {}: Descubrir | {}: Episodios | {}: Explorar | {}: Letra | {}: Salir",
+                key(Action::ViewPlayer),
+                key(Action::ViewSearch),
+                key(Action::ViewPlaylists),
+                key(Action::ViewFavorites),
+                key(Action::ViewQueue),
+                key(Action::ViewTop),
+                key(Action::ViewDiscover),
+                key(Action::ViewEpisodes),
+                key(Action::ViewBrowse),
+                key(Action::ViewLyrics),
+                key(Action::Quit)
+            )),
         ];
 
         let controls = Paragraph::new(controls_text)
-            .block(Block::default().title("Controles").borders(Borders::ALL))
-            .style(Style::default().fg(Color::Cyan));
+            .block(Block::default().title(tr!("Controles")).borders(Borders::ALL))
+            .style(Style::default().fg(self.theme.info));
 
         f.render_widget(controls, chunks[2]);
+
+        // Visualizador
+        let analysis = self
+            .current_playback
+            .as_ref()
+            .and_then(|p| p.track().map(|t| (p, t)))
+            .and_then(|(p, t)| Some((p, self.audio_analyses.get(&t.id)?.as_ref()?)));
+
+        if let Some((playback, analysis)) = analysis {
+            let block = Block::default().title(tr!("Visualizador")).borders(Borders::ALL);
+            let inner = block.inner(chunks[3]);
+            f.render_widget(block, chunks[3]);
+
+            let progress_ms = self.estimated_progress_ms(playback).unwrap_or(0);
+            f.render_widget(Visualizer::new(analysis, progress_ms).style(Style::default().fg(self.theme.gauge)), inner);
+        }
     }
 
     fn render_search_view(&self, f: &mut Frame, area: Rect) {
@@ -701,175 +5568,1352 @@ impl App {
             .split(area);
 
         // Search info
-        let search_info = if self.search_results.is_empty() {
-            "Presiona '/' para buscar canciones"
+        let search_info = match self.search_type {
+            SearchType::Track if !self.search_results.is_empty() => {
+                tr!("↑/↓: Navegar | Enter: Reproducir | a: Cola | P: Añadir a playlist | m: Más | Tab: Tipo | /: Nueva búsqueda")
+            }
+            SearchType::Album if !self.search_albums.is_empty() => {
+                tr!("↑/↓: Navegar | Enter: Reproducir álbum | i: Ver canciones | m: Más | Tab: Tipo | /: Nueva búsqueda")
+            }
+            SearchType::Artist if !self.search_artists.is_empty() => {
+                tr!("↑/↓: Navegar | Enter: Reproducir artista | i: Ver artista | m: Más | Tab: Tipo | /: Nueva búsqueda")
+            }
+            SearchType::Playlist if !self.search_playlists.is_empty() => {
+                tr!("↑/↓: Navegar | Enter: Reproducir | i: Vista previa | F: Seguir | m: Más | Tab: Tipo | /: Nueva búsqueda")
+            }
+            SearchType::Show if !self.search_shows.is_empty() => {
+                tr!("↑/↓: Navegar | Enter: Reproducir podcast | m: Más | Tab: Tipo | /: Nueva búsqueda")
+            }
+            _ => tr!("Presiona '/' para buscar | Tab / Alt+1..5: Cambiar pestaña"),
+        };
+
+        // Pestañas con el número de resultados de cada tipo
+        let mut tabs = vec![Span::raw(" ")];
+        for (i, search_type) in SearchType::ALL.into_iter().enumerate() {
+            let count = match search_type {
+                SearchType::Track => self.search_results.len(),
+                SearchType::Album => self.search_albums.len(),
+                SearchType::Artist => self.search_artists.len(),
+                SearchType::Playlist => self.search_playlists.len(),
+                SearchType::Show => self.search_shows.len(),
+            };
+            let style = if self.search_type == search_type {
+                self.theme.active_tab()
+            } else {
+                Style::default().fg(self.theme.secondary)
+            };
+            tabs.push(Span::styled(
+                format!(" {} {} ({}) ", i + 1, Self::search_type_label(search_type), count),
+                style,
+            ));
+            tabs.push(Span::raw(" "));
+        }
+
+        let search_paragraph = Paragraph::new(search_info)
+            .style(Style::default().fg(self.theme.info))
+            .alignment(Alignment::Center)
+            .block(Block::default().title(Line::from(tabs)).borders(Borders::ALL));
+
+        f.render_widget(search_paragraph, chunks[0]);
+
+        // Search results
+        // Las canciones van en tabla (más abajo); aquí solo el resto de tipos
+        let (items, state): (Vec<ListItem>, ListState) = match self.search_type {
+            SearchType::Track => (Vec::new(), self.search_list_state.clone()),
+            SearchType::Album => (
+                self.search_albums
+                    .iter()
+                    .enumerate()
+                    .map(|(i, album)| self.album_list_item(i, album))
+                    .collect(),
+                self.search_albums_list_state.clone(),
+            ),
+            SearchType::Artist => (
+                self.search_artists
+                    .iter()
+                    .enumerate()
+                    .map(|(i, artist)| self.artist_list_item(i, artist))
+                    .collect(),
+                self.search_artists_list_state.clone(),
+            ),
+            SearchType::Playlist => (
+                self.search_playlists
+                    .iter()
+                    .enumerate()
+                    .map(|(i, playlist)| self.playlist_list_item(i, playlist))
+                    .collect(),
+                self.search_playlists_list_state.clone(),
+            ),
+            SearchType::Show => (
+                self.search_shows
+                    .iter()
+                    .enumerate()
+                    .map(|(i, show)| self.show_list_item(i, show))
+                    .collect(),
+                self.search_shows_list_state.clone(),
+            ),
+        };
+
+        // Vista previa de la playlist seleccionada
+        let preview = match (self.search_type, &self.playlist_preview) {
+            (SearchType::Playlist, Some((id, tracks))) if self.selected_search_playlist().is_some_and(|p| &p.id == id) => Some(tracks),
+            _ => None,
+        };
+        let results_area = if let Some(tracks) = preview {
+            let columns = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(chunks[1]);
+
+            let rows = tracks.iter().enumerate().map(|(i, track)| (i + 1, TrackRow::Track(track))).collect();
+            let block = Block::default().title(tr!("Vista previa")).borders(Borders::ALL);
+            self.render_track_table(f, columns[1], block, rows, TrackColumns { album: true, popularity: false }, None);
+
+            columns[0]
+        } else {
+            chunks[1]
+        };
+
+        let total = self.search_totals.get(&self.search_type).copied().unwrap_or(0);
+        if self.search_type == SearchType::Track && !self.search_results.is_empty() {
+            let title = format!("{} · {}", tr!("Resultados"), self.sort_title(ViewList::Search));
+            let block = Block::default().title(self.with_loading(self.with_filter(ViewList::Search, title))).borders(Borders::ALL);
+            let rows: Vec<_> = self
+                .view_order(ViewList::Search)
+                .into_iter()
+                .enumerate()
+                .map(|(pos, i)| (pos + 1, Some(&self.search_results[i]).into()))
+                .collect();
+            let total = self.scroll_total(ViewList::Search, rows.len(), total);
+            self.render_track_table(f, results_area, block, rows, TrackColumns { album: true, popularity: true }, Some(&state));
+            self.render_scrollbar(f, results_area, &state, total);
+        } else if !items.is_empty() {
+            let items_len = items.len();
+            let list = List::new(items)
+                .block(Block::default().title(self.with_loading(tr!("Resultados").to_string())).borders(Borders::ALL))
+                .highlight_style(self.theme.highlight())
+                .highlight_symbol(self.selection_symbol());
+
+            self.render_list(f, list, results_area, &state);
+            self.render_scrollbar(f, results_area, &state, total.max(items_len));
+        } else if let Some(notice) = self.loading_notice() {
+            self.render_loading(f, results_area, notice);
+        }
+    }
+
+    fn search_type_label(search_type: SearchType) -> &'static str {
+        match search_type {
+            SearchType::Track => tr!("Canciones"),
+            SearchType::Album => tr!("Álbumes"),
+            SearchType::Artist => tr!("Artistas"),
+            SearchType::Playlist => "Playlists",
+            SearchType::Show => "Podcasts",
+        }
+    }
+
+    fn playlist_list_item<'a>(&self, i: usize, playlist: &'a Playlist) -> ListItem<'a> {
+        let content = Line::from(vec![
+            Span::styled(format!("{:2}. ", i + 1), Style::default().fg(self.theme.title)),
+            Span::styled(&playlist.name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+            Span::styled(" - ", Style::default().fg(self.theme.secondary)),
+            Span::styled(
+                tr!("{} canciones", playlist.tracks.total),
+                Style::default().fg(self.theme.info),
+            ),
+        ]);
+        ListItem::new(content)
+    }
+
+    fn show_list_item<'a>(&self, i: usize, show: &'a Show) -> ListItem<'a> {
+        ListItem::new(Line::from(vec![
+            Span::styled(format!("{:2}. ", i + 1), Style::default().fg(self.theme.title)),
+            Span::styled(&show.name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+            Span::styled(" - ", Style::default().fg(self.theme.secondary)),
+            Span::styled(&show.publisher, Style::default().fg(self.theme.info)),
+        ]))
+    }
+
+    fn artist_list_item<'a>(&self, i: usize, artist: &'a FullArtist) -> ListItem<'a> {
+        let mut spans = vec![
+            Span::styled(format!("{:2}. ", i + 1), Style::default().fg(self.theme.title)),
+            Span::styled(&artist.name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+            Span::styled(tr!(" - {} seguidores", artist.followers.total), Style::default().fg(self.theme.info)),
+        ];
+        if !artist.genres.is_empty() {
+            spans.push(Span::styled(format!(" ({})", artist.genres.join(", ")), Style::default().fg(self.theme.detail)));
+        }
+        ListItem::new(Line::from(spans))
+    }
+
+    fn album_list_item<'a>(&self, i: usize, album: &'a Album) -> ListItem<'a> {
+        let artists = album.artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", ");
+        let year = album.release_date.split('-').next().unwrap_or_default();
+        let content = Line::from(vec![
+            Span::styled(format!("{:2}. ", i + 1), Style::default().fg(self.theme.title)),
+            Span::styled(&album.name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+            Span::styled(" - ", Style::default().fg(self.theme.secondary)),
+            Span::styled(artists, Style::default().fg(self.theme.info)),
+            Span::styled(tr!(" ({}, {} canciones)", year, album.total_tracks), Style::default().fg(self.theme.detail)),
+        ]);
+        ListItem::new(content)
+    }
+
+    fn render_playlists_view(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Título
+                Constraint::Min(0),    // Lista de playlists
+            ])
+            .split(area);
+
+        // Título
+        let title = Paragraph::new(tr!(
+            "Tus Playlists ({}/{}) | Enter: Abrir | c: Crear | e: Editar | x: Dejar de seguir",
+            self.playlists.len(),
+            self.playlists_total
+        ))
+            .style(Style::default().fg(self.theme.title))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+
+        f.render_widget(title, chunks[0]);
+
+        // Lista de playlists
+        if !self.playlists.is_empty() {
+            let items: Vec<ListItem> = self
+                .view_order(ViewList::Playlists)
+                .into_iter()
+                .enumerate()
+                .map(|(pos, i)| self.playlist_list_item(pos, &self.playlists[i]))
+                .collect();
+
+            let items_len = items.len();
+            let list = List::new(items)
+                .block(Block::default().title(self.with_loading(self.with_filter(ViewList::Playlists, String::new()))).borders(Borders::ALL))
+                .highlight_style(self.theme.highlight())
+                .highlight_symbol(self.selection_symbol());
+
+            self.render_list(f, list, chunks[1], &self.playlist_list_state);
+            let total = self.scroll_total(ViewList::Playlists, items_len, self.playlists_total);
+            self.render_scrollbar(f, chunks[1], &self.playlist_list_state, total);
+        } else {
+            let no_playlists = Paragraph::new(self.loading_notice().unwrap_or_else(|| tr!("No se encontraron playlists").to_string()))
+                .style(Style::default().fg(self.theme.title))
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+
+            f.render_widget(no_playlists, chunks[1]);
+        }
+    }
+
+    fn render_favorites_view(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Título
+                Constraint::Min(0),    // Lista de favoritos
+            ])
+            .split(area);
+
+        // Título
+        let title = Paragraph::new(tr!(
+            "Tus Canciones Favoritas ({}/{} cargadas)",
+            self.favorites.len(),
+            self.favorites_total
+        ))
+            .style(Style::default().fg(self.theme.title))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+
+        f.render_widget(title, chunks[0]);
+
+        // Lista de favoritos
+        if !self.favorites.is_empty() {
+            let rows: Vec<_> = self
+                .view_order(ViewList::Favorites)
+                .into_iter()
+                .enumerate()
+                .map(|(pos, i)| (pos + 1, Some(&self.favorites[i]).into()))
+                .collect();
+
+            let total = self.scroll_total(ViewList::Favorites, rows.len(), self.favorites_total);
+            let block = Block::default()
+                .title(self.with_loading(self.with_filter(ViewList::Favorites, self.sort_title(ViewList::Favorites))))
+                .borders(Borders::ALL);
+            self.render_track_table(f, chunks[1], block, rows, TrackColumns { album: true, popularity: true }, Some(&self.favorites_list_state));
+            self.render_scrollbar(f, chunks[1], &self.favorites_list_state, total);
+        } else {
+            let no_favorites = Paragraph::new(self.loading_notice().unwrap_or_else(|| tr!("No se encontraron canciones favoritas").to_string()))
+                .style(Style::default().fg(self.theme.title))
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+
+            f.render_widget(no_favorites, chunks[1]);
+        }
+    }
+
+    fn render_sidebar(&mut self, f: &mut Frame, area: Rect) {
+        let current = self.current_tab();
+        let open_playlist = match self.app_state {
+            AppState::PlaylistTracks => self.open_playlist.as_ref().map(|p| p.id.as_str()),
+            _ => None,
+        };
+        let items: Vec<ListItem> = self
+            .sidebar_entries()
+            .into_iter()
+            .map(|entry| match entry {
+                SidebarEntry::View(action) => {
+                    let name = SIDEBAR_VIEWS.iter().find(|(a, _)| *a == action).map_or("", |(_, name)| tr(name));
+                    let label = if action == Action::ViewPlaylists {
+                        format!("{} {}", self.glyphs(if self.sidebar_playlists_open { "▾" } else { "▸" }), name)
+                    } else {
+                        format!("  {}", name)
+                    };
+                    let style = if current == Some(action) && open_playlist.is_none() {
+                        self.theme.active_tab()
+                    } else {
+                        Style::default().fg(self.theme.text)
+                    };
+                    ListItem::new(Span::styled(label, style))
+                }
+                SidebarEntry::Playlist(i) => {
+                    let playlist = &self.playlists[i];
+                    let style = if open_playlist == Some(playlist.id.as_str()) {
+                        self.theme.active_tab()
+                    } else {
+                        Style::default().fg(self.theme.secondary)
+                    };
+                    ListItem::new(Span::styled(format!("    {}", playlist.name), style))
+                }
+            })
+            .collect();
+
+        let border = if self.sidebar_focused { self.theme.accent } else { self.theme.secondary };
+        let mut block = Block::default().borders(Borders::ALL).border_style(Style::default().fg(border));
+        if self.sidebar_focused && self.theme.emphasis {
+            block = block.border_type(BorderType::Thick);
+        }
+        let mut list = List::new(items).block(block);
+        // La selección solo se ve mientras la barra tiene el foco
+        let mut state = self.sidebar_state.clone();
+        if self.sidebar_focused {
+            list = list.highlight_style(self.theme.highlight()).highlight_symbol(self.selection_symbol());
+        } else {
+            *state.offset_mut() = 0;
+            state.select(None);
+        }
+        f.render_stateful_widget(list, area, &mut state);
+        self.sidebar_area = Some((area, state.offset()));
+    }
+
+    fn render_followed_artists_view(&self, f: &mut Frame, area: Rect) {
+        if self.followed_artists.is_empty() {
+            let empty = Paragraph::new(self.loading_notice().unwrap_or_else(|| tr!("No sigues a ningún artista").to_string()))
+                .style(Style::default().fg(self.theme.title))
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+            f.render_widget(empty, area);
+            return;
+        }
+
+        let items: Vec<ListItem> = self
+            .followed_artists
+            .iter()
+            .enumerate()
+            .map(|(i, artist)| self.artist_list_item(i, artist))
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(self.with_loading(tr!("Artistas que sigues ({}) | Enter: Ver artista", self.followed_artists.len())))
+                    .borders(Borders::ALL),
+            )
+            .highlight_style(self.theme.highlight())
+            .highlight_symbol(self.selection_symbol());
+
+        self.render_list(f, list, area, &self.followed_artists_list_state);
+    }
+
+    /// Registro de errores, avisos y acciones, con lo más reciente arriba
+    fn render_log_view(&self, f: &mut Frame, area: Rect) {
+        if self.log.is_empty() {
+            let empty = Paragraph::new(tr!("Todavía no hay nada en el registro"))
+                .style(Style::default().fg(self.theme.title))
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+            f.render_widget(empty, area);
+            return;
+        }
+
+        let items: Vec<ListItem> = self
+            .log
+            .iter()
+            .rev()
+            .map(|entry| {
+                let (label, color) = match entry.kind {
+                    LogKind::Error => ("error ", self.theme.error),
+                    LogKind::Info => (tr!("aviso "), self.theme.success),
+                    LogKind::Action => (tr!("acción"), self.theme.secondary),
+                };
+                ListItem::new(Line::from(vec![
+                    Span::styled(entry.time.format("%H:%M:%S ").to_string(), Style::default().fg(self.theme.dim)),
+                    Span::styled(format!("{} ", label), Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                    Span::styled(entry.text.as_str(), Style::default().fg(self.theme.text)),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(
+                Block::default()
+                    .title(tr!("Registro ({}/{}) | c: Vaciar", self.log.len(), LOG_SIZE))
+                    .borders(Borders::ALL),
+            )
+            .highlight_style(self.theme.highlight())
+            .highlight_symbol(self.selection_symbol());
+
+        self.render_list(f, list, area, &self.log_list_state);
+    }
+
+    /// Panel de depuración: las últimas peticiones a la API, con lo más reciente arriba, en la
+    /// esquina inferior derecha de `area`
+    fn render_debug_overlay(&self, f: &mut Frame, area: Rect) {
+        let width = area.width.min(80);
+        let height = area.height.min(API_CALLS_SIZE as u16 + 2).min(area.height / 2 + 2);
+        let area = Rect::new(area.right() - width, area.bottom() - height, width, height);
+        f.render_widget(Clear, area);
+
+        let rows: Vec<Row> = self
+            .api_calls
+            .iter()
+            .rev()
+            .map(|call| {
+                let (status, color) = match call.status {
+                    Some(status @ 200..=399) => (status.to_string(), self.theme.success),
+                    Some(status) => (status.to_string(), self.theme.error),
+                    None => (tr!("red").to_string(), self.theme.error),
+                };
+                Row::new(vec![
+                    TableCell::from(call.at.with_timezone(&chrono::Local).format("%H:%M:%S").to_string())
+                        .style(Style::default().fg(self.theme.dim)),
+                    TableCell::from(call.method.clone()).style(Style::default().fg(self.theme.secondary)),
+                    TableCell::from(call.path.clone()).style(Style::default().fg(self.theme.text)),
+                    TableCell::from(status).style(Style::default().fg(color).add_modifier(Modifier::BOLD)),
+                    TableCell::from(format!("{}ms", call.duration_ms)).style(Style::default().fg(self.theme.dim)),
+                ])
+            })
+            .collect();
+        let title = tr!(
+            "Peticiones a la API ({}/{}) | {}: cerrar",
+            self.api_calls.len(),
+            API_CALLS_SIZE,
+            self.keymap.label(Action::DebugOverlay)
+        );
+        let table = Table::new(
+            rows,
+            [
+                Constraint::Length(8),
+                Constraint::Length(6),
+                Constraint::Min(10),
+                Constraint::Length(3),
+                Constraint::Length(7),
+            ],
+        )
+        .block(Block::default().title(title).borders(Borders::ALL).border_style(Style::default().fg(self.theme.accent)));
+        f.render_widget(table, area);
+    }
+
+    fn render_saved_albums_view(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Título
+                Constraint::Min(0),    // Lista de álbumes
+            ])
+            .split(area);
+
+        let title = Paragraph::new(tr!(
+            "Tus Álbumes ({}/{} cargados) | Enter: Ver canciones",
+            self.saved_albums.len(),
+            self.saved_albums_total
+        ))
+            .style(Style::default().fg(self.theme.title))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+
+        f.render_widget(title, chunks[0]);
+
+        if !self.saved_albums.is_empty() {
+            let items: Vec<ListItem> = self
+                .saved_albums
+                .iter()
+                .enumerate()
+                .map(|(i, album)| self.album_list_item(i, album))
+                .collect();
+
+            let items_len = items.len();
+            let list = List::new(items)
+                .block(Block::default().title(self.with_loading(String::new())).borders(Borders::ALL))
+                .highlight_style(self.theme.highlight())
+                .highlight_symbol(self.selection_symbol());
+
+            self.render_list(f, list, chunks[1], &self.saved_albums_list_state);
+            self.render_scrollbar(f, chunks[1], &self.saved_albums_list_state, self.saved_albums_total.max(items_len));
+        } else {
+            let no_albums = Paragraph::new(self.loading_notice().unwrap_or_else(|| tr!("No se encontraron álbumes guardados").to_string()))
+                .style(Style::default().fg(self.theme.title))
+                .alignment(Alignment::Center)
+                .block(Block::default().borders(Borders::ALL));
+
+            f.render_widget(no_albums, chunks[1]);
+        }
+    }
+
+    fn render_queue_view(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Sonando ahora
+                Constraint::Min(0),    // Próximas canciones
+            ])
+            .split(area);
+
+        // Sonando ahora
+        let now_playing = match self.queue_playing {
+            Some(PlayableItem::Track(ref track)) => {
+                let artists = track.artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", ");
+                Line::from(vec![
+                    Span::styled(self.glyphs("🎵 "), Style::default().fg(self.theme.accent)),
+                    Span::styled(&track.name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+                    Span::styled(" - ", Style::default().fg(self.theme.secondary)),
+                    Span::styled(artists, Style::default().fg(self.theme.info)),
+                ])
+            }
+            Some(PlayableItem::Episode(ref episode)) => Line::from(vec![
+                Span::styled(self.glyphs("🎙️  "), Style::default().fg(self.theme.accent)),
+                Span::styled(&episode.name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+                Span::styled(" - ", Style::default().fg(self.theme.secondary)),
+                Span::styled(&episode.show.name, Style::default().fg(self.theme.info)),
+            ]),
+            None => Line::from(Span::styled(tr!("No hay canción reproduciéndose"), Style::default().fg(self.theme.title))),
+        };
+
+        let now_playing = Paragraph::new(now_playing)
+            .alignment(Alignment::Center)
+            .block(Block::default().title(tr!("Sonando ahora")).borders(Borders::ALL));
+
+        f.render_widget(now_playing, chunks[0]);
+
+        // Próximas canciones
+        if !self.queue.is_empty() {
+            let rows = self
+                .view_order(ViewList::Queue)
+                .into_iter()
+                .enumerate()
+                .map(|(pos, i)| (pos + 1, TrackRow::from(&self.queue[i])))
+                .collect();
+
+            let title = if self.own_queue.is_some() {
+                tr!("A continuación (cola propia) | Shift+↑/↓: Mover | Supr: Quitar")
+            } else {
+                tr!("A continuación | Shift+↑/↓: Mover | Supr: Quitar")
+            };
+            let block = Block::default().title(self.with_loading(self.with_filter(ViewList::Queue, title.to_string()))).borders(Borders::ALL);
+            self.render_track_table(f, chunks[1], block, rows, TrackColumns { album: true, popularity: false }, Some(&self.queue_list_state));
+        } else {
+            let empty_queue = Paragraph::new(self.loading_notice().unwrap_or_else(|| tr!("La cola está vacía").to_string()))
+                .style(Style::default().fg(self.theme.title))
+                .alignment(Alignment::Center)
+                .block(Block::default().title(tr!("A continuación")).borders(Borders::ALL));
+
+            f.render_widget(empty_queue, chunks[1]);
+        }
+    }
+
+    fn render_top_view(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Periodo
+                Constraint::Min(0),    // Canciones y artistas
+            ])
+            .split(area);
+
+        let title = Paragraph::new(tr!(
+            "Periodo: {} | t: Cambiar periodo | Enter: Reproducir | a: Añadir a la cola",
+            Self::time_range_label(self.top_time_range)
+        ))
+        .style(Style::default().fg(self.theme.title))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+
+        f.render_widget(title, chunks[0]);
+
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Percentage(60), Constraint::Percentage(40)])
+            .split(chunks[1]);
+
+        // Canciones más escuchadas
+        let rows = self
+            .view_order(ViewList::Top)
+            .into_iter()
+            .enumerate()
+            .map(|(pos, i)| (pos + 1, Some(&self.top_tracks[i]).into()))
+            .collect();
+
+        let block = Block::default().title(self.with_loading(self.with_filter(ViewList::Top, tr!("Canciones").to_string()))).borders(Borders::ALL);
+        self.render_track_table(f, columns[0], block, rows, TrackColumns { album: true, popularity: false }, Some(&self.top_tracks_list_state));
+
+        // Artistas más escuchados
+        let items: Vec<ListItem> = self
+            .top_artists
+            .iter()
+            .enumerate()
+            .map(|(i, artist)| self.artist_list_item(i, artist))
+            .collect();
+
+        let list = List::new(items).block(Block::default().title(tr!("Artistas")).borders(Borders::ALL));
+
+        f.render_widget(list, columns[1]);
+    }
+
+    fn render_discover_view(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(4), // Semillas y ayuda
+                Constraint::Min(0),    // Recomendaciones
+            ])
+            .split(area);
+
+        let seeds = if self.discover_seeds.is_empty() {
+            vec![Span::styled(tr!("Sin semillas"), Style::default().fg(self.theme.secondary))]
         } else {
-            "↑/↓: Navegar | Enter: Reproducir | /: Nueva búsqueda"
+            self.discover_seeds
+                .iter()
+                .map(|seed| match seed {
+                    DiscoverSeed::Track { name, .. } => Span::styled(format!("{} {}  ", self.glyphs("🎵"), name), Style::default().fg(self.theme.text)),
+                    DiscoverSeed::Artist { name, .. } => Span::styled(format!("{} {}  ", self.glyphs("👤"), name), Style::default().fg(self.theme.info)),
+                })
+                .collect()
         };
 
-        let search_paragraph = Paragraph::new(search_info)
-            .style(Style::default().fg(Color::Cyan))
-            .alignment(Alignment::Center)
-            .block(Block::default().title("Búsqueda").borders(Borders::ALL));
+        let header = Paragraph::new(vec![
+            Line::from(seeds),
+            Line::from(Span::styled(
+                tr!("c: Canción actual | C: Artistas actuales | +: Canción seleccionada | x: Limpiar | g: Generar | S: Guardar playlist"),
+                Style::default().fg(self.theme.title),
+            )),
+        ])
+        .block(Block::default().title(tr!("Semillas")).borders(Borders::ALL));
 
-        f.render_widget(search_paragraph, chunks[0]);
+        f.render_widget(header, chunks[0]);
 
-        // Search results
-        if !self.search_results.is_empty() {
-            let items: Vec<ListItem> = self
-                .search_results
-                .iter()
+        if !self.discover_results.is_empty() {
+            let rows = self
+                .view_order(ViewList::Discover)
+                .into_iter()
                 .enumerate()
-                .map(|(i, track)| {
-                    let artists = track.artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", ");
-                    let content = Line::from(vec![
-                        Span::styled(format!("{:2}. ", i + 1), Style::default().fg(Color::Yellow)),
-                        Span::styled(&track.name, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
-                        Span::styled(" - ", Style::default().fg(Color::Gray)),
-                        Span::styled(artists, Style::default().fg(Color::Cyan)),
-                        Span::styled(" (", Style::default().fg(Color::Gray)),
-                        Span::styled(&track.album.name, Style::default().fg(Color::Magenta)),
-                        Span::styled(")", Style::default().fg(Color::Gray)),
-                    ]);
-                    ListItem::new(content)
-                })
+                .map(|(pos, i)| (pos + 1, Some(&self.discover_results[i]).into()))
                 .collect();
 
-            let list = List::new(items)
-                .block(Block::default().title("Resultados").borders(Borders::ALL))
-                .highlight_style(Style::default().fg(Color::Black).bg(Color::Green))
-                .highlight_symbol("► ");
+            let title = self.with_filter(ViewList::Discover, tr!("Recomendaciones (Enter: Reproducir | a: Añadir a la cola)").to_string());
+            let block = Block::default().title(self.with_loading(title)).borders(Borders::ALL);
+            self.render_track_table(f, chunks[1], block, rows, TrackColumns { album: true, popularity: false }, Some(&self.discover_list_state));
+        } else {
+            let empty = Paragraph::new(
+                self.loading_notice()
+                    .unwrap_or_else(|| tr!("Añade semillas y pulsa 'g' para generar recomendaciones").to_string()),
+            )
+                .style(Style::default().fg(self.theme.title))
+                .alignment(Alignment::Center)
+                .block(Block::default().title(tr!("Recomendaciones")).borders(Borders::ALL));
 
-            f.render_stateful_widget(list, chunks[1], &mut self.search_list_state.clone());
+            f.render_widget(empty, chunks[1]);
         }
     }
 
-    fn render_playlists_view(&self, f: &mut Frame, area: Rect) {
+    fn render_lyrics_view(&self, f: &mut Frame, area: Rect) {
+        let playback = self.current_playback.as_ref();
+        let track = playback.and_then(|p| p.track());
+        let title = match track {
+            Some(track) => tr!("Letra: {}", Self::lyrics_key(track)),
+            None => tr!("Letra").to_string(),
+        };
+        let block = Block::default().title(title).borders(Borders::ALL);
+
+        let lyrics = track.and_then(|t| self.lyrics.as_ref().filter(|(key, _)| *key == Self::lyrics_key(t)));
+        let message = match (track, lyrics) {
+            _ if self.lyrics_provider.is_none() => Some(tr!("Letras desactivadas (lyrics_provider: \"none\")")),
+            (None, _) => Some(tr!("No hay canción reproduciéndose")),
+            (Some(_), None) => Some(tr!("Cargando letra...")),
+            (Some(_), Some((_, None))) => Some(tr!("No se ha encontrado la letra de esta canción")),
+            (Some(_), Some((_, Some(Lyrics::Instrumental)))) => Some(tr!("Instrumental")),
+            _ => None,
+        };
+        if let Some(message) = message {
+            let paragraph = Paragraph::new(message)
+                .style(Style::default().fg(self.theme.title))
+                .alignment(Alignment::Center)
+                .block(block);
+            f.render_widget(paragraph, area);
+            return;
+        }
+
+        let Some((_, Some(lyrics))) = lyrics else {
+            return;
+        };
+        let height = block.inner(area).height;
+        let paragraph = match lyrics {
+            // La línea que suena va resaltada y se mantiene en el centro
+            Lyrics::Synced(lines) => {
+                let progress_ms = playback.and_then(|p| self.estimated_progress_ms(p)).unwrap_or(0).max(0) as u64;
+                let current = lyrics.current_line(progress_ms);
+                let text: Vec<Line> = lines
+                    .iter()
+                    .enumerate()
+                    .map(|(i, line)| {
+                        let style = match current {
+                            Some(c) if c == i => Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD),
+                            Some(c) if i < c => Style::default().fg(self.theme.dim),
+                            _ => Style::default().fg(self.theme.text),
+                        };
+                        Line::styled(line.text.as_str(), style)
+                    })
+                    .collect();
+                let scroll = current.unwrap_or(0).saturating_sub(height as usize / 2) as u16;
+                Paragraph::new(text).scroll((scroll, 0))
+            }
+            Lyrics::Plain(lines) => {
+                let text: Vec<Line> = lines.iter().map(|line| Line::raw(line.as_str())).collect();
+                let max_scroll = (text.len() as u16).saturating_sub(height);
+                Paragraph::new(text)
+                    .style(Style::default().fg(self.theme.text))
+                    .scroll((self.lyrics_scroll.min(max_scroll), 0))
+            }
+            Lyrics::Instrumental => return,
+        };
+        f.render_widget(paragraph.alignment(Alignment::Center).block(block), area);
+    }
+
+    fn render_episodes_view(&self, f: &mut Frame, area: Rect) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3), // Título
-                Constraint::Min(0),    // Lista de playlists
+                Constraint::Min(0),    // Lista de episodios
             ])
             .split(area);
 
-        // Título
-        let title = Paragraph::new("Tus Playlists")
-            .style(Style::default().fg(Color::Yellow))
+        let title = Paragraph::new(tr!("Tus Episodios Guardados | Enter: Reanudar | x: Quitar/Guardar"))
+            .style(Style::default().fg(self.theme.title))
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
 
         f.render_widget(title, chunks[0]);
 
-        // Lista de playlists
-        if !self.playlists.is_empty() {
+        if !self.saved_episodes.is_empty() {
             let items: Vec<ListItem> = self
-                .playlists
+                .saved_episodes
                 .iter()
                 .enumerate()
-                .map(|(i, playlist)| {
-                    let content = Line::from(vec![
-                        Span::styled(format!("{:2}. ", i + 1), Style::default().fg(Color::Yellow)),
-                        Span::styled(&playlist.name, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
-                        Span::styled(" - ", Style::default().fg(Color::Gray)),
-                        Span::styled(
-                            format!("{} canciones", playlist.tracks.total),
-                            Style::default().fg(Color::Cyan),
+                .map(|(i, episode)| {
+                    let removed = self.removed_episodes.contains(&episode.id);
+                    let progress = match episode.resume_point {
+                        Some(ref r) if r.fully_played => tr!("escuchado").to_string(),
+                        Some(ref r) if r.resume_position_ms > 0 => format!(
+                            "{} / {}",
+                            Self::format_duration(r.resume_position_ms),
+                            Self::format_duration(episode.duration_ms)
                         ),
-                    ]);
-                    ListItem::new(content)
+                        _ => Self::format_duration(episode.duration_ms),
+                    };
+                    let name_style = if removed {
+                        Style::default().fg(self.theme.dim).add_modifier(Modifier::CROSSED_OUT)
+                    } else {
+                        Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)
+                    };
+                    ListItem::new(Line::from(vec![
+                        Span::styled(format!("{:2}. ", i + 1), Style::default().fg(self.theme.title)),
+                        Span::styled(&episode.name, name_style),
+                        Span::styled(" - ", Style::default().fg(self.theme.secondary)),
+                        Span::styled(&episode.show.name, Style::default().fg(self.theme.info)),
+                        Span::styled(format!(" ({})", progress), Style::default().fg(self.theme.detail)),
+                    ]))
                 })
                 .collect();
 
             let list = List::new(items)
-                .block(Block::default().borders(Borders::ALL))
-                .highlight_style(Style::default().fg(Color::Black).bg(Color::Green))
-                .highlight_symbol("► ");
+                .block(Block::default().title(self.with_loading(String::new())).borders(Borders::ALL))
+                .highlight_style(self.theme.highlight())
+                .highlight_symbol(self.selection_symbol());
 
-            f.render_stateful_widget(list, chunks[1], &mut self.playlist_list_state.clone());
+            self.render_list(f, list, chunks[1], &self.saved_episodes_list_state);
         } else {
-            let no_playlists = Paragraph::new("No se encontraron playlists")
-                .style(Style::default().fg(Color::Yellow))
+            let no_episodes = Paragraph::new(self.loading_notice().unwrap_or_else(|| tr!("No se encontraron episodios guardados").to_string()))
+                .style(Style::default().fg(self.theme.title))
                 .alignment(Alignment::Center)
                 .block(Block::default().borders(Borders::ALL));
 
-            f.render_widget(no_playlists, chunks[1]);
+            f.render_widget(no_episodes, chunks[1]);
         }
     }
 
-    fn render_favorites_view(&self, f: &mut Frame, area: Rect) {
+    fn render_playlist_tracks_view(&self, f: &mut Frame, area: Rect) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
                 Constraint::Length(3), // Título
-                Constraint::Min(0),    // Lista de favoritos
+                Constraint::Min(0),    // Canciones
             ])
             .split(area);
 
-        // Título
-        let title = Paragraph::new("Tus Canciones Favoritas")
-            .style(Style::default().fg(Color::Yellow))
+        let name = self.open_playlist.as_ref().map(|p| p.name.as_str()).unwrap_or_default();
+        let title = Paragraph::new(tr!(
+            "{} ({}/{}) | Enter: Reproducir desde aquí | Shift+↑/↓: Mover | Esc: Volver",
+            name,
+            self.playlist_items.len(),
+            self.playlist_items_total
+        ))
+        .style(Style::default().fg(self.theme.title))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+
+        f.render_widget(title, chunks[0]);
+
+        if self.playlist_items.is_empty() {
+            if let Some(notice) = self.loading_notice() {
+                self.render_loading(f, chunks[1], notice);
+                return;
+            }
+        }
+
+        let rows: Vec<_> = self
+            .view_order(ViewList::PlaylistItems)
+            .into_iter()
+            .enumerate()
+            .map(|(pos, i)| (pos + 1, self.playlist_items[i].track.as_ref().into()))
+            .collect();
+
+        let total = self.scroll_total(ViewList::PlaylistItems, rows.len(), self.playlist_items_total);
+        let block = Block::default()
+            .title(self.with_loading(self.with_filter(ViewList::PlaylistItems, self.sort_title(ViewList::PlaylistItems))))
+            .borders(Borders::ALL);
+        self.render_track_table(f, chunks[1], block, rows, TrackColumns { album: true, popularity: true }, Some(&self.playlist_items_list_state));
+        self.render_scrollbar(f, chunks[1], &self.playlist_items_list_state, total);
+    }
+
+    fn render_album_tracks_view(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Título
+                Constraint::Min(0),    // Canciones
+            ])
+            .split(area);
+
+        let (name, artists) = match self.open_album {
+            Some(ref album) => (
+                album.name.as_str(),
+                album.artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", "),
+            ),
+            None => ("", String::new()),
+        };
+        let title = Paragraph::new(tr!(
+            "{} - {} | Enter: Reproducir desde aquí | R: Álbum completo | Esc: Volver",
+            name, artists
+        ))
+        .style(Style::default().fg(self.theme.title))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+
+        f.render_widget(title, chunks[0]);
+
+        let total_ms: i64 = self.album_tracks.iter().map(|t| t.duration_ms).sum();
+        // Se conserva el número de pista aunque haya filtro; el álbum es el mismo en todas
+        let rows = self
+            .view_order(ViewList::AlbumTracks)
+            .into_iter()
+            .map(|i| (i + 1, TrackRow::Track(&self.album_tracks[i])))
+            .collect();
+
+        let block = Block::default()
+            .title(self.with_loading(self.with_filter(
+                ViewList::AlbumTracks,
+                tr!(
+                    "Canciones ({}/{}) - {}",
+                    self.album_tracks.len(),
+                    self.album_tracks_total,
+                    Self::format_duration(total_ms)
+                ),
+            )))
+            .borders(Borders::ALL);
+        self.render_track_table(f, chunks[1], block, rows, TrackColumns { album: false, popularity: false }, Some(&self.album_tracks_list_state));
+    }
+
+    fn render_artist_view(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Título
+                Constraint::Min(0),    // Secciones
+            ])
+            .split(area);
+
+        // Ruta recorrida por el grafo de artistas relacionados
+        let mut path: Vec<&str> = self
+            .nav_stack
+            .iter()
+            .rev()
+            .map_while(|entry| match &entry.detail {
+                Some(Detail::Artist { artist, .. }) => Some(artist.name.as_str()),
+                _ => None,
+            })
+            .collect();
+        path.reverse();
+        path.extend(self.open_artist.iter().map(|a| a.name.as_str()));
+        let name = path.join(" › ");
+        let hint = match self.artist_section {
+            ArtistSection::TopTracks => tr!("Enter: Reproducir | a: Cola | P: Añadir a playlist"),
+            ArtistSection::Albums => tr!("Enter: Reproducir álbum | i: Ver canciones"),
+            ArtistSection::Related => tr!("Enter: Saltar al artista"),
+        };
+        let title = Paragraph::new(tr!("{} | Tab: Sección | {} | Esc: Volver", name, hint))
+            .style(Style::default().fg(self.theme.title))
             .alignment(Alignment::Center)
             .block(Block::default().borders(Borders::ALL));
 
         f.render_widget(title, chunks[0]);
 
-        // Lista de favoritos
-        if !self.favorites.is_empty() {
-            let items: Vec<ListItem> = self
-                .favorites
-                .iter()
-                .enumerate()
-                .map(|(i, track)| {
-                    let artists = track.artists.iter().map(|a| a.name.clone()).collect::<Vec<_>>().join(", ");
-                    let content = Line::from(vec![
-                        Span::styled(format!("{:2}. ", i + 1), Style::default().fg(Color::Yellow)),
-                        Span::styled(&track.name, Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
-                        Span::styled(" - ", Style::default().fg(Color::Gray)),
-                        Span::styled(artists, Style::default().fg(Color::Cyan)),
-                        Span::styled(" (", Style::default().fg(Color::Gray)),
-                        Span::styled(&track.album.name, Style::default().fg(Color::Magenta)),
-                        Span::styled(")", Style::default().fg(Color::Gray)),
-                    ]);
-                    ListItem::new(content)
-                })
-                .collect();
+        let columns = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([
+                Constraint::Percentage(40), // Canciones populares
+                Constraint::Percentage(35), // Álbumes
+                Constraint::Percentage(25), // Relacionados
+            ])
+            .split(chunks[1]);
+
+        let sections: [(ArtistSection, &str, Vec<ListItem>, &ListState); 3] = [
+            // Las canciones van en tabla (más abajo)
+            (ArtistSection::TopTracks, "", Vec::new(), &self.artist_top_tracks_list_state),
+            (
+                ArtistSection::Albums,
+                tr!("Álbumes"),
+                self.artist_albums
+                    .iter()
+                    .enumerate()
+                    .map(|(i, album)| self.album_list_item(i, album))
+                    .collect(),
+                &self.artist_albums_list_state,
+            ),
+            (
+                ArtistSection::Related,
+                tr!("Relacionados"),
+                self.related_artists
+                    .iter()
+                    .enumerate()
+                    .map(|(i, artist)| self.artist_list_item(i, artist))
+                    .collect(),
+                &self.related_artists_list_state,
+            ),
+        ];
+
+        for ((section, title, items, state), column) in sections.into_iter().zip(columns.iter()) {
+            let (border_style, border_type) = if self.artist_section == section {
+                let border_type = if self.theme.emphasis { BorderType::Thick } else { BorderType::Plain };
+                (Style::default().fg(self.theme.accent), border_type)
+            } else {
+                (Style::default().fg(self.theme.dim), BorderType::Plain)
+            };
+            let block = Block::default().borders(Borders::ALL).border_style(border_style).border_type(border_type);
+
+            if section == ArtistSection::TopTracks {
+                let rows = self
+                    .view_order(ViewList::ArtistTopTracks)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(pos, i)| (pos + 1, Some(&self.artist_top_tracks[i]).into()))
+                    .collect();
+                let block = block.title(self.with_filter(ViewList::ArtistTopTracks, tr!("Populares").to_string()));
+                // Si la sección activa es otra, su lista se dibuja después y es la que atiende al ratón
+                self.render_track_table(f, *column, block, rows, TrackColumns { album: true, popularity: false }, Some(state));
+                continue;
+            }
 
             let list = List::new(items)
-                .block(Block::default().borders(Borders::ALL))
-                .highlight_style(Style::default().fg(Color::Black).bg(Color::Green))
-                .highlight_symbol("► ");
+                .block(block.title(title))
+                .highlight_style(self.theme.highlight())
+                .highlight_symbol(self.selection_symbol());
+
+            if self.artist_section == section {
+                self.render_list(f, list, *column, state);
+            } else {
+                f.render_stateful_widget(list, *column, &mut state.clone());
+            }
+        }
+    }
+
+    fn render_browse_view(&self, f: &mut Frame, area: Rect) {
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(3), // Pestañas
+                Constraint::Min(0),    // Contenido de la pestaña
+            ])
+            .split(area);
+
+        let tab_style = |tab: BrowseTab| {
+            if self.browse_tab == tab {
+                self.theme.active_tab()
+            } else {
+                Style::default().fg(self.theme.secondary)
+            }
+        };
+        let hint = match self.browse_tab {
+            BrowseTab::NewReleases => tr!("Enter: Reproducir álbum | i: Ver canciones"),
+            BrowseTab::Featured => tr!("Enter: Reproducir | F: Seguir"),
+            BrowseTab::Categories if self.open_category.is_some() => tr!("Enter: Reproducir | F: Seguir | Esc: Volver"),
+            BrowseTab::Categories => tr!("Enter: Abrir categoría"),
+        };
+        let tabs = Paragraph::new(Line::from(vec![
+            Span::styled(tr!(" Novedades "), tab_style(BrowseTab::NewReleases)),
+            Span::raw(" "),
+            Span::styled(tr!(" Destacadas "), tab_style(BrowseTab::Featured)),
+            Span::raw(" "),
+            Span::styled(tr!(" Categorías "), tab_style(BrowseTab::Categories)),
+            Span::styled(tr!("  Tab: Cambiar | {}", hint), Style::default().fg(self.theme.title)),
+        ]))
+        .alignment(Alignment::Center)
+        .block(Block::default().borders(Borders::ALL));
+
+        f.render_widget(tabs, chunks[0]);
+
+        let (items, title, state): (Vec<ListItem>, String, ListState) = match self.browse_tab {
+            BrowseTab::NewReleases => (
+                self.new_releases
+                    .iter()
+                    .enumerate()
+                    .map(|(i, album)| self.album_list_item(i, album))
+                    .collect(),
+                tr!("Novedades ({}/{})", self.new_releases.len(), self.new_releases_total),
+                self.new_releases_list_state.clone(),
+            ),
+            BrowseTab::Featured => (
+                self.featured_playlists
+                    .iter()
+                    .enumerate()
+                    .map(|(i, playlist)| self.playlist_list_item(i, playlist))
+                    .collect(),
+                // Spotify devuelve un mensaje localizado como cabecera ("Música para tu lunes", ...)
+                self.featured_message.clone().unwrap_or_else(|| tr!("Playlists destacadas").to_string()),
+                self.featured_list_state.clone(),
+            ),
+            BrowseTab::Categories => match self.open_category {
+                Some((ref category, ref playlists)) => (
+                    playlists
+                        .iter()
+                        .enumerate()
+                        .map(|(i, playlist)| self.playlist_list_item(i, playlist))
+                        .collect(),
+                    tr!("Categorías › {}", category.name),
+                    self.category_playlists_list_state.clone(),
+                ),
+                None => (
+                    self.categories
+                        .iter()
+                        .enumerate()
+                        .map(|(i, category)| {
+                            ListItem::new(Line::from(vec![
+                                Span::styled(format!("{:2}. ", i + 1), Style::default().fg(self.theme.title)),
+                                Span::styled(&category.name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+                            ]))
+                        })
+                        .collect(),
+                    tr!("Categorías").to_string(),
+                    self.categories_list_state.clone(),
+                ),
+            },
+        };
+
+        let list = List::new(items)
+            .block(Block::default().title(self.with_loading(title)).borders(Borders::ALL))
+            .highlight_style(self.theme.highlight())
+            .highlight_symbol(self.selection_symbol());
+
+        self.render_list(f, list, chunks[1], &state);
+    }
+
+    /// Dibuja la lista de la vista activa y recuerda su zona para los clics del ratón
+    /// Panel vacío con el aviso de carga mientras llega la primera página
+    fn render_loading(&self, f: &mut Frame, area: Rect, notice: String) {
+        let loading = Paragraph::new(notice)
+            .style(Style::default().fg(self.theme.title))
+            .alignment(Alignment::Center)
+            .block(Block::default().borders(Borders::ALL));
+        f.render_widget(loading, area);
+    }
+
+    /// Barra de desplazamiento sobre el borde derecho de una lista: sitúa la selección dentro de
+    /// toda la colección aunque todavía no esté cargada entera
+    fn render_scrollbar(&self, f: &mut Frame, area: Rect, state: &ListState, total: usize) {
+        let visible = area.height.saturating_sub(2) as usize;
+        if total <= visible {
+            return;
+        }
+        let mut scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight)
+            .begin_symbol(None)
+            .end_symbol(None)
+            .track_style(Style::default().fg(self.theme.dim))
+            .thumb_style(Style::default().fg(self.theme.accent));
+        if self.ascii_symbols {
+            scrollbar = scrollbar.track_symbol(Some("|")).thumb_symbol("#");
+        }
+        let mut scrollbar_state = ScrollbarState::new(total)
+            .position(state.selected().unwrap_or(0))
+            .viewport_content_length(visible);
+        f.render_stateful_widget(scrollbar, area.inner(&Margin { vertical: 1, horizontal: 0 }), &mut scrollbar_state);
+    }
 
-            f.render_stateful_widget(list, chunks[1], &mut self.favorites_list_state.clone());
+    /// Elementos que abarca la barra de desplazamiento: con filtro solo los que se ven
+    fn scroll_total(&self, list: ViewList, shown: usize, total: usize) -> usize {
+        if self.filters.contains_key(&list) {
+            shown
         } else {
-            let no_favorites = Paragraph::new("No se encontraron canciones favoritas")
-                .style(Style::default().fg(Color::Yellow))
-                .alignment(Alignment::Center)
-                .block(Block::default().borders(Borders::ALL));
+            total.max(shown)
+        }
+    }
 
-            f.render_widget(no_favorites, chunks[1]);
+    fn render_list(&self, f: &mut Frame, list: List, area: Rect, state: &ListState) {
+        let mut state = state.clone();
+        f.render_stateful_widget(list, area, &mut state);
+        self.list_area.set(Some((area, state.offset())));
+    }
+
+    fn time_range_label(time_range: TimeRange) -> &'static str {
+        match time_range {
+            TimeRange::Short => tr!("Últimas 4 semanas"),
+            TimeRange::Medium => tr!("Últimos 6 meses"),
+            TimeRange::Long => tr!("Desde siempre"),
+        }
+    }
+
+    /// Con canciones marcadas, la columna de la izquierda indica cuáles
+    fn mark_span(&self, track: &Track) -> Span<'static> {
+        match self.current_marks() {
+            Some(marks) if marks.contains(&track.id) => Span::styled(
+                self.glyphs("● ").into_owned(),
+                Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD),
+            ),
+            Some(_) => Span::raw("  "),
+            None => Span::raw(""),
+        }
+    }
+
+    /// Lista de canciones como tabla de columnas alineadas (#, título, artista, álbum, popularidad
+    /// y duración). Cada fila lleva el número que se muestra y la canción, el episodio o que ya no
+    /// está disponible. Con poco ancho se quitan el álbum, la popularidad y después la duración; lo que
+    /// no cabe se recorta con "…".
+    /// Sin `state` la tabla solo se muestra (vista previa) y no responde al ratón
+    fn render_track_table(
+        &self,
+        f: &mut Frame,
+        area: Rect,
+        block: Block,
+        rows: Vec<(usize, TrackRow)>,
+        columns: TrackColumns,
+        state: Option<&ListState>,
+    ) {
+        let marks = self.current_marks().is_some();
+        let number_width = rows.iter().map(|(n, _)| n.to_string().len()).max().unwrap_or(1) as u16 + 1 + if marks { 2 } else { 0 };
+        let inner = area.width.saturating_sub(2 + Span::raw(self.selection_symbol()).width() as u16);
+        let show_album = columns.album && inner >= 70;
+        let show_popularity = columns.popularity && inner >= 60;
+        let show_duration = inner >= 40;
+        let spacing = 2 + show_album as u16 + show_popularity as u16 + show_duration as u16;
+        let fixed = number_width
+            + if show_popularity { POPULARITY_WIDTH } else { 0 }
+            + if show_duration { DURATION_WIDTH } else { 0 }
+            + spacing;
+        let flexible = inner.saturating_sub(fixed);
+        let (title_width, artist_width) = if show_album {
+            (flexible * 2 / 5, flexible * 3 / 10)
+        } else {
+            (flexible * 3 / 5, flexible - flexible * 3 / 5)
+        };
+        let album_width = flexible - title_width - artist_width;
+
+        let mut widths = vec![Constraint::Length(number_width), Constraint::Length(title_width), Constraint::Length(artist_width)];
+        let mut header = vec![TableCell::from("#"), TableCell::from(tr!("Título")), TableCell::from(tr!("Artista"))];
+        if show_album {
+            widths.push(Constraint::Length(album_width));
+            header.push(TableCell::from(tr!("Álbum")));
+        }
+        if show_popularity {
+            widths.push(Constraint::Length(POPULARITY_WIDTH));
+            header.push(TableCell::from("Pop."));
+        }
+        if show_duration {
+            widths.push(Constraint::Length(DURATION_WIDTH));
+            header.push(TableCell::from(Line::from(tr!("Tiempo")).alignment(Alignment::Right)));
         }
+
+        let title = title_width.saturating_sub(2) as usize;
+        let playing = self.current_playback.as_ref().and_then(|p| p.item.as_ref()).map(PlayableItem::id);
+        let rows: Vec<Row> = rows
+            .into_iter()
+            .map(|(number, row)| {
+                let number_width = number_width as usize - 1 - if marks { 2 } else { 0 };
+                // Lo que suena lleva ▶ en lugar del número y el título resaltado
+                let is_playing = match row {
+                    TrackRow::Track(track) => track.is_playable() && Some(track.id.as_str()) == playing,
+                    TrackRow::Episode(episode) => Some(episode.id.as_str()) == playing,
+                    TrackRow::Unavailable => false,
+                };
+                let mut number_cell = vec![if is_playing {
+                    Span::styled(
+                        format!("{:>1$} ", self.glyphs("▶"), number_width),
+                        Style::default().fg(self.theme.accent).add_modifier(Modifier::BOLD),
+                    )
+                } else {
+                    Span::styled(format!("{:>1$}.", number, number_width), Style::default().fg(self.theme.title))
+                }];
+                let track = match row {
+                    TrackRow::Track(track) => track,
+                    TrackRow::Episode(episode) => {
+                        // Como en el Reproductor: el programa en lugar del artista
+                        number_cell.insert(0, Span::raw(if marks { "  " } else { "" }));
+                        let title_style = Style::default().fg(if is_playing { self.theme.accent } else { self.theme.text }).add_modifier(Modifier::BOLD);
+                        let mut cells = vec![
+                            TableCell::from(Line::from(number_cell)),
+                            TableCell::from(Line::from(vec![
+                                Span::styled(self.glyphs("🎙️ ").into_owned(), Style::default().fg(self.theme.accent)),
+                                Span::styled(truncate(&episode.name, title), title_style),
+                            ])),
+                            TableCell::from(Span::styled(truncate(&episode.show.name, artist_width as usize).into_owned(), Style::default().fg(self.theme.info))),
+                        ];
+                        if show_album {
+                            cells.push(TableCell::from(Span::styled(truncate(&episode.show.publisher, album_width as usize), Style::default().fg(self.theme.detail))));
+                        }
+                        if show_popularity {
+                            cells.push(TableCell::from(""));
+                        }
+                        if show_duration {
+                            let duration = Line::from(Self::format_duration(episode.duration_ms)).alignment(Alignment::Right);
+                            cells.push(TableCell::from(duration.style(Style::default().fg(self.theme.secondary))));
+                        }
+                        return Row::new(cells);
+                    }
+                    TrackRow::Unavailable => {
+                        return Row::new(vec![
+                            TableCell::from(Line::from(number_cell)),
+                            TableCell::from(Span::styled(truncate(tr!("(no disponible)"), title_width as usize), Style::default().fg(self.theme.dim))),
+                        ]);
+                    }
+                };
+                number_cell.insert(0, self.mark_span(track));
+                let artists = track.artists.iter().map(|a| a.name.as_str()).collect::<Vec<_>>().join(", ");
+                // Archivos locales: se muestran atenuados porque no se pueden reproducir ni encolar
+                let local = !track.is_playable();
+                let (icon, title_style, artist_style, album_style) = if local {
+                    let dim = Style::default().fg(self.theme.dim);
+                    (self.glyphs("📁 "), dim, dim, dim)
+                } else {
+                    let saved = self.saved_tracks.get(&track.id).copied().unwrap_or(false);
+                    (
+                        self.glyphs(if saved { "♥ " } else { "  " }),
+                        Style::default().fg(if is_playing { self.theme.accent } else { self.theme.text }).add_modifier(Modifier::BOLD),
+                        Style::default().fg(self.theme.info),
+                        Style::default().fg(self.theme.detail),
+                    )
+                };
+                let album = if local { tr!("(archivo local)") } else { track.album.name.as_str() };
+                let mut title_cell = vec![Span::styled(icon.into_owned(), Style::default().fg(self.theme.accent))];
+                if track.explicit {
+                    title_cell.push(Span::styled(truncate(&track.name, title.saturating_sub(2)), title_style));
+                    title_cell.push(Span::raw(" "));
+                    title_cell.push(Span::styled("E", Style::default().fg(self.theme.secondary).add_modifier(Modifier::REVERSED)));
+                } else {
+                    title_cell.push(Span::styled(truncate(&track.name, title), title_style));
+                }
+                let mut cells = vec![
+                    TableCell::from(Line::from(number_cell)),
+                    TableCell::from(Line::from(title_cell)),
+                    TableCell::from(Span::styled(truncate(&artists, artist_width as usize).into_owned(), artist_style)),
+                ];
+                if show_album {
+                    cells.push(TableCell::from(Span::styled(truncate(album, album_width as usize), album_style)));
+                }
+                if show_popularity {
+                    let meter = if local { String::new() } else { self.popularity_meter(track.popularity) };
+                    cells.push(TableCell::from(Span::styled(meter, Style::default().fg(self.theme.gauge))));
+                }
+                if show_duration {
+                    let duration = Line::from(Self::format_duration(track.duration_ms)).alignment(Alignment::Right);
+                    cells.push(TableCell::from(duration.style(Style::default().fg(self.theme.secondary))));
+                }
+                Row::new(cells)
+            })
+            .collect();
+
+        let table = Table::new(rows, widths)
+            .header(Row::new(header).style(Style::default().fg(self.theme.title).add_modifier(Modifier::BOLD)))
+            .block(block)
+            .column_spacing(1)
+            .highlight_style(self.theme.highlight())
+            .highlight_symbol(self.selection_symbol());
+
+        let Some(state) = state else {
+            f.render_widget(table, area);
+            return;
+        };
+        let mut table_state = TableState::default().with_selected(state.selected()).with_offset(state.offset());
+        f.render_stateful_widget(table, area, &mut table_state);
+        // El ratón cuenta las filas desde debajo de la cabecera
+        let rows_area = Rect { y: area.y + 1, height: area.height.saturating_sub(1), ..area };
+        self.list_area.set(Some((rows_area, table_state.offset())));
+    }
+
+    /// Popularidad (0-100) como barra de `POPULARITY_WIDTH` celdas
+    fn popularity_meter(&self, popularity: i32) -> String {
+        let filled = ((popularity.clamp(0, 100) as usize * POPULARITY_WIDTH as usize) + 50) / 100;
+        let meter = format!("{}{}", "▮".repeat(filled), "▯".repeat(POPULARITY_WIDTH as usize - filled));
+        self.glyphs(&meter).into_owned()
+    }
+
+    /// Línea del filtro en lugar del pie, para no tapar la lista que se está filtrando
+    fn render_filter_prompt(&self, f: &mut Frame, area: Rect) {
+        let text = self.current_filter_list().and_then(|list| self.filters.get(&list)).map_or("", String::as_str);
+        let prompt = Paragraph::new(Line::from(vec![
+            Span::styled(text, Style::default().fg(self.theme.text)),
+            Span::styled("█", Style::default().fg(self.theme.accent)),
+        ]))
+        .block(Block::default().title(tr!("Filtrar (Enter: aplicar | Esc: quitar)")).borders(Borders::ALL));
+        f.render_widget(prompt, area);
     }
 
     fn render_footer(&self, f: &mut Frame, area: Rect) {
         let footer_text = if let Some(ref error) = self.error_message {
             vec![Line::from(vec![
-                Span::styled("❌ Error: ", Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
-                Span::styled(error, Style::default().fg(Color::Red)),
+                Span::styled(self.glyphs(tr!("❌ Error: ")), Style::default().fg(self.theme.error).add_modifier(Modifier::BOLD)),
+                Span::styled(self.glyphs(error), Style::default().fg(self.theme.error)),
             ])]
         } else if let Some(ref success) = self.success_message {
             vec![Line::from(vec![
-                Span::styled("✅ ", Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
-                Span::styled(success, Style::default().fg(Color::Green)),
+                Span::styled(self.glyphs("✅ "), Style::default().fg(self.theme.success).add_modifier(Modifier::BOLD)),
+                Span::styled(self.glyphs(success), Style::default().fg(self.theme.success)),
             ])]
         } else {
             vec![Line::from(vec![
-                Span::styled("Estado: ", Style::default().fg(Color::Cyan)),
-                Span::styled("Listo", Style::default().fg(Color::Green)),
-                Span::styled(" | ", Style::default().fg(Color::Gray)),
+                Span::styled(tr!("Estado: "), Style::default().fg(self.theme.info)),
+                Span::styled(tr!("Listo"), Style::default().fg(self.theme.success)),
+                Span::styled(" | ", Style::default().fg(self.theme.secondary)),
                 Span::styled(
-                    format!("Actualizado: {:.1}s", self.last_update.elapsed().as_secs_f32()),
-                    Style::default().fg(Color::Gray),
+                    tr!("Actualizado: {}s", format!("{:.1}", self.last_update.elapsed().as_secs_f32())),
+                    Style::default().fg(self.theme.secondary),
                 ),
             ])]
         };
@@ -886,18 +6930,69 @@ impl App {
         f.render_widget(Clear, popup_area);
 
         let input_text = if self.search_input.is_empty() {
-            "Escribe para buscar..."
+            tr!("Escribe para buscar...")
         } else {
             &self.search_input
         };
 
         let input = Paragraph::new(input_text)
-            .style(Style::default().fg(if self.search_input.is_empty() { Color::Gray } else { Color::White }))
-            .block(Block::default().title("Buscar Canciones").borders(Borders::ALL));
+            .style(Style::default().fg(if self.search_input.is_empty() { self.theme.secondary } else { self.theme.text }))
+            .block(Block::default().title(tr!("Buscar (Tab: abrir en {})", Self::search_type_label(self.search_type))).borders(Borders::ALL));
+
+        f.render_widget(input, popup_area);
+    }
+
+    fn render_command_popup(&self, f: &mut Frame) {
+        let popup_area = Self::centered_rect(60, 20, f.size());
+        f.render_widget(Clear, popup_area);
+
+        let input = Paragraph::new(format!(":{}", self.command_input))
+            .style(Style::default().fg(self.theme.text))
+            .block(Block::default().title(tr!("Orden (theme <nombre>)")).borders(Borders::ALL));
 
         f.render_widget(input, popup_area);
     }
 
+    fn render_launcher_popup(&self, f: &mut Frame) {
+        let popup_area = Self::centered_rect(60, 60, f.size());
+        f.render_widget(Clear, popup_area);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(3), Constraint::Min(0)])
+            .split(popup_area);
+
+        let input = Paragraph::new(format!("{}█", self.launcher_input))
+            .style(Style::default().fg(self.theme.text))
+            .block(Block::default().title(tr!("Ir a (Enter: abrir | Tab: reproducir | Esc: cerrar)")).borders(Borders::ALL));
+        f.render_widget(input, chunks[0]);
+
+        let items: Vec<ListItem> = self
+            .launcher_results
+            .iter()
+            .map(|&item| {
+                let (icon, name, detail) = self.launcher_entry(item);
+                let mut spans = vec![
+                    Span::styled(self.glyphs(icon).into_owned(), Style::default().fg(self.theme.accent)),
+                    Span::styled(name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+                ];
+                if !detail.is_empty() {
+                    spans.push(Span::styled(format!(" - {}", detail), Style::default().fg(self.theme.secondary)));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+        let title = if self.launcher_results.len() == LAUNCHER_LIMIT {
+            tr!("Primeros {} resultados", LAUNCHER_LIMIT)
+        } else {
+            tr!("{} resultados", self.launcher_results.len())
+        };
+        let list = List::new(items)
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .highlight_style(self.theme.highlight())
+            .highlight_symbol(self.selection_symbol());
+        f.render_stateful_widget(list, chunks[1], &mut self.launcher_list_state.clone());
+    }
+
     fn render_volume_popup(&self, f: &mut Frame) {
         let popup_area = Self::centered_rect(40, 15, f.size());
         f.render_widget(Clear, popup_area);
@@ -909,12 +7004,167 @@ impl App {
         };
 
         let input = Paragraph::new(input_text)
-            .style(Style::default().fg(if self.volume_input.is_empty() { Color::Gray } else { Color::White }))
-            .block(Block::default().title("Volumen (%)").borders(Borders::ALL));
+            .style(Style::default().fg(if self.volume_input.is_empty() { self.theme.secondary } else { self.theme.text }))
+            .block(Block::default().title(tr!("Volumen (%)")).borders(Borders::ALL));
+
+        f.render_widget(input, popup_area);
+    }
+
+    fn render_create_playlist_popup(&self, f: &mut Frame) {
+        let popup_area = Self::centered_rect(50, 20, f.size());
+        f.render_widget(Clear, popup_area);
+
+        let name = if self.new_playlist_name.is_empty() {
+            Span::styled(tr!("Nombre de la playlist..."), Style::default().fg(self.theme.secondary))
+        } else {
+            Span::styled(&self.new_playlist_name, Style::default().fg(self.theme.text))
+        };
+        let visibility = if self.new_playlist_public { tr!("Pública") } else { tr!("Privada") };
+
+        let input = Paragraph::new(vec![
+            Line::from(name),
+            Line::from(Span::styled(
+                tr!("Visibilidad: {} (Tab: cambiar)", visibility),
+                Style::default().fg(self.theme.title),
+            )),
+        ])
+        .block(Block::default().title(tr!("Nueva playlist (Enter: crear | Esc: cancelar)")).borders(Borders::ALL));
 
         f.render_widget(input, popup_area);
     }
 
+    fn render_reauth_popup(&self, f: &mut Frame) {
+        let popup_area = Self::centered_rect(70, 40, f.size());
+        f.render_widget(Clear, popup_area);
+
+        let waiting = if self.reauth_callback.is_some() {
+            tr!("Se ha abierto el navegador; la sesión se renovará sola al autorizar.")
+        } else {
+            tr!("Abre la URL en cualquier navegador y autoriza el acceso.")
+        };
+        let input = if self.reauth_input.is_empty() {
+            Span::styled(tr!("URL de redirección o código..."), Style::default().fg(self.theme.secondary))
+        } else {
+            Span::styled(&self.reauth_input, Style::default().fg(self.theme.text))
+        };
+
+        let content = Paragraph::new(vec![
+            Line::from(Span::styled(waiting, Style::default().fg(self.theme.title))),
+            Line::from(""),
+            Line::from(Span::styled(&self.reauth_url, Style::default().fg(self.theme.info))),
+            Line::from(""),
+            Line::from(Span::styled(
+                tr!("O pega aquí la URL a la que te redirigió Spotify:"),
+                Style::default().fg(self.theme.title),
+            )),
+            Line::from(input),
+        ])
+        .wrap(Wrap { trim: false })
+        .block(Block::default().title(self.glyphs(tr!("🔐 Sesión caducada (Enter: confirmar | Esc: cancelar)")).into_owned()).borders(Borders::ALL));
+
+        f.render_widget(content, popup_area);
+    }
+
+    fn render_edit_playlist_popup(&self, f: &mut Frame) {
+        let popup_area = Self::centered_rect(60, 25, f.size());
+        f.render_widget(Clear, popup_area);
+
+        let field_style = |active: bool| {
+            if active {
+                Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)
+            } else {
+                Style::default().fg(self.theme.secondary)
+            }
+        };
+        let cursor = |active: bool| if active { "█" } else { "" };
+
+        let form = Paragraph::new(vec![
+            Line::from(vec![
+                Span::styled(tr!("Nombre: "), Style::default().fg(self.theme.title)),
+                Span::styled(&self.edit_playlist_name, field_style(!self.editing_description)),
+                Span::raw(cursor(!self.editing_description)),
+            ]),
+            Line::from(vec![
+                Span::styled(tr!("Descripción: "), Style::default().fg(self.theme.title)),
+                Span::styled(&self.edit_playlist_description, field_style(self.editing_description)),
+                Span::raw(cursor(self.editing_description)),
+            ]),
+        ])
+        .wrap(Wrap { trim: false })
+        .block(Block::default().title(tr!("Editar playlist (Tab: cambiar campo | Enter: guardar | Esc: cancelar)")).borders(Borders::ALL));
+
+        f.render_widget(form, popup_area);
+    }
+
+    fn render_playlist_picker_popup(&self, f: &mut Frame) {
+        let popup_area = Self::centered_rect(50, 50, f.size());
+        f.render_widget(Clear, popup_area);
+
+        let items: Vec<ListItem> = self
+            .picker_playlists
+            .iter()
+            .enumerate()
+            .map(|(i, playlist)| self.playlist_list_item(i, playlist))
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().title(tr!("Añadir a playlist (Enter: añadir | Esc: cancelar)")).borders(Borders::ALL))
+            .highlight_style(self.theme.highlight())
+            .highlight_symbol(self.selection_symbol());
+
+        f.render_stateful_widget(list, popup_area, &mut self.picker_list_state.clone());
+    }
+
+    fn render_context_menu_popup(&self, f: &mut Frame) {
+        let Some((target, entries)) = self.context_menu.as_ref() else {
+            return;
+        };
+        let popup_area = Self::centered_rect(40, 40, f.size());
+        f.render_widget(Clear, popup_area);
+
+        let items: Vec<ListItem> = entries
+            .iter()
+            .map(|entry| ListItem::new(Span::styled(self.menu_entry_label(target, *entry), Style::default().fg(self.theme.text))))
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().title(tr!("{} (Enter: elegir | Esc: cerrar)", target.name())).borders(Borders::ALL))
+            .highlight_style(self.theme.highlight())
+            .highlight_symbol(self.selection_symbol());
+
+        f.render_stateful_widget(list, popup_area, &mut self.context_menu_state.clone());
+    }
+
+    fn render_devices_popup(&self, f: &mut Frame) {
+        let popup_area = Self::centered_rect(50, 40, f.size());
+        f.render_widget(Clear, popup_area);
+
+        let items: Vec<ListItem> = self
+            .devices
+            .iter()
+            .map(|device| {
+                let mut spans = vec![
+                    Span::styled(&device.name, Style::default().fg(self.theme.text).add_modifier(Modifier::BOLD)),
+                    Span::styled(format!(" ({})", device.device_type), Style::default().fg(self.theme.secondary)),
+                ];
+                if device.is_active {
+                    spans.push(Span::styled(tr!(" • activo"), Style::default().fg(self.theme.success)));
+                }
+                if device.is_restricted {
+                    spans.push(Span::styled(tr!(" • restringido"), Style::default().fg(self.theme.error)));
+                }
+                ListItem::new(Line::from(spans))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().title(tr!("Dispositivos (Enter: transferir | Esc: cerrar)")).borders(Borders::ALL))
+            .highlight_style(self.theme.highlight())
+            .highlight_symbol(self.selection_symbol());
+
+        f.render_stateful_widget(list, popup_area, &mut self.devices_list_state.clone());
+    }
+
     fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
         let popup_layout = Layout::default()
             .direction(Direction::Vertical)
@@ -941,4 +7191,130 @@ impl App {
         let remaining_seconds = seconds % 60;
         format!("{}:{:02}", minutes, remaining_seconds)
     }
-} 
\ No newline at end of file
+}
+
+fn select_previous(state: &mut ListState, len: usize) {
+    if len == 0 {
+        return;
+    }
+    let i = match state.selected() {
+        Some(0) => len - 1,
+        Some(i) => i - 1,
+        None => 0,
+    };
+    state.select(Some(i));
+}
+
+fn select_first_if_none(state: &mut ListState, len: usize) {
+    if state.selected().is_none() && len > 0 {
+        state.select(Some(0));
+    }
+}
+
+fn select_next(state: &mut ListState, len: usize) {
+    if len == 0 {
+        return;
+    }
+    let i = match state.selected() {
+        Some(i) if i + 1 < len => i + 1,
+        Some(_) | None => 0,
+    };
+    state.select(Some(i));
+}
+
+/// Recorta el texto a `width` columnas del terminal, terminando en "…" si no cabe entero
+pub(crate) fn truncate(text: &str, width: usize) -> Cow<'_, str> {
+    if text.width() <= width {
+        return Cow::Borrowed(text);
+    }
+    let mut truncated = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w + 1 > width {
+            break;
+        }
+        truncated.push(c);
+        used += w;
+    }
+    if width > 0 {
+        truncated.push('…');
+    }
+    Cow::Owned(truncated)
+}
+
+/// Mueve la selección `offset` posiciones sin dar la vuelta al llegar a un extremo
+fn move_selection(state: &mut ListState, len: usize, offset: isize) {
+    if len == 0 {
+        return;
+    }
+    let current = state.selected().unwrap_or(0) as isize;
+    let i = current.saturating_add(offset).clamp(0, len as isize - 1);
+    state.select(Some(i as usize));
+}
+
+/// Cada palabra del filtro aparece en el texto con sus letras en orden, aunque no sean seguidas
+/// ("bhmn" encaja con "Bohemian"); sin distinguir mayúsculas
+fn fuzzy_match(filter: &str, text: &str) -> bool {
+    let text = text.to_lowercase();
+    filter.to_lowercase().split_whitespace().all(|word| {
+        let mut chars = text.chars();
+        word.chars().all(|c| chars.any(|t| t == c))
+    })
+}
+
+/// Termina en cuanto hay un evento del terminal esperando a ser leído
+async fn key_pending() {
+    while !crossterm::event::poll(Duration::ZERO).unwrap_or(false) {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
+
+/// URIs de una lista de canciones, para reproducirlas como una lista suelta
+fn track_uris(tracks: &[Track]) -> Vec<String> {
+    tracks.iter().filter_map(Track::uri).collect()
+}
+
+/// Devuelve el terminal a su estado normal (sin modo raw, pantalla alternativa ni ratón)
+fn restore_terminal() -> io::Result<()> {
+    disable_raw_mode()?;
+    execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, cursor::Show)
+}
+
+/// Escucha SIGINT/SIGTERM/SIGHUP y pide al bucle principal que termine ordenadamente
+fn spawn_signal_listener(shutdown: Arc<AtomicBool>) {
+    tokio::spawn(async move {
+        wait_for_shutdown_signal().await;
+        shutdown.store(true, Ordering::SeqCst);
+    });
+}
+
+#[cfg(unix)]
+async fn wait_for_shutdown_signal() {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let (Ok(mut terminate), Ok(mut hangup)) = (signal(SignalKind::terminate()), signal(SignalKind::hangup())) else {
+        let _ = tokio::signal::ctrl_c().await;
+        return;
+    };
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => {}
+        _ = terminate.recv() => {}
+        _ = hangup.recv() => {}
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_shutdown_signal() {
+    let _ = tokio::signal::ctrl_c().await;
+}
+
+/// Restaura el terminal antes de imprimir un panic para que no quede inservible
+fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let _ = restore_terminal();
+        tracing::error!("{}", info);
+        default_hook(info);
+    }));
+}
@@ -0,0 +1,331 @@
+//! Scripts de Lua del usuario: los `*.lua` de `scripts/` en el directorio del config.
+//!
+//! Cada script se ejecuta una vez al arrancar y asigna funciones a teclas con `spotigod.bind`.
+//! Las funciones de la API no llaman a Spotify: apuntan órdenes que la interfaz ejecuta en
+//! orden cuando vuelve el script, así que "añadir a la playlist y saltar" ocurre en ese orden.
+
+use anyhow::{anyhow, Result};
+use mlua::{Function, HookTriggers, Lua, LuaSerdeExt, RegistryKey, SerializeOptions, Table, Value, Variadic};
+use spotigod_core::config::Config;
+use spotigod_core::spotify::Track;
+use spotigod_core::status::PlaybackStatus;
+use spotigod_core::tr;
+use std::cell::{Cell, RefCell};
+use std::collections::VecDeque;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use super::Keymap;
+
+/// Orden de un script para la interfaz
+pub(super) enum ScriptCommand {
+    PlayPause,
+    /// Reanudar, o reproducir la URI (canción, álbum, playlist...)
+    Play(Option<String>),
+    Pause,
+    Next,
+    Previous,
+    Volume(u8),
+    Queue(String),
+    /// `playlist` es el nombre o el id de una playlist de la biblioteca
+    AddToPlaylist { playlist: String, uri: String },
+    /// Busca canciones y llama a `callback` con los resultados
+    Search { query: String, callback: RegistryKey },
+    Message(String),
+    Error(String),
+}
+
+/// Órdenes de una llamada que aún no se han ejecutado. Las que pasan por Spotify se encargan
+/// al trabajador y la siguiente espera a su respuesta, para que se cumplan en orden
+#[derive(Default)]
+pub(super) struct ScriptRun {
+    pub(super) commands: VecDeque<ScriptCommand>,
+    /// Órdenes ejecutadas, contando las que apuntan las funciones de `spotigod.search`
+    pub(super) executed: usize,
+    /// Orden o búsqueda cuya respuesta se espera para seguir
+    pub(super) waiting: Option<u64>,
+    /// Función de `spotigod.search` que espera los resultados de `waiting`
+    pub(super) search: Option<RegistryKey>,
+}
+
+/// Lo que comparten las funciones de la API mientras corre un script
+#[derive(Default)]
+struct ScriptState {
+    /// Reproducción en el momento de llamar al script
+    current: PlaybackStatus,
+    commands: Vec<ScriptCommand>,
+    /// Tecla, función y script que la asignó, en el orden de `spotigod.bind`
+    bindings: Vec<(String, RegistryKey, String)>,
+}
+
+/// Tiempo que puede correr un script (al cargarse o en cada llamada) antes de detenerlo, para
+/// que un bucle infinito no bloquee la interfaz
+const TIME_LIMIT: Duration = Duration::from_secs(2);
+
+/// Cada cuántas instrucciones de Lua se comprueba `TIME_LIMIT`
+const HOOK_INSTRUCTIONS: u32 = 10_000;
+
+pub struct ScriptEngine {
+    lua: Lua,
+    state: Rc<RefCell<ScriptState>>,
+    /// Hasta cuándo puede seguir el script que está corriendo
+    deadline: Rc<Cell<Option<Instant>>>,
+}
+
+impl ScriptEngine {
+    /// Ejecuta los scripts en orden alfabético y asigna sus teclas en `keymap`. Sin directorio
+    /// `scripts/` no hay nada que cargar
+    pub fn load(keymap: &mut Keymap) -> Result<Self> {
+        let engine = Self {
+            lua: Lua::new(),
+            state: Rc::new(RefCell::new(ScriptState::default())),
+            deadline: Rc::new(Cell::new(None)),
+        };
+        engine.register_api().map_err(|e| anyhow!(error_message(&e)))?;
+        let deadline = engine.deadline.clone();
+        engine.lua.set_hook(HookTriggers::new().every_nth_instruction(HOOK_INSTRUCTIONS), move |_, _| {
+            match deadline.get() {
+                Some(deadline) if Instant::now() > deadline => {
+                    Err(mlua::Error::runtime(tr!("El script ha tardado más de {} s y se ha detenido", TIME_LIMIT.as_secs())))
+                }
+                _ => Ok(()),
+            }
+        });
+
+        let dir = Config::config_dir()?.join("scripts");
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            return Ok(engine);
+        };
+        let mut paths: Vec<_> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "lua"))
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let name = path.file_name().unwrap_or_default().to_string_lossy().to_string();
+            let source = std::fs::read_to_string(&path).map_err(|e| anyhow!(tr!("No se pudo leer el script {}: {}", name, e)))?;
+            engine.deadline.set(Some(Instant::now() + TIME_LIMIT));
+            let loaded = engine.lua.load(&source).set_name(format!("@{}", name)).exec();
+            engine.deadline.set(None);
+            // Lo que apunte al cargarse (p. ej. un `print`) no tiene a quién mostrarse
+            let commands = std::mem::take(&mut engine.state.borrow_mut().commands);
+            engine.discard(commands);
+            loaded.map_err(|e| anyhow!(tr!("Error en el script {}: {}", name, error_message(&e))))?;
+        }
+
+        for (index, (key, _, script)) in engine.state.borrow().bindings.iter().enumerate() {
+            keymap.bind_script(key, index).map_err(|e| anyhow!("{}: spotigod.bind: {}", script, e))?;
+        }
+        Ok(engine)
+    }
+
+    /// Script y tecla de una función, para el registro ("gym.lua g")
+    pub(super) fn name(&self, index: usize) -> String {
+        let state = self.state.borrow();
+        let (key, _, script) = &state.bindings[index];
+        format!("{} {}", script, key)
+    }
+
+    /// Llama a la función asignada a una tecla y devuelve las órdenes que ha apuntado
+    pub(super) fn call(&self, index: usize, current: PlaybackStatus) -> Result<Vec<ScriptCommand>, String> {
+        self.run(current, |lua, state| {
+            let function: Function = lua.registry_value(&state.borrow().bindings[index].1)?;
+            function.call::<_, ()>(())
+        })
+    }
+
+    /// Llama a la función de `spotigod.search` con las canciones encontradas
+    pub(super) fn call_search(
+        &self,
+        callback: RegistryKey,
+        tracks: &[Track],
+        current: PlaybackStatus,
+    ) -> Result<Vec<ScriptCommand>, String> {
+        let result = self.run(current, |lua, _| {
+            let function: Function = lua.registry_value(&callback)?;
+            let results = lua.create_table()?;
+            for (i, track) in tracks.iter().enumerate() {
+                let table = status_table(lua, &PlaybackStatus::for_track(track))?;
+                table.set("uri", track.uri())?;
+                results.set(i + 1, table)?;
+            }
+            function.call::<_, ()>(results)
+        });
+        self.release(callback);
+        result
+    }
+
+    fn run(
+        &self,
+        current: PlaybackStatus,
+        call: impl FnOnce(&Lua, &Rc<RefCell<ScriptState>>) -> mlua::Result<()>,
+    ) -> Result<Vec<ScriptCommand>, String> {
+        self.state.borrow_mut().current = current;
+        self.deadline.set(Some(Instant::now() + TIME_LIMIT));
+        let result = call(&self.lua, &self.state);
+        self.deadline.set(None);
+        let commands = std::mem::take(&mut self.state.borrow_mut().commands);
+        match result {
+            Ok(()) => Ok(commands),
+            Err(e) => {
+                self.discard(commands);
+                Err(error_message(&e))
+            }
+        }
+    }
+
+    /// Libera las funciones de `spotigod.search` de las órdenes que no se van a ejecutar
+    pub(super) fn discard(&self, commands: impl IntoIterator<Item = ScriptCommand>) {
+        for command in commands {
+            if let ScriptCommand::Search { callback, .. } = command {
+                self.release(callback);
+            }
+        }
+    }
+
+    /// Deja a medias una llamada, liberando las funciones de `spotigod.search` que quedaban
+    pub(super) fn abort(&self, run: ScriptRun) {
+        if let Some(callback) = run.search {
+            self.release(callback);
+        }
+        self.discard(run.commands);
+    }
+
+    /// Libera una función de `spotigod.search` que ya no se va a llamar
+    pub(super) fn release(&self, callback: RegistryKey) {
+        let _ = self.lua.remove_registry_value(callback);
+    }
+
+    /// Crea la tabla global `spotigod` y cambia `print` para que escriba en el pie
+    fn register_api(&self) -> mlua::Result<()> {
+        let lua = &self.lua;
+        let api = lua.create_table()?;
+
+        let state = self.state.clone();
+        api.set(
+            "bind",
+            lua.create_function(move |lua, (key, function): (String, Function)| {
+                let script = lua.inspect_stack(1).and_then(|debug| debug.source().short_src.map(|s| s.to_string()));
+                let function = lua.create_registry_value(function)?;
+                state.borrow_mut().bindings.push((key, function, script.unwrap_or_default()));
+                Ok(())
+            })?,
+        )?;
+
+        let state = self.state.clone();
+        api.set(
+            "current",
+            lua.create_function(move |lua, ()| {
+                let state = state.borrow();
+                if !state.current.active {
+                    return Ok(Value::Nil);
+                }
+                let table = status_table(lua, &state.current)?;
+                table.set("uri", current_uri(&state.current))?;
+                Ok(Value::Table(table))
+            })?,
+        )?;
+
+        for name in ["play_pause", "pause", "next", "previous"] {
+            let state = self.state.clone();
+            let function = lua.create_function(move |_, ()| {
+                let command = match name {
+                    "play_pause" => ScriptCommand::PlayPause,
+                    "pause" => ScriptCommand::Pause,
+                    "next" => ScriptCommand::Next,
+                    _ => ScriptCommand::Previous,
+                };
+                push(&state, command)
+            })?;
+            api.set(name, function)?;
+        }
+
+        let state = self.state.clone();
+        api.set("play", lua.create_function(move |_, uri: Option<String>| push(&state, ScriptCommand::Play(uri)))?)?;
+
+        let state = self.state.clone();
+        api.set(
+            "volume",
+            lua.create_function(move |_, volume: i64| push(&state, ScriptCommand::Volume(volume.clamp(0, 100) as u8)))?,
+        )?;
+
+        let state = self.state.clone();
+        api.set(
+            "queue",
+            lua.create_function(move |_, uri: Option<String>| {
+                let uri = uri_or_current(&state, uri)?;
+                push(&state, ScriptCommand::Queue(uri))
+            })?,
+        )?;
+
+        let state = self.state.clone();
+        api.set(
+            "add_to_playlist",
+            lua.create_function(move |_, (playlist, uri): (String, Option<String>)| {
+                let uri = uri_or_current(&state, uri)?;
+                push(&state, ScriptCommand::AddToPlaylist { playlist, uri })
+            })?,
+        )?;
+
+        let state = self.state.clone();
+        api.set(
+            "search",
+            lua.create_function(move |lua, (query, callback): (String, Function)| {
+                let callback = lua.create_registry_value(callback)?;
+                push(&state, ScriptCommand::Search { query, callback })
+            })?,
+        )?;
+
+        let state = self.state.clone();
+        api.set("message", lua.create_function(move |_, text: String| push(&state, ScriptCommand::Message(text)))?)?;
+
+        let state = self.state.clone();
+        api.set("error", lua.create_function(move |_, text: String| push(&state, ScriptCommand::Error(text)))?)?;
+
+        let state = self.state.clone();
+        let print = lua.create_function(move |lua, values: Variadic<Value>| {
+            let tostring: Function = lua.globals().get("tostring")?;
+            let texts = values.into_iter().map(|value| tostring.call::<_, String>(value)).collect::<mlua::Result<Vec<_>>>()?;
+            push(&state, ScriptCommand::Message(texts.join("\t")))
+        })?;
+
+        lua.globals().set("spotigod", api)?;
+        lua.globals().set("print", print)?;
+        Ok(())
+    }
+}
+
+fn push(state: &Rc<RefCell<ScriptState>>, command: ScriptCommand) -> mlua::Result<()> {
+    state.borrow_mut().commands.push(command);
+    Ok(())
+}
+
+/// La URI indicada o, si falta, la de lo que está sonando
+fn uri_or_current(state: &Rc<RefCell<ScriptState>>, uri: Option<String>) -> mlua::Result<String> {
+    uri.or_else(|| current_uri(&state.borrow().current))
+        .ok_or_else(|| mlua::Error::runtime(tr!("No hay canción reproduciéndose")))
+}
+
+/// `spotify:track:...` o `spotify:episode:...`; los archivos locales no tienen
+fn current_uri(status: &PlaybackStatus) -> Option<String> {
+    Some(format!("spotify:{}:{}", status.kind.as_deref()?, status.id.as_deref()?))
+}
+
+/// Los campos de `spotigod status --json`, con `nil` en los que no se conocen
+fn status_table<'lua>(lua: &'lua Lua, status: &PlaybackStatus) -> mlua::Result<Table<'lua>> {
+    let options = SerializeOptions::new().serialize_none_to_null(false).serialize_unit_to_null(false);
+    match lua.to_value_with(status, options)? {
+        Value::Table(table) => Ok(table),
+        _ => lua.create_table(),
+    }
+}
+
+/// Primera línea del error, sin la traza de Lua
+fn error_message(error: &mlua::Error) -> String {
+    match error {
+        mlua::Error::CallbackError { cause, .. } => error_message(cause),
+        mlua::Error::RuntimeError(message) => message.lines().next().unwrap_or_default().to_string(),
+        error => error.to_string().lines().next().unwrap_or_default().to_string(),
+    }
+}
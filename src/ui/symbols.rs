@@ -0,0 +1,62 @@
+use std::borrow::Cow;
+
+/// Equivalentes ASCII de los emoji y símbolos de la interfaz. Las secuencias con selector de
+/// variación (U+FE0F) van antes que su carácter base para sustituirse enteras
+const ASCII_GLYPHS: &[(&str, &str)] = &[
+    ("🎙️", "~"),
+    ("🎛️", ">"),
+    ("⏭️", ">>"),
+    ("🎵", "*"),
+    ("🎶", "*"),
+    ("🔍", "?"),
+    ("📋", "="),
+    ("⏭", ">>"),
+    ("📊", "#"),
+    ("✨", "+"),
+    ("🎙", "~"),
+    ("🧭", "^"),
+    ("🎤", "@"),
+    ("💿", "o"),
+    ("📴", "x"),
+    ("👤", "@"),
+    ("📻", "~"),
+    ("📅", "-"),
+    ("🎛", ">"),
+    ("🔀", "%"),
+    ("🔁", "&"),
+    ("📁", "L"),
+    ("❌", "X"),
+    ("✅", "OK"),
+    ("🔐", "!"),
+    ("📜", "!"),
+    ("⏳", "..."),
+    ("🔈", "!"),
+    ("🔇", "(mute)"),
+    ("►", ">"),
+    ("▶", ">"),
+    ("⏸", "||"),
+    ("●", "*"),
+    ("♥", "*"),
+    ("♡", "-"),
+    ("›", ">"),
+    ("▸", ">"),
+    ("▾", "v"),
+    ("│", "|"),
+    ("▮", "#"),
+    ("▯", "."),
+    ("\u{FE0F}", ""),
+];
+
+/// El texto tal cual o, en modo ASCII, con los emoji cambiados por sus equivalentes
+pub fn glyphs(text: &str, ascii: bool) -> Cow<'_, str> {
+    if !ascii || text.is_ascii() {
+        return Cow::Borrowed(text);
+    }
+    let mut text = text.to_string();
+    for (glyph, replacement) in ASCII_GLYPHS {
+        if text.contains(glyph) {
+            text = text.replace(glyph, replacement);
+        }
+    }
+    Cow::Owned(text)
+}
@@ -0,0 +1,251 @@
+use super::*;
+use serde_json::json;
+use std::sync::Once;
+use tokio::sync::mpsc::UnboundedReceiver;
+
+/// Spotify simulado: una cola y una sola playlist, que se reordena de verdad
+#[derive(Default)]
+struct MockSpotify {
+    queue: Vec<Track>,
+    playlist: Vec<Track>,
+    fail_reorder: bool,
+    /// (range_start, insert_before) de cada reordenación recibida
+    reorders: Vec<(usize, usize)>,
+}
+
+fn unsupported<T>() -> ApiResult<T> {
+    Err(SpotifyError::ApiError { status: 501, message: "no simulado".to_string() })
+}
+
+impl SpotifyApi for MockSpotify {
+    fn begin_reauthorization(&mut self) -> Reauthorization {
+        Reauthorization { url: String::new(), callback: None, callback_error: None }
+    }
+    async fn complete_reauthorization(&mut self, _input: &str) -> ApiResult<()> { unsupported() }
+    async fn save_session(&mut self) -> ApiResult<()> { Ok(()) }
+    async fn logout(&mut self) -> ApiResult<()> { unsupported() }
+    fn take_auto_selected_device(&mut self) -> Option<String> { None }
+    fn take_rate_limit_waits(&mut self) -> Vec<u64> { Vec::new() }
+    fn take_api_calls(&mut self) -> Vec<ApiCall> { Vec::new() }
+
+    async fn get_current_playback(&mut self) -> ApiResult<Option<PlaybackState>> { Ok(None) }
+    async fn play(&mut self) -> ApiResult<()> { unsupported() }
+    async fn pause(&mut self) -> ApiResult<()> { unsupported() }
+    async fn next_track(&mut self) -> ApiResult<()> { unsupported() }
+    async fn previous_track(&mut self) -> ApiResult<()> { unsupported() }
+    async fn set_volume(&mut self, _volume: u8) -> ApiResult<()> { unsupported() }
+    async fn seek(&mut self, _position_ms: u64) -> ApiResult<()> { unsupported() }
+    async fn get_devices(&mut self) -> ApiResult<Vec<Device>> { Ok(Vec::new()) }
+    async fn transfer_playback(&mut self, _device_id: &str, _play: bool) -> ApiResult<()> { unsupported() }
+    async fn search(&mut self, _query: &str, _types: &[SearchType], _limit: u8, _offset: u32) -> ApiResult<SearchResults> { unsupported() }
+    async fn add_to_queue(&mut self, _uri: &str) -> ApiResult<()> { unsupported() }
+    async fn get_queue(&mut self) -> ApiResult<QueueResponse> {
+        Ok(QueueResponse { currently_playing: None, queue: self.queue.iter().cloned().map(PlayableItem::Track).collect() })
+    }
+    async fn get_user_playlists(&mut self, _limit: u8, _offset: u32) -> ApiResult<PlaylistsResponse> { unsupported() }
+    async fn get_saved_tracks(&mut self, _limit: u8, _offset: u32) -> ApiResult<SavedTracksResponse> { unsupported() }
+    async fn get_saved_albums(&mut self, _limit: u8, _offset: u32) -> ApiResult<SavedAlbumsResponse> { unsupported() }
+    async fn get_top_tracks(&mut self, _time_range: TimeRange, _limit: u8) -> ApiResult<Vec<Track>> { unsupported() }
+    async fn get_top_artists(&mut self, _time_range: TimeRange, _limit: u8) -> ApiResult<Vec<FullArtist>> { unsupported() }
+    async fn get_followed_artists(&mut self) -> ApiResult<Vec<FullArtist>> { unsupported() }
+    async fn get_artist_top_tracks(&mut self, _artist_id: &str) -> ApiResult<Vec<Track>> { unsupported() }
+    async fn get_artist_albums(&mut self, _artist_id: &str, _limit: u8) -> ApiResult<Vec<Album>> { unsupported() }
+    async fn get_related_artists(&mut self, _artist_id: &str) -> ApiResult<Vec<FullArtist>> { unsupported() }
+    async fn get_recommendations(&mut self, _seed_tracks: &[String], _seed_artists: &[String], _limit: u8) -> ApiResult<Vec<Track>> { unsupported() }
+    async fn get_audio_analysis(&mut self, _track_id: &str) -> ApiResult<AudioAnalysis> { unsupported() }
+    async fn get_new_releases(&mut self, _limit: u8, _offset: u32) -> ApiResult<AlbumSearchResult> { unsupported() }
+    async fn get_featured_playlists(&mut self, _limit: u8) -> ApiResult<(Option<String>, Vec<Playlist>)> { unsupported() }
+    async fn get_categories(&mut self, _limit: u8) -> ApiResult<Vec<Category>> { unsupported() }
+    async fn get_category_playlists(&mut self, _category_id: &str, _limit: u8) -> ApiResult<Vec<Playlist>> { unsupported() }
+    async fn get_user_profile(&mut self) -> ApiResult<UserProfile> { Ok(profile()) }
+    async fn create_playlist(&mut self, _user_id: &str, _name: &str, _public: bool) -> ApiResult<Playlist> { unsupported() }
+    async fn add_tracks_to_playlist(&mut self, _playlist_id: &str, _uris: &[String]) -> ApiResult<()> { unsupported() }
+    async fn get_playlist_tracks(&mut self, _playlist_id: &str, limit: u8, offset: u32) -> ApiResult<PlaylistTracksResponse> {
+        let items = self.playlist.iter().skip(offset as usize).take(limit as usize);
+        Ok(PlaylistTracksResponse {
+            href: String::new(),
+            items: items.map(|track| PlaylistTrackItem { added_at: String::new(), track: Some(track.clone()) }).collect(),
+            limit: limit as i32,
+            next: None,
+            offset: offset as i32,
+            previous: None,
+            total: self.playlist.len() as i32,
+        })
+    }
+    async fn get_album_tracks(&mut self, _album_id: &str, _limit: u8, _offset: u32) -> ApiResult<AlbumTracksResponse> { unsupported() }
+    async fn reorder_playlist_tracks(&mut self, _playlist_id: &str, range_start: usize, insert_before: usize) -> ApiResult<String> {
+        if self.fail_reorder {
+            return Err(SpotifyError::ApiError { status: 500, message: "fallo simulado".to_string() });
+        }
+        self.reorders.push((range_start, insert_before));
+        let track = self.playlist.remove(range_start);
+        let target = if insert_before > range_start { insert_before - 1 } else { insert_before };
+        self.playlist.insert(target, track);
+        Ok("snapshot".to_string())
+    }
+    async fn remove_playlist_tracks(&mut self, _playlist_id: &str, _uris: &[String]) -> ApiResult<()> { unsupported() }
+    async fn update_playlist_details(&mut self, _playlist_id: &str, _name: &str, _description: &str) -> ApiResult<()> { unsupported() }
+    async fn follow_playlist(&mut self, _playlist_id: &str) -> ApiResult<()> { unsupported() }
+    async fn save_tracks(&mut self, _ids: &[String]) -> ApiResult<()> { unsupported() }
+    async fn remove_saved_tracks(&mut self, _ids: &[String]) -> ApiResult<()> { unsupported() }
+    async fn check_saved_tracks(&mut self, ids: &[String]) -> ApiResult<Vec<bool>> { Ok(vec![false; ids.len()]) }
+    async fn get_saved_episodes(&mut self) -> ApiResult<Vec<Episode>> { unsupported() }
+    async fn save_episodes(&mut self, _ids: &[String]) -> ApiResult<()> { unsupported() }
+    async fn remove_saved_episodes(&mut self, _ids: &[String]) -> ApiResult<()> { unsupported() }
+    async fn unfollow_playlist(&mut self, _playlist_id: &str) -> ApiResult<()> { unsupported() }
+    async fn start_playback(&mut self, _context: PlaybackContext, _offset: Option<PlaybackOffset>, _position_ms: Option<i64>) -> ApiResult<()> { unsupported() }
+    async fn toggle_shuffle(&mut self) -> ApiResult<()> { unsupported() }
+    async fn toggle_repeat(&mut self) -> ApiResult<()> { unsupported() }
+}
+
+fn track(n: usize) -> Track {
+    serde_json::from_value(json!({
+        "id": format!("t{}", n),
+        "name": format!("Canción {}", n),
+        "artists": [{ "id": "a1", "name": "Artista", "external_urls": {} }],
+        "album": { "id": "al1", "name": "Álbum", "artists": [], "external_urls": {} },
+        "duration_ms": 180_000,
+        "external_urls": {}
+    }))
+    .unwrap()
+}
+
+fn tracks(count: usize) -> Vec<Track> {
+    (1..=count).map(track).collect()
+}
+
+fn profile() -> UserProfile {
+    serde_json::from_value(json!({
+        "id": "yo", "display_name": null, "email": null, "country": "ES",
+        "followers": { "href": null, "total": 0 }, "images": [], "product": "premium"
+    }))
+    .unwrap()
+}
+
+fn playlist(total: usize) -> Playlist {
+    serde_json::from_value(json!({
+        "id": "p1", "name": "Mi playlist", "description": null, "images": [], "public": false,
+        "owner": { "id": "yo", "display_name": null, "external_urls": {} },
+        "tracks": { "href": "", "total": total },
+        "external_urls": {}
+    }))
+    .unwrap()
+}
+
+/// App con el trabajador en marcha sobre el Spotify simulado. La biblioteca, el estado y los
+/// scripts se leen de un directorio temporal para no tocar los del usuario
+fn app(spotify: MockSpotify) -> (App<MockSpotify>, UnboundedReceiver<Response>) {
+    static XDG: Once = Once::new();
+    XDG.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("spotigod-tests-{}", std::process::id()));
+        for var in ["XDG_CONFIG_HOME", "XDG_STATE_HOME", "XDG_CACHE_HOME"] {
+            std::env::set_var(var, &dir);
+        }
+    });
+    let config: Config = serde_json::from_value(json!({
+        "client_id": "prueba",
+        "redirect_uri": "http://127.0.0.1:8888/callback",
+        "lyrics_provider": "none"
+    }))
+    .unwrap();
+    let mut keymap = Keymap::from_config(&config.keymap, config.keymap_preset).unwrap();
+    let scripts = ScriptEngine::load(&mut keymap).unwrap();
+    let mut app = App::new(spotify, keymap, scripts, Theme::default(), &config);
+    let (requests, responses) = worker::spawn(app.spotify_client.clone(), None, Vec::new());
+    app.requests = Some(requests);
+    (app, responses)
+}
+
+/// Aplica respuestas hasta que el trabajador no tenga nada más pendiente
+async fn settle(app: &mut App<MockSpotify>, responses: &mut UnboundedReceiver<Response>) {
+    while let Ok(Some(response)) = tokio::time::timeout(Duration::from_millis(50), responses.recv()).await {
+        app.handle_response(response);
+    }
+}
+
+fn names(app: &App<MockSpotify>) -> Vec<String> {
+    app.playlist_items.iter().filter_map(|item| item.track.as_ref()).map(|track| track.name.clone()).collect()
+}
+
+#[tokio::test(start_paused = true)]
+async fn loads_the_queue() {
+    LocalSet::new()
+        .run_until(async {
+            let (mut app, mut responses) = app(MockSpotify { queue: tracks(2), ..Default::default() });
+            app.load_queue();
+            assert!(app.loading.contains(&Loading::Queue));
+            settle(&mut app, &mut responses).await;
+
+            assert!(!app.loading.contains(&Loading::Queue));
+            let queued: Vec<&str> = app.queue.iter().filter_map(PlayableItem::track).map(|t| t.name.as_str()).collect();
+            assert_eq!(queued, ["Canción 1", "Canción 2"]);
+            assert_eq!(app.queue_list_state.selected(), Some(0));
+            assert_eq!(app.success_message.as_deref(), Some("2 canciones en cola"));
+            assert_eq!(app.saved_tracks.get("t1"), Some(&false));
+        })
+        .await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn loads_playlist_pages() {
+    LocalSet::new()
+        .run_until(async {
+            let (mut app, mut responses) = app(MockSpotify { playlist: tracks(150), ..Default::default() });
+            app.show_playlist(playlist(150));
+            settle(&mut app, &mut responses).await;
+            assert_eq!(app.playlist_items.len(), 100);
+            assert_eq!(app.playlist_items_total, 150);
+            assert_eq!(app.playlist_items_list_state.selected(), Some(0));
+
+            app.load_playlist_items();
+            // Mientras llega la página no se pide otra vez la misma
+            app.load_playlist_items();
+            settle(&mut app, &mut responses).await;
+            assert_eq!(names(&app), tracks(150).into_iter().map(|t| t.name).collect::<Vec<_>>());
+        })
+        .await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn moves_playlist_tracks_in_order() {
+    LocalSet::new()
+        .run_until(async {
+            let (mut app, mut responses) = app(MockSpotify { playlist: tracks(3), ..Default::default() });
+            app.user_profile = Some(profile());
+            app.show_playlist(playlist(3));
+            settle(&mut app, &mut responses).await;
+
+            // Dos pulsaciones seguidas, sin esperar a Spotify
+            app.move_selected_playlist_item(false);
+            app.move_selected_playlist_item(false);
+            assert_eq!(names(&app), ["Canción 2", "Canción 3", "Canción 1"]);
+            assert_eq!(app.playlist_items_list_state.selected(), Some(2));
+            settle(&mut app, &mut responses).await;
+
+            let spotify = app.spotify_client.lock().await;
+            assert_eq!(spotify.reorders, [(0, 2), (1, 3)]);
+            assert_eq!(spotify.playlist.iter().map(|t| t.name.clone()).collect::<Vec<_>>(), names(&app));
+            drop(spotify);
+            assert_eq!(app.error_message, None);
+        })
+        .await;
+}
+
+#[tokio::test(start_paused = true)]
+async fn failed_move_reloads_the_playlist() {
+    LocalSet::new()
+        .run_until(async {
+            let (mut app, mut responses) = app(MockSpotify { playlist: tracks(3), fail_reorder: true, ..Default::default() });
+            app.user_profile = Some(profile());
+            app.show_playlist(playlist(3));
+            settle(&mut app, &mut responses).await;
+
+            app.move_selected_playlist_item(false);
+            assert_eq!(names(&app), ["Canción 2", "Canción 1", "Canción 3"]);
+            settle(&mut app, &mut responses).await;
+
+            assert_eq!(names(&app), ["Canción 1", "Canción 2", "Canción 3"]);
+            assert!(app.error_message.as_deref().is_some_and(|e| e.starts_with("Error al mover la canción")));
+        })
+        .await;
+}
@@ -0,0 +1,304 @@
+use anyhow::{anyhow, Result};
+use ratatui::style::{Color, Modifier, Style};
+use spotigod_core::tr;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Colores de la interfaz: los de siempre con los cambios de la sección `theme` del config
+#[derive(Debug, Clone, Copy)]
+pub struct Theme {
+    /// Cabecera, iconos, "me gusta" y borde de la sección activa
+    pub accent: Color,
+    /// Nombres de canciones, playlists y álbumes
+    pub text: Color,
+    /// Separadores, duraciones y textos secundarios
+    pub secondary: Color,
+    /// Elementos no disponibles o atenuados
+    pub dim: Color,
+    /// Títulos de los paneles, números de fila y avisos
+    pub title: Color,
+    /// Artistas, autores y enlaces
+    pub info: Color,
+    /// Álbumes, géneros y otros detalles
+    pub detail: Color,
+    pub error: Color,
+    pub success: Color,
+    /// Fondo y texto de la fila seleccionada y de la pestaña activa
+    pub highlight_bg: Color,
+    pub highlight_fg: Color,
+    /// Barra de progreso y visualizador
+    pub gauge: Color,
+    /// Marca los estados también con negrita, subrayado y bordes gruesos, no solo con el color
+    pub emphasis: bool,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            accent: Color::Green,
+            text: Color::White,
+            secondary: Color::Gray,
+            dim: Color::DarkGray,
+            title: Color::Yellow,
+            info: Color::Cyan,
+            detail: Color::Magenta,
+            error: Color::Red,
+            success: Color::Green,
+            highlight_bg: Color::Green,
+            highlight_fg: Color::Black,
+            gauge: Color::Green,
+            emphasis: false,
+        }
+    }
+}
+
+/// Temas incluidos, seleccionables con `preset` en `[theme]` o con `:theme`
+pub const PRESETS: &[&str] = &[
+    "default",
+    "gruvbox",
+    "gruvbox-light",
+    "dracula",
+    "nord",
+    "solarized-light",
+    "high-contrast",
+    "monochrome",
+];
+
+impl Theme {
+    /// Aplica la sección `theme` del config sobre el tema incluido elegido (o el de siempre);
+    /// los colores se escriben por nombre ("green", "lightblue"...), en hexadecimal ("#1db954")
+    /// o como índice de 256 colores. Con `accessible` los estados se marcan siempre con algo más que el color
+    pub fn from_config(preset: Option<&str>, overrides: &HashMap<String, String>, accessible: bool) -> Result<Self> {
+        let mut theme = match preset {
+            Some(name) => Self::preset(name)?,
+            None => Self::default(),
+        };
+        theme.emphasis |= accessible;
+        for (name, value) in overrides {
+            let color = Color::from_str(value).map_err(|_| anyhow!(tr!("theme.{}: \"{}\" no es un color", name, value)))?;
+            let slot = match name.as_str() {
+                "accent" => &mut theme.accent,
+                "text" => &mut theme.text,
+                "secondary" => &mut theme.secondary,
+                "dim" => &mut theme.dim,
+                "title" => &mut theme.title,
+                "info" => &mut theme.info,
+                "detail" => &mut theme.detail,
+                "error" => &mut theme.error,
+                "success" => &mut theme.success,
+                "highlight_bg" => &mut theme.highlight_bg,
+                "highlight_fg" => &mut theme.highlight_fg,
+                "gauge" => &mut theme.gauge,
+                other => {
+                    return Err(anyhow!(tr!(
+                        "theme: color desconocido \"{}\" (válidos: accent, text, secondary, dim, title, info, detail, error, success, highlight_bg, highlight_fg, gauge)",
+                        other
+                    )))
+                }
+            };
+            *slot = color;
+        }
+        Ok(theme)
+    }
+
+    /// Tema incluido por nombre
+    pub fn preset(name: &str) -> Result<Self> {
+        match name {
+            "default" => Ok(Self::default()),
+            "gruvbox" => Ok(Self::gruvbox()),
+            "gruvbox-light" => Ok(Self::gruvbox_light()),
+            "dracula" => Ok(Self::dracula()),
+            "nord" => Ok(Self::nord()),
+            "solarized-light" => Ok(Self::solarized_light()),
+            "high-contrast" => Ok(Self::high_contrast()),
+            "monochrome" => Ok(Self::monochrome()),
+            other => Err(anyhow!(tr!("tema desconocido \"{}\" (disponibles: {})", other, PRESETS.join(", ")))),
+        }
+    }
+
+    /// Gruvbox oscuro
+    fn gruvbox() -> Self {
+        Self {
+            accent: Color::Rgb(0xb8, 0xbb, 0x26),
+            text: Color::Rgb(0xeb, 0xdb, 0xb2),
+            secondary: Color::Rgb(0xa8, 0x99, 0x84),
+            dim: Color::Rgb(0x66, 0x5c, 0x54),
+            title: Color::Rgb(0xfa, 0xbd, 0x2f),
+            info: Color::Rgb(0x83, 0xa5, 0x98),
+            detail: Color::Rgb(0xd3, 0x86, 0x9b),
+            error: Color::Rgb(0xfb, 0x49, 0x34),
+            success: Color::Rgb(0xb8, 0xbb, 0x26),
+            highlight_bg: Color::Rgb(0xfe, 0x80, 0x19),
+            highlight_fg: Color::Rgb(0x28, 0x28, 0x28),
+            gauge: Color::Rgb(0x8e, 0xc0, 0x7c),
+            emphasis: false,
+        }
+    }
+
+    /// Gruvbox para terminales claros
+    fn gruvbox_light() -> Self {
+        Self {
+            accent: Color::Rgb(0x79, 0x74, 0x0e),
+            text: Color::Rgb(0x3c, 0x38, 0x36),
+            secondary: Color::Rgb(0x7c, 0x6f, 0x64),
+            dim: Color::Rgb(0xa8, 0x99, 0x84),
+            title: Color::Rgb(0xb5, 0x76, 0x14),
+            info: Color::Rgb(0x07, 0x66, 0x78),
+            detail: Color::Rgb(0x8f, 0x3f, 0x71),
+            error: Color::Rgb(0x9d, 0x00, 0x06),
+            success: Color::Rgb(0x79, 0x74, 0x0e),
+            highlight_bg: Color::Rgb(0xaf, 0x3a, 0x03),
+            highlight_fg: Color::Rgb(0xfb, 0xf1, 0xc7),
+            gauge: Color::Rgb(0x42, 0x7b, 0x58),
+            emphasis: false,
+        }
+    }
+
+    /// Dracula
+    fn dracula() -> Self {
+        Self {
+            accent: Color::Rgb(0xbd, 0x93, 0xf9),
+            text: Color::Rgb(0xf8, 0xf8, 0xf2),
+            secondary: Color::Rgb(0xa4, 0xa9, 0xc2),
+            dim: Color::Rgb(0x62, 0x72, 0xa4),
+            title: Color::Rgb(0xf1, 0xfa, 0x8c),
+            info: Color::Rgb(0x8b, 0xe9, 0xfd),
+            detail: Color::Rgb(0xff, 0x79, 0xc6),
+            error: Color::Rgb(0xff, 0x55, 0x55),
+            success: Color::Rgb(0x50, 0xfa, 0x7b),
+            highlight_bg: Color::Rgb(0xbd, 0x93, 0xf9),
+            highlight_fg: Color::Rgb(0x28, 0x2a, 0x36),
+            gauge: Color::Rgb(0xff, 0x79, 0xc6),
+            emphasis: false,
+        }
+    }
+
+    /// Nord
+    fn nord() -> Self {
+        Self {
+            accent: Color::Rgb(0x88, 0xc0, 0xd0),
+            text: Color::Rgb(0xec, 0xef, 0xf4),
+            secondary: Color::Rgb(0xd8, 0xde, 0xe9),
+            dim: Color::Rgb(0x4c, 0x56, 0x6a),
+            title: Color::Rgb(0xeb, 0xcb, 0x8b),
+            info: Color::Rgb(0x81, 0xa1, 0xc1),
+            detail: Color::Rgb(0xb4, 0x8e, 0xad),
+            error: Color::Rgb(0xbf, 0x61, 0x6a),
+            success: Color::Rgb(0xa3, 0xbe, 0x8c),
+            highlight_bg: Color::Rgb(0x88, 0xc0, 0xd0),
+            highlight_fg: Color::Rgb(0x2e, 0x34, 0x40),
+            gauge: Color::Rgb(0x5e, 0x81, 0xac),
+            emphasis: false,
+        }
+    }
+
+    /// Solarized para terminales claros
+    fn solarized_light() -> Self {
+        Self {
+            accent: Color::Rgb(0x26, 0x8b, 0xd2),
+            text: Color::Rgb(0x07, 0x36, 0x42),
+            secondary: Color::Rgb(0x58, 0x6e, 0x75),
+            dim: Color::Rgb(0x93, 0xa1, 0xa1),
+            title: Color::Rgb(0xb5, 0x89, 0x00),
+            info: Color::Rgb(0x2a, 0xa1, 0x98),
+            detail: Color::Rgb(0xd3, 0x36, 0x82),
+            error: Color::Rgb(0xdc, 0x32, 0x2f),
+            success: Color::Rgb(0x85, 0x99, 0x00),
+            highlight_bg: Color::Rgb(0x26, 0x8b, 0xd2),
+            highlight_fg: Color::Rgb(0xfd, 0xf6, 0xe3),
+            gauge: Color::Rgb(0x85, 0x99, 0x00),
+            emphasis: false,
+        }
+    }
+
+    /// Colores vivos del terminal y estados marcados con negrita y subrayado
+    fn high_contrast() -> Self {
+        Self {
+            accent: Color::LightYellow,
+            text: Color::White,
+            secondary: Color::White,
+            dim: Color::Gray,
+            title: Color::LightYellow,
+            info: Color::LightCyan,
+            detail: Color::LightMagenta,
+            error: Color::LightRed,
+            success: Color::LightGreen,
+            highlight_bg: Color::LightYellow,
+            highlight_fg: Color::Black,
+            gauge: Color::LightYellow,
+            emphasis: true,
+        }
+    }
+
+    /// Sin colores: todo con los del terminal, la selección invertida y los estados marcados
+    /// con negrita y subrayado
+    fn monochrome() -> Self {
+        Self {
+            accent: Color::Reset,
+            text: Color::Reset,
+            secondary: Color::Reset,
+            dim: Color::Reset,
+            title: Color::Reset,
+            info: Color::Reset,
+            detail: Color::Reset,
+            error: Color::Reset,
+            success: Color::Reset,
+            highlight_bg: Color::Reset,
+            highlight_fg: Color::Reset,
+            gauge: Color::Gray,
+            emphasis: true,
+        }
+    }
+
+    /// Estilo de la fila seleccionada; sin color de fondo se marca invirtiendo los colores
+    pub fn highlight(&self) -> Style {
+        let mut style = Style::default().fg(self.highlight_fg).bg(self.highlight_bg);
+        if self.highlight_bg == Color::Reset {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        if self.emphasis {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        style
+    }
+
+    /// Estilo de la pestaña activa
+    pub fn active_tab(&self) -> Style {
+        let style = self.highlight().add_modifier(Modifier::BOLD);
+        if self.emphasis {
+            style.add_modifier(Modifier::UNDERLINED)
+        } else {
+            style
+        }
+    }
+
+    /// Si algún color está en hexadecimal: sin color de 24 bits la terminal lo aproxima
+    pub fn uses_rgb(&self) -> bool {
+        [
+            self.accent,
+            self.text,
+            self.secondary,
+            self.dim,
+            self.title,
+            self.info,
+            self.detail,
+            self.error,
+            self.success,
+            self.highlight_bg,
+            self.highlight_fg,
+            self.gauge,
+        ]
+        .iter()
+        .any(|color| matches!(color, Color::Rgb(..)))
+    }
+
+    /// Estilo de un indicador encendido o apagado (aleatorio, "me gusta"...)
+    pub fn toggle(&self, on: bool) -> Style {
+        match (on, self.emphasis) {
+            (true, false) => Style::default().fg(self.success),
+            (false, false) => Style::default().fg(self.error),
+            (true, true) => Style::default().fg(self.success).add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+            (false, true) => Style::default().fg(self.error).add_modifier(Modifier::DIM),
+        }
+    }
+}
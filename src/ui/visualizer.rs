@@ -0,0 +1,88 @@
+use spotigod_core::spotify::AudioAnalysis;
+use ratatui::{buffer::Buffer, layout::Rect, style::Style, widgets::Widget};
+
+/// Segundos de audio que representa cada columna del visualizador
+const SECONDS_PER_BAR: f64 = 0.1;
+/// Volumen (dB) que se considera silencio
+const SILENCE_DB: f64 = -60.0;
+const BAR_SYMBOLS: [&str; 8] = ["▁", "▂", "▃", "▄", "▅", "▆", "▇", "█"];
+
+/// Barras sintéticas calculadas a partir de la sonoridad de los segmentos del
+/// análisis de audio. La barra más a la derecha corresponde a `progress_ms` y
+/// el resto se desplaza hacia la izquierda a medida que avanza la canción.
+pub struct Visualizer<'a> {
+    analysis: &'a AudioAnalysis,
+    progress_ms: i64,
+    style: Style,
+}
+
+impl<'a> Visualizer<'a> {
+    pub fn new(analysis: &'a AudioAnalysis, progress_ms: i64) -> Self {
+        Self {
+            analysis,
+            progress_ms,
+            style: Style::default(),
+        }
+    }
+
+    pub fn style(mut self, style: Style) -> Self {
+        self.style = style;
+        self
+    }
+
+    /// Sonoridad normalizada (0.0 - 1.0) en el instante `t` (segundos)
+    fn level_at(&self, t: f64) -> f64 {
+        let segments = &self.analysis.segments;
+        let index = segments.partition_point(|s| s.start <= t);
+        if index == 0 {
+            return 0.0;
+        }
+
+        let segment = &segments[index - 1];
+        let offset = t - segment.start;
+        if offset > segment.duration {
+            return 0.0;
+        }
+
+        // Sube desde loudness_start hasta loudness_max y decae hacia el inicio del siguiente segmento
+        let loudness = if offset < segment.loudness_max_time && segment.loudness_max_time > 0.0 {
+            let frac = offset / segment.loudness_max_time;
+            segment.loudness_start + (segment.loudness_max - segment.loudness_start) * frac
+        } else {
+            let end = segments.get(index).map(|s| s.loudness_start).unwrap_or(SILENCE_DB);
+            let decay = segment.duration - segment.loudness_max_time;
+            let frac = if decay > 0.0 { (offset - segment.loudness_max_time) / decay } else { 1.0 };
+            segment.loudness_max + (end - segment.loudness_max) * frac
+        };
+
+        ((loudness - SILENCE_DB) / -SILENCE_DB).clamp(0.0, 1.0)
+    }
+}
+
+impl Widget for Visualizer<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
+
+        let now = self.progress_ms as f64 / 1000.0;
+        let levels_per_row = BAR_SYMBOLS.len() as f64;
+
+        for column in 0..area.width {
+            let t = now - f64::from(area.width - 1 - column) * SECONDS_PER_BAR;
+            let level = if t < 0.0 { 0.0 } else { self.level_at(t) };
+            let mut remaining = (level * f64::from(area.height) * levels_per_row).round() as usize;
+
+            for row in (0..area.height).rev() {
+                if remaining == 0 {
+                    break;
+                }
+                let step = remaining.min(BAR_SYMBOLS.len());
+                buf.get_mut(area.x + column, area.y + row)
+                    .set_symbol(BAR_SYMBOLS[step - 1])
+                    .set_style(self.style);
+                remaining -= step;
+            }
+        }
+    }
+}
@@ -0,0 +1,365 @@
+use spotigod_core::lyrics::{Lyrics, LyricsProvider, LyricsQuery};
+use spotigod_core::scrobble::{self, ScrobbleEvent, Scrobbler};
+use spotigod_core::spotify::error::Result;
+use spotigod_core::spotify::{
+    Album, AlbumSearchResult, AlbumTracksResponse, Artist, AudioAnalysis, Category, Device, Episode, FullArtist,
+    PlaybackContext, PlaybackOffset, PlaybackState, Playlist, PlaylistTracksResponse, PlaylistsResponse, QueueResponse,
+    Reauthorization, SavedAlbumsResponse, SavedTracksResponse, SearchResults, SearchType, SpotifyApi, TimeRange, Track,
+    UserProfile,
+};
+use std::rc::Rc;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use tokio::sync::Mutex;
+
+use super::{AfterCommand, ArtistSection, RADIO_SIZE, SEARCH_PAGE_SIZE};
+
+/// Petición que la interfaz encarga en segundo plano para no dejar de dibujar ni de leer teclas
+/// mientras espera la respuesta
+#[derive(Debug)]
+pub(super) enum Request {
+    /// Estado de la reproducción, que se consulta cada segundo
+    Playback,
+    /// Primera página de todos los tipos; `live` si se lanzó mientras se escribía
+    Search { query: String, live: bool },
+    /// Siguiente página de resultados de un solo tipo
+    SearchPage { query: String, search_type: SearchType, offset: u32 },
+    /// Las páginas de la biblioteca: con `offset` 0 sustituyen a lo cargado
+    Playlists { offset: u32 },
+    Favorites { offset: u32 },
+    SavedAlbums { offset: u32 },
+    /// Las playlists que faltan desde `offset`, para elegir a cuál añadir `uris`
+    PlaylistPicker { offset: u32, uris: Vec<String> },
+    PlaylistItems { playlist_id: String, offset: u32 },
+    /// Primeras canciones de una playlist de la búsqueda
+    PlaylistPreview { playlist: Playlist },
+    AlbumTracks { album: Album, offset: u32 },
+    /// Populares, álbumes y relacionados; se abre en `section`
+    Artist { artist: Artist, section: ArtistSection },
+    FollowedArtists,
+    Queue,
+    Top { time_range: TimeRange },
+    Recommendations { seed_tracks: Vec<String>, seed_artists: Vec<String> },
+    /// Canciones parecidas a `track` para encolar
+    Radio { track: Track },
+    SavedEpisodes,
+    NewReleases { offset: u32 },
+    FeaturedPlaylists,
+    Categories,
+    CategoryPlaylists { category: Category },
+    Devices,
+    Profile,
+    BeginReauthorization,
+    /// Canjea el código de la URL de redirección pegada o recibida
+    CompleteReauthorization { input: String },
+    Logout,
+    AudioAnalysis { track_id: String },
+    Lyrics { key: String, query: LyricsQuery },
+    /// Se envía a todos los servicios de scrobbling configurados
+    Scrobble { event: ScrobbleEvent },
+    /// Si las canciones están en "Tus me gusta"
+    CheckSaved { ids: Vec<String> },
+    /// Orden que cambia la reproducción o la biblioteca; `then` es lo que hace la interfaz al
+    /// terminar, e `id` la distingue de las demás para quien espera por ella
+    Command { id: u64, command: Command, then: Box<AfterCommand> },
+    /// Canciones para la función de `spotigod.search` del script que espera la orden `id`
+    ScriptSearch { id: u64, query: String },
+}
+
+/// Orden que cambia la reproducción o la biblioteca
+#[derive(Debug)]
+pub(super) enum Command {
+    Play,
+    Pause,
+    Next,
+    Previous,
+    Seek { position_ms: u64 },
+    ToggleShuffle,
+    ToggleRepeat,
+    SetVolume(u8),
+    StartPlayback { context: PlaybackContext, offset: Option<PlaybackOffset>, position_ms: Option<i64> },
+    TransferPlayback { device_id: String, play: bool },
+    AddToQueue(String),
+    SaveTracks(Vec<String>),
+    RemoveSavedTracks(Vec<String>),
+    SaveEpisodes(Vec<String>),
+    RemoveSavedEpisodes(Vec<String>),
+    FollowPlaylist(String),
+    UnfollowPlaylist(String),
+    AddTracksToPlaylist { playlist_id: String, uris: Vec<String> },
+    RemovePlaylistTracks { playlist_id: String, uris: Vec<String> },
+    ReorderPlaylistTracks { playlist_id: String, range_start: usize, insert_before: usize },
+    UpdatePlaylistDetails { playlist_id: String, name: String, description: String },
+    /// Crea la playlist y le añade `uris`, si hay alguna
+    CreatePlaylist { user_id: String, name: String, public: bool, uris: Vec<String> },
+}
+
+impl Command {
+    /// Cambia lo que suena o dónde: Spotify tarda un poco en reflejarlo en la reproducción
+    fn changes_track(&self) -> bool {
+        matches!(
+            self,
+            Command::Next | Command::Previous | Command::StartPlayback { .. } | Command::TransferPlayback { .. }
+        )
+    }
+}
+
+/// Respuesta a una petición, con lo necesario para saber si todavía corresponde a lo que se ve
+pub(super) enum Response {
+    Playback(Result<Option<PlaybackState>>),
+    Search { query: String, live: bool, result: Result<SearchResults> },
+    SearchPage { query: String, search_type: SearchType, result: Result<SearchResults> },
+    Playlists { offset: u32, result: Result<PlaylistsResponse> },
+    Favorites { offset: u32, result: Result<SavedTracksResponse> },
+    SavedAlbums { offset: u32, result: Result<SavedAlbumsResponse> },
+    PlaylistPicker { offset: u32, uris: Vec<String>, result: Result<Vec<PlaylistsResponse>> },
+    PlaylistItems { playlist_id: String, offset: u32, result: Result<PlaylistTracksResponse> },
+    PlaylistPreview { playlist: Playlist, result: Result<PlaylistTracksResponse> },
+    AlbumTracks { album: Album, offset: u32, result: Result<AlbumTracksResponse> },
+    /// Los álbumes y relacionados son secundarios: pueden fallar sin que falle la vista
+    Artist {
+        artist: Artist,
+        section: ArtistSection,
+        top_tracks: Result<Vec<Track>>,
+        albums: Result<Vec<Album>>,
+        related: Result<Vec<FullArtist>>,
+    },
+    FollowedArtists(Result<Vec<FullArtist>>),
+    Queue(Result<QueueResponse>),
+    Top { time_range: TimeRange, tracks: Result<Vec<Track>>, artists: Result<Vec<FullArtist>> },
+    Recommendations(Result<Vec<Track>>),
+    Radio { track: Track, result: Result<Vec<Track>> },
+    SavedEpisodes(Result<Vec<Episode>>),
+    NewReleases { offset: u32, result: Result<AlbumSearchResult> },
+    FeaturedPlaylists(Result<(Option<String>, Vec<Playlist>)>),
+    Categories(Result<Vec<Category>>),
+    CategoryPlaylists { category: Category, result: Result<Vec<Playlist>> },
+    Devices(Result<Vec<Device>>),
+    Profile(Result<UserProfile>),
+    Reauthorization(Reauthorization),
+    Reauthorized(Result<()>),
+    LoggedOut(Result<()>),
+    /// `None` si no se pudo descargar; no se reintenta para esa canción
+    AudioAnalysis { track_id: String, analysis: Option<AudioAnalysis> },
+    Lyrics { key: String, result: anyhow::Result<Option<Lyrics>> },
+    /// Servicios en los que falló el envío
+    Scrobbled { event: ScrobbleEvent, errors: Vec<(&'static str, anyhow::Error)> },
+    Saved { ids: Vec<String>, result: Result<Vec<bool>> },
+    Command { id: u64, then: Box<AfterCommand>, result: Result<()> },
+    ScriptSearch { id: u64, result: Result<SearchResults> },
+}
+
+/// Arranca el trabajador: cada petición que llega se atiende en su propia tarea y su respuesta
+/// vuelve por el canal. Todas las peticiones a Spotify de la interfaz pasan por aquí y comparten
+/// el cliente, así que se hacen de una en una. Tiene que llamarse dentro de un `LocalSet`, porque
+/// el cliente no tiene por qué poder cambiar de hilo
+pub(super) fn spawn<C: SpotifyApi + 'static>(
+    client: Rc<Mutex<C>>,
+    lyrics: Option<Arc<dyn LyricsProvider>>,
+    scrobblers: Vec<Arc<dyn Scrobbler>>,
+) -> (UnboundedSender<Request>, UnboundedReceiver<Response>) {
+    let (request_tx, mut request_rx) = mpsc::unbounded_channel();
+    let (response_tx, response_rx) = mpsc::unbounded_channel();
+    tokio::task::spawn_local(async move {
+        while let Some(request) = request_rx.recv().await {
+            let client = client.clone();
+            let lyrics = lyrics.clone();
+            let scrobblers = scrobblers.clone();
+            let responses = response_tx.clone();
+            tokio::task::spawn_local(async move {
+                let response = handle(&client, lyrics.as_deref(), &scrobblers, request).await;
+                // Si la interfaz ya terminó nadie espera la respuesta
+                let _ = responses.send(response);
+            });
+        }
+    });
+    (request_tx, response_rx)
+}
+
+async fn handle<C: SpotifyApi>(
+    client: &Mutex<C>,
+    lyrics: Option<&dyn LyricsProvider>,
+    scrobblers: &[Arc<dyn Scrobbler>],
+    request: Request,
+) -> Response {
+    match request {
+        Request::Playback => Response::Playback(client.lock().await.get_current_playback().await),
+        Request::Search { query, live } => {
+            let result = client.lock().await.search(&query, &SearchType::ALL, SEARCH_PAGE_SIZE, 0).await;
+            Response::Search { query, live, result }
+        }
+        Request::SearchPage { query, search_type, offset } => {
+            let result = client.lock().await.search(&query, &[search_type], SEARCH_PAGE_SIZE, offset).await;
+            Response::SearchPage { query, search_type, result }
+        }
+        Request::Playlists { offset } => {
+            let result = client.lock().await.get_user_playlists(50, offset).await;
+            Response::Playlists { offset, result }
+        }
+        Request::Favorites { offset } => {
+            let result = client.lock().await.get_saved_tracks(50, offset).await;
+            Response::Favorites { offset, result }
+        }
+        Request::SavedAlbums { offset } => {
+            let result = client.lock().await.get_saved_albums(50, offset).await;
+            Response::SavedAlbums { offset, result }
+        }
+        Request::PlaylistPicker { offset, uris } => {
+            let result = remaining_playlists(client, offset).await;
+            Response::PlaylistPicker { offset, uris, result }
+        }
+        Request::PlaylistItems { playlist_id, offset } => {
+            let result = client.lock().await.get_playlist_tracks(&playlist_id, 100, offset).await;
+            Response::PlaylistItems { playlist_id, offset, result }
+        }
+        Request::PlaylistPreview { playlist } => {
+            let result = client.lock().await.get_playlist_tracks(&playlist.id, 50, 0).await;
+            Response::PlaylistPreview { playlist, result }
+        }
+        Request::AlbumTracks { album, offset } => {
+            let result = client.lock().await.get_album_tracks(&album.id, 50, offset).await;
+            Response::AlbumTracks { album, offset, result }
+        }
+        Request::Artist { artist, section } => {
+            let top_tracks = client.lock().await.get_artist_top_tracks(&artist.id).await;
+            // Sin las populares no se abre la vista: no hace falta pedir lo demás
+            let (albums, related) = match top_tracks {
+                Ok(_) => (
+                    client.lock().await.get_artist_albums(&artist.id, 50).await,
+                    client.lock().await.get_related_artists(&artist.id).await,
+                ),
+                Err(_) => (Ok(Vec::new()), Ok(Vec::new())),
+            };
+            Response::Artist { artist, section, top_tracks, albums, related }
+        }
+        Request::FollowedArtists => Response::FollowedArtists(client.lock().await.get_followed_artists().await),
+        Request::Queue => Response::Queue(client.lock().await.get_queue().await),
+        Request::Top { time_range } => {
+            let tracks = client.lock().await.get_top_tracks(time_range, 50).await;
+            let artists = client.lock().await.get_top_artists(time_range, 50).await;
+            Response::Top { time_range, tracks, artists }
+        }
+        Request::Recommendations { seed_tracks, seed_artists } => {
+            Response::Recommendations(client.lock().await.get_recommendations(&seed_tracks, &seed_artists, 30).await)
+        }
+        Request::Radio { track } => {
+            let seed_artists: Vec<String> = track.artists.first().map(|a| a.id.clone()).into_iter().collect();
+            let result = client
+                .lock()
+                .await
+                .get_recommendations(std::slice::from_ref(&track.id), &seed_artists, RADIO_SIZE)
+                .await;
+            Response::Radio { track, result }
+        }
+        Request::SavedEpisodes => Response::SavedEpisodes(client.lock().await.get_saved_episodes().await),
+        Request::NewReleases { offset } => {
+            let result = client.lock().await.get_new_releases(50, offset).await;
+            Response::NewReleases { offset, result }
+        }
+        Request::FeaturedPlaylists => Response::FeaturedPlaylists(client.lock().await.get_featured_playlists(50).await),
+        Request::Categories => Response::Categories(client.lock().await.get_categories(50).await),
+        Request::CategoryPlaylists { category } => {
+            let result = client.lock().await.get_category_playlists(&category.id, 50).await;
+            Response::CategoryPlaylists { category, result }
+        }
+        Request::Devices => Response::Devices(client.lock().await.get_devices().await),
+        Request::Profile => Response::Profile(client.lock().await.get_user_profile().await),
+        Request::BeginReauthorization => Response::Reauthorization(client.lock().await.begin_reauthorization()),
+        Request::CompleteReauthorization { input } => {
+            Response::Reauthorized(client.lock().await.complete_reauthorization(&input).await)
+        }
+        Request::Logout => Response::LoggedOut(client.lock().await.logout().await),
+        Request::AudioAnalysis { track_id } => {
+            let analysis = client.lock().await.get_audio_analysis(&track_id).await.ok();
+            Response::AudioAnalysis { track_id, analysis }
+        }
+        // Las letras no pasan por Spotify: no esperan a que quede libre el cliente
+        Request::Lyrics { key, query } => {
+            let result = match lyrics {
+                Some(provider) => provider.fetch(&query).await,
+                None => Ok(None),
+            };
+            Response::Lyrics { key, result }
+        }
+        Request::Scrobble { event } => {
+            let mut errors = Vec::new();
+            for scrobbler in scrobblers {
+                if let Err(e) = scrobble::send(scrobbler.as_ref(), &event).await {
+                    errors.push((scrobbler.name(), e));
+                }
+            }
+            Response::Scrobbled { event, errors }
+        }
+        Request::CheckSaved { ids } => {
+            let result = client.lock().await.check_saved_tracks(&ids).await;
+            Response::Saved { ids, result }
+        }
+        Request::Command { id, command, then } => {
+            let settle = command.changes_track();
+            let result = run(&mut *client.lock().await, command).await;
+            // Se espera sin el cliente, para no retener las demás peticiones
+            if settle && result.is_ok() {
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+            Response::Command { id, then, result }
+        }
+        Request::ScriptSearch { id, query } => {
+            let result = client.lock().await.search(&query, &[SearchType::Track], SEARCH_PAGE_SIZE, 0).await;
+            Response::ScriptSearch { id, result }
+        }
+    }
+}
+
+/// Páginas de playlists desde `offset` hasta la última. El cliente se suelta entre página y
+/// página para no retener las demás peticiones
+async fn remaining_playlists<C: SpotifyApi>(client: &Mutex<C>, mut offset: u32) -> Result<Vec<PlaylistsResponse>> {
+    let mut pages = Vec::new();
+    loop {
+        let page = client.lock().await.get_user_playlists(50, offset).await?;
+        let received = page.items.len() as u32;
+        let total = page.total.max(0) as u32;
+        pages.push(page);
+        offset += received;
+        if received == 0 || offset >= total {
+            return Ok(pages);
+        }
+    }
+}
+
+async fn run<C: SpotifyApi>(client: &mut C, command: Command) -> Result<()> {
+    match command {
+        Command::Play => client.play().await,
+        Command::Pause => client.pause().await,
+        Command::Next => client.next_track().await,
+        Command::Previous => client.previous_track().await,
+        Command::Seek { position_ms } => client.seek(position_ms).await,
+        Command::ToggleShuffle => client.toggle_shuffle().await,
+        Command::ToggleRepeat => client.toggle_repeat().await,
+        Command::SetVolume(volume) => client.set_volume(volume).await,
+        Command::StartPlayback { context, offset, position_ms } => client.start_playback(context, offset, position_ms).await,
+        Command::TransferPlayback { device_id, play } => client.transfer_playback(&device_id, play).await,
+        Command::AddToQueue(uri) => client.add_to_queue(&uri).await,
+        Command::SaveTracks(ids) => client.save_tracks(&ids).await,
+        Command::RemoveSavedTracks(ids) => client.remove_saved_tracks(&ids).await,
+        Command::SaveEpisodes(ids) => client.save_episodes(&ids).await,
+        Command::RemoveSavedEpisodes(ids) => client.remove_saved_episodes(&ids).await,
+        Command::FollowPlaylist(playlist_id) => client.follow_playlist(&playlist_id).await,
+        Command::UnfollowPlaylist(playlist_id) => client.unfollow_playlist(&playlist_id).await,
+        Command::AddTracksToPlaylist { playlist_id, uris } => client.add_tracks_to_playlist(&playlist_id, &uris).await,
+        Command::RemovePlaylistTracks { playlist_id, uris } => client.remove_playlist_tracks(&playlist_id, &uris).await,
+        Command::ReorderPlaylistTracks { playlist_id, range_start, insert_before } => {
+            client.reorder_playlist_tracks(&playlist_id, range_start, insert_before).await.map(|_| ())
+        }
+        Command::UpdatePlaylistDetails { playlist_id, name, description } => {
+            client.update_playlist_details(&playlist_id, &name, &description).await
+        }
+        Command::CreatePlaylist { user_id, name, public, uris } => {
+            let playlist = client.create_playlist(&user_id, &name, public).await?;
+            if uris.is_empty() {
+                return Ok(());
+            }
+            client.add_tracks_to_playlist(&playlist.id, &uris).await
+        }
+    }
+}